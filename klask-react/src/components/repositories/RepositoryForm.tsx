@@ -38,6 +38,9 @@ const createRepositorySchema = (isEditing: boolean, hasExistingToken: boolean) =
     .string()
     .optional(),
   isGroup: z.boolean().optional(),
+  githubUrl: z
+    .string()
+    .optional(),
   githubNamespace: z
     .string()
     .optional(),
@@ -214,6 +217,7 @@ export const RepositoryForm: React.FC<RepositoryFormProps> = ({
       gitlabExcludedProjects: repository.gitlabExcludedProjects || '',
       gitlabExcludedPatterns: repository.gitlabExcludedPatterns || '',
       isGroup: repository.isGroup || false,
+      githubUrl: repository.githubUrl || '',
       githubNamespace: repository.githubNamespace || '',
       githubExcludedRepositories: repository.githubExcludedRepositories || '',
       githubExcludedPatterns: repository.githubExcludedPatterns || '',
@@ -234,6 +238,7 @@ export const RepositoryForm: React.FC<RepositoryFormProps> = ({
       gitlabExcludedProjects: '',
       gitlabExcludedPatterns: '',
       isGroup: false,
+      githubUrl: '',
       githubNamespace: '',
       githubExcludedRepositories: '',
       githubExcludedPatterns: '',
@@ -413,6 +418,7 @@ export const RepositoryForm: React.FC<RepositoryFormProps> = ({
           gitlabExcludedProjects: repository.gitlabExcludedProjects || '',
           gitlabExcludedPatterns: repository.gitlabExcludedPatterns || '',
           isGroup: repository.isGroup || false,
+          githubUrl: repository.githubUrl || '',
           githubNamespace: repository.githubNamespace || '',
           githubExcludedRepositories: repository.githubExcludedRepositories || '',
           githubExcludedPatterns: repository.githubExcludedPatterns || '',
@@ -443,6 +449,7 @@ export const RepositoryForm: React.FC<RepositoryFormProps> = ({
           gitlabExcludedProjects: '',
           gitlabExcludedPatterns: '',
           isGroup: false,
+          githubUrl: '',
           githubNamespace: '',
           githubExcludedRepositories: '',
           githubExcludedPatterns: '',
@@ -482,6 +489,7 @@ export const RepositoryForm: React.FC<RepositoryFormProps> = ({
       includedProjectsPatterns: trimOrEmpty(data.includedProjectsPatterns),
       gitlabExcludedProjects: trimOrEmpty(data.gitlabExcludedProjects),
       gitlabExcludedPatterns: trimOrEmpty(data.gitlabExcludedPatterns),
+      githubUrl: trimOrEmpty(data.githubUrl),
       githubExcludedRepositories: trimOrEmpty(data.githubExcludedRepositories),
       githubExcludedPatterns: trimOrEmpty(data.githubExcludedPatterns),
       // For GitLab repositories, default to gitlab.com if URL is empty
@@ -807,17 +815,17 @@ export const RepositoryForm: React.FC<RepositoryFormProps> = ({
                       </div>
 
                       <div>
-                        <label htmlFor="url" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+                        <label htmlFor="githubUrl" className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
                           GitHub API URL (Optional)
                         </label>
                         <input
-                          {...register('url')}
+                          {...register('githubUrl')}
                           type="url"
-                          className={`input-field ${errors.url ? 'border-red-300 focus:ring-red-500 focus:border-red-500' : ''}`}
+                          className={`input-field ${errors.githubUrl ? 'border-red-300 focus:ring-red-500 focus:border-red-500' : ''}`}
                           placeholder="https://api.github.com"
                         />
-                        {errors.url && (
-                          <p className="mt-1 text-sm text-red-600">{errors.url.message}</p>
+                        {errors.githubUrl && (
+                          <p className="mt-1 text-sm text-red-600">{errors.githubUrl.message}</p>
                         )}
                         <p className="mt-1 text-xs text-gray-500">
                           Leave empty to use github.com, or enter your GitHub Enterprise API URL
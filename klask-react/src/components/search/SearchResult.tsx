@@ -6,7 +6,7 @@ import {
   ArrowTopRightOnSquareIcon
 } from '@heroicons/react/24/outline';
 import { RepositoryBadge } from '../ui/RepositoryBadge';
-import type { SearchResult as SearchResultType } from '../../types';
+import type { HighlightRange, SearchResult as SearchResultType } from '../../types';
 
 interface SearchResultProps {
   result: SearchResultType;
@@ -69,6 +69,35 @@ export const SearchResult: React.FC<SearchResultProps> = ({
     }
   };
 
+  const renderSnippet = (snippet: string, highlights: HighlightRange[]): React.ReactNode[] => {
+    // Highlight ranges use code point offsets, while JavaScript strings index UTF-16 units
+    const chars = Array.from(snippet);
+    const ranges = [...highlights].sort((a, b) => a.char_start - b.char_start);
+    const nodes: React.ReactNode[] = [];
+    let cursor = 0;
+
+    ranges.forEach((range, index) => {
+      const start = Math.max(range.char_start, cursor);
+      const end = Math.min(range.char_end, chars.length);
+      if (start >= end) return;
+
+      if (start > cursor) {
+        nodes.push(chars.slice(cursor, start).join(''));
+      }
+      nodes.push(
+        <mark key={index} className="bg-yellow-200 font-semibold">
+          {chars.slice(start, end).join('')}
+        </mark>
+      );
+      cursor = end;
+    });
+
+    if (cursor < chars.length) {
+      nodes.push(chars.slice(cursor).join(''));
+    }
+    return nodes;
+  };
+
   const formatPath = (path: string): { directory: string; filename: string } => {
     if (!path) {
       return { directory: '', filename: 'Unknown file' };
@@ -164,34 +193,11 @@ export const SearchResult: React.FC<SearchResultProps> = ({
                 whiteSpace: 'pre-wrap',
                 wordBreak: 'break-word',
               }}
-              dangerouslySetInnerHTML={{
-                __html: (() => {
-                  // Tantivy sends HTML with entities encoded and <b> tags for highlighting
-                  // We need to:
-                  // 1. Decode HTML entities (safe because Tantivy already escaped them)
-                  // 2. Convert <b> tags to safe <mark> tags for highlighting
-                  const snippet = result.content_snippet || 'No content preview available';
-
-                  // Decode HTML entities
-                  const textarea = document.createElement('textarea');
-                  textarea.innerHTML = snippet;
-                  const decoded = textarea.value;
-
-                  // Now re-encode for safe insertion, except for our <b> tags
-                  let html = decoded
-                    .replace(/&/g, '&amp;')
-                    .replace(/</g, '&lt;')
-                    .replace(/>/g, '&gt;');
-
-                  // Finally, convert <b> tags to safe highlight marks
-                  html = html
-                    .replace(/&lt;b&gt;/g, '<mark class="bg-yellow-200 font-semibold">')
-                    .replace(/&lt;\/b&gt;/g, '</mark>');
-
-                  return html;
-                })()
-              }}
-            />
+            >
+              {result.content_snippet
+                ? renderSnippet(result.content_snippet, result.highlights ?? [])
+                : 'No content preview available'}
+            </div>
           </div>
         </div>
         
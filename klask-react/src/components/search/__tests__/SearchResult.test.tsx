@@ -0,0 +1,66 @@
+import { describe, it, expect, vi } from 'vitest';
+import { render } from '../../../test/utils';
+import { SearchResult } from '../SearchResult';
+import type { SearchResult as SearchResultType } from '../../../types';
+
+const baseResult: SearchResultType = {
+  file_id: '1',
+  doc_address: '0:1',
+  name: 'compare.rs',
+  path: 'src/compare.rs',
+  content_snippet: '',
+  project: 'shop',
+  version: 'main',
+  extension: 'rs',
+  score: 1,
+};
+
+describe('SearchResult Component', () => {
+  it('should highlight the ranges sent by the backend', () => {
+    const { container } = render(
+      <SearchResult
+        result={{
+          ...baseResult,
+          content_snippet: 'if a < b && deprecated("x") {}',
+          highlights: [{ start: 12, end: 22, char_start: 12, char_end: 22 }],
+        }}
+        query="deprecated"
+        onFileClick={vi.fn()}
+      />
+    );
+
+    const marks = container.querySelectorAll('mark');
+    expect(marks).toHaveLength(1);
+    expect(marks[0].textContent).toBe('deprecated');
+    expect(container.textContent).toContain('if a < b && deprecated("x") {}');
+  });
+
+  it('should use code point offsets for text outside the basic multilingual plane', () => {
+    const { container } = render(
+      <SearchResult
+        result={{
+          ...baseResult,
+          content_snippet: '// 🦀 matcher',
+          highlights: [{ start: 8, end: 15, char_start: 5, char_end: 12 }],
+        }}
+        query="matcher"
+        onFileClick={vi.fn()}
+      />
+    );
+
+    expect(container.querySelector('mark')?.textContent).toBe('matcher');
+  });
+
+  it('should render markup in snippets as plain text', () => {
+    const { container } = render(
+      <SearchResult
+        result={{ ...baseResult, content_snippet: '<b>not bold</b>', highlights: [] }}
+        query="bold"
+        onFileClick={vi.fn()}
+      />
+    );
+
+    expect(container.querySelector('b')).toBeNull();
+    expect(container.textContent).toContain('<b>not bold</b>');
+  });
+});
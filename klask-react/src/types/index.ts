@@ -95,6 +95,8 @@ export interface Repository {
   gitlabExcludedPatterns?: string;
   isGroup?: boolean;
   // GitHub specific fields
+  /** GitHub Enterprise Server address; github.com when unset */
+  githubUrl?: string;
   githubNamespace?: string;
   githubExcludedRepositories?: string;
   githubExcludedPatterns?: string;
@@ -159,12 +161,22 @@ export interface SearchQuery {
   regexSearch?: boolean; // Enable regex pattern matching - default: false
 }
 
+// Highlighted match inside a snippet, relative to the snippet start (end exclusive)
+export interface HighlightRange {
+  start: number; // Byte offsets
+  end: number;
+  char_start: number; // Code point offsets
+  char_end: number;
+}
+
 export interface SearchResult {
   file_id: string;
   doc_address: string;
   name: string;
   path: string;
-  content_snippet: string;
+  content_snippet: string; // Plain text, highlighted through `highlights`
+  highlights?: HighlightRange[];
+  fragment_start_line?: number; // 1-based line of the snippet's first character
   project: string;
   version: string;
   extension: string;
@@ -310,6 +322,8 @@ export interface CreateRepositoryRequest {
   gitlabExcludedProjects?: string;
   gitlabExcludedPatterns?: string;
   // GitHub specific fields
+  /** GitHub Enterprise Server address; github.com when unset */
+  githubUrl?: string;
   githubNamespace?: string;
   githubExcludedRepositories?: string;
   githubExcludedPatterns?: string;
@@ -344,6 +358,8 @@ export interface UpdateRepositoryRequest {
   gitlabExcludedProjects?: string;
   gitlabExcludedPatterns?: string;
   // GitHub specific fields
+  /** GitHub Enterprise Server address; github.com when unset */
+  githubUrl?: string;
   githubNamespace?: string;
   githubExcludedRepositories?: string;
   githubExcludedPatterns?: string;
@@ -53,8 +53,14 @@ async fn main() -> Result<()> {
             repository: "test-repo",
             project: "test-project",
             version: "main",
-            extension: file_name.split('.').last().unwrap_or(""),
+            extension: file_name.split('.').next_back().unwrap_or(""),
             size: content.len() as u64,
+            last_modified: None,
+            language: None,
+            sections: &[],
+            line_offset: 0,
+            is_truncated: false,
+            is_lfs: false,
         };
         search_service.upsert_file(file_data).await?;
         println!("  Indexed: {}", file_name);
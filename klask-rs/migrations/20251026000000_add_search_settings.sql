@@ -0,0 +1,8 @@
+-- Search settings editable by admins at runtime (e.g. result ranking)
+-- Values are JSON documents keyed by setting name
+
+CREATE TABLE search_settings (
+    key VARCHAR(100) PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Add per-repository file inclusion rules to repositories table
+-- These extend the global file inclusion rules stored in search_settings
+
+ALTER TABLE repositories
+  ADD COLUMN included_extensions TEXT,
+  ADD COLUMN included_file_names TEXT,
+  ADD COLUMN included_path_patterns TEXT,
+  ADD COLUMN excluded_path_patterns TEXT;
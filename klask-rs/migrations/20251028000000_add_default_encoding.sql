@@ -0,0 +1,5 @@
+-- Add per-repository default encoding for files that are not valid UTF-8
+-- (WHATWG label such as 'windows-1252' or 'shift_jis'; NULL guesses the encoding from the content)
+
+ALTER TABLE repositories
+  ADD COLUMN default_encoding TEXT;
@@ -0,0 +1,5 @@
+-- Add per-repository override of the global "index archives" file inclusion rule
+-- (NULL uses the global rule)
+
+ALTER TABLE repositories
+  ADD COLUMN index_archives BOOLEAN;
@@ -0,0 +1,7 @@
+-- Add per-repository handling of files above a size limit
+-- (max_file_size_mb: NULL uses the default 10 MB limit;
+--  large_file_mode: 'skip', 'truncate' or 'chunk', NULL skips large files)
+
+ALTER TABLE repositories
+  ADD COLUMN max_file_size_mb INTEGER,
+  ADD COLUMN large_file_mode VARCHAR(20);
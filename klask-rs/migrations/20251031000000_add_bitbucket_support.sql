@@ -0,0 +1,11 @@
+-- Add Bitbucket Server / Data Center support
+-- (repository_type is a VARCHAR(20), 'Bitbucket' needs no schema change)
+
+ALTER TABLE repositories
+  ADD COLUMN excluded_projects TEXT,
+  ADD COLUMN excluded_projects_patterns TEXT,
+  ADD COLUMN bitbucket_project_key TEXT;
+
+COMMENT ON COLUMN repositories.excluded_projects IS 'Comma-separated list of projects/repositories to exclude (e.g., "PLAT/legacy,OPS/scripts")';
+COMMENT ON COLUMN repositories.excluded_projects_patterns IS 'Comma-separated list of wildcard patterns to exclude projects/repositories (e.g., "ARCHIVE/*,*-old")';
+COMMENT ON COLUMN repositories.bitbucket_project_key IS 'Bitbucket project key to discover repositories in (e.g., "PLAT"); every visible project when NULL';
@@ -0,0 +1,7 @@
+-- Add Gitea / Forgejo support
+-- (repository_type is a VARCHAR(20), 'Gitea' needs no schema change)
+
+ALTER TABLE repositories
+  ADD COLUMN gitea_organization TEXT;
+
+COMMENT ON COLUMN repositories.gitea_organization IS 'Gitea/Forgejo organization to discover repositories in (e.g., "platform"); the token owner''s repositories when NULL';
@@ -0,0 +1,7 @@
+-- Add Subversion support
+-- (repository_type is a VARCHAR(20), 'Svn' needs no schema change)
+
+ALTER TABLE repositories
+  ADD COLUMN svn_revision BIGINT;
+
+COMMENT ON COLUMN repositories.svn_revision IS 'Youngest Subversion revision indexed by the last crawl; NULL for other repository types';
@@ -0,0 +1,8 @@
+-- Add SSH authentication for Git repositories cloned over ssh:// or git@host:path URLs
+
+ALTER TABLE repositories
+  ADD COLUMN ssh_private_key TEXT,
+  ADD COLUMN ssh_known_hosts TEXT;
+
+COMMENT ON COLUMN repositories.ssh_private_key IS 'SSH private key encrypted with the application key; the shared deploy key is used when NULL';
+COMMENT ON COLUMN repositories.ssh_known_hosts IS 'known_hosts lines pinning the host keys of the Git server; the shared deploy key pins, then the system known_hosts, are used when NULL';
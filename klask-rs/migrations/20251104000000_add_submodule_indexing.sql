@@ -0,0 +1,6 @@
+-- Add opt-in indexing of the commits Git submodules are pinned to
+
+ALTER TABLE repositories
+  ADD COLUMN index_submodules BOOLEAN;
+
+COMMENT ON COLUMN repositories.index_submodules IS 'Whether to fetch the pinned commits of Git submodules and index their files under the submodule path; off when NULL';
@@ -0,0 +1,6 @@
+-- Add opt-in download of Git LFS objects, indexed by path only otherwise
+
+ALTER TABLE repositories
+  ADD COLUMN fetch_lfs_objects BOOLEAN;
+
+COMMENT ON COLUMN repositories.fetch_lfs_objects IS 'Whether to download the text files tracked by Git LFS from the LFS batch API to index their content; off when NULL';
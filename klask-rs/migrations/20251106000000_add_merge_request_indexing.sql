@@ -0,0 +1,6 @@
+-- Add opt-in indexing of open merge requests / pull requests as virtual versions
+
+ALTER TABLE repositories
+  ADD COLUMN index_merge_requests BOOLEAN;
+
+COMMENT ON COLUMN repositories.index_merge_requests IS 'Whether to fetch the head of open GitLab merge requests / GitHub pull requests and index them as versions named MR!<iid> / PR#<number>; off when NULL';
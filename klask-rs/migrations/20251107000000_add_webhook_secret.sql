@@ -0,0 +1,6 @@
+-- Add a secret verifying the push webhooks of a repository
+
+ALTER TABLE repositories
+  ADD COLUMN webhook_secret TEXT;
+
+COMMENT ON COLUMN repositories.webhook_secret IS 'Encrypted secret of push webhooks: compared to the X-Gitlab-Token header, or key of the HMAC-SHA256 signature of GitHub and generic deliveries; webhooks are rejected when NULL';
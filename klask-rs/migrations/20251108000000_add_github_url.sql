@@ -0,0 +1,12 @@
+-- Add the GitHub Enterprise address of a repository, which used to be read from the url column
+
+ALTER TABLE repositories
+  ADD COLUMN github_url TEXT;
+
+UPDATE repositories
+  SET github_url = url
+  WHERE repository_type = 'GitHub'
+    AND url ~* '^https?://'
+    AND url !~* '^https?://(api\.|www\.)?github\.com(/|$)';
+
+COMMENT ON COLUMN repositories.github_url IS 'GitHub Enterprise Server address (e.g. https://github.example.com, with or without /api/v3) used for API calls; github.com when NULL';
@@ -1,6 +1,8 @@
 use crate::auth::extractors::{AdminUser, AppState};
-use crate::repositories::{UserRepository, user_repository::UserStats};
+use crate::models::{FileInclusionRules, SshDeployKey};
+use crate::repositories::{SearchSettingsRepository, UserRepository, user_repository::UserStats};
 use crate::services::seeding::{SeedingService, SeedingStats};
+use crate::services::ssh::{validate_known_hosts, validate_private_key};
 use anyhow::Result;
 use axum::{
     Router,
@@ -12,7 +14,7 @@ use axum::{
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 pub mod search;
 
@@ -143,6 +145,14 @@ pub async fn create_router() -> Result<Router<AppState>> {
         .route("/seed/clear", post(clear_seed_data))
         .route("/seed/stats", get(get_seed_stats))
         .route("/search/reset-index", post(reset_search_index))
+        .route(
+            "/file-inclusion",
+            get(get_file_inclusion_rules).put(update_file_inclusion_rules),
+        )
+        .route(
+            "/ssh-deploy-key",
+            get(get_ssh_deploy_key).put(update_ssh_deploy_key).delete(delete_ssh_deploy_key),
+        )
         .nest("/search", search::create_router().await?);
 
     Ok(router)
@@ -536,3 +546,130 @@ async fn reset_search_index(
         }
     }
 }
+
+// Crawler configuration endpoints
+
+/// GET /api/admin/file-inclusion
+///
+/// Returns the global file inclusion rules (extensions, file names and path patterns to index).
+async fn get_file_inclusion_rules(
+    _admin_user: AdminUser,
+    State(app_state): State<AppState>,
+) -> Result<Json<FileInclusionRules>, StatusCode> {
+    let settings_repository = SearchSettingsRepository::new(app_state.database.pool().clone());
+    settings_repository.get_file_inclusion_rules().await.map(Json).map_err(|e| {
+        error!("Failed to load file inclusion rules: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// PUT /api/admin/file-inclusion
+///
+/// Validates and persists new global file inclusion rules. Omitted fields fall back to their defaults.
+/// Takes effect on the next crawl and in repository file counts.
+async fn update_file_inclusion_rules(
+    _admin_user: AdminUser,
+    State(app_state): State<AppState>,
+    Json(rules): Json<FileInclusionRules>,
+) -> Result<Json<FileInclusionRules>, StatusCode> {
+    if let Err(e) = rules.validate() {
+        warn!("Rejected invalid file inclusion rules: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let settings_repository = SearchSettingsRepository::new(app_state.database.pool().clone());
+    if let Err(e) = settings_repository.save_file_inclusion_rules(&rules).await {
+        error!("Failed to save file inclusion rules: {:?}", e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    info!("File inclusion rules updated");
+    Ok(Json(rules))
+}
+
+/// New shared SSH deploy key; the private key is encrypted before being stored
+#[derive(Deserialize)]
+pub struct SshDeployKeyRequest {
+    pub private_key: String,
+    pub known_hosts: Option<String>,
+}
+
+/// Shared SSH deploy key as shown to admins, without the private key
+#[derive(Debug, Serialize)]
+pub struct SshDeployKeyStatus {
+    pub configured: bool,
+    pub known_hosts: Option<String>,
+}
+
+/// GET /api/admin/ssh-deploy-key
+///
+/// Returns whether a shared SSH deploy key is configured, and the host keys it pins.
+async fn get_ssh_deploy_key(
+    _admin_user: AdminUser,
+    State(app_state): State<AppState>,
+) -> Result<Json<SshDeployKeyStatus>, StatusCode> {
+    let settings_repository = SearchSettingsRepository::new(app_state.database.pool().clone());
+    match settings_repository.get_ssh_deploy_key().await {
+        Ok(deploy_key) => Ok(Json(SshDeployKeyStatus {
+            configured: deploy_key.is_some(),
+            known_hosts: deploy_key.and_then(|key| key.known_hosts),
+        })),
+        Err(e) => {
+            error!("Failed to load SSH deploy key: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// PUT /api/admin/ssh-deploy-key
+///
+/// Validates, encrypts and stores the SSH key used by Git repositories cloned over SSH without a key of
+/// their own. Takes effect on the next crawl.
+async fn update_ssh_deploy_key(
+    _admin_user: AdminUser,
+    State(app_state): State<AppState>,
+    Json(request): Json<SshDeployKeyRequest>,
+) -> Result<Json<SshDeployKeyStatus>, StatusCode> {
+    if let Err(e) = validate_private_key(&request.private_key) {
+        warn!("Rejected invalid SSH deploy key: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let known_hosts = request.known_hosts.filter(|hosts| !hosts.trim().is_empty());
+    if let Some(Err(e)) = known_hosts.as_deref().map(validate_known_hosts) {
+        warn!("Rejected invalid SSH deploy key known hosts: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let private_key = app_state.encryption_service.encrypt(&request.private_key).map_err(|e| {
+        error!("Failed to encrypt SSH deploy key: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let settings_repository = SearchSettingsRepository::new(app_state.database.pool().clone());
+    let deploy_key = SshDeployKey { private_key, known_hosts };
+    if let Err(e) = settings_repository.save_ssh_deploy_key(&deploy_key).await {
+        error!("Failed to save SSH deploy key: {:?}", e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    info!("SSH deploy key updated");
+    Ok(Json(SshDeployKeyStatus {
+        configured: true,
+        known_hosts: deploy_key.known_hosts,
+    }))
+}
+
+/// DELETE /api/admin/ssh-deploy-key
+async fn delete_ssh_deploy_key(
+    _admin_user: AdminUser,
+    State(app_state): State<AppState>,
+) -> Result<StatusCode, StatusCode> {
+    let settings_repository = SearchSettingsRepository::new(app_state.database.pool().clone());
+    if let Err(e) = settings_repository.delete_ssh_deploy_key().await {
+        error!("Failed to delete SSH deploy key: {:?}", e);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    info!("SSH deploy key removed");
+    Ok(StatusCode::NO_CONTENT)
+}
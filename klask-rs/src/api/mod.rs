@@ -6,6 +6,7 @@ pub mod repositories;
 pub mod scheduler;
 pub mod search;
 pub mod users;
+pub mod webhooks;
 
 use crate::auth::extractors::AppState;
 use anyhow::Result;
@@ -20,7 +21,8 @@ pub async fn create_router() -> Result<Router<AppState>> {
         .nest("/search", search::create_router().await?)
         .nest("/repositories", repositories::create_router().await?)
         .nest("/scheduler", scheduler::create_router().await?)
-        .nest("/users", users::create_router().await?);
+        .nest("/users", users::create_router().await?)
+        .nest("/webhooks", webhooks::create_router().await?);
 
     Ok(router)
 }
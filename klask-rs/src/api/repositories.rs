@@ -1,9 +1,19 @@
-use crate::auth::extractors::{AdminUser, AppState};
-use crate::models::{Repository, RepositoryType};
-use crate::repositories::RepositoryRepository;
+use crate::auth::extractors::{AdminUser, AppState, AuthenticatedUser};
+use crate::models::{LargeFileMode, Repository, RepositoryType};
+use crate::repositories::{RepositoryRepository, SearchSettingsRepository};
+use crate::services::azure_devops::{AzureDevOpsRepository, AzureDevOpsService};
+use crate::services::bitbucket::{BitbucketRepository, BitbucketService};
+use crate::services::crawler::encoding::encoding_for_label;
+use crate::services::crawler::file_processing::FileProcessor;
+use crate::services::crawler::git_operations::GitOperations;
+use crate::services::crawler::large_files::{LargeFilePolicy, MAX_LARGE_FILE_SIZE};
+use crate::services::gitea::{GiteaRepository, GiteaService};
 use crate::services::github::{GitHubRepository, GitHubService};
 use crate::services::gitlab::{GitLabProject, GitLabService};
-use anyhow::Result;
+use crate::services::ssh::{is_ssh_url, validate_known_hosts, validate_private_key};
+use crate::services::svn::{SvnLocation, SvnService};
+use crate::services::{RepositoryTree, TreeQuery};
+use anyhow::{Result, anyhow};
 use axum::{
     Router,
     body::Bytes,
@@ -40,6 +50,7 @@ pub struct CreateRepositoryRequest {
     pub gitlab_excluded_projects: Option<String>,
     pub gitlab_excluded_patterns: Option<String>,
     // GitHub fields
+    pub github_url: Option<String>, // GitHub Enterprise address; github.com when absent
     pub github_namespace: Option<String>,
     pub github_excluded_repositories: Option<String>,
     pub github_excluded_patterns: Option<String>,
@@ -51,6 +62,35 @@ pub struct CreateRepositoryRequest {
     // Project filtering fields
     pub included_projects: Option<String>,
     pub included_projects_patterns: Option<String>,
+    pub excluded_projects: Option<String>,
+    pub excluded_projects_patterns: Option<String>,
+    // File inclusion fields
+    pub included_extensions: Option<String>,
+    pub included_file_names: Option<String>,
+    pub included_path_patterns: Option<String>,
+    pub excluded_path_patterns: Option<String>,
+    // Encoding of files that are not valid UTF-8, e.g. "windows-1252"
+    pub default_encoding: Option<String>,
+    // Index the files inside archives (overrides the global file inclusion rule)
+    pub index_archives: Option<bool>,
+    // Size limit of indexed files in megabytes, and handling of larger files
+    pub max_file_size_mb: Option<i32>,
+    pub large_file_mode: Option<LargeFileMode>,
+    // Bitbucket fields
+    pub bitbucket_project_key: Option<String>,
+    // Gitea fields
+    pub gitea_organization: Option<String>,
+    // SSH fields: private key (encrypted before storage) and pinned known_hosts lines
+    pub ssh_private_key: Option<String>,
+    pub ssh_known_hosts: Option<String>,
+    // Fetch and index the pinned commits of Git submodules
+    pub index_submodules: Option<bool>,
+    // Download text files tracked by Git LFS from the LFS server
+    pub fetch_lfs_objects: Option<bool>,
+    // Index open merge requests / pull requests (GitLab and GitHub)
+    pub index_merge_requests: Option<bool>,
+    // Secret verifying push webhooks (encrypted before storage)
+    pub webhook_secret: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -73,6 +113,7 @@ pub struct UpdateRepositoryRequest {
     pub gitlab_excluded_projects: Option<String>,
     pub gitlab_excluded_patterns: Option<String>,
     // GitHub fields
+    pub github_url: Option<String>, // GitHub Enterprise address; github.com when absent
     pub github_namespace: Option<String>,
     pub github_excluded_repositories: Option<String>,
     pub github_excluded_patterns: Option<String>,
@@ -84,6 +125,35 @@ pub struct UpdateRepositoryRequest {
     // Project filtering fields
     pub included_projects: Option<String>,
     pub included_projects_patterns: Option<String>,
+    pub excluded_projects: Option<String>,
+    pub excluded_projects_patterns: Option<String>,
+    // File inclusion fields
+    pub included_extensions: Option<String>,
+    pub included_file_names: Option<String>,
+    pub included_path_patterns: Option<String>,
+    pub excluded_path_patterns: Option<String>,
+    // Encoding of files that are not valid UTF-8, e.g. "windows-1252"
+    pub default_encoding: Option<String>,
+    // Index the files inside archives (overrides the global file inclusion rule)
+    pub index_archives: Option<bool>,
+    // Size limit of indexed files in megabytes, and handling of larger files
+    pub max_file_size_mb: Option<i32>,
+    pub large_file_mode: Option<LargeFileMode>,
+    // Bitbucket fields
+    pub bitbucket_project_key: Option<String>,
+    // Gitea fields
+    pub gitea_organization: Option<String>,
+    // SSH fields: private key (encrypted before storage) and pinned known_hosts lines
+    pub ssh_private_key: Option<String>,
+    pub ssh_known_hosts: Option<String>,
+    // Fetch and index the pinned commits of Git submodules
+    pub index_submodules: Option<bool>,
+    // Download text files tracked by Git LFS from the LFS server
+    pub fetch_lfs_objects: Option<bool>,
+    // Index open merge requests / pull requests (GitLab and GitHub)
+    pub index_merge_requests: Option<bool>,
+    // Secret verifying push webhooks (encrypted before storage)
+    pub webhook_secret: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -105,6 +175,7 @@ pub struct RepositoriesResponse {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiscoverGitHubRequest {
+    pub github_url: Option<String>, // GitHub Enterprise address; github.com when absent
     pub access_token: String,
     pub namespace: Option<String>,
 }
@@ -112,6 +183,7 @@ pub struct DiscoverGitHubRequest {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestGitHubTokenRequest {
+    pub github_url: Option<String>, // GitHub Enterprise address; github.com when absent
     pub access_token: String,
 }
 
@@ -128,6 +200,12 @@ pub struct TestTokenResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestConnectionResponse {
+    pub success: bool,
+    pub message: String,
+}
+
 // GitLab API request/response structures
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -150,6 +228,84 @@ pub struct DiscoverGitLabResponse {
     pub projects: Vec<GitLabProject>,
 }
 
+// Bitbucket API request/response structures
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverBitbucketRequest {
+    pub bitbucket_url: String,
+    pub access_token: String,
+    pub project_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestBitbucketTokenRequest {
+    pub bitbucket_url: String,
+    pub access_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverBitbucketResponse {
+    pub repositories: Vec<BitbucketRepository>,
+}
+
+// Gitea API request/response structures
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverGiteaRequest {
+    pub gitea_url: String,
+    pub access_token: String,
+    pub organization: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestGiteaTokenRequest {
+    pub gitea_url: String,
+    pub access_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverGiteaResponse {
+    pub repositories: Vec<GiteaRepository>,
+}
+
+// Azure DevOps API request/response structures
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverAzureDevOpsRequest {
+    pub organization_url: String, // https://dev.azure.com/{organization} or a collection URL
+    pub access_token: String,
+    pub project: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestAzureDevOpsTokenRequest {
+    pub organization_url: String,
+    pub access_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverAzureDevOpsResponse {
+    pub repositories: Vec<AzureDevOpsRepository>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TreeRequest {
+    pub version: Option<String>, // Defaults to the repository's configured branch
+    pub path: Option<String>,    // Directory to list, defaults to the root
+    pub project: Option<String>, // Restrict to one project (GitLab/GitHub sub-projects)
+}
+
+/// File size limits must be positive and at most the largest file read by crawls
+fn valid_max_file_size_mb(max_file_size_mb: i32) -> bool {
+    max_file_size_mb > 0 && max_file_size_mb as u64 * 1024 * 1024 <= MAX_LARGE_FILE_SIZE
+}
+
 /// Validates GitHub namespace format
 /// GitHub namespaces (users/organizations) can only contain:
 /// - Alphanumeric characters (a-z, A-Z, 0-9)
@@ -172,6 +328,49 @@ fn validate_github_namespace(namespace: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Validates Bitbucket project key format
+/// Bitbucket project keys start with a letter and contain only letters, digits and underscores
+fn validate_bitbucket_project_key(key: &str) -> Result<(), String> {
+    // Empty key is valid (optional field)
+    if key.is_empty() {
+        return Ok(());
+    }
+
+    let re = Regex::new(r"^[A-Za-z][A-Za-z0-9_]*$").unwrap();
+    if !re.is_match(key) {
+        return Err(
+            "Invalid Bitbucket project key format. Keys start with a letter and contain only letters, digits and underscores."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Validates Gitea/Forgejo organization name format
+/// Organization names contain only alphanumeric characters, hyphens, underscores and dots
+fn validate_gitea_organization(organization: &str) -> Result<(), String> {
+    // Empty organization is valid (optional field)
+    if organization.is_empty() {
+        return Ok(());
+    }
+
+    let re = Regex::new(r"^[a-zA-Z0-9_.-]+$").unwrap();
+    if !re.is_match(organization) {
+        return Err(
+            "Invalid Gitea organization format. Only alphanumeric characters, hyphens, underscores and dots are allowed."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Whether `value` is the plaintext of the encrypted secret already stored on a repository
+fn matches_stored_secret(app_state: &AppState, stored: Option<&String>, value: &str) -> bool {
+    stored.is_some_and(|encrypted| {
+        app_state.encryption_service.decrypt(encrypted).is_ok_and(|decrypted| decrypted == value)
+    })
+}
+
 pub async fn create_router() -> Result<Router<AppState>> {
     let router = Router::new()
         .route("/", get(list_repositories).post(create_repository))
@@ -182,11 +381,18 @@ pub async fn create_router() -> Result<Router<AppState>> {
         .route("/{id}/crawl", post(crawl_repository).delete(stop_crawl_repository))
         .route("/{id}/test", post(test_repository_connection))
         .route("/{id}/stats", get(get_repository_stats))
+        .route("/{id}/tree", get(get_repository_tree))
         .route("/import/gitlab", post(import_gitlab_projects))
         .route("/github/discover", post(discover_github_repositories))
         .route("/github/test-token", post(test_github_token))
         .route("/gitlab/discover", post(discover_gitlab_repositories))
         .route("/gitlab/test-token", post(test_gitlab_token))
+        .route("/bitbucket/discover", post(discover_bitbucket_repositories))
+        .route("/bitbucket/test-token", post(test_bitbucket_token))
+        .route("/gitea/discover", post(discover_gitea_repositories))
+        .route("/gitea/test-token", post(test_gitea_token))
+        .route("/azure-devops/discover", post(discover_azure_devops_repositories))
+        .route("/azure-devops/test-token", post(test_azure_devops_token))
         .route("/bulk/enable", post(bulk_enable_repositories))
         .route("/bulk/disable", post(bulk_disable_repositories))
         .route("/bulk/crawl", post(bulk_crawl_repositories))
@@ -293,6 +499,39 @@ async fn get_repository_stats(
     }
 }
 
+async fn get_repository_tree(
+    _auth: AuthenticatedUser,
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<TreeRequest>,
+) -> Result<Json<RepositoryTree>, StatusCode> {
+    let repo_repository = RepositoryRepository::new(app_state.database.pool().clone());
+
+    let repository = match repo_repository.get_repository(id).await {
+        Ok(Some(repository)) => repository,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("Failed to get repository {}: {}", id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let tree_query = TreeQuery {
+        version: params.version.or(repository.branch).unwrap_or_else(|| "main".to_string()),
+        repository: repository.name,
+        project: params.project,
+        path: params.path.unwrap_or_default(),
+    };
+
+    match app_state.search_service.browse_tree(tree_query).await {
+        Ok(tree) => Ok(Json(tree)),
+        Err(e) => {
+            error!("Failed to browse tree of repository {}: {}", id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 // Helper function to calculate repository disk size
 async fn calculate_repository_disk_size(repository: &Repository) -> Result<f64> {
     match repository.repository_type {
@@ -306,7 +545,13 @@ async fn calculate_repository_disk_size(repository: &Repository) -> Result<f64>
                 Ok(0.0)
             }
         }
-        RepositoryType::Git | RepositoryType::GitLab | RepositoryType::GitHub => {
+        RepositoryType::Git
+        | RepositoryType::GitLab
+        | RepositoryType::GitHub
+        | RepositoryType::Bitbucket
+        | RepositoryType::Gitea
+        | RepositoryType::AzureDevOps
+        | RepositoryType::Svn => {
             // For Git repos, estimate based on .git directory if cloned locally
             // Or use a placeholder calculation
             // In practice, you might want to track this during crawling
@@ -345,121 +590,41 @@ async fn calculate_directory_size(dir: &PathBuf) -> Result<u64> {
 }
 
 // Helper function to get file count for a repository
-async fn get_repository_file_count(repository: &Repository, _app_state: &AppState) -> Result<i64> {
+async fn get_repository_file_count(repository: &Repository, app_state: &AppState) -> Result<i64> {
     // Try to get count from search index if available
     // For now, return estimated count based on repository type
     match repository.repository_type {
         RepositoryType::FileSystem => {
             let path = PathBuf::from(&repository.url);
             if path.exists() && path.is_dir() {
-                count_files_in_directory(&path).await
+                // Count the files the next crawl would index, using the same file inclusion rules
+                let rules = SearchSettingsRepository::new(app_state.database.pool().clone())
+                    .get_file_inclusion_rules()
+                    .await?
+                    .for_repository(repository);
+                let large_files = LargeFilePolicy::for_repository(repository);
+                let files = tokio::task::spawn_blocking(move || {
+                    FileProcessor::collect_indexable_files(&path, &rules, large_files)
+                })
+                .await??;
+                Ok(files.len() as i64)
             } else {
                 Ok(0)
             }
         }
-        RepositoryType::Git | RepositoryType::GitLab | RepositoryType::GitHub => {
+        RepositoryType::Git
+        | RepositoryType::GitLab
+        | RepositoryType::GitHub
+        | RepositoryType::Bitbucket
+        | RepositoryType::Gitea
+        | RepositoryType::AzureDevOps
+        | RepositoryType::Svn => {
             // Could query the search index for files from this project
             Ok(0) // Placeholder
         }
     }
 }
 
-// Helper function to count files in directory
-async fn count_files_in_directory(dir: &PathBuf) -> Result<i64> {
-    use tokio::fs;
-
-    let mut file_count = 0i64;
-    let mut read_dir = fs::read_dir(dir).await?;
-
-    while let Some(entry) = read_dir.next_entry().await? {
-        let metadata = entry.metadata().await?;
-
-        if metadata.is_file() {
-            // Check if it's a supported file type
-            if let Some(extension) = entry.path().extension()
-                && is_supported_extension(extension.to_string_lossy().as_ref())
-            {
-                file_count += 1;
-            }
-        } else if metadata.is_dir() {
-            let dir_name = entry.file_name();
-            let dir_name_str = dir_name.to_string_lossy();
-
-            if !dir_name_str.starts_with('.')
-                && !matches!(dir_name_str.as_ref(), "node_modules" | "target" | "build" | "dist")
-                && let Ok(subdir_count) = Box::pin(count_files_in_directory(&entry.path())).await
-            {
-                file_count += subdir_count;
-            }
-        }
-    }
-
-    Ok(file_count)
-}
-
-// Helper function to check if file extension is supported
-fn is_supported_extension(extension: &str) -> bool {
-    const SUPPORTED_EXTENSIONS: &[&str] = &[
-        "rs",
-        "py",
-        "js",
-        "ts",
-        "java",
-        "c",
-        "cpp",
-        "h",
-        "hpp",
-        "go",
-        "rb",
-        "php",
-        "cs",
-        "swift",
-        "kt",
-        "scala",
-        "clj",
-        "hs",
-        "ml",
-        "fs",
-        "elm",
-        "dart",
-        "vue",
-        "jsx",
-        "tsx",
-        "html",
-        "css",
-        "scss",
-        "less",
-        "sql",
-        "sh",
-        "bash",
-        "zsh",
-        "fish",
-        "ps1",
-        "bat",
-        "cmd",
-        "dockerfile",
-        "yaml",
-        "yml",
-        "json",
-        "toml",
-        "xml",
-        "md",
-        "txt",
-        "cfg",
-        "conf",
-        "ini",
-        "properties",
-        "gradle",
-        "maven",
-        "pom",
-        "sbt",
-        "cmake",
-        "makefile",
-    ];
-
-    SUPPORTED_EXTENSIONS.contains(&extension.to_lowercase().as_str())
-}
-
 // Rest of the repository API functions would be implemented here...
 // (create_repository, update_repository, delete_repository, etc.)
 // For brevity, I'm focusing on the disk size functionality
@@ -528,6 +693,78 @@ async fn create_repository(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    // Validate Bitbucket project key if provided
+    if let Some(ref bitbucket_project_key) = request.bitbucket_project_key
+        && let Err(e) = validate_bitbucket_project_key(bitbucket_project_key)
+    {
+        error!("Invalid Bitbucket project key '{}': {}", bitbucket_project_key, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Validate Gitea organization if provided
+    if let Some(ref gitea_organization) = request.gitea_organization
+        && let Err(e) = validate_gitea_organization(gitea_organization)
+    {
+        error!("Invalid Gitea organization '{}': {}", gitea_organization, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Validate default encoding if provided
+    if let Some(ref default_encoding) = request.default_encoding
+        && !default_encoding.trim().is_empty()
+        && encoding_for_label(default_encoding).is_none()
+    {
+        error!("Unknown default encoding '{}'", default_encoding);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Validate file size limit if provided
+    if let Some(max_file_size_mb) = request.max_file_size_mb
+        && !valid_max_file_size_mb(max_file_size_mb)
+    {
+        error!("Invalid file size limit {} MB", max_file_size_mb);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Validate SSH key and known hosts if provided
+    if let Some(ref ssh_private_key) = request.ssh_private_key
+        && !ssh_private_key.trim().is_empty()
+        && let Err(e) = validate_private_key(ssh_private_key)
+    {
+        error!("Invalid SSH private key: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if let Some(ref ssh_known_hosts) = request.ssh_known_hosts
+        && let Err(e) = validate_known_hosts(ssh_known_hosts)
+    {
+        error!("Invalid SSH known hosts: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Encrypt SSH private key if provided
+    let encrypted_ssh_key = match request.ssh_private_key.as_deref().filter(|key| !key.trim().is_empty()) {
+        Some(ssh_private_key) => match app_state.encryption_service.encrypt(ssh_private_key) {
+            Ok(encrypted) => Some(encrypted),
+            Err(e) => {
+                error!("Failed to encrypt SSH private key: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        },
+        None => None,
+    };
+
+    // Encrypt webhook secret if provided
+    let encrypted_webhook_secret = match request.webhook_secret.as_deref().filter(|secret| !secret.trim().is_empty()) {
+        Some(webhook_secret) => match app_state.encryption_service.encrypt(webhook_secret) {
+            Ok(encrypted) => Some(encrypted),
+            Err(e) => {
+                error!("Failed to encrypt webhook secret: {}", e);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        },
+        None => None,
+    };
+
     // Encrypt access token if provided
     let encrypted_token = if let Some(token) = &request.access_token {
         match app_state.encryption_service.encrypt(token) {
@@ -566,6 +803,7 @@ async fn create_repository(
         last_crawl_duration_seconds: None,
         gitlab_excluded_projects: clean_optional_string(request.gitlab_excluded_projects),
         gitlab_excluded_patterns: clean_optional_string(request.gitlab_excluded_patterns),
+        github_url: clean_optional_string(request.github_url),
         github_namespace: clean_optional_string(request.github_namespace),
         github_excluded_repositories: clean_optional_string(request.github_excluded_repositories),
         github_excluded_patterns: clean_optional_string(request.github_excluded_patterns),
@@ -578,11 +816,31 @@ async fn create_repository(
         excluded_branches_patterns: clean_optional_string(request.excluded_branches_patterns),
         included_projects: clean_optional_string(request.included_projects),
         included_projects_patterns: clean_optional_string(request.included_projects_patterns),
+        excluded_projects: clean_optional_string(request.excluded_projects),
+        excluded_projects_patterns: clean_optional_string(request.excluded_projects_patterns),
+        included_extensions: clean_optional_string(request.included_extensions),
+        included_file_names: clean_optional_string(request.included_file_names),
+        included_path_patterns: clean_optional_string(request.included_path_patterns),
+        excluded_path_patterns: clean_optional_string(request.excluded_path_patterns),
+        default_encoding: clean_optional_string(request.default_encoding),
+        index_archives: request.index_archives,
+        max_file_size_mb: request.max_file_size_mb,
+        large_file_mode: request.large_file_mode,
+        bitbucket_project_key: clean_optional_string(request.bitbucket_project_key),
+        gitea_organization: clean_optional_string(request.gitea_organization),
+        svn_revision: None,
+        ssh_private_key: encrypted_ssh_key,
+        ssh_known_hosts: clean_optional_string(request.ssh_known_hosts),
+        index_submodules: request.index_submodules,
+        fetch_lfs_objects: request.fetch_lfs_objects,
+        index_merge_requests: request.index_merge_requests,
+        webhook_secret: encrypted_webhook_secret,
     };
 
     match repo_repository.create_repository(&repository).await {
         Ok(created_repo) => {
             info!("Created repository: {} ({})", created_repo.name, created_repo.id);
+            app_state.search_service.set_default_branch(&created_repo.name, created_repo.branch.as_deref());
             Ok(Json(created_repo))
         }
         Err(e) => {
@@ -702,6 +960,9 @@ async fn update_repository(
             Some(gitlab_excluded_patterns)
         };
     }
+    if let Some(github_url) = request.github_url {
+        repository.github_url = if github_url.trim().is_empty() { None } else { Some(github_url) };
+    }
     if let Some(github_namespace) = request.github_namespace {
         // Validate GitHub namespace before updating
         if let Err(e) = validate_github_namespace(&github_namespace) {
@@ -759,6 +1020,85 @@ async fn update_repository(
             Some(included_projects_patterns)
         };
     }
+    if let Some(excluded_projects) = request.excluded_projects {
+        repository.excluded_projects = if excluded_projects.trim().is_empty() { None } else { Some(excluded_projects) };
+    }
+    if let Some(excluded_projects_patterns) = request.excluded_projects_patterns {
+        repository.excluded_projects_patterns = if excluded_projects_patterns.trim().is_empty() {
+            None
+        } else {
+            Some(excluded_projects_patterns)
+        };
+    }
+    if let Some(included_extensions) = request.included_extensions {
+        repository.included_extensions = if included_extensions.trim().is_empty() {
+            None
+        } else {
+            Some(included_extensions)
+        };
+    }
+    if let Some(included_file_names) = request.included_file_names {
+        repository.included_file_names = if included_file_names.trim().is_empty() {
+            None
+        } else {
+            Some(included_file_names)
+        };
+    }
+    if let Some(included_path_patterns) = request.included_path_patterns {
+        repository.included_path_patterns = if included_path_patterns.trim().is_empty() {
+            None
+        } else {
+            Some(included_path_patterns)
+        };
+    }
+    if let Some(excluded_path_patterns) = request.excluded_path_patterns {
+        repository.excluded_path_patterns = if excluded_path_patterns.trim().is_empty() {
+            None
+        } else {
+            Some(excluded_path_patterns)
+        };
+    }
+    if let Some(default_encoding) = request.default_encoding {
+        repository.default_encoding = if default_encoding.trim().is_empty() {
+            None
+        } else if encoding_for_label(&default_encoding).is_none() {
+            error!("Unknown default encoding '{}'", default_encoding);
+            return Err(StatusCode::BAD_REQUEST);
+        } else {
+            Some(default_encoding)
+        };
+    }
+    if let Some(index_archives) = request.index_archives {
+        repository.index_archives = Some(index_archives);
+    }
+    if let Some(max_file_size_mb) = request.max_file_size_mb {
+        if !valid_max_file_size_mb(max_file_size_mb) {
+            error!("Invalid file size limit {} MB", max_file_size_mb);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        repository.max_file_size_mb = Some(max_file_size_mb);
+    }
+    if let Some(large_file_mode) = request.large_file_mode {
+        repository.large_file_mode = Some(large_file_mode);
+    }
+    if let Some(bitbucket_project_key) = request.bitbucket_project_key {
+        if let Err(e) = validate_bitbucket_project_key(&bitbucket_project_key) {
+            error!("Invalid Bitbucket project key '{}': {}", bitbucket_project_key, e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        repository.bitbucket_project_key = if bitbucket_project_key.is_empty() {
+            None
+        } else {
+            Some(bitbucket_project_key)
+        };
+    }
+    if let Some(gitea_organization) = request.gitea_organization {
+        if let Err(e) = validate_gitea_organization(&gitea_organization) {
+            error!("Invalid Gitea organization '{}': {}", gitea_organization, e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        repository.gitea_organization = if gitea_organization.is_empty() { None } else { Some(gitea_organization) };
+    }
 
     // Handle access token update with encryption
     if let Some(access_token) = request.access_token {
@@ -779,10 +1119,70 @@ async fn update_repository(
         }
     }
 
+    // Handle SSH key update with encryption; an empty key removes it
+    if let Some(ssh_private_key) = request.ssh_private_key {
+        if ssh_private_key.trim().is_empty() {
+            repository.ssh_private_key = None;
+        } else if !matches_stored_secret(&app_state, repository.ssh_private_key.as_ref(), &ssh_private_key) {
+            if let Err(e) = validate_private_key(&ssh_private_key) {
+                error!("Invalid SSH private key: {}", e);
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            repository.ssh_private_key = match app_state.encryption_service.encrypt(&ssh_private_key) {
+                Ok(encrypted) => Some(encrypted),
+                Err(e) => {
+                    error!("Failed to encrypt SSH private key: {}", e);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            };
+            info!("SSH private key updated and encrypted");
+        }
+    }
+    if let Some(ssh_known_hosts) = request.ssh_known_hosts {
+        if let Err(e) = validate_known_hosts(&ssh_known_hosts) {
+            error!("Invalid SSH known hosts: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        repository.ssh_known_hosts = if ssh_known_hosts.trim().is_empty() { None } else { Some(ssh_known_hosts) };
+    }
+
+    if let Some(index_submodules) = request.index_submodules {
+        repository.index_submodules = Some(index_submodules);
+    }
+
+    if let Some(fetch_lfs_objects) = request.fetch_lfs_objects {
+        repository.fetch_lfs_objects = Some(fetch_lfs_objects);
+    }
+
+    if let Some(index_merge_requests) = request.index_merge_requests {
+        repository.index_merge_requests = Some(index_merge_requests);
+    }
+
+    // Handle webhook secret update with encryption; an empty secret removes it
+    if let Some(webhook_secret) = request.webhook_secret {
+        if webhook_secret.trim().is_empty() {
+            repository.webhook_secret = None;
+        } else if !matches_stored_secret(&app_state, repository.webhook_secret.as_ref(), &webhook_secret) {
+            repository.webhook_secret = match app_state.encryption_service.encrypt(&webhook_secret) {
+                Ok(encrypted) => Some(encrypted),
+                Err(e) => {
+                    error!("Failed to encrypt webhook secret: {}", e);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            };
+            info!("Webhook secret updated and encrypted");
+        }
+    }
+
     repository.updated_at = Utc::now();
 
     match repo_repository.update_repository(id, &repository).await {
         Ok(updated_repo) => {
+            if name_changed {
+                app_state.search_service.set_default_branch(&old_name, None);
+            }
+            app_state.search_service.set_default_branch(&updated_repo.name, updated_repo.branch.as_deref());
+
             // If repository name was changed, update search index
             if name_changed {
                 match app_state.search_service.update_project_name(&old_name, &updated_repo.name).await {
@@ -975,11 +1375,123 @@ async fn stop_crawl_repository(
 
 async fn test_repository_connection(
     _user: AdminUser,
-    State(_app_state): State<AppState>,
-    Path(_id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    // Implementation would go here
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TestConnectionResponse>, StatusCode> {
+    let repo_repository = RepositoryRepository::new(app_state.database.pool().clone());
+
+    let repository = match repo_repository.get_repository(id).await {
+        Ok(Some(repository)) => repository,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("Failed to get repository {}: {}", id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    info!(
+        "Testing connection to repository: {} ({})",
+        repository.name, repository.url
+    );
+
+    match check_repository_connection(&repository, &app_state).await {
+        Ok(message) => {
+            info!("{}", message);
+            Ok(Json(TestConnectionResponse { success: true, message }))
+        }
+        Err(e) => {
+            warn!("Connection test failed for repository {}: {}", repository.name, e);
+            Ok(Json(TestConnectionResponse { success: false, message: e.to_string() }))
+        }
+    }
+}
+
+/// Reach a repository with its credentials, returning what was found
+async fn check_repository_connection(repository: &Repository, app_state: &AppState) -> Result<String> {
+    let access_token = match &repository.access_token {
+        Some(encrypted_token) => Some(
+            app_state
+                .encryption_service
+                .decrypt(encrypted_token)
+                .map_err(|e| anyhow!("Failed to decrypt access token: {}", e))?,
+        ),
+        None => None,
+    };
+    let required_token = || access_token.as_deref().ok_or_else(|| anyhow!("No access token configured"));
+
+    let (provider, valid) = match repository.repository_type {
+        RepositoryType::Git => {
+            let deploy_key =
+                SearchSettingsRepository::new(app_state.database.pool().clone()).get_ssh_deploy_key().await?;
+            let git_operations = GitOperations::new(
+                app_state.encryption_service.clone(),
+                std::sync::Arc::new(std::sync::RwLock::new(deploy_key)),
+            );
+            let has_ssh_key = git_operations.ssh_credentials(repository)?.is_some_and(|c| c.private_key.is_some());
+            let authentication = match has_ssh_key {
+                true => " with SSH key",
+                false if is_ssh_url(&repository.url) => " over SSH without a configured key",
+                false if access_token.is_some() => " with access token",
+                false => "",
+            };
+            let references = git_operations.test_connection(repository).await?;
+            return Ok(format!("Connected{}, {} references found", authentication, references));
+        }
+        RepositoryType::FileSystem => {
+            let path = PathBuf::from(&repository.url);
+            return match std::fs::read_dir(&path) {
+                Ok(_) => Ok(format!("Directory {} is readable", path.display())),
+                Err(e) => Err(anyhow!("Directory {} is not readable: {}", path.display(), e)),
+            };
+        }
+        RepositoryType::Svn => {
+            return match SvnLocation::parse(&repository.url)? {
+                SvnLocation::Http(url) => {
+                    let entry = SvnService::new().stat(&url, access_token.as_deref()).await?;
+                    Ok(format!(
+                        "Connected to Subversion repository at revision {}",
+                        entry.revision
+                    ))
+                }
+                SvnLocation::Local(path) => Ok(format!("Local Subversion repository {} found", path.display())),
+                SvnLocation::Dump(path) if path.is_file() => Ok(format!("Subversion dump {} found", path.display())),
+                SvnLocation::Dump(path) => Err(anyhow!("Subversion dump {} not found", path.display())),
+            };
+        }
+        RepositoryType::GitHub => (
+            "GitHub",
+            GitHubService::for_repository(repository).test_token(required_token()?).await?,
+        ),
+        RepositoryType::GitLab => {
+            let gitlab_url = if repository.url.is_empty() || repository.url == "placeholder" {
+                "https://gitlab.com"
+            } else {
+                repository.url.as_str()
+            };
+            (
+                "GitLab",
+                GitLabService::new().test_token(gitlab_url, required_token()?).await?,
+            )
+        }
+        RepositoryType::Bitbucket => (
+            "Bitbucket",
+            BitbucketService::new().test_token(&repository.url, required_token()?).await?,
+        ),
+        RepositoryType::Gitea => (
+            "Gitea",
+            GiteaService::new().test_token(&repository.url, required_token()?).await?,
+        ),
+        RepositoryType::AzureDevOps => (
+            "Azure DevOps",
+            AzureDevOpsService::new().test_token(&repository.url, required_token()?).await?,
+        ),
+    };
+
+    if valid {
+        Ok(format!("{} token is valid", provider))
+    } else {
+        Err(anyhow!("{} token is invalid or expired", provider))
+    }
 }
 
 async fn import_gitlab_projects(
@@ -1010,7 +1522,7 @@ async fn discover_github_repositories(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    let github_service = GitHubService::new();
+    let github_service = GitHubService::new().with_api_url(request.github_url.as_deref().unwrap_or_default());
 
     match github_service.discover_repositories(&request.access_token, request.namespace.as_deref()).await {
         Ok(repositories) => {
@@ -1032,7 +1544,7 @@ async fn test_github_token(
 ) -> Result<Json<TestTokenResponse>, StatusCode> {
     info!("Testing GitHub token");
 
-    let github_service = GitHubService::new();
+    let github_service = GitHubService::new().with_api_url(request.github_url.as_deref().unwrap_or_default());
 
     match github_service.test_token(&request.access_token).await {
         Ok(valid) => {
@@ -1112,6 +1624,211 @@ async fn test_gitlab_token(
     }
 }
 
+// Bitbucket discovery endpoint
+async fn discover_bitbucket_repositories(
+    _user: AdminUser,
+    State(_app_state): State<AppState>,
+    Json(request): Json<DiscoverBitbucketRequest>,
+) -> Result<Json<DiscoverBitbucketResponse>, StatusCode> {
+    info!(
+        "Discovering Bitbucket repositories from {} with project key: {:?}",
+        request.bitbucket_url, request.project_key
+    );
+
+    // Validate Bitbucket project key if provided
+    if let Some(ref project_key) = request.project_key
+        && let Err(e) = validate_bitbucket_project_key(project_key)
+    {
+        error!("Invalid Bitbucket project key '{}': {}", project_key, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let bitbucket_service = BitbucketService::new();
+
+    match bitbucket_service
+        .discover_repositories(
+            &request.bitbucket_url,
+            &request.access_token,
+            request.project_key.as_deref(),
+        )
+        .await
+    {
+        Ok(repositories) => {
+            info!("Successfully discovered {} Bitbucket repositories", repositories.len());
+            Ok(Json(DiscoverBitbucketResponse { repositories }))
+        }
+        Err(e) => {
+            error!("Failed to discover Bitbucket repositories: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Bitbucket token test endpoint
+async fn test_bitbucket_token(
+    _user: AdminUser,
+    State(_app_state): State<AppState>,
+    Json(request): Json<TestBitbucketTokenRequest>,
+) -> Result<Json<TestTokenResponse>, StatusCode> {
+    info!("Testing Bitbucket token for URL: {}", request.bitbucket_url);
+
+    let bitbucket_service = BitbucketService::new();
+
+    match bitbucket_service.test_token(&request.bitbucket_url, &request.access_token).await {
+        Ok(valid) => {
+            let message = if valid {
+                "Bitbucket token is valid".to_string()
+            } else {
+                "Bitbucket token is invalid".to_string()
+            };
+            info!("{}", message);
+            Ok(Json(TestTokenResponse { valid, message }))
+        }
+        Err(e) => {
+            error!("Failed to test Bitbucket token: {}", e);
+            Ok(Json(TestTokenResponse {
+                valid: false,
+                message: format!("Error testing token: {}", e),
+            }))
+        }
+    }
+}
+
+// Gitea discovery endpoint
+async fn discover_gitea_repositories(
+    _user: AdminUser,
+    State(_app_state): State<AppState>,
+    Json(request): Json<DiscoverGiteaRequest>,
+) -> Result<Json<DiscoverGiteaResponse>, StatusCode> {
+    info!(
+        "Discovering Gitea repositories from {} with organization: {:?}",
+        request.gitea_url, request.organization
+    );
+
+    // Validate Gitea organization if provided
+    if let Some(ref organization) = request.organization
+        && let Err(e) = validate_gitea_organization(organization)
+    {
+        error!("Invalid Gitea organization '{}': {}", organization, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let gitea_service = GiteaService::new();
+
+    match gitea_service
+        .discover_repositories(
+            &request.gitea_url,
+            &request.access_token,
+            request.organization.as_deref(),
+        )
+        .await
+    {
+        Ok(repositories) => {
+            info!("Successfully discovered {} Gitea repositories", repositories.len());
+            Ok(Json(DiscoverGiteaResponse { repositories }))
+        }
+        Err(e) => {
+            error!("Failed to discover Gitea repositories: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Gitea token test endpoint
+async fn test_gitea_token(
+    _user: AdminUser,
+    State(_app_state): State<AppState>,
+    Json(request): Json<TestGiteaTokenRequest>,
+) -> Result<Json<TestTokenResponse>, StatusCode> {
+    info!("Testing Gitea token for URL: {}", request.gitea_url);
+
+    let gitea_service = GiteaService::new();
+
+    match gitea_service.test_token(&request.gitea_url, &request.access_token).await {
+        Ok(valid) => {
+            let message = if valid {
+                "Gitea token is valid".to_string()
+            } else {
+                "Gitea token is invalid".to_string()
+            };
+            info!("{}", message);
+            Ok(Json(TestTokenResponse { valid, message }))
+        }
+        Err(e) => {
+            error!("Failed to test Gitea token: {}", e);
+            Ok(Json(TestTokenResponse {
+                valid: false,
+                message: format!("Error testing token: {}", e),
+            }))
+        }
+    }
+}
+
+// Azure DevOps discovery endpoint
+async fn discover_azure_devops_repositories(
+    _user: AdminUser,
+    State(_app_state): State<AppState>,
+    Json(request): Json<DiscoverAzureDevOpsRequest>,
+) -> Result<Json<DiscoverAzureDevOpsResponse>, StatusCode> {
+    info!(
+        "Discovering Azure DevOps repositories from {} with project: {:?}",
+        request.organization_url, request.project
+    );
+
+    let azure_devops_service = AzureDevOpsService::new();
+
+    match azure_devops_service
+        .discover_repositories(
+            &request.organization_url,
+            &request.access_token,
+            request.project.as_deref(),
+        )
+        .await
+    {
+        Ok(repositories) => {
+            info!(
+                "Successfully discovered {} Azure DevOps repositories",
+                repositories.len()
+            );
+            Ok(Json(DiscoverAzureDevOpsResponse { repositories }))
+        }
+        Err(e) => {
+            error!("Failed to discover Azure DevOps repositories: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Azure DevOps token test endpoint
+async fn test_azure_devops_token(
+    _user: AdminUser,
+    State(_app_state): State<AppState>,
+    Json(request): Json<TestAzureDevOpsTokenRequest>,
+) -> Result<Json<TestTokenResponse>, StatusCode> {
+    info!("Testing Azure DevOps token for URL: {}", request.organization_url);
+
+    let azure_devops_service = AzureDevOpsService::new();
+
+    match azure_devops_service.test_token(&request.organization_url, &request.access_token).await {
+        Ok(valid) => {
+            let message = if valid {
+                "Azure DevOps token is valid".to_string()
+            } else {
+                "Azure DevOps token is invalid".to_string()
+            };
+            info!("{}", message);
+            Ok(Json(TestTokenResponse { valid, message }))
+        }
+        Err(e) => {
+            error!("Failed to test Azure DevOps token: {}", e);
+            Ok(Json(TestTokenResponse {
+                valid: false,
+                message: format!("Error testing token: {}", e),
+            }))
+        }
+    }
+}
+
 async fn bulk_enable_repositories(
     _user: AdminUser,
     State(_app_state): State<AppState>,
@@ -1,16 +1,20 @@
 use crate::auth::extractors::{AppState, AuthenticatedUser};
-use crate::services::SearchQuery;
+use crate::services::crawler::archive::containing_archive;
+use crate::services::{HighlightRange, SearchQuery, SimilarQuery, SortField, SortOrder};
 use anyhow::Result;
 use axum::{
     Router,
+    body::{Body, Bytes},
     extract::{Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{StatusCode, header},
+    response::{Json, Response},
     routing::get,
 };
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 const MAX_FILTER_LENGTH: usize = 1000; // Maximum length for filter parameters
+const EXPORT_PAGE_SIZE: usize = 500; // Matches fetched per search when streaming an export
 
 /// Validates filter parameters for search endpoints.
 ///
@@ -62,13 +66,22 @@ pub struct SearchRequest {
     pub projects: Option<String>,
     pub versions: Option<String>,
     pub extensions: Option<String>,
+    pub languages: Option<String>, // Language ids or aliases, e.g. "rust,cpp" (`lang:` in the query also works)
     // Size filters in bytes
     pub min_size: Option<u64>,
     pub max_size: Option<u64>,
+    // Directory prefix, e.g. "src/api" (includes subdirectories)
+    pub path: Option<String>,
+    // Sorting: score (default), path, repository, size or date; order asc or desc
+    pub sort_by: Option<SortField>,
+    pub sort_order: Option<SortOrder>,
+    // Cursor from a previous response's next_cursor; replaces page-based pagination
+    pub search_after: Option<String>,
     pub include_facets: Option<bool>,
     pub fuzzy_search: Option<bool>, // Enable fuzzy search (1 char edit distance) - default: false
     pub regex_search: Option<bool>, // Enable regex search (pattern matching) - default: false
     pub regex_flags: Option<String>, // Regex flags: "i" (case-insensitive), "m" (multiline), "s" (dotall), or combinations like "ims"
+    pub include_generated: Option<bool>, // Include generated files (lockfiles, minified assets...) - default: false
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -80,9 +93,68 @@ pub struct FacetsRequest {
     pub projects: Option<String>,
     pub versions: Option<String>,
     pub extensions: Option<String>,
+    pub languages: Option<String>,
     // Size filters in bytes
     pub min_size: Option<u64>,
     pub max_size: Option<u64>,
+    // Directory prefix, e.g. "src/api" (includes subdirectories)
+    pub path: Option<String>,
+    pub include_generated: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportRequest {
+    pub q: Option<String>,
+    pub query: Option<String>,
+    // Same filters and sorting as /api/search
+    pub repositories: Option<String>,
+    pub projects: Option<String>,
+    pub versions: Option<String>,
+    pub extensions: Option<String>,
+    pub languages: Option<String>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub path: Option<String>,
+    pub sort_by: Option<SortField>,
+    pub sort_order: Option<SortOrder>,
+    pub fuzzy_search: Option<bool>,
+    pub regex_search: Option<bool>,
+    pub regex_flags: Option<String>,
+    pub include_generated: Option<bool>,
+    pub format: Option<ExportFormat>, // ndjson (default) or csv
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Ndjson,
+    Csv,
+}
+
+/// One exported match
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportRow {
+    pub repository: String,
+    pub version: String,
+    pub path: String,
+    pub line: Option<u32>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarRequest {
+    // Source document, as returned by /api/search or /api/files
+    pub file_id: Option<Uuid>,
+    pub doc_address: Option<String>,
+    pub limit: Option<u32>,
+    pub exclude_same_path: Option<bool>, // Skip the same path on other branches of the same project - default: true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarResponse {
+    pub results: Vec<SearchResult>,
+    pub total: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -92,6 +164,7 @@ pub struct SearchResponse {
     pub page: u32,
     pub limit: u32,
     pub facets: Option<SearchFacets>,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -100,7 +173,10 @@ pub struct SearchFacets {
     pub projects: Vec<FacetValue>,
     pub versions: Vec<FacetValue>,
     pub extensions: Vec<FacetValue>,
+    pub languages: Vec<FacetValue>,
     pub size_ranges: Vec<FacetValue>,
+    pub directories: Vec<FacetValue>, // Direct subdirectories of the requested path
+    pub generated: u64,               // Matches in generated files, even when they are excluded
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,6 +185,25 @@ pub struct FacetValue {
     pub count: u64,
 }
 
+impl From<crate::services::SearchFacets> for SearchFacets {
+    fn from(facets: crate::services::SearchFacets) -> Self {
+        let to_values = |values: Vec<(String, u64)>| -> Vec<FacetValue> {
+            values.into_iter().map(|(value, count)| FacetValue { value, count }).collect()
+        };
+
+        Self {
+            repositories: to_values(facets.repositories),
+            projects: to_values(facets.projects),
+            versions: to_values(facets.versions),
+            extensions: to_values(facets.extensions),
+            languages: to_values(facets.languages),
+            size_ranges: to_values(facets.size_ranges),
+            directories: to_values(facets.directories),
+            generated: facets.generated,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResult {
     pub id: String,
@@ -116,16 +211,60 @@ pub struct SearchResult {
     pub doc_address: String,
     pub name: String,
     pub path: String,
-    pub content_snippet: String,
+    pub archive_path: Option<String>, // Archive containing the file, for files indexed from inside archives
+    pub content_snippet: String,      // Plain text, never HTML
+    pub highlights: Vec<HighlightRange>,
+    pub fragment_start_line: Option<u32>,
     pub project: String,
     pub version: String,
     pub extension: String,
+    pub language: String,
+    pub is_generated: bool,
     pub score: f32,
     pub line_number: Option<u32>,
+    pub source_location: Option<String>, // Notebook cell or document page of the match, for extracted documents
+    pub line_offset: u32,                // Lines of the file before this chunk, for large files indexed in chunks
+    pub is_truncated: bool,              // Only the beginning of a large file is indexed
+    pub is_lfs: bool,                    // Tracked by Git LFS, the content is only indexed when fetched
+    pub size: u64,
+    pub last_modified: Option<i64>, // Unix timestamp in seconds
+}
+
+impl From<crate::services::SearchResult> for SearchResult {
+    fn from(r: crate::services::SearchResult) -> Self {
+        SearchResult {
+            id: r.file_id.to_string(),
+            file_id: r.file_id.to_string(),
+            doc_address: r.doc_address,
+            name: r.file_name,
+            archive_path: containing_archive(&r.file_path).map(str::to_string),
+            path: r.file_path,
+            content_snippet: r.content_snippet,
+            highlights: r.highlights,
+            fragment_start_line: r.fragment_start_line,
+            project: r.project,
+            version: r.version,
+            extension: r.extension,
+            language: r.language,
+            is_generated: r.is_generated,
+            score: r.score,
+            line_number: r.line_number,
+            source_location: r.source_location,
+            line_offset: r.line_offset,
+            is_truncated: r.is_truncated,
+            is_lfs: r.is_lfs,
+            size: r.size,
+            last_modified: r.last_modified,
+        }
+    }
 }
 
 pub async fn create_router() -> Result<Router<AppState>> {
-    let router = Router::new().route("/", get(search_files)).route("/facets", get(get_facets_with_filters));
+    let router = Router::new()
+        .route("/", get(search_files))
+        .route("/facets", get(get_facets_with_filters))
+        .route("/similar", get(find_similar_files))
+        .route("/export", get(export_search_results));
 
     Ok(router)
 }
@@ -139,7 +278,8 @@ async fn search_files(
 
     let page = params.page.unwrap_or(1);
     let limit = params.limit.unwrap_or(50).min(1000); // Cap at 1000 results max
-    let offset = (page - 1) * limit;
+    // A search_after cursor already points past the previous pages
+    let offset = if params.search_after.is_some() { 0 } else { (page - 1) * limit };
 
     // Get search query from either 'q' or 'query' parameter
     let query_string = params.q.or(params.query).ok_or(StatusCode::BAD_REQUEST)?;
@@ -159,67 +299,43 @@ async fn search_files(
         project_filter: params.projects,
         version_filter: params.versions,
         extension_filter: params.extensions,
+        language_filter: params.languages,
         min_size: params.min_size,
         max_size: params.max_size,
+        path_filter: params.path,
+        sort_by: params.sort_by,
+        sort_order: params.sort_order,
+        search_after: params.search_after,
         limit: limit as usize,
         offset: offset as usize,
         include_facets: params.include_facets.unwrap_or(false),
         fuzzy_search: params.fuzzy_search.unwrap_or(false),
         regex_search: params.regex_search.unwrap_or(false),
         regex_flags: params.regex_flags,
+        include_generated: params.include_generated.unwrap_or(false),
     };
 
+    if let Err(e) = search_query.validate_cursor() {
+        tracing::warn!("Invalid search cursor: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     // Perform search using Tantivy
     match app_state.search_service.search(search_query).await {
         Ok(search_response) => {
-            let results: Vec<SearchResult> = search_response
-                .results
-                .into_iter()
-                .map(|r| SearchResult {
-                    id: r.file_id.to_string(),
-                    file_id: r.file_id.to_string(),
-                    doc_address: r.doc_address,
-                    name: r.file_name,
-                    path: r.file_path,
-                    content_snippet: r.content_snippet,
-                    project: r.project,
-                    version: r.version,
-                    extension: r.extension,
-                    score: r.score,
-                    line_number: r.line_number,
-                })
-                .collect();
+            let results: Vec<SearchResult> = search_response.results.into_iter().map(SearchResult::from).collect();
 
             // Convert facets to API format if present
-            let facets = search_response.facets.map(|service_facets| SearchFacets {
-                repositories: service_facets
-                    .repositories
-                    .into_iter()
-                    .map(|(value, count)| FacetValue { value, count })
-                    .collect(),
-                projects: service_facets
-                    .projects
-                    .into_iter()
-                    .map(|(value, count)| FacetValue { value, count })
-                    .collect(),
-                versions: service_facets
-                    .versions
-                    .into_iter()
-                    .map(|(value, count)| FacetValue { value, count })
-                    .collect(),
-                extensions: service_facets
-                    .extensions
-                    .into_iter()
-                    .map(|(value, count)| FacetValue { value, count })
-                    .collect(),
-                size_ranges: service_facets
-                    .size_ranges
-                    .into_iter()
-                    .map(|(value, count)| FacetValue { value, count })
-                    .collect(),
-            });
+            let facets = search_response.facets.map(SearchFacets::from);
 
-            let response = SearchResponse { total: search_response.total, results, page, limit, facets };
+            let response = SearchResponse {
+                total: search_response.total,
+                results,
+                page,
+                limit,
+                facets,
+                next_cursor: search_response.next_cursor,
+            };
 
             Ok(Json(response))
         }
@@ -266,6 +382,13 @@ async fn get_facets_with_filters(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    if let Some(ref languages) = params.languages
+        && let Err(e) = validate_filter_param("languages", languages)
+    {
+        tracing::warn!("Invalid filter parameter - languages: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     // Build search query with optional search query and filters
     // If no query provided, use "*" to match all documents
     // NOTE: If query parsing fails (e.g., invalid QueryParser syntax like "string[abc]"),
@@ -283,55 +406,35 @@ async fn get_facets_with_filters(
         project_filter: params.projects,
         version_filter: params.versions,
         extension_filter: params.extensions,
+        language_filter: params.languages,
         min_size: params.min_size,
         max_size: params.max_size,
+        path_filter: params.path,
+        sort_by: None,
+        sort_order: None,
+        search_after: None,
         limit: 0, // We only need facets, not results
         offset: 0,
         include_facets: true, // Always include facets for this endpoint
         fuzzy_search: false,  // Facets request doesn't use fuzzy search
         regex_search: false,  // Facets request doesn't use regex search
         regex_flags: None,    // Facets request doesn't use regex flags
+        include_generated: params.include_generated.unwrap_or(false),
     };
 
     // Perform search using Tantivy
     match app_state.search_service.search(search_query).await {
         Ok(search_response) => {
-            let facets = search_response
-                .facets
-                .map(|service_facets| SearchFacets {
-                    repositories: service_facets
-                        .repositories
-                        .into_iter()
-                        .map(|(value, count)| FacetValue { value, count })
-                        .collect(),
-                    projects: service_facets
-                        .projects
-                        .into_iter()
-                        .map(|(value, count)| FacetValue { value, count })
-                        .collect(),
-                    versions: service_facets
-                        .versions
-                        .into_iter()
-                        .map(|(value, count)| FacetValue { value, count })
-                        .collect(),
-                    extensions: service_facets
-                        .extensions
-                        .into_iter()
-                        .map(|(value, count)| FacetValue { value, count })
-                        .collect(),
-                    size_ranges: service_facets
-                        .size_ranges
-                        .into_iter()
-                        .map(|(value, count)| FacetValue { value, count })
-                        .collect(),
-                })
-                .unwrap_or_else(|| SearchFacets {
-                    repositories: vec![],
-                    projects: vec![],
-                    versions: vec![],
-                    extensions: vec![],
-                    size_ranges: vec![],
-                });
+            let facets = search_response.facets.map(SearchFacets::from).unwrap_or_else(|| SearchFacets {
+                repositories: vec![],
+                projects: vec![],
+                versions: vec![],
+                extensions: vec![],
+                languages: vec![],
+                size_ranges: vec![],
+                directories: vec![],
+                generated: 0,
+            });
 
             Ok(Json(facets))
         }
@@ -342,6 +445,163 @@ async fn get_facets_with_filters(
     }
 }
 
+async fn find_similar_files(
+    _auth: AuthenticatedUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<SimilarRequest>,
+) -> Result<Json<SimilarResponse>, StatusCode> {
+    tracing::debug!("Similar files request params: {:?}", params);
+
+    if params.file_id.is_none() && params.doc_address.is_none() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let similar_query = SimilarQuery {
+        file_id: params.file_id,
+        doc_address: params.doc_address,
+        exclude_same_path: params.exclude_same_path.unwrap_or(true),
+        limit: params.limit.unwrap_or(20).min(100) as usize, // Cap at 100 similar files
+    };
+
+    match app_state.search_service.find_similar(similar_query).await {
+        Ok(Some(similar)) => Ok(Json(SimilarResponse {
+            results: similar.results.into_iter().map(SearchResult::from).collect(),
+            total: similar.total,
+        })),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Similar files search failed: {}", e);
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// Stream every match of a search as NDJSON or CSV.
+///
+/// Pages are fetched with the search_after cursor and written as they arrive, so memory use does not
+/// grow with the number of matches. Stops early if the client disconnects.
+async fn export_search_results(
+    _auth: AuthenticatedUser,
+    State(app_state): State<AppState>,
+    Query(params): Query<ExportRequest>,
+) -> Result<Response, StatusCode> {
+    tracing::debug!("Search export request params: {:?}", params);
+
+    let query_string = params.q.or(params.query).ok_or(StatusCode::BAD_REQUEST)?;
+
+    if params.regex_search.unwrap_or(false)
+        && let Err(e) = crate::api::regex_validator::validate_regex_pattern(&query_string)
+    {
+        tracing::warn!("Invalid regex pattern attempted: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut search_query = SearchQuery {
+        query: query_string,
+        repository_filter: params.repositories,
+        project_filter: params.projects,
+        version_filter: params.versions,
+        extension_filter: params.extensions,
+        language_filter: params.languages,
+        min_size: params.min_size,
+        max_size: params.max_size,
+        path_filter: params.path,
+        sort_by: params.sort_by,
+        sort_order: params.sort_order,
+        search_after: None,
+        limit: EXPORT_PAGE_SIZE,
+        offset: 0,
+        include_facets: false,
+        fuzzy_search: params.fuzzy_search.unwrap_or(false),
+        regex_search: params.regex_search.unwrap_or(false),
+        regex_flags: params.regex_flags,
+        include_generated: params.include_generated.unwrap_or(false),
+    };
+    let format = params.format.unwrap_or_default();
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<Bytes, std::io::Error>>(4);
+    let search_service = app_state.search_service.clone();
+
+    tokio::spawn(async move {
+        if format == ExportFormat::Csv
+            && tx.send(Ok(Bytes::from("repository,version,path,line,snippet\n"))).await.is_err()
+        {
+            return;
+        }
+
+        loop {
+            let page = match search_service.search(search_query.clone()).await {
+                Ok(page) => page,
+                Err(e) => {
+                    tracing::error!("Search export failed: {}", e);
+                    let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+                    return;
+                }
+            };
+
+            let mut chunk = String::new();
+            for result in page.results {
+                let row = ExportRow {
+                    snippet: result.content_snippet,
+                    repository: result.repository,
+                    version: result.version,
+                    path: result.file_path,
+                    line: result.line_number,
+                };
+                match format {
+                    ExportFormat::Ndjson => {
+                        if let Ok(line) = serde_json::to_string(&row) {
+                            chunk.push_str(&line);
+                            chunk.push('\n');
+                        }
+                    }
+                    ExportFormat::Csv => {
+                        let line = row.line.map(|l| l.to_string()).unwrap_or_default();
+                        let fields = [&row.repository, &row.version, &row.path, &line, &row.snippet];
+                        let escaped: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                        chunk.push_str(&escaped.join(","));
+                        chunk.push('\n');
+                    }
+                }
+            }
+
+            if !chunk.is_empty() && tx.send(Ok(Bytes::from(chunk))).await.is_err() {
+                tracing::debug!("Search export client disconnected");
+                return;
+            }
+
+            match page.next_cursor {
+                Some(cursor) => search_query.search_after = Some(cursor),
+                None => return,
+            }
+        }
+    });
+
+    let stream = futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|chunk| (chunk, rx)) });
+    let (content_type, file_name) = match format {
+        ExportFormat::Ndjson => ("application/x-ndjson", "search-export.ndjson"),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "search-export.csv"),
+    };
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", file_name),
+        )
+        .body(Body::from_stream(stream))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 #[cfg(test)]
 mod validation_tests {
     use super::*;
@@ -428,3 +688,16 @@ mod validation_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("src/main.rs"), "src/main.rs");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line1\nline2"), "\"line1\nline2\"");
+    }
+}
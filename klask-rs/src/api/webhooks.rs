@@ -0,0 +1,91 @@
+use crate::auth::extractors::{AdminUser, AppState};
+use crate::services::webhooks::{DeliveryStatus, WebhookDelivery, WebhookProvider, WebhookRequest};
+use anyhow::Result;
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+    routing::{get, post},
+};
+
+pub async fn create_router() -> Result<Router<AppState>> {
+    let router = Router::new()
+        .route("/gitlab", post(gitlab_webhook))
+        .route("/github", post(github_webhook))
+        .route("/generic", post(generic_webhook))
+        .route("/deliveries", get(list_deliveries));
+
+    Ok(router)
+}
+
+/// GitLab push webhook, authenticated by the `X-Gitlab-Token` header
+async fn gitlab_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<WebhookDelivery>) {
+    let request = WebhookRequest {
+        provider: WebhookProvider::GitLab,
+        delivery_id: header(&headers, "X-Gitlab-Event-UUID"),
+        event: header(&headers, "X-Gitlab-Event"),
+        credential: header(&headers, "X-Gitlab-Token"),
+    };
+    handle(&app_state, request, &body).await
+}
+
+/// GitHub push webhook, authenticated by the `X-Hub-Signature-256` HMAC signature
+async fn github_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<WebhookDelivery>) {
+    let request = WebhookRequest {
+        provider: WebhookProvider::GitHub,
+        delivery_id: header(&headers, "X-GitHub-Delivery"),
+        event: header(&headers, "X-GitHub-Event"),
+        credential: header(&headers, "X-Hub-Signature-256"),
+    };
+    handle(&app_state, request, &body).await
+}
+
+/// Generic push webhook for other Git servers and CI jobs, authenticated by the `X-Klask-Signature-256` HMAC
+/// signature
+async fn generic_webhook(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<WebhookDelivery>) {
+    let request = WebhookRequest {
+        provider: WebhookProvider::Generic,
+        delivery_id: header(&headers, "X-Klask-Delivery"),
+        event: None,
+        credential: header(&headers, "X-Klask-Signature-256"),
+    };
+    handle(&app_state, request, &body).await
+}
+
+async fn handle(app_state: &AppState, request: WebhookRequest, body: &[u8]) -> (StatusCode, Json<WebhookDelivery>) {
+    let delivery = app_state.webhook_service.handle_delivery(request, body).await;
+    let status = match delivery.status {
+        DeliveryStatus::Queued => StatusCode::ACCEPTED,
+        DeliveryStatus::Ignored => StatusCode::OK,
+        DeliveryStatus::Invalid => StatusCode::BAD_REQUEST,
+        DeliveryStatus::Unauthorized => StatusCode::UNAUTHORIZED,
+        DeliveryStatus::Failed => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(delivery))
+}
+
+/// Recent deliveries, newest first, to troubleshoot webhook configuration
+async fn list_deliveries(
+    State(app_state): State<AppState>,
+    _admin_user: AdminUser, // Require admin authentication
+) -> Json<Vec<WebhookDelivery>> {
+    Json(app_state.webhook_service.recent_deliveries().await)
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+}
@@ -20,6 +20,7 @@ pub struct AppState {
     pub crawler_service: Arc<crate::services::crawler::CrawlerService>,
     pub progress_tracker: Arc<ProgressTracker>,
     pub scheduler_service: Option<Arc<crate::services::scheduler::SchedulerService>>,
+    pub webhook_service: Arc<crate::services::webhooks::WebhookService>,
     pub jwt_service: JwtService,
     pub encryption_service: Arc<EncryptionService>,
     #[allow(dead_code)]
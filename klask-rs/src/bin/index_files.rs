@@ -1,7 +1,6 @@
 use anyhow::Result;
-use klask_rs::models::{Repository, RepositoryType};
+use klask_rs::models::{FileInclusionRules, Repository, RepositoryType};
 use klask_rs::services::search::{FileData, SearchService};
-use std::path::Path;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -45,6 +44,26 @@ async fn main() -> Result<()> {
         excluded_branches_patterns: None,
         included_projects: None,
         included_projects_patterns: None,
+        included_extensions: None,
+        included_file_names: None,
+        included_path_patterns: None,
+        excluded_path_patterns: None,
+        default_encoding: None,
+        index_archives: None,
+        max_file_size_mb: None,
+        large_file_mode: None,
+        excluded_projects: None,
+        excluded_projects_patterns: None,
+        bitbucket_project_key: None,
+        gitea_organization: None,
+        svn_revision: None,
+        ssh_private_key: None,
+        ssh_known_hosts: None,
+        index_submodules: None,
+        fetch_lfs_objects: None,
+        index_merge_requests: None,
+        webhook_secret: None,
+        github_url: None,
     };
 
     // Initialize crawler service (database is optional for this direct indexing)
@@ -53,8 +72,12 @@ async fn main() -> Result<()> {
         println!("No DATABASE_URL provided, proceeding without database connection");
 
         // Manually process files directly with SearchService
-        let mut progress =
-            klask_rs::services::crawler::CrawlProgress { files_processed: 0, files_indexed: 0, errors: Vec::new() };
+        let mut progress = klask_rs::services::crawler::CrawlProgress {
+            files_processed: 0,
+            files_indexed: 0,
+            files_skipped_binary: 0,
+            errors: Vec::new(),
+        };
 
         // Use a minimal crawler setup to process files
         let repo_path = std::path::PathBuf::from(&repository.url);
@@ -64,6 +87,8 @@ async fn main() -> Result<()> {
 
         println!("Starting to index files from: {}", repository.url);
 
+        let rules = FileInclusionRules::default().for_repository(&repository);
+
         for entry in WalkDir::new(repo_path).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
             let file_path = entry.path();
             let relative_path = file_path.strip_prefix(&repository.url)?;
@@ -74,8 +99,8 @@ async fn main() -> Result<()> {
                 continue;
             }
 
-            // Check if file is supported
-            if !is_supported_file(file_path) {
+            // Check if file is included by the default file inclusion rules
+            if !rules.is_included(&relative_path_str) {
                 continue;
             }
 
@@ -114,6 +139,12 @@ async fn main() -> Result<()> {
                             version: "HEAD",
                             extension: &extension,
                             size: content.len() as u64,
+                            last_modified: None,
+                            language: None,
+                            sections: &[],
+                            line_offset: 0,
+                            is_truncated: false,
+                            is_lfs: false,
                         })
                         .await
                     {
@@ -149,96 +180,3 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
-
-fn is_supported_file(file_path: &Path) -> bool {
-    const SUPPORTED_EXTENSIONS: &[&str] = &[
-        "rs",
-        "py",
-        "js",
-        "ts",
-        "java",
-        "c",
-        "cpp",
-        "h",
-        "hpp",
-        "go",
-        "rb",
-        "php",
-        "cs",
-        "swift",
-        "kt",
-        "scala",
-        "clj",
-        "hs",
-        "ml",
-        "fs",
-        "elm",
-        "dart",
-        "vue",
-        "jsx",
-        "tsx",
-        "html",
-        "css",
-        "scss",
-        "less",
-        "sql",
-        "sh",
-        "bash",
-        "zsh",
-        "fish",
-        "ps1",
-        "bat",
-        "cmd",
-        "dockerfile",
-        "yaml",
-        "yml",
-        "json",
-        "toml",
-        "xml",
-        "md",
-        "txt",
-        "cfg",
-        "conf",
-        "ini",
-        "properties",
-        "gradle",
-        "maven",
-        "pom",
-        "sbt",
-        "cmake",
-        "makefile",
-        "r",
-        "m",
-        "perl",
-        "pl",
-        "lua",
-    ];
-
-    if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
-        SUPPORTED_EXTENSIONS.contains(&extension.to_lowercase().as_str())
-    } else {
-        // Support files without extensions that might be scripts or config files
-        if let Some(file_name) = file_path.file_name().and_then(|name| name.to_str()) {
-            matches!(
-                file_name.to_lowercase().as_str(),
-                "dockerfile"
-                    | "makefile"
-                    | "rakefile"
-                    | "gemfile"
-                    | "vagrantfile"
-                    | "procfile"
-                    | "readme"
-                    | "license"
-                    | "changelog"
-                    | "authors"
-                    | "contributors"
-                    | "copying"
-                    | "install"
-                    | "news"
-                    | "todo"
-            )
-        } else {
-            false
-        }
-    }
-}
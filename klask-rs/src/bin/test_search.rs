@@ -78,6 +78,26 @@ async fn main() -> Result<()> {
         excluded_branches_patterns: None,
         included_projects: None,
         included_projects_patterns: None,
+        included_extensions: None,
+        included_file_names: None,
+        included_path_patterns: None,
+        excluded_path_patterns: None,
+        default_encoding: None,
+        index_archives: None,
+        max_file_size_mb: None,
+        large_file_mode: None,
+        excluded_projects: None,
+        excluded_projects_patterns: None,
+        bitbucket_project_key: None,
+        gitea_organization: None,
+        svn_revision: None,
+        ssh_private_key: None,
+        ssh_known_hosts: None,
+        index_submodules: None,
+        fetch_lfs_objects: None,
+        index_merge_requests: None,
+        webhook_secret: None,
+        github_url: None,
     };
 
     info!("Starting crawler for test repository: {}", test_repo.url);
@@ -104,12 +124,18 @@ async fn main() -> Result<()> {
         extension_filter: None,
         min_size: None,
         max_size: None,
+        path_filter: None,
+        sort_by: None,
+        sort_order: None,
+        search_after: None,
+        language_filter: None,
         limit: 10,
         offset: 0,
         include_facets: false,
         fuzzy_search: false,
         regex_search: false,
         regex_flags: None,
+        include_generated: false,
     };
 
     match search_service.search(search_query).await {
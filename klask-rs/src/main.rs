@@ -12,9 +12,10 @@ use auth::{extractors::AppState, jwt::JwtService};
 use axum::{Router, routing::get};
 use config::AppConfig;
 use database::Database;
+use repositories::{RepositoryRepository, SearchSettingsRepository};
 use services::{
     SearchService, crawler::CrawlerService, encryption::EncryptionService, progress::ProgressTracker,
-    scheduler::SchedulerService,
+    scheduler::SchedulerService, webhooks::WebhookService,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -73,6 +74,26 @@ async fn main() -> Result<()> {
         }
     };
 
+    // Apply the ranking configuration saved by admins, if any
+    match SearchSettingsRepository::new(database.pool().clone()).get_ranking_config().await {
+        Ok(Some(ranking)) => {
+            info!("Loaded search ranking configuration from database");
+            search_service.set_ranking_config(ranking);
+        }
+        Ok(None) => info!("Using default search ranking configuration"),
+        Err(e) => error!("Failed to load search ranking configuration, using defaults: {}", e),
+    }
+
+    // Default branches boosted by the ranking (those of hosted projects are recorded by their crawls)
+    match RepositoryRepository::new(database.pool().clone()).list_repositories().await {
+        Ok(repositories) => {
+            for repository in &repositories {
+                search_service.set_default_branch(&repository.name, repository.branch.as_deref());
+            }
+        }
+        Err(e) => error!("Failed to load repository default branches: {}", e),
+    }
+
     // Initialize JWT service
     let jwt_service = match JwtService::new(&config.auth) {
         Ok(service) => {
@@ -110,14 +131,35 @@ async fn main() -> Result<()> {
         Ok(service) => {
             info!("Crawler service initialized successfully");
 
-            // Check for incomplete crawls and resume them in background
+            // Check for incomplete crawls and resume them in background, then re-index every repository when
+            // the index had an outdated schema. Both run in one task so that no repository is crawled twice at once.
             // This must not block server startup
             let service_clone = service.clone();
+            let search_service = search_service_arc.clone();
             tokio::spawn(async move {
                 info!("Checking for incomplete crawls to resume (in background)...");
                 if let Err(e) = service_clone.check_and_resume_incomplete_crawls().await {
                     error!("Failed to resume incomplete crawls: {}", e);
                 }
+
+                // An index with an outdated schema was replaced by an empty one: crawl every repository again
+                if let Some(outdated_index_dir) = search_service.outdated_index_dir() {
+                    info!(
+                        "Previous search index kept at {:?}, re-indexing every repository (in background)...",
+                        outdated_index_dir
+                    );
+                    match service_clone.reindex_all_repositories().await {
+                        Ok(()) => {
+                            if let Err(e) = search_service.remove_outdated_index() {
+                                error!("Failed to delete the previous search index: {}", e);
+                            }
+                        }
+                        Err(e) => error!(
+                            "Failed to re-index repositories, keeping the previous search index at {:?}: {}",
+                            outdated_index_dir, e
+                        ),
+                    }
+                }
             });
 
             // Clean up any abandoned crawls (older than 2 hours) in background
@@ -155,6 +197,13 @@ async fn main() -> Result<()> {
         }
     };
 
+    // Initialize webhook service
+    let webhook_service = Arc::new(WebhookService::new(
+        database.pool().clone(),
+        crawler_service_arc.clone(),
+        encryption_service.clone(),
+    ));
+
     // Create application state
     let app_state = AppState {
         database,
@@ -162,6 +211,7 @@ async fn main() -> Result<()> {
         crawler_service: crawler_service_arc,
         progress_tracker,
         scheduler_service: Some(Arc::new(scheduler_service)),
+        webhook_service,
         jwt_service,
         encryption_service,
         config: config.clone(),
@@ -0,0 +1,279 @@
+use super::Repository;
+use crate::services::crawler::archive::is_archive;
+use crate::services::crawler::filter::{matches_pattern, parse_list};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Rules deciding which files a crawl indexes.
+///
+/// Global rules are stored in the `search_settings` table and editable by admins. Each repository
+/// can add extensions, file names and path patterns of its own, and exclude paths.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileInclusionRules {
+    /// File extensions to index, without the leading dot (case-insensitive)
+    pub extensions: Vec<String>,
+    /// File names indexed whatever their extension (case-insensitive), e.g. "Dockerfile"
+    pub file_names: Vec<String>,
+    /// Glob patterns (`*` matches any sequence, including `/`) of relative paths to index
+    pub path_patterns: Vec<String>,
+    /// Glob patterns of relative paths never to index, even when matched by another rule
+    pub excluded_path_patterns: Vec<String>,
+    /// Open archives (zip, jar, war, ear, tar, tar.gz) and index the files inside them
+    pub index_archives: bool,
+}
+
+impl Default for FileInclusionRules {
+    fn default() -> Self {
+        let extensions = [
+            "rs",
+            "py",
+            "ipynb",
+            "js",
+            "ts",
+            "java",
+            "c",
+            "cpp",
+            "h",
+            "hpp",
+            "go",
+            "rb",
+            "php",
+            "cs",
+            "swift",
+            "kt",
+            "kts",
+            "scala",
+            "groovy",
+            "clj",
+            "hs",
+            "ml",
+            "fs",
+            "elm",
+            "dart",
+            "vue",
+            "jsx",
+            "tsx",
+            "html",
+            "css",
+            "scss",
+            "less",
+            "sql",
+            "sh",
+            "bash",
+            "zsh",
+            "fish",
+            "ps1",
+            "bat",
+            "cmd",
+            "dockerfile",
+            "yaml",
+            "yml",
+            "json",
+            "toml",
+            "xml",
+            "md",
+            "txt",
+            "pdf",
+            "docx",
+            "pptx",
+            "xlsx",
+            "odt",
+            "odp",
+            "ods",
+            "cfg",
+            "conf",
+            "ini",
+            "properties",
+            "gradle",
+            "maven",
+            "pom",
+            "sbt",
+            "cmake",
+            "makefile",
+            "r",
+            "m",
+            "perl",
+            "pl",
+            "lua",
+            "tf",
+            "tfvars",
+            "proto",
+            "graphql",
+            "gql",
+        ];
+        let file_names = [
+            "dockerfile",
+            "makefile",
+            "rakefile",
+            "gemfile",
+            "vagrantfile",
+            "procfile",
+            "jenkinsfile",
+            "readme",
+            "license",
+            "changelog",
+            "authors",
+            "contributors",
+            "copying",
+            "install",
+            "news",
+            "todo",
+        ];
+
+        Self {
+            extensions: extensions.iter().map(|s| s.to_string()).collect(),
+            file_names: file_names.iter().map(|s| s.to_string()).collect(),
+            path_patterns: Vec::new(),
+            excluded_path_patterns: Vec::new(),
+            index_archives: false,
+        }
+    }
+}
+
+impl FileInclusionRules {
+    /// Validate configuration values.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, values) in [
+            ("extensions", &self.extensions),
+            ("file_names", &self.file_names),
+            ("path_patterns", &self.path_patterns),
+            ("excluded_path_patterns", &self.excluded_path_patterns),
+        ] {
+            if values.iter().any(|v| v.trim().is_empty()) {
+                return Err(format!("{} must not contain empty entries", name));
+            }
+        }
+        if self.extensions.iter().any(|e| e.contains('/')) {
+            return Err("extensions must not contain '/'".to_string());
+        }
+        Ok(())
+    }
+
+    /// Effective rules for a repository: the repository's own lists are added to these rules
+    pub fn for_repository(&self, repository: &Repository) -> Self {
+        let extend = |base: &[String], extra: Option<&str>| {
+            let mut values = base.to_vec();
+            values.extend(parse_list(extra));
+            values
+        };
+
+        Self {
+            extensions: extend(&self.extensions, repository.included_extensions.as_deref()),
+            file_names: extend(&self.file_names, repository.included_file_names.as_deref()),
+            path_patterns: extend(&self.path_patterns, repository.included_path_patterns.as_deref()),
+            excluded_path_patterns: extend(
+                &self.excluded_path_patterns,
+                repository.excluded_path_patterns.as_deref(),
+            ),
+            index_archives: repository.index_archives.unwrap_or(self.index_archives),
+        }
+    }
+
+    /// Whether the file at `relative_path` (relative to the repository root, `/`-separated) is indexed
+    pub fn is_included(&self, relative_path: &str) -> bool {
+        if self.excluded_path_patterns.iter().any(|pattern| matches_pattern(relative_path, pattern.trim())) {
+            return false;
+        }
+
+        let path = Path::new(relative_path);
+        let extension_included = path.extension().and_then(|ext| ext.to_str()).is_some_and(|extension| {
+            self.extensions.iter().any(|e| e.trim().trim_start_matches('.').eq_ignore_ascii_case(extension))
+        });
+        let file_name_included = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|file_name| self.file_names.iter().any(|n| n.trim().eq_ignore_ascii_case(file_name)));
+
+        extension_included
+            || file_name_included
+            || self.path_patterns.iter().any(|pattern| matches_pattern(relative_path, pattern.trim()))
+            || self.opens_archive(relative_path)
+    }
+
+    /// Whether the file at `relative_path` is an archive whose files are indexed
+    pub fn opens_archive(&self, relative_path: &str) -> bool {
+        self.index_archives && is_archive(relative_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository() -> Repository {
+        serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::nil(),
+            "name": "infra",
+            "url": "/srv/infra",
+            "repositoryType": "FileSystem",
+            "enabled": true,
+            "isGroup": false,
+            "createdAt": "2025-01-01T00:00:00Z",
+            "updatedAt": "2025-01-01T00:00:00Z",
+            "autoCrawlEnabled": false,
+            "includedExtensions": "hcl, .nomad",
+            "includedFileNames": "OWNERS",
+            "includedPathPatterns": "deploy/*.env",
+            "excludedPathPatterns": "vendor/*, *.min.js"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_default_rules() {
+        let rules = FileInclusionRules::default();
+        assert!(rules.is_included("src/main.rs"));
+        assert!(rules.is_included("infra/main.tf"));
+        assert!(rules.is_included("api/service.proto"));
+        assert!(rules.is_included("schema/query.graphql"));
+        assert!(rules.is_included("build.gradle.kts"));
+        assert!(rules.is_included("Jenkinsfile"));
+        assert!(rules.is_included("docker/Dockerfile"));
+        assert!(rules.is_included("src/Main.JAVA"));
+        assert!(!rules.is_included("assets/logo.png"));
+        assert!(!rules.is_included("bin/tool"));
+    }
+
+    #[test]
+    fn test_repository_rules_extend_global_rules() {
+        let rules = FileInclusionRules::default().for_repository(&repository());
+        assert!(rules.is_included("jobs/web.nomad"));
+        assert!(rules.is_included("modules/vpc.hcl"));
+        assert!(rules.is_included("team/OWNERS"));
+        assert!(rules.is_included("deploy/prod.env"));
+        assert!(!rules.is_included("config/prod.env"));
+        assert!(rules.is_included("src/main.rs"), "Global rules still apply");
+    }
+
+    #[test]
+    fn test_excluded_paths_win() {
+        let rules = FileInclusionRules::default().for_repository(&repository());
+        assert!(!rules.is_included("vendor/lib/util.go"));
+        assert!(!rules.is_included("static/app.min.js"));
+        assert!(rules.is_included("static/app.js"));
+    }
+
+    #[test]
+    fn test_archives() {
+        let mut rules = FileInclusionRules::default();
+        assert!(!rules.is_included("lib/foo.jar"));
+
+        rules.index_archives = true;
+        assert!(rules.is_included("lib/foo.jar"));
+        assert!(rules.opens_archive("dist/release.tar.gz"));
+        assert!(!rules.opens_archive("src/main.rs"));
+
+        let rules = rules.for_repository(&repository());
+        assert!(!rules.is_included("vendor/lib/util.jar"), "Excluded paths still win");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(FileInclusionRules::default().validate().is_ok());
+        let rules = FileInclusionRules { extensions: vec!["rs".to_string(), " ".to_string()], ..Default::default() };
+        assert!(rules.validate().is_err());
+        let rules = FileInclusionRules { extensions: vec!["src/rs".to_string()], ..Default::default() };
+        assert!(rules.validate().is_err());
+    }
+}
@@ -1,7 +1,13 @@
+pub mod file_inclusion;
 pub mod index_metrics;
+pub mod ranking;
 pub mod repository;
+pub mod ssh_key;
 pub mod user;
 
+pub use file_inclusion::*;
 pub use index_metrics::*;
+pub use ranking::*;
 pub use repository::*;
+pub use ssh_key::*;
 pub use user::*;
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+
+/// Ranking configuration applied on top of BM25 relevance scoring.
+///
+/// Stored in the `search_settings` table and editable by admins at runtime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    /// Query-time boost for matches in file content
+    pub content_boost: f32,
+    /// Query-time boost for matches in the file name
+    pub file_name_boost: f32,
+    /// Query-time boost for matches in the file path
+    pub file_path_boost: f32,
+    /// Versions treated as default branches (exact names) of repositories whose default branch is unknown
+    pub default_branches: Vec<String>,
+    /// Score multiplier for documents on a default branch
+    pub default_branch_boost: f32,
+    /// Case-insensitive substrings of "/" + file path that mark tests, vendored or generated code
+    pub demoted_path_patterns: Vec<String>,
+    /// Score multiplier for documents matching a demoted pattern (0 < factor <= 1)
+    pub demotion_factor: f32,
+    /// Bonus for shallow paths: the score is multiplied by 1 + bonus / (1 + directory depth)
+    pub shallow_path_bonus: f32,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            content_boost: 1.0,
+            file_name_boost: 3.0,
+            file_path_boost: 1.5,
+            default_branches: vec!["main".to_string(), "master".to_string()],
+            default_branch_boost: 1.2,
+            demoted_path_patterns: [
+                "/test/",
+                "/tests/",
+                "/__tests__/",
+                "/spec/",
+                "_test.",
+                ".test.",
+                ".spec.",
+                "/vendor/",
+                "/node_modules/",
+                "/third_party/",
+                "generated",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            demotion_factor: 0.5,
+            shallow_path_bonus: 0.3,
+        }
+    }
+}
+
+impl RankingConfig {
+    /// Validate configuration values.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, boost) in [
+            ("content_boost", self.content_boost),
+            ("file_name_boost", self.file_name_boost),
+            ("file_path_boost", self.file_path_boost),
+            ("default_branch_boost", self.default_branch_boost),
+        ] {
+            if !(boost > 0.0 && boost <= 100.0) {
+                return Err(format!("{} must be greater than 0 and at most 100", name));
+            }
+        }
+        if !(self.demotion_factor > 0.0 && self.demotion_factor <= 1.0) {
+            return Err("demotion_factor must be greater than 0 and at most 1".to_string());
+        }
+        if !(0.0..=10.0).contains(&self.shallow_path_bonus) {
+            return Err("shallow_path_bonus must be between 0 and 10".to_string());
+        }
+        if self.demoted_path_patterns.iter().any(|p| p.trim().is_empty()) {
+            return Err("demoted_path_patterns must not contain empty patterns".to_string());
+        }
+        Ok(())
+    }
+
+    /// Score multiplier for one document, given its version, the default branch of its repository
+    /// (when known) and its file path
+    pub fn score_multiplier(&self, version: &str, default_branch: Option<&str>, file_path: &str) -> f32 {
+        self.version_multiplier(version, default_branch) * self.path_multiplier(file_path)
+    }
+
+    /// Default-branch part of the score multiplier
+    pub fn version_multiplier(&self, version: &str, default_branch: Option<&str>) -> f32 {
+        let is_default_branch = match default_branch {
+            Some(default_branch) => default_branch == version,
+            None => self.default_branches.iter().any(|branch| branch == version),
+        };
+        if is_default_branch { self.default_branch_boost } else { 1.0 }
+    }
+
+    /// Path part of the score multiplier: demotion patterns and shallow-path bonus
+    pub fn path_multiplier(&self, file_path: &str) -> f32 {
+        let path = format!("/{}", file_path.trim_start_matches('/')).to_lowercase();
+        let demotion = if self.demoted_path_patterns.iter().any(|pattern| path.contains(&pattern.to_lowercase())) {
+            self.demotion_factor
+        } else {
+            1.0
+        };
+
+        let depth = path.matches('/').count().saturating_sub(1);
+        demotion * (1.0 + self.shallow_path_bonus / (1 + depth) as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(RankingConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation_rejects_out_of_range_values() {
+        let config = RankingConfig { file_name_boost: 0.0, ..Default::default() };
+        assert!(config.validate().is_err());
+
+        let config = RankingConfig { demotion_factor: 1.5, ..Default::default() };
+        assert!(config.validate().is_err());
+
+        let config = RankingConfig { shallow_path_bonus: -1.0, ..Default::default() };
+        assert!(config.validate().is_err());
+
+        let config = RankingConfig { demoted_path_patterns: vec![" ".to_string()], ..Default::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_score_multiplier_demotes_tests_and_vendor() {
+        let config = RankingConfig::default();
+        let source = config.score_multiplier("develop", None, "src/service/UserService.java");
+        let test = config.score_multiplier("develop", None, "src/test/UserServiceTest.java");
+        let vendor = config.score_multiplier("develop", None, "vendor/lib/UserService.java");
+
+        assert!(test < source);
+        assert!(vendor < source);
+        assert!(config.score_multiplier("develop", None, "src/user_test.go") < source);
+        assert!(config.score_multiplier("develop", None, "src/api.generated.ts") < source);
+    }
+
+    #[test]
+    fn test_score_multiplier_prefers_default_branch_and_shallow_paths() {
+        let config = RankingConfig::default();
+        assert!(
+            config.score_multiplier("main", None, "src/lib.rs")
+                > config.score_multiplier("feature/x", None, "src/lib.rs")
+        );
+        assert!(
+            config.score_multiplier("main", None, "lib.rs") > config.score_multiplier("main", None, "a/b/c/lib.rs")
+        );
+    }
+
+    #[test]
+    fn test_known_default_branch_replaces_configured_names() {
+        let config = RankingConfig::default();
+        assert_eq!(
+            config.version_multiplier("develop", Some("develop")),
+            config.default_branch_boost
+        );
+        assert_eq!(config.version_multiplier("main", Some("develop")), 1.0);
+        assert_eq!(config.version_multiplier("master", None), config.default_branch_boost);
+    }
+
+    #[test]
+    fn test_neutral_config_keeps_scores() {
+        let config = RankingConfig {
+            default_branch_boost: 1.0,
+            demotion_factor: 1.0,
+            shallow_path_bonus: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(config.score_multiplier("main", None, "tests/deep/path/file.rs"), 1.0);
+    }
+
+    #[test]
+    fn test_partial_json_uses_defaults() {
+        let config: RankingConfig = serde_json::from_str(r#"{"file_name_boost": 5.0}"#).unwrap();
+        assert_eq!(config.file_name_boost, 5.0);
+        assert_eq!(config.demotion_factor, RankingConfig::default().demotion_factor);
+    }
+}
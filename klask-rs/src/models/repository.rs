@@ -44,6 +44,9 @@ pub struct Repository {
     #[serde(rename = "gitlabExcludedPatterns")]
     pub gitlab_excluded_patterns: Option<String>,
     // GitHub fields
+    /// GitHub Enterprise Server address (e.g., "https://github.example.com") used for API calls; github.com when unset.
+    #[serde(rename = "githubUrl")]
+    pub github_url: Option<String>,
     #[serde(rename = "githubNamespace")]
     pub github_namespace: Option<String>,
     #[serde(rename = "githubExcludedRepositories")]
@@ -77,6 +80,78 @@ pub struct Repository {
     /// Comma-separated list of glob patterns for projects/repositories to include (e.g., "my-org/*", "*-sdk").
     #[serde(rename = "includedProjectsPatterns")]
     pub included_projects_patterns: Option<String>,
+    /// Comma-separated list of projects/repositories to exclude (Bitbucket, Gitea, Azure DevOps).
+    #[serde(rename = "excludedProjects")]
+    pub excluded_projects: Option<String>,
+    /// Comma-separated list of glob patterns for projects/repositories to exclude (e.g., "ARCHIVE/*").
+    #[serde(rename = "excludedProjectsPatterns")]
+    pub excluded_projects_patterns: Option<String>,
+    // File inclusion fields, added to the global file inclusion rules
+    /// Comma-separated list of extra file extensions to index (e.g., "tf, proto").
+    #[serde(rename = "includedExtensions")]
+    pub included_extensions: Option<String>,
+    /// Comma-separated list of extra file names to index regardless of extension (e.g., "Jenkinsfile").
+    #[serde(rename = "includedFileNames")]
+    pub included_file_names: Option<String>,
+    /// Comma-separated list of glob patterns for extra file paths to index (e.g., "config/*.env").
+    #[serde(rename = "includedPathPatterns")]
+    pub included_path_patterns: Option<String>,
+    /// Comma-separated list of glob patterns for file paths never to index (e.g., "vendor/*", "*.min.js").
+    #[serde(rename = "excludedPathPatterns")]
+    pub excluded_path_patterns: Option<String>,
+    /// Encoding of files that are not valid UTF-8 (e.g., "windows-1252"); guessed from the content when unset.
+    #[serde(rename = "defaultEncoding")]
+    pub default_encoding: Option<String>,
+    /// Whether to index the files inside archives (zip, jar, tar.gz...); the global file inclusion rule when unset.
+    #[serde(rename = "indexArchives")]
+    pub index_archives: Option<bool>,
+    /// Size limit of indexed files in megabytes; 10 MB when unset.
+    #[serde(rename = "maxFileSizeMb")]
+    pub max_file_size_mb: Option<i32>,
+    /// What to do with files above the size limit; skipped when unset.
+    #[serde(rename = "largeFileMode")]
+    pub large_file_mode: Option<LargeFileMode>,
+    // Bitbucket fields
+    /// Bitbucket project key to discover repositories in (e.g., "PLAT"); every visible project when unset.
+    #[serde(rename = "bitbucketProjectKey")]
+    pub bitbucket_project_key: Option<String>,
+    // Gitea fields
+    /// Gitea/Forgejo organization to discover repositories in; the token owner's repositories when unset.
+    #[serde(rename = "giteaOrganization")]
+    pub gitea_organization: Option<String>,
+    // Subversion fields
+    /// Youngest Subversion revision indexed by the last crawl.
+    #[serde(rename = "svnRevision")]
+    pub svn_revision: Option<i64>,
+    // SSH fields
+    /// SSH private key for ssh:// and git@host:path URLs; the shared deploy key is used when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_deserializing)]
+    #[serde(rename = "sshPrivateKey")]
+    pub ssh_private_key: Option<String>, // Store encrypted in DB
+    /// known_hosts lines pinning the host keys of the Git server (e.g., "git.example.com ssh-ed25519 AAAA...").
+    #[serde(rename = "sshKnownHosts")]
+    pub ssh_known_hosts: Option<String>,
+    // Submodule fields
+    /// Whether to fetch the pinned commits of Git submodules and index their files; off when unset.
+    #[serde(rename = "indexSubmodules")]
+    pub index_submodules: Option<bool>,
+    // Git LFS fields
+    /// Whether to download text files tracked by Git LFS to index their content; indexed by path only when unset.
+    #[serde(rename = "fetchLfsObjects")]
+    pub fetch_lfs_objects: Option<bool>,
+    // Merge request fields
+    /// Whether to index the open merge requests / pull requests of GitLab and GitHub projects as versions
+    /// named `MR!<iid>` / `PR#<number>`; off when unset.
+    #[serde(rename = "indexMergeRequests")]
+    pub index_merge_requests: Option<bool>,
+    // Webhook fields
+    /// Secret verifying push webhooks (GitLab token, or key of GitHub and generic HMAC signatures); webhooks are
+    /// rejected when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_deserializing)]
+    #[serde(rename = "webhookSecret")]
+    pub webhook_secret: Option<String>, // Store encrypted in DB
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
@@ -86,5 +161,24 @@ pub enum RepositoryType {
     Git,
     GitLab,
     GitHub,
+    Bitbucket,
+    Gitea,
+    AzureDevOps,
+    Svn,
     FileSystem,
 }
+
+/// Handling of files larger than a repository's size limit
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[sqlx(type_name = "varchar")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LargeFileMode {
+    /// Do not index the file
+    #[default]
+    Skip,
+    /// Index the beginning of the file, flagged as truncated
+    Truncate,
+    /// Index the file as several documents of at most the size limit, split between lines
+    Chunk,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// SSH key shared by every Git repository cloned over SSH without a key of its own.
+///
+/// Stored in the `search_settings` table, with the private key encrypted by the `EncryptionService`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshDeployKey {
+    /// Encrypted private key
+    pub private_key: String,
+    /// known_hosts lines pinning the host keys of the Git servers, used by repositories that pin none
+    pub known_hosts: Option<String>,
+}
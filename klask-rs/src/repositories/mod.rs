@@ -1,8 +1,10 @@
 pub mod repository_repository;
+pub mod search_settings_repository;
 pub mod user_repository;
 
 #[cfg(any(test, debug_assertions))]
 pub mod test_user_repository;
 
 pub use repository_repository::*;
+pub use search_settings_repository::*;
 pub use user_repository::*;
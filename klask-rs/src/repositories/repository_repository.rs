@@ -14,7 +14,7 @@ impl RepositoryRepository {
 
     pub async fn create_repository(&self, repository: &Repository) -> Result<Repository> {
         let result = sqlx::query_as::<_, Repository>(
-            "INSERT INTO repositories (id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28) RETURNING id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns"
+            "INSERT INTO repositories (id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $45, $46, $47) RETURNING id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, svn_revision, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url"
         )
         .bind(repository.id)
         .bind(&repository.name)
@@ -44,6 +44,25 @@ impl RepositoryRepository {
         .bind(&repository.excluded_branches_patterns)
         .bind(&repository.included_projects)
         .bind(&repository.included_projects_patterns)
+        .bind(&repository.included_extensions)
+        .bind(&repository.included_file_names)
+        .bind(&repository.included_path_patterns)
+        .bind(&repository.excluded_path_patterns)
+        .bind(&repository.default_encoding)
+        .bind(repository.index_archives)
+        .bind(repository.max_file_size_mb)
+        .bind(repository.large_file_mode)
+        .bind(&repository.excluded_projects)
+        .bind(&repository.excluded_projects_patterns)
+        .bind(&repository.bitbucket_project_key)
+        .bind(&repository.gitea_organization)
+        .bind(&repository.ssh_private_key)
+        .bind(&repository.ssh_known_hosts)
+        .bind(repository.index_submodules)
+        .bind(repository.fetch_lfs_objects)
+        .bind(repository.index_merge_requests)
+        .bind(&repository.webhook_secret)
+        .bind(&repository.github_url)
         .fetch_one(&self.pool)
         .await?;
 
@@ -52,7 +71,7 @@ impl RepositoryRepository {
 
     pub async fn get_repository(&self, id: Uuid) -> Result<Option<Repository>> {
         let repository = sqlx::query_as::<_, Repository>(
-            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns FROM repositories WHERE id = $1"
+            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, svn_revision, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url FROM repositories WHERE id = $1"
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -63,7 +82,7 @@ impl RepositoryRepository {
 
     pub async fn list_repositories(&self) -> Result<Vec<Repository>> {
         let repositories = sqlx::query_as::<_, Repository>(
-            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns FROM repositories ORDER BY created_at DESC"
+            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, svn_revision, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url FROM repositories ORDER BY created_at DESC"
         )
         .fetch_all(&self.pool)
         .await?;
@@ -83,7 +102,7 @@ impl RepositoryRepository {
 
     pub async fn update_repository(&self, id: Uuid, repository: &Repository) -> Result<Repository> {
         let result = sqlx::query_as::<_, Repository>(
-            "UPDATE repositories SET name = $2, url = $3, repository_type = $4, branch = $5, enabled = $6, access_token = $7, gitlab_namespace = $8, is_group = $9, auto_crawl_enabled = $10, cron_schedule = $11, next_crawl_at = $12, crawl_frequency_hours = $13, max_crawl_duration_minutes = $14, gitlab_excluded_projects = $15, gitlab_excluded_patterns = $16, github_namespace = $17, github_excluded_repositories = $18, github_excluded_patterns = $19, crawl_state = $20, last_processed_project = $21, crawl_started_at = $22, included_branches = $23, included_branches_patterns = $24, excluded_branches = $25, excluded_branches_patterns = $26, included_projects = $27, included_projects_patterns = $28, updated_at = NOW() WHERE id = $1 RETURNING id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns"
+            "UPDATE repositories SET name = $2, url = $3, repository_type = $4, branch = $5, enabled = $6, access_token = $7, gitlab_namespace = $8, is_group = $9, auto_crawl_enabled = $10, cron_schedule = $11, next_crawl_at = $12, crawl_frequency_hours = $13, max_crawl_duration_minutes = $14, gitlab_excluded_projects = $15, gitlab_excluded_patterns = $16, github_namespace = $17, github_excluded_repositories = $18, github_excluded_patterns = $19, crawl_state = $20, last_processed_project = $21, crawl_started_at = $22, included_branches = $23, included_branches_patterns = $24, excluded_branches = $25, excluded_branches_patterns = $26, included_projects = $27, included_projects_patterns = $28, included_extensions = $29, included_file_names = $30, included_path_patterns = $31, excluded_path_patterns = $32, default_encoding = $33, index_archives = $34, max_file_size_mb = $35, large_file_mode = $36, excluded_projects = $37, excluded_projects_patterns = $38, bitbucket_project_key = $39, gitea_organization = $40, ssh_private_key = $41, ssh_known_hosts = $42, index_submodules = $43, fetch_lfs_objects = $44, index_merge_requests = $45, webhook_secret = $46, github_url = $47, updated_at = NOW() WHERE id = $1 RETURNING id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, svn_revision, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url"
         )
         .bind(id)
         .bind(&repository.name)
@@ -113,6 +132,25 @@ impl RepositoryRepository {
         .bind(&repository.excluded_branches_patterns)
         .bind(&repository.included_projects)
         .bind(&repository.included_projects_patterns)
+        .bind(&repository.included_extensions)
+        .bind(&repository.included_file_names)
+        .bind(&repository.included_path_patterns)
+        .bind(&repository.excluded_path_patterns)
+        .bind(&repository.default_encoding)
+        .bind(repository.index_archives)
+        .bind(repository.max_file_size_mb)
+        .bind(repository.large_file_mode)
+        .bind(&repository.excluded_projects)
+        .bind(&repository.excluded_projects_patterns)
+        .bind(&repository.bitbucket_project_key)
+        .bind(&repository.gitea_organization)
+        .bind(&repository.ssh_private_key)
+        .bind(&repository.ssh_known_hosts)
+        .bind(repository.index_submodules)
+        .bind(repository.fetch_lfs_objects)
+        .bind(repository.index_merge_requests)
+        .bind(&repository.webhook_secret)
+        .bind(&repository.github_url)
         .fetch_one(&self.pool)
         .await?;
 
@@ -159,7 +197,7 @@ impl RepositoryRepository {
     #[allow(dead_code)]
     pub async fn find_scheduled_repositories(&self) -> Result<Vec<Repository>> {
         let repositories = sqlx::query_as::<_, Repository>(
-            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns FROM repositories WHERE auto_crawl_enabled = true ORDER BY next_crawl_at ASC"
+            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, svn_revision, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url FROM repositories WHERE auto_crawl_enabled = true ORDER BY next_crawl_at ASC"
         )
         .fetch_all(&self.pool)
         .await?;
@@ -200,6 +238,16 @@ impl RepositoryRepository {
         Ok(())
     }
 
+    pub async fn update_svn_revision(&self, repository_id: Uuid, revision: i64) -> Result<()> {
+        sqlx::query("UPDATE repositories SET svn_revision = $2, updated_at = NOW() WHERE id = $1")
+            .bind(repository_id)
+            .bind(revision)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn update_crawl_progress(
         &self,
         repository_id: Uuid,
@@ -216,7 +264,7 @@ impl RepositoryRepository {
 
     pub async fn find_incomplete_crawls(&self) -> Result<Vec<Repository>> {
         let repositories = sqlx::query_as::<_, Repository>(
-            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns FROM repositories WHERE crawl_state = 'in_progress' AND enabled = true ORDER BY crawl_started_at ASC"
+            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, svn_revision, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url FROM repositories WHERE crawl_state = 'in_progress' AND enabled = true ORDER BY crawl_started_at ASC"
         )
         .fetch_all(&self.pool)
         .await?;
@@ -226,7 +274,7 @@ impl RepositoryRepository {
 
     pub async fn find_abandoned_crawls(&self, timeout_minutes: i64) -> Result<Vec<Repository>> {
         let repositories = sqlx::query_as::<_, Repository>(
-            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns FROM repositories WHERE crawl_state = 'in_progress' AND crawl_started_at < NOW() - INTERVAL '1 minute' * $1 ORDER BY crawl_started_at ASC"
+            "SELECT id, name, url, repository_type, branch, enabled, access_token, gitlab_namespace, is_group, last_crawled, created_at, updated_at, auto_crawl_enabled, cron_schedule, next_crawl_at, crawl_frequency_hours, max_crawl_duration_minutes, last_crawl_duration_seconds, gitlab_excluded_projects, gitlab_excluded_patterns, github_namespace, github_excluded_repositories, github_excluded_patterns, crawl_state, last_processed_project, crawl_started_at, included_branches, included_branches_patterns, excluded_branches, excluded_branches_patterns, included_projects, included_projects_patterns, included_extensions, included_file_names, included_path_patterns, excluded_path_patterns, default_encoding, index_archives, max_file_size_mb, large_file_mode, excluded_projects, excluded_projects_patterns, bitbucket_project_key, gitea_organization, svn_revision, ssh_private_key, ssh_known_hosts, index_submodules, fetch_lfs_objects, index_merge_requests, webhook_secret, github_url FROM repositories WHERE crawl_state = 'in_progress' AND crawl_started_at < NOW() - INTERVAL '1 minute' * $1 ORDER BY crawl_started_at ASC"
         )
         .bind(timeout_minutes)
         .fetch_all(&self.pool)
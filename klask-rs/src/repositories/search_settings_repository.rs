@@ -0,0 +1,78 @@
+use crate::models::{FileInclusionRules, RankingConfig, SshDeployKey};
+use anyhow::Result;
+use serde::{Serialize, de::DeserializeOwned};
+use sqlx::{PgPool, Row};
+
+const RANKING_KEY: &str = "ranking";
+const FILE_INCLUSION_KEY: &str = "file_inclusion";
+const SSH_DEPLOY_KEY: &str = "ssh_deploy_key";
+
+pub struct SearchSettingsRepository {
+    pool: PgPool,
+}
+
+impl SearchSettingsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Load the stored ranking configuration, if an admin has saved one
+    pub async fn get_ranking_config(&self) -> Result<Option<RankingConfig>> {
+        self.get_setting(RANKING_KEY).await
+    }
+
+    pub async fn save_ranking_config(&self, config: &RankingConfig) -> Result<()> {
+        self.save_setting(RANKING_KEY, config).await
+    }
+
+    /// Load the global file inclusion rules, falling back to the defaults if none were saved
+    pub async fn get_file_inclusion_rules(&self) -> Result<FileInclusionRules> {
+        Ok(self.get_setting(FILE_INCLUSION_KEY).await?.unwrap_or_default())
+    }
+
+    pub async fn save_file_inclusion_rules(&self, rules: &FileInclusionRules) -> Result<()> {
+        self.save_setting(FILE_INCLUSION_KEY, rules).await
+    }
+
+    /// Load the shared SSH deploy key, if an admin has saved one
+    pub async fn get_ssh_deploy_key(&self) -> Result<Option<SshDeployKey>> {
+        self.get_setting(SSH_DEPLOY_KEY).await
+    }
+
+    pub async fn save_ssh_deploy_key(&self, deploy_key: &SshDeployKey) -> Result<()> {
+        self.save_setting(SSH_DEPLOY_KEY, deploy_key).await
+    }
+
+    pub async fn delete_ssh_deploy_key(&self) -> Result<()> {
+        sqlx::query("DELETE FROM search_settings WHERE key = $1").bind(SSH_DEPLOY_KEY).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_setting<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let row = sqlx::query("SELECT value FROM search_settings WHERE key = $1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => {
+                let value: String = row.get("value");
+                Ok(Some(serde_json::from_str(&value)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn save_setting<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO search_settings (key, value, updated_at) VALUES ($1, $2, NOW())
+             ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, updated_at = NOW()",
+        )
+        .bind(key)
+        .bind(serde_json::to_string(value)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
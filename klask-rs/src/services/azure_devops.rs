@@ -0,0 +1,219 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// REST API version understood by Azure DevOps Services and Azure DevOps Server 2019 and later
+const API_VERSION: &str = "5.0";
+
+/// Client for the REST API of Azure DevOps Services and Azure DevOps Server.
+/// URLs are organization (`https://dev.azure.com/my-org`) or collection
+/// (`https://tfs.example.com/tfs/DefaultCollection`) addresses.
+#[derive(Debug, Clone)]
+pub struct AzureDevOpsService {
+    client: Client,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureDevOpsProject {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureDevOpsRepository {
+    pub id: String,
+    pub name: String,
+    pub default_branch: Option<String>, // Full ref name, e.g. "refs/heads/main"; missing for empty repositories
+    pub remote_url: String,
+    pub ssh_url: Option<String>,
+    pub web_url: Option<String>,
+    #[serde(default)]
+    pub is_disabled: bool,
+    pub project: AzureDevOpsProject,
+}
+
+/// Collection wrapper of Azure DevOps list responses
+#[derive(Debug, Deserialize)]
+struct AzureDevOpsList<T> {
+    value: Vec<T>,
+}
+
+impl AzureDevOpsRepository {
+    /// Repository name qualified by its project (e.g., "Payments/api")
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.project.name, self.name)
+    }
+
+    /// Default branch without the "refs/heads/" prefix
+    pub fn default_branch_name(&self) -> Option<String> {
+        self.default_branch.as_deref().map(|branch| branch.trim_start_matches("refs/heads/").to_string())
+    }
+}
+
+impl Default for AzureDevOpsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AzureDevOpsService {
+    pub fn new() -> Self {
+        let accept_invalid_certs = std::env::var("KLASK_AZURE_DEVOPS_ACCEPT_INVALID_CERTS")
+            .map(|v| v.to_lowercase() == "true")
+            .unwrap_or(false);
+
+        let mut builder = Client::builder().user_agent("klask-rs/2.0").timeout(std::time::Duration::from_secs(30));
+
+        if accept_invalid_certs {
+            tracing::warn!(
+                "Azure DevOps client configured to accept invalid certificates (KLASK_AZURE_DEVOPS_ACCEPT_INVALID_CERTS=true)"
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Self { client: builder.build().unwrap_or_else(|_| Client::new()) }
+    }
+
+    /// Discover the repositories of one project, or of every project visible to the token
+    pub async fn discover_repositories(
+        &self,
+        organization_url: &str,
+        access_token: &str,
+        project: Option<&str>,
+    ) -> Result<Vec<AzureDevOpsRepository>> {
+        // Treat empty string as None (all projects)
+        let project = project.filter(|s| !s.is_empty());
+
+        let project_names = match project {
+            Some(name) => vec![name.to_string()],
+            None => self.list_projects(organization_url, access_token).await?.into_iter().map(|p| p.name).collect(),
+        };
+
+        let mut repositories = Vec::new();
+        for name in &project_names {
+            repositories.extend(self.list_project_repositories(organization_url, access_token, name).await?);
+        }
+
+        Ok(repositories)
+    }
+
+    /// List every project of the organization or collection visible to the token
+    pub async fn list_projects(&self, organization_url: &str, access_token: &str) -> Result<Vec<AzureDevOpsProject>> {
+        let mut projects = Vec::new();
+        let mut skip = 0;
+        let top = 100;
+
+        loop {
+            let url = format!(
+                "{}/_apis/projects?$top={}&$skip={}&api-version={}",
+                organization_url.trim_end_matches('/'),
+                top,
+                skip,
+                API_VERSION
+            );
+
+            let page: Vec<AzureDevOpsProject> =
+                self.get_list(&url, access_token).await.context("Failed to fetch Azure DevOps projects")?;
+            let page_len = page.len();
+            projects.extend(page);
+
+            if page_len < top {
+                break;
+            }
+            skip += top;
+        }
+
+        Ok(projects)
+    }
+
+    /// List the Git repositories of a project, leaving out disabled ones
+    pub async fn list_project_repositories(
+        &self,
+        organization_url: &str,
+        access_token: &str,
+        project: &str,
+    ) -> Result<Vec<AzureDevOpsRepository>> {
+        let url = format!(
+            "{}/{}/_apis/git/repositories?api-version={}",
+            organization_url.trim_end_matches('/'),
+            urlencoding::encode(project),
+            API_VERSION
+        );
+
+        let mut repositories: Vec<AzureDevOpsRepository> = self
+            .get_list(&url, access_token)
+            .await
+            .with_context(|| format!("Failed to fetch repositories of Azure DevOps project {}", project))?;
+
+        let initial_count = repositories.len();
+        repositories.retain(|r| !r.is_disabled);
+        if repositories.len() < initial_count {
+            tracing::info!(
+                "Filtered out {} disabled Azure DevOps repositories in project {}",
+                initial_count - repositories.len(),
+                project
+            );
+        }
+
+        Ok(repositories)
+    }
+
+    async fn get_list<T: DeserializeOwned>(&self, url: &str, access_token: &str) -> Result<Vec<T>> {
+        tracing::debug!("Making Azure DevOps API request to: {}", url);
+        let response =
+            self.client.get(url).basic_auth("", Some(access_token)).header("Accept", "application/json").send().await?;
+
+        // Rejected tokens may get a 203 sign-in page instead of a 401
+        let status = response.status();
+        if status != StatusCode::OK {
+            let error_body = response.text().await.unwrap_or_default();
+            tracing::error!(
+                "Azure DevOps API request failed - URL: {}, Status: {}, Body: {}",
+                url,
+                status,
+                error_body
+            );
+            return Err(anyhow!("Azure DevOps API error: {} - {}", status, error_body));
+        }
+
+        let list: AzureDevOpsList<T> = response.json().await.context("Failed to parse Azure DevOps response")?;
+        Ok(list.value)
+    }
+
+    /// Test if the personal access token is valid
+    pub async fn test_token(&self, organization_url: &str, access_token: &str) -> Result<bool> {
+        let url = format!(
+            "{}/_apis/projects?$top=1&api-version={}",
+            organization_url.trim_end_matches('/'),
+            API_VERSION
+        );
+
+        tracing::debug!("Testing Azure DevOps token with URL: {}", url);
+
+        let response =
+            self.client.get(&url).basic_auth("", Some(access_token)).header("Accept", "application/json").send().await;
+
+        match response {
+            Ok(resp) => {
+                let status = resp.status();
+                tracing::info!("Azure DevOps token test status: {}", status);
+
+                if status == StatusCode::OK {
+                    Ok(true)
+                } else {
+                    let error_body = resp.text().await.unwrap_or_default();
+                    tracing::error!("Azure DevOps token test failed: {} - {}", status, error_body);
+                    Ok(false)
+                }
+            }
+            Err(e) => {
+                tracing::error!("Azure DevOps API request failed for URL: {}", url);
+                tracing::error!("Request error: {}", e);
+                Err(anyhow!("Network error: {} (URL: {})", e, url))
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Client for the REST API of Bitbucket Server / Data Center (`/rest/api/1.0`)
+#[derive(Debug, Clone)]
+pub struct BitbucketService {
+    client: Client,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketProject {
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BitbucketRepository {
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub project: BitbucketProject,
+    #[serde(default)]
+    pub archived: bool, // Only reported by Bitbucket 8.0 and later
+    #[serde(default)]
+    pub links: BitbucketLinks,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BitbucketLinks {
+    #[serde(default)]
+    pub clone: Vec<BitbucketLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketLink {
+    pub href: String,
+    pub name: Option<String>, // "http" or "ssh" for clone links
+}
+
+/// One page of a paged Bitbucket API response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitbucketPage<T> {
+    values: Vec<T>,
+    #[serde(default = "default_is_last_page")]
+    is_last_page: bool,
+    next_page_start: Option<u32>,
+}
+
+fn default_is_last_page() -> bool {
+    true
+}
+
+impl BitbucketRepository {
+    /// Repository name qualified by its project key (e.g., "PLAT/api"), used for project filters
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.project.key, self.slug)
+    }
+
+    /// HTTP(S) clone URL advertised by the server
+    pub fn http_clone_url(&self) -> Option<&str> {
+        self.links.clone.iter().find(|link| link.name.as_deref() == Some("http")).map(|link| link.href.as_str())
+    }
+}
+
+impl Default for BitbucketService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitbucketService {
+    pub fn new() -> Self {
+        let accept_invalid_certs =
+            std::env::var("KLASK_BITBUCKET_ACCEPT_INVALID_CERTS").map(|v| v.to_lowercase() == "true").unwrap_or(false);
+
+        let mut builder = Client::builder().user_agent("klask-rs/2.0").timeout(std::time::Duration::from_secs(30));
+
+        if accept_invalid_certs {
+            tracing::warn!(
+                "Bitbucket client configured to accept invalid certificates (KLASK_BITBUCKET_ACCEPT_INVALID_CERTS=true)"
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Self { client: builder.build().unwrap_or_else(|_| Client::new()) }
+    }
+
+    /// Discover the repositories of one project, or of every project visible to the token
+    pub async fn discover_repositories(
+        &self,
+        bitbucket_url: &str,
+        access_token: &str,
+        project_key: Option<&str>,
+    ) -> Result<Vec<BitbucketRepository>> {
+        // Treat empty string as None (all projects)
+        let project_key = project_key.filter(|s| !s.is_empty());
+
+        let project_keys = match project_key {
+            Some(key) => vec![key.to_string()],
+            None => self.list_projects(bitbucket_url, access_token).await?.into_iter().map(|p| p.key).collect(),
+        };
+
+        let mut repositories = Vec::new();
+        for key in &project_keys {
+            repositories.extend(self.list_project_repositories(bitbucket_url, access_token, key).await?);
+        }
+
+        // Filter out archived repositories
+        let initial_count = repositories.len();
+        repositories.retain(|r| !r.archived);
+        let archived_count = initial_count - repositories.len();
+        if archived_count > 0 {
+            tracing::info!(
+                "Filtered out {} archived Bitbucket repositories (kept {} active)",
+                archived_count,
+                repositories.len()
+            );
+        }
+
+        Ok(repositories)
+    }
+
+    /// List every project visible to the token
+    pub async fn list_projects(&self, bitbucket_url: &str, access_token: &str) -> Result<Vec<BitbucketProject>> {
+        let url = format!("{}/rest/api/1.0/projects", bitbucket_url.trim_end_matches('/'));
+        self.get_all_pages(&url, access_token).await.context("Failed to fetch Bitbucket projects")
+    }
+
+    /// List the repositories of a project
+    pub async fn list_project_repositories(
+        &self,
+        bitbucket_url: &str,
+        access_token: &str,
+        project_key: &str,
+    ) -> Result<Vec<BitbucketRepository>> {
+        let url = format!(
+            "{}/rest/api/1.0/projects/{}/repos",
+            bitbucket_url.trim_end_matches('/'),
+            urlencoding::encode(project_key)
+        );
+        self.get_all_pages(&url, access_token)
+            .await
+            .with_context(|| format!("Failed to fetch repositories of Bitbucket project {}", project_key))
+    }
+
+    /// Follow `nextPageStart` until the server reports the last page
+    async fn get_all_pages<T: DeserializeOwned>(&self, url: &str, access_token: &str) -> Result<Vec<T>> {
+        let mut values = Vec::new();
+        let mut start = 0;
+        let limit = 100;
+
+        loop {
+            let page_url = format!("{}?start={}&limit={}", url, start, limit);
+
+            tracing::debug!("Making Bitbucket API request to: {}", page_url);
+            let response = self
+                .client
+                .get(&page_url)
+                .header("Authorization", format!("Bearer {}", access_token))
+                .header("Accept", "application/json")
+                .send()
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let error_body = response.text().await.unwrap_or_default();
+                tracing::error!(
+                    "Bitbucket API request failed - URL: {}, Status: {}, Body: {}",
+                    page_url,
+                    status,
+                    error_body
+                );
+                return Err(anyhow!("Bitbucket API error: {} - {}", status, error_body));
+            }
+
+            let page: BitbucketPage<T> = response.json().await.context("Failed to parse Bitbucket response")?;
+            values.extend(page.values);
+
+            match page.next_page_start {
+                Some(next) if !page.is_last_page && next > start => start = next,
+                _ => break,
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Test if the access token is valid
+    pub async fn test_token(&self, bitbucket_url: &str, access_token: &str) -> Result<bool> {
+        let url = format!("{}/rest/api/1.0/projects?limit=1", bitbucket_url.trim_end_matches('/'));
+
+        tracing::debug!("Testing Bitbucket token with URL: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", access_token))
+            .header("Accept", "application/json")
+            .send()
+            .await;
+
+        match response {
+            Ok(resp) => {
+                let status = resp.status();
+                tracing::info!("Bitbucket token test status: {}", status);
+
+                if status.is_success() {
+                    Ok(true)
+                } else {
+                    let error_body = resp.text().await.unwrap_or_default();
+                    tracing::error!("Bitbucket token test failed: {} - {}", status, error_body);
+                    Ok(false)
+                }
+            }
+            Err(e) => {
+                tracing::error!("Bitbucket API request failed for URL: {}", url);
+                tracing::error!("Request error: {}", e);
+                Err(anyhow!("Network error: {} (URL: {})", e, url))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_names_and_clone_url() {
+        let repository: BitbucketRepository = serde_json::from_value(serde_json::json!({
+            "slug": "api",
+            "id": 1,
+            "name": "API",
+            "scmId": "git",
+            "state": "AVAILABLE",
+            "project": { "key": "PLAT", "id": 1, "name": "Platform" },
+            "links": {
+                "clone": [
+                    { "href": "ssh://git@bitbucket.example.com:7999/plat/api.git", "name": "ssh" },
+                    { "href": "https://admin@bitbucket.example.com/scm/plat/api.git", "name": "http" }
+                ]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(repository.full_name(), "PLAT/api");
+        assert_eq!(
+            repository.http_clone_url(),
+            Some("https://admin@bitbucket.example.com/scm/plat/api.git")
+        );
+        assert!(!repository.archived);
+    }
+}
@@ -0,0 +1,331 @@
+//! Archive contents for crawled files
+//!
+//! When enabled by the file inclusion rules, archives found in a tree (zip, jar, war, ear, tar, tar.gz)
+//! are opened and their entries indexed under virtual paths such as `lib/foo.jar!/com/x/Config.properties`.
+//! Nested archives are opened up to `MAX_ARCHIVE_DEPTH` levels, and extraction stops at the entry count
+//! and extracted size limits, so that a single archive cannot exhaust the crawler.
+
+use super::git_tree_walker::MAX_FILE_SIZE;
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read};
+use tracing::{debug, warn};
+
+/// Separator between an archive path and the path of an entry inside it
+pub const ARCHIVE_SEPARATOR: &str = "!/";
+
+/// Maximum size of an archive to open
+pub const MAX_ARCHIVE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Maximum nesting of archives inside archives (1 only opens archives found in the tree)
+const MAX_ARCHIVE_DEPTH: usize = 2;
+
+/// Maximum number of entries read from one archive, nested archives included
+const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+
+/// Maximum total size extracted from one archive, nested archives included
+const MAX_EXTRACTED_SIZE: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+fn archive_kind(path: &str) -> Option<ArchiveKind> {
+    let name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else if [".zip", ".jar", ".war", ".ear"].iter().any(|ext| name.ends_with(ext)) {
+        Some(ArchiveKind::Zip)
+    } else {
+        None
+    }
+}
+
+/// Whether a file is an archive that the crawler can open
+pub fn is_archive(path: &str) -> bool {
+    archive_kind(path).is_some()
+}
+
+/// Path of the archive (in the repository) containing a virtual path, e.g. `lib/foo.jar` for
+/// `lib/foo.jar!/com/x/Config.properties`
+pub fn containing_archive(path: &str) -> Option<&str> {
+    path.split_once(ARCHIVE_SEPARATOR).map(|(archive, _)| archive)
+}
+
+/// A file read from an archive
+#[derive(Debug)]
+pub struct ArchiveEntry {
+    /// Virtual path, e.g. `lib/foo.jar!/com/x/Config.properties`
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+/// Limits shared by an archive and the archives nested in it
+struct ExtractionBudget {
+    entries: usize,
+    bytes: u64,
+}
+
+impl ExtractionBudget {
+    fn is_exhausted(&self) -> bool {
+        self.entries >= MAX_ARCHIVE_ENTRIES || self.bytes >= MAX_EXTRACTED_SIZE
+    }
+}
+
+/// Read the files of an archive located at `archive_path`.
+///
+/// `include` decides from an entry's path inside its archive whether it is read; nested archives it
+/// accepts are opened in turn.
+pub fn extract_entries(archive_path: &str, bytes: &[u8], include: &dyn Fn(&str) -> bool) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = Vec::new();
+    let mut budget = ExtractionBudget { entries: 0, bytes: 0 };
+    extract(archive_path, bytes, 1, include, &mut budget, &mut entries)?;
+    if budget.is_exhausted() {
+        warn!(
+            "Stopped reading archive {} after {} entries ({} bytes)",
+            archive_path, budget.entries, budget.bytes
+        );
+    }
+    Ok(entries)
+}
+
+fn extract(
+    archive_path: &str,
+    bytes: &[u8],
+    depth: usize,
+    include: &dyn Fn(&str) -> bool,
+    budget: &mut ExtractionBudget,
+    entries: &mut Vec<ArchiveEntry>,
+) -> Result<()> {
+    match archive_kind(archive_path) {
+        Some(ArchiveKind::Zip) => {
+            let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+            for index in 0..archive.len() {
+                if budget.is_exhausted() {
+                    break;
+                }
+                let mut file = archive.by_index(index)?;
+                if file.is_dir() {
+                    continue;
+                }
+                let name = file.name().to_string();
+                let size = file.size();
+                read_entry(archive_path, &name, size, &mut file, depth, include, budget, entries)?;
+            }
+        }
+        Some(kind @ (ArchiveKind::Tar | ArchiveKind::TarGz)) => {
+            let reader: Box<dyn Read> = if kind == ArchiveKind::TarGz {
+                Box::new(GzDecoder::new(bytes))
+            } else {
+                Box::new(bytes)
+            };
+            let mut archive = tar::Archive::new(reader);
+            for entry in archive.entries()? {
+                if budget.is_exhausted() {
+                    break;
+                }
+                let mut entry = entry?;
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+                let name = entry.path()?.to_string_lossy().to_string();
+                let size = entry.header().size()?;
+                read_entry(archive_path, &name, size, &mut entry, depth, include, budget, entries)?;
+            }
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Read one file of an archive, opening it when it is a nested archive. `declared_size` is the size
+/// recorded in the archive, which may not match the actual content.
+#[allow(clippy::too_many_arguments)]
+fn read_entry(
+    archive_path: &str,
+    name: &str,
+    declared_size: u64,
+    reader: &mut dyn Read,
+    depth: usize,
+    include: &dyn Fn(&str) -> bool,
+    budget: &mut ExtractionBudget,
+    entries: &mut Vec<ArchiveEntry>,
+) -> Result<()> {
+    let name = name.trim_start_matches("./").trim_start_matches('/');
+    if name.is_empty() || name.ends_with('/') || !include(name) {
+        return Ok(());
+    }
+
+    let nested = is_archive(name);
+    if nested && depth >= MAX_ARCHIVE_DEPTH {
+        debug!(
+            "Not opening nested archive {}{}{}",
+            archive_path, ARCHIVE_SEPARATOR, name
+        );
+        return Ok(());
+    }
+
+    let limit = if nested { MAX_ARCHIVE_SIZE } else { MAX_FILE_SIZE };
+    budget.entries += 1;
+    if declared_size > limit {
+        debug!(
+            "Skipping large archive entry: {}{}{} ({} bytes)",
+            archive_path, ARCHIVE_SEPARATOR, name, declared_size
+        );
+        return Ok(());
+    }
+
+    // Everything read counts against the budget, including entries larger than declared that are skipped
+    let mut content = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut content)?;
+    budget.bytes += content.len() as u64;
+    if content.len() as u64 > limit {
+        debug!(
+            "Skipping large archive entry: {}{}{}",
+            archive_path, ARCHIVE_SEPARATOR, name
+        );
+        return Ok(());
+    }
+
+    let path = format!("{}{}{}", archive_path, ARCHIVE_SEPARATOR, name);
+    if nested {
+        if let Err(e) = extract(&path, &content, depth + 1, include, budget, entries) {
+            warn!("Failed to read nested archive {}: {}", path, e);
+        }
+    } else {
+        entries.push(ArchiveEntry { path, content });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_bytes(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz_bytes(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast()));
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *content).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn paths(entries: &[ArchiveEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_archive_kinds() {
+        assert!(is_archive("lib/foo.jar"));
+        assert!(is_archive("dist/app.WAR"));
+        assert!(is_archive("release/src.tar.gz"));
+        assert!(is_archive("release/src.tgz"));
+        assert!(!is_archive("src/archive.rs"));
+        assert_eq!(
+            containing_archive("lib/foo.jar!/com/x/Config.properties"),
+            Some("lib/foo.jar")
+        );
+        assert_eq!(containing_archive("src/main.rs"), None);
+    }
+
+    #[test]
+    fn test_zip_entries_with_virtual_paths() {
+        let jar = zip_bytes(&[
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0"),
+            ("com/x/Config.properties", b"timeout=30"),
+            ("com/x/Config.class", b"\xCA\xFE\xBA\xBE"),
+        ]);
+        let entries = extract_entries("lib/foo.jar", &jar, &|name| !name.ends_with(".class")).unwrap();
+        assert_eq!(
+            paths(&entries),
+            ["lib/foo.jar!/META-INF/MANIFEST.MF", "lib/foo.jar!/com/x/Config.properties"]
+        );
+        assert_eq!(entries[1].content, b"timeout=30");
+    }
+
+    #[test]
+    fn test_tar_gz_and_nested_archives() {
+        let inner = zip_bytes(&[("application.yml", b"port: 8080")]);
+        let outer = tar_gz_bytes(&[("./README.md", b"# Release"), ("lib/inner.jar", &inner)]);
+        let entries = extract_entries("release.tar.gz", &outer, &|_| true).unwrap();
+        assert_eq!(
+            paths(&entries),
+            ["release.tar.gz!/README.md", "release.tar.gz!/lib/inner.jar!/application.yml"]
+        );
+
+        // Archives nested deeper than the limit are not opened
+        let innermost = zip_bytes(&[("deep.txt", b"deep")]);
+        let middle = zip_bytes(&[("innermost.zip", &innermost)]);
+        let outer = zip_bytes(&[("middle.zip", &middle)]);
+        assert!(extract_entries("outer.zip", &outer, &|_| true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_large_entries_count_against_the_budget() {
+        struct Unreadable;
+        impl Read for Unreadable {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                panic!("entries declared too large are not read");
+            }
+        }
+
+        let mut budget = ExtractionBudget { entries: 0, bytes: 0 };
+        let mut entries = Vec::new();
+        let include = |_: &str| true;
+        read_entry(
+            "a.zip",
+            "big.txt",
+            MAX_FILE_SIZE + 1,
+            &mut Unreadable,
+            1,
+            &include,
+            &mut budget,
+            &mut entries,
+        )
+        .unwrap();
+        assert_eq!(budget.entries, 1);
+        assert_eq!(budget.bytes, 0);
+
+        // An entry larger than its declared size is skipped once read, and what was read is counted
+        let mut reader = std::io::repeat(b'a');
+        read_entry(
+            "a.zip",
+            "lying.txt",
+            10,
+            &mut reader,
+            1,
+            &include,
+            &mut budget,
+            &mut entries,
+        )
+        .unwrap();
+        assert!(entries.is_empty());
+        assert_eq!(budget.entries, 2);
+        assert_eq!(budget.bytes, MAX_FILE_SIZE + 1);
+    }
+
+    #[test]
+    fn test_corrupt_archive() {
+        assert!(extract_entries("broken.zip", b"not a zip file", &|_| true).is_err());
+    }
+}
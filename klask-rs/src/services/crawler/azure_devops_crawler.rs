@@ -0,0 +1,65 @@
+use super::filter::filter_projects;
+use super::provider_crawler::{DiscoveredRepository, RepositoryDiscovery};
+use crate::models::Repository;
+use crate::services::azure_devops::AzureDevOpsService;
+use anyhow::Result;
+use tracing::warn;
+
+/// Discovery of the repositories of the projects of an Azure DevOps organization
+pub struct AzureDevOpsDiscovery {
+    service: AzureDevOpsService,
+    organization_url: String, // The repository URL is the organization or collection address
+}
+
+impl AzureDevOpsDiscovery {
+    pub fn new(repository: &Repository) -> Self {
+        Self { service: AzureDevOpsService::new(), organization_url: repository.url.clone() }
+    }
+}
+
+impl RepositoryDiscovery for AzureDevOpsDiscovery {
+    fn provider(&self) -> &'static str {
+        "Azure DevOps"
+    }
+
+    async fn test_token(&self, access_token: &str) -> Result<bool> {
+        self.service.test_token(&self.organization_url, access_token).await
+    }
+
+    async fn discover(
+        &self,
+        repository: &Repository,
+        access_token: &str,
+        errors: &mut Vec<String>,
+    ) -> Result<Vec<DiscoveredRepository>> {
+        let projects = self.service.list_projects(&self.organization_url, access_token).await?;
+
+        // Apply filtering logic to project names: included filters first, then exclusions
+        let filtered_project_names = filter_projects(
+            projects.into_iter().map(|p| p.name).collect(),
+            repository.included_projects.as_deref(),
+            repository.included_projects_patterns.as_deref(),
+            repository.excluded_projects.as_deref(),
+            repository.excluded_projects_patterns.as_deref(),
+        );
+
+        // Discover the repositories of the remaining projects
+        let mut discovered = Vec::new();
+        for project_name in &filtered_project_names {
+            match self.service.list_project_repositories(&self.organization_url, access_token, project_name).await {
+                Ok(repositories) => discovered.extend(repositories.into_iter().map(|r| DiscoveredRepository {
+                    full_name: r.full_name(),
+                    default_branch: r.default_branch_name(),
+                    clone_url: Some(r.remote_url),
+                })),
+                Err(e) => {
+                    let error_msg = format!("Failed to discover repositories of project {}: {}", project_name, e);
+                    warn!("{}", error_msg);
+                    errors.push(error_msg);
+                }
+            }
+        }
+
+        Ok(discovered)
+    }
+}
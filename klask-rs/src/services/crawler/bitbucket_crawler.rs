@@ -0,0 +1,62 @@
+use super::filter::filter_projects;
+use super::provider_crawler::{DiscoveredRepository, RepositoryDiscovery};
+use crate::models::Repository;
+use crate::services::bitbucket::BitbucketService;
+use anyhow::Result;
+
+/// Discovery of the repositories of a Bitbucket Server repository
+pub struct BitbucketDiscovery {
+    service: BitbucketService,
+    bitbucket_url: String, // The repository URL is the address of the Bitbucket server
+}
+
+impl BitbucketDiscovery {
+    pub fn new(repository: &Repository) -> Self {
+        Self { service: BitbucketService::new(), bitbucket_url: repository.url.clone() }
+    }
+}
+
+impl RepositoryDiscovery for BitbucketDiscovery {
+    fn provider(&self) -> &'static str {
+        "Bitbucket"
+    }
+
+    async fn test_token(&self, access_token: &str) -> Result<bool> {
+        self.service.test_token(&self.bitbucket_url, access_token).await
+    }
+
+    async fn discover(
+        &self,
+        repository: &Repository,
+        access_token: &str,
+        _errors: &mut Vec<String>,
+    ) -> Result<Vec<DiscoveredRepository>> {
+        let repositories = self
+            .service
+            .discover_repositories(
+                &self.bitbucket_url,
+                access_token,
+                repository.bitbucket_project_key.as_deref(),
+            )
+            .await?;
+
+        // Apply filtering logic to "PROJECT/slug" names: included filters first, then exclusions
+        let filtered_repo_names = filter_projects(
+            repositories.iter().map(|r| r.full_name()).collect(),
+            repository.included_projects.as_deref(),
+            repository.included_projects_patterns.as_deref(),
+            repository.excluded_projects.as_deref(),
+            repository.excluded_projects_patterns.as_deref(),
+        );
+
+        Ok(repositories
+            .iter()
+            .filter(|r| filtered_repo_names.contains(&r.full_name()))
+            .map(|r| DiscoveredRepository {
+                full_name: r.full_name(),
+                clone_url: r.http_clone_url().map(str::to_string),
+                default_branch: None, // Not part of the repository listing; every branch is crawled anyway
+            })
+            .collect())
+    }
+}
@@ -1,11 +1,19 @@
-use super::file_processing::FileProcessor;
+use super::encoding::{decode_text, encoding_for_label};
+use super::file_processing::{ExtractedText, FileOutcome, FileProcessor, extract_document};
 use super::filter::filter_branches;
-use super::git_tree_walker::GitTreeWalker;
-use crate::models::Repository;
+use super::git_tree_walker::{BlobText, GitTreeWalker};
+use super::ignore_files::IgnoreRules;
+use super::large_files::LargeFilePolicy;
+use super::review_refs::fetched_review_versions;
+use super::submodules::MAX_SUBMODULE_DEPTH;
+use crate::models::{FileInclusionRules, Repository};
+use crate::services::encryption::EncryptionService;
+use crate::services::lfs::{LfsPointer, LfsService};
 use crate::services::progress::ProgressTracker;
 use crate::services::search::SearchService;
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use gix::ObjectId;
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
@@ -24,20 +32,51 @@ pub struct GitFileEntry {
 pub struct CrawlProgress {
     pub files_processed: usize,
     pub files_indexed: usize,
+    pub files_skipped_binary: usize,
     pub errors: Vec<String>,
 }
 
+/// Content read from a Git blob
+enum BlobContent {
+    Text(ExtractedText),
+    Archive(Vec<u8>),
+    Binary,
+    TooLarge,
+    LfsPointer(LfsPointer),
+}
+
 /// Branch processing operations for the crawler
 #[derive(Clone)]
 pub struct BranchProcessor {
+    search_service: Arc<SearchService>,
     progress_tracker: Arc<ProgressTracker>,
     file_processor: FileProcessor,
+    inclusion_rules: Arc<std::sync::RwLock<FileInclusionRules>>,
+    encryption_service: Arc<EncryptionService>,
+    lfs_service: LfsService,
 }
 
 impl BranchProcessor {
-    pub fn new(search_service: Arc<SearchService>, progress_tracker: Arc<ProgressTracker>) -> Self {
+    pub fn new(
+        search_service: Arc<SearchService>,
+        progress_tracker: Arc<ProgressTracker>,
+        inclusion_rules: Arc<std::sync::RwLock<FileInclusionRules>>,
+        encryption_service: Arc<EncryptionService>,
+    ) -> Self {
         let file_processor = FileProcessor::new(search_service.clone());
-        Self { progress_tracker, file_processor }
+        Self {
+            search_service,
+            progress_tracker,
+            file_processor,
+            inclusion_rules,
+            encryption_service,
+            lfs_service: LfsService::new(),
+        }
+    }
+
+    /// Effective file inclusion rules for a repository (global rules plus the repository's own)
+    fn inclusion_rules_for(&self, repository: &Repository) -> FileInclusionRules {
+        self.inclusion_rules.read().unwrap_or_else(|e| e.into_inner()).for_repository(repository)
     }
 
     /// Process all branches in a repository
@@ -49,6 +88,8 @@ impl BranchProcessor {
         cancellation_token: &CancellationToken,
     ) -> Result<()> {
         info!("Discovering branches for repository: {}", repository.name);
+        // The default branch of the repository or hosted project is boosted by the ranking
+        self.search_service.set_default_branch(&repository.name, repository.branch.as_deref());
 
         // Get all branches using gix
         let repo_path_owned = repo_path.to_owned();
@@ -146,6 +187,7 @@ impl BranchProcessor {
         // Just call the normal process_all_branches but pass through the tracking info
         // to each branch processing call
         info!("Discovering branches for repository: {}", repository.name);
+        self.search_service.set_default_branch(&repository.name, repository.branch.as_deref());
 
         // Get all branches using gix
         let repo_path_owned = repo_path.to_owned();
@@ -167,29 +209,47 @@ impl BranchProcessor {
         if filtered_branches.is_empty() {
             info!("No branches matched the filter criteria, using default branch");
             let branch_name = repository.branch.as_deref().unwrap_or("main");
-            return self
-                .process_repository_files_internal(
-                    repository,
-                    repo_path,
-                    branch_name,
-                    progress,
-                    cancellation_token,
-                    Some((parent_repository_id, _project_start_files)),
-                    Some(parent_project_name),
-                )
-                .await;
+            self.process_repository_files_internal(
+                repository,
+                repo_path,
+                branch_name,
+                progress,
+                cancellation_token,
+                Some((parent_repository_id, _project_start_files)),
+                Some(parent_project_name),
+            )
+            .await?;
+        } else {
+            info!(
+                "Found {} branches for repository {}, after filtering: {} branches will be processed: {:?}",
+                branches.len(),
+                repository.name,
+                filtered_branches.len(),
+                filtered_branches
+            );
         }
 
-        info!(
-            "Found {} branches for repository {}, after filtering: {} branches will be processed: {:?}",
-            branches.len(),
-            repository.name,
-            filtered_branches.len(),
-            filtered_branches
-        );
+        // Open merge requests / pull requests fetched by the provider crawler are indexed as their own versions,
+        // whatever the branch filters
+        let mut versions = filtered_branches;
+        if repository.index_merge_requests == Some(true) {
+            let repo_path_owned = repo_path.to_owned();
+            let reviews = tokio::task::spawn_blocking(move || -> Result<Vec<String>> {
+                fetched_review_versions(&gix::open(&repo_path_owned)?)
+            })
+            .await??;
+            if !reviews.is_empty() {
+                info!(
+                    "Found {} open merge requests / pull requests for repository {}",
+                    reviews.len(),
+                    repository.name
+                );
+            }
+            versions.extend(reviews);
+        }
 
         // Process each branch
-        for branch_name in filtered_branches {
+        for branch_name in versions {
             if cancellation_token.is_cancelled() {
                 return Ok(());
             }
@@ -240,40 +300,125 @@ impl BranchProcessor {
         parent_repository_id: Option<Uuid>,
         parent_project_name: Option<&str>, // Parent repository name for GitLab/GitHub multi-project repos
     ) -> Result<()> {
-        use super::git_tree_walker::{GitFileEntry, MAX_FILE_SIZE};
+        self.process_branch_files(
+            repository,
+            repo_path,
+            branch_name,
+            None,
+            progress,
+            cancellation_token,
+            parent_repository_id,
+            parent_project_name,
+        )
+        .await
+    }
+
+    /// Process only the given files of a branch, e.g. the files changed by a push (see `GitTreeWalker::changed_paths`).
+    /// Paths that are no longer in the branch are skipped.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn process_branch_paths(
+        &self,
+        repository: &Repository,
+        repo_path: &Path,
+        branch_name: &str,
+        paths: &HashSet<String>,
+        progress: &mut CrawlProgress,
+        cancellation_token: &CancellationToken,
+        parent_project_name: Option<&str>,
+    ) -> Result<()> {
+        self.process_branch_files(
+            repository,
+            repo_path,
+            branch_name,
+            Some(paths),
+            progress,
+            cancellation_token,
+            None,
+            parent_project_name,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn process_branch_files(
+        &self,
+        repository: &Repository,
+        repo_path: &Path,
+        branch_name: &str,
+        only_paths: Option<&HashSet<String>>,
+        progress: &mut CrawlProgress,
+        cancellation_token: &CancellationToken,
+        parent_repository_id: Option<Uuid>,
+        parent_project_name: Option<&str>,
+    ) -> Result<()> {
+        use super::archive::MAX_ARCHIVE_SIZE;
+        use super::git_tree_walker::GitFileEntry;
 
         let repo_path_owned = repo_path.to_owned();
         let branch_name_owned = branch_name.to_string();
+        let submodule_depth = if repository.index_submodules == Some(true) { MAX_SUBMODULE_DEPTH } else { 0 };
 
-        // Get tree ID and files from the Git database
-        let files = tokio::task::spawn_blocking(move || -> Result<Vec<GitFileEntry>> {
+        // Get tree ID, files and ignore rules from the Git database
+        let (files, ignore_rules) = tokio::task::spawn_blocking(move || -> Result<(Vec<GitFileEntry>, IgnoreRules)> {
             let git_repo = gix::open(&repo_path_owned)?;
 
             // Get the tree ID for this branch
             let tree_id = GitTreeWalker::get_branch_tree_id(&git_repo, &branch_name_owned)?;
 
             // Walk the tree and collect all files
-            let files = GitTreeWalker::walk_tree(&git_repo, &tree_id, "")?;
+            let files = GitTreeWalker::walk_tree(&git_repo, &tree_id, "", submodule_depth)?;
 
             info!("Found {} files in branch '{}'", files.len(), branch_name_owned);
-            Ok(files)
+
+            // Read .klaskignore and .gitattributes files committed in this branch
+            let mut ignore_files = Vec::new();
+            for file in &files {
+                let file_name = file.path.rsplit('/').next().unwrap_or(&file.path);
+                if IgnoreRules::is_ignore_file(file_name, false)
+                    && let BlobText::Text(content) = GitTreeWalker::read_blob_content(&git_repo, &file.oid, None)?
+                {
+                    ignore_files.push((file.path.clone(), content));
+                }
+            }
+            if !ignore_files.is_empty() {
+                debug!(
+                    "Read {} ignore files in branch '{}'",
+                    ignore_files.len(),
+                    branch_name_owned
+                );
+            }
+            let ignore_rules =
+                IgnoreRules::from_files(ignore_files.iter().map(|(path, content)| (path.as_str(), content.as_str())));
+
+            Ok((files, ignore_rules))
         })
         .await??;
 
+        // Ignore rules are read from the whole tree, only the requested files are indexed
+        let mut files = files;
+        if let Some(only_paths) = only_paths {
+            files.retain(|file| only_paths.contains(&file.path));
+        }
+
+        let rules = self.inclusion_rules_for(repository);
+        let is_indexed = |path: &str| rules.is_included(path) && !ignore_rules.is_ignored(path);
+
         // Update progress tracking if parent_repository_id is provided
         if let Some(parent_id) = parent_repository_id {
             let project_with_branch = format!("{} ({})", repository.name, branch_name);
             self.progress_tracker.set_current_gitlab_project(parent_id, Some(project_with_branch)).await;
 
-            // Filter to supported files for progress tracking
-            let supported_files: Vec<&GitFileEntry> =
-                files.iter().filter(|f| Self::is_supported_file_static(std::path::Path::new(&f.path))).collect();
+            // Filter to included files for progress tracking
+            let supported_files: Vec<&GitFileEntry> = files.iter().filter(|f| is_indexed(&f.path)).collect();
 
             self.progress_tracker.set_current_project_files_total(parent_id, supported_files.len()).await;
         }
 
         let total_files = files.len();
         let repo_path_owned = repo_path.to_owned();
+        let default_encoding = repository.default_encoding.as_deref().and_then(encoding_for_label);
+        let read_limit = LargeFilePolicy::for_repository(repository).read_limit();
+        let tracked_repository_id = parent_repository_id.unwrap_or(repository.id);
 
         info!(
             "Starting to process {} files for branch '{}' in repository {}",
@@ -284,18 +429,20 @@ impl BranchProcessor {
         let mut files_read_success = 0;
         let mut files_read_failed = 0;
         let mut files_binary_skipped = 0;
+        let mut files_too_large = 0;
 
         // Process each file by reading directly from Git
         for (idx, file_entry) in files.iter().enumerate() {
             if idx % 100 == 0 && idx > 0 {
                 debug!(
-                    "Progress: {}/{} files in branch '{}' - indexed: {}, skipped_filter: {}, binary/too_large: {}, failed: {}",
+                    "Progress: {}/{} files in branch '{}' - indexed: {}, skipped_filter: {}, binary: {}, too_large: {}, failed: {}",
                     idx,
                     total_files,
                     branch_name,
                     files_read_success,
                     files_skipped_by_filter,
                     files_binary_skipped,
+                    files_too_large,
                     files_read_failed
                 );
             }
@@ -305,8 +452,8 @@ impl BranchProcessor {
                 return Ok(());
             }
 
-            // Check if file is supported - skip unsupported files
-            if !Self::is_supported_file_static(std::path::Path::new(&file_entry.path)) {
+            // Skip files not matched by the file inclusion rules or excluded by ignore files
+            if !is_indexed(&file_entry.path) {
                 files_skipped_by_filter += 1;
                 continue;
             }
@@ -315,39 +462,68 @@ impl BranchProcessor {
             let repo_path_for_task = repo_path_owned.clone();
             let oid = file_entry.oid;
             let path = file_entry.path.clone();
+            let opens_archive = rules.opens_archive(&path);
+            let extractor = self.file_processor.extractor_for(&path);
+            let is_text_file = !opens_archive && extractor.is_none();
 
-            let content_result = tokio::task::spawn_blocking(move || -> Result<Option<String>> {
+            let content_result = tokio::task::spawn_blocking(move || -> Result<BlobContent> {
                 let git_repo = gix::open(&repo_path_for_task)?;
 
+                // Archives and documents tracked by Git LFS are pointers too (text files are detected when read)
+                if !is_text_file && let Some(pointer) = GitTreeWalker::read_lfs_pointer(&git_repo, &oid)? {
+                    return Ok(BlobContent::LfsPointer(pointer));
+                }
+
+                // Archives are read as is, their files are extracted later
+                if opens_archive {
+                    let bytes = GitTreeWalker::read_blob_bytes(&git_repo, &oid)?;
+                    if bytes.len() as u64 > MAX_ARCHIVE_SIZE {
+                        debug!("[GIT] Skipping large archive: {} (> {} bytes)", path, MAX_ARCHIVE_SIZE);
+                        return Ok(BlobContent::TooLarge);
+                    }
+                    return Ok(BlobContent::Archive(bytes));
+                }
+
                 // Check file size first
-                if !GitTreeWalker::check_blob_size(&git_repo, &oid)? {
-                    debug!("[GIT] Skipping large file: {} (> {} bytes)", path, MAX_FILE_SIZE);
-                    return Ok(None);
+                if !GitTreeWalker::check_blob_size(&git_repo, &oid, read_limit)? {
+                    debug!("[GIT] Skipping large file: {} (> {} bytes)", path, read_limit);
+                    return Ok(BlobContent::TooLarge);
                 }
 
-                // Read the content
-                let result = GitTreeWalker::read_blob_content(&git_repo, &oid);
-                match &result {
-                    Ok(Some(content)) => {
+                // Documents are indexed with the text extracted from them
+                if let Some(extractor) = extractor {
+                    let bytes = GitTreeWalker::read_blob_bytes(&git_repo, &oid)?;
+                    return Ok(match extract_document(extractor.as_ref(), &path, &bytes) {
+                        Some(extracted) => BlobContent::Text(extracted),
+                        None => BlobContent::Binary,
+                    });
+                }
+
+                // Read the content, transcoded to UTF-8
+                match GitTreeWalker::read_blob_content(&git_repo, &oid, default_encoding) {
+                    Ok(BlobText::Text(content)) => {
                         debug!(
                             "[GIT] Successfully read blob for file {} ({} bytes)",
                             path,
                             content.len()
                         );
+                        Ok(BlobContent::Text(content.into()))
                     }
-                    Ok(None) => {
-                        debug!("[GIT] Blob returned None (likely binary) for file {}", path);
+                    Ok(BlobText::Binary) => {
+                        debug!("[GIT] Blob is binary for file {}", path);
+                        Ok(BlobContent::Binary)
                     }
+                    Ok(BlobText::LfsPointer(pointer)) => Ok(BlobContent::LfsPointer(pointer)),
                     Err(e) => {
                         debug!("[GIT] Failed to read blob for file {}: {}", path, e);
+                        Err(e)
                     }
                 }
-                result
             })
             .await;
 
             match content_result {
-                Ok(Ok(Some(content))) => {
+                Ok(Ok(BlobContent::Text(content))) => {
                     // Index the file - pass the content we already read from Git
                     let file_path = std::path::PathBuf::from(&file_entry.path);
                     match self
@@ -362,7 +538,7 @@ impl BranchProcessor {
                         )
                         .await
                     {
-                        Ok(()) => {
+                        Ok(FileOutcome::Indexed) => {
                             progress.files_indexed += 1;
                             files_read_success += 1;
                             debug!(
@@ -370,6 +546,88 @@ impl BranchProcessor {
                                 file_entry.path, branch_name
                             );
                         }
+                        Ok(FileOutcome::Binary) => {
+                            files_binary_skipped += 1;
+                            progress.files_skipped_binary += 1;
+                            self.progress_tracker.record_binary_skipped(tracked_repository_id).await;
+                        }
+                        Ok(FileOutcome::TooLarge) => {
+                            files_too_large += 1;
+                            debug!("[GIT] Skipped file {} (too large)", file_entry.path);
+                        }
+                        Err(e) => {
+                            files_read_failed += 1;
+                            warn!("[GIT] Failed to index file {}: {}", file_entry.path, e);
+                            progress.errors.push(format!("Failed to index {}: {}", file_entry.path, e));
+                        }
+                    }
+
+                    progress.files_processed += 1;
+                }
+                Ok(Ok(BlobContent::Archive(bytes))) => {
+                    match self
+                        .file_processor
+                        .process_archive(
+                            repository,
+                            &file_entry.path,
+                            bytes,
+                            branch_name,
+                            parent_project_name,
+                            &rules,
+                        )
+                        .await
+                    {
+                        Ok(indexed) => {
+                            progress.files_indexed += indexed;
+                            files_read_success += indexed;
+                            debug!(
+                                "[GIT] Indexed {} files from archive {} in branch '{}'",
+                                indexed, file_entry.path, branch_name
+                            );
+                        }
+                        Err(e) => {
+                            files_read_failed += 1;
+                            warn!("[GIT] Failed to index archive {}: {}", file_entry.path, e);
+                            progress.errors.push(format!("Failed to index {}: {}", file_entry.path, e));
+                        }
+                    }
+
+                    progress.files_processed += 1;
+                }
+                Ok(Ok(BlobContent::LfsPointer(pointer))) => {
+                    // Indexed by path, with the content of text files when the repository fetches LFS objects
+                    let content = if is_text_file {
+                        self.fetch_lfs_content(repository, &file_entry.path, &pointer).await
+                    } else {
+                        None
+                    };
+                    let file_path = std::path::PathBuf::from(&file_entry.path);
+                    match self
+                        .file_processor
+                        .process_lfs_file(
+                            repository,
+                            &file_path,
+                            &file_entry.path,
+                            branch_name,
+                            parent_project_name,
+                            &pointer,
+                            content,
+                        )
+                        .await
+                    {
+                        Ok(FileOutcome::Indexed) => {
+                            progress.files_indexed += 1;
+                            files_read_success += 1;
+                        }
+                        Ok(FileOutcome::Binary) => {
+                            files_binary_skipped += 1;
+                            progress.files_skipped_binary += 1;
+                            self.progress_tracker.record_binary_skipped(tracked_repository_id).await;
+                        }
+                        Ok(FileOutcome::TooLarge) => {
+                            files_too_large += 1;
+                            debug!("[GIT] Skipped file {} (too large)", file_entry.path);
+                        }
                         Err(e) => {
                             files_read_failed += 1;
                             warn!("[GIT] Failed to index file {}: {}", file_entry.path, e);
@@ -379,10 +637,15 @@ impl BranchProcessor {
 
                     progress.files_processed += 1;
                 }
-                Ok(Ok(None)) => {
-                    // Skipped (binary or too large)
+                Ok(Ok(BlobContent::Binary)) => {
                     files_binary_skipped += 1;
-                    debug!("[GIT] Skipped file {} (binary or too large)", file_entry.path);
+                    progress.files_skipped_binary += 1;
+                    self.progress_tracker.record_binary_skipped(tracked_repository_id).await;
+                    debug!("[GIT] Skipped binary file {}", file_entry.path);
+                }
+                Ok(Ok(BlobContent::TooLarge)) => {
+                    files_too_large += 1;
+                    debug!("[GIT] Skipped file {} (too large)", file_entry.path);
                 }
                 Ok(Err(e)) => {
                     files_read_failed += 1;
@@ -398,12 +661,13 @@ impl BranchProcessor {
         }
 
         info!(
-            "Completed branch '{}': total={}, indexed={}, skipped_filter={}, binary/too_large={}, failed={}",
+            "Completed branch '{}': total={}, indexed={}, skipped_filter={}, binary={}, too_large={}, failed={}",
             branch_name,
             total_files,
             files_read_success,
             files_skipped_by_filter,
             files_binary_skipped,
+            files_too_large,
             files_read_failed
         );
 
@@ -412,6 +676,55 @@ impl BranchProcessor {
         Ok(())
     }
 
+    /// Download the content of a text file tracked by Git LFS, when the repository fetches LFS objects and the
+    /// object is within its size limit. `None` leaves the file indexed by path only.
+    async fn fetch_lfs_content(
+        &self,
+        repository: &Repository,
+        relative_path: &str,
+        pointer: &LfsPointer,
+    ) -> Option<ExtractedText> {
+        if repository.fetch_lfs_objects != Some(true) {
+            return None;
+        }
+        let read_limit = LargeFilePolicy::for_repository(repository).read_limit();
+        if pointer.size > read_limit {
+            debug!(
+                "[LFS] Not fetching large file: {} ({} bytes)",
+                relative_path, pointer.size
+            );
+            return None;
+        }
+
+        let access_token = match repository.access_token.as_deref().map(|token| self.encryption_service.decrypt(token))
+        {
+            Some(Ok(token)) => Some(token),
+            Some(Err(e)) => {
+                warn!(
+                    "Failed to decrypt access token: {}. Fetching LFS objects without authentication.",
+                    e
+                );
+                None
+            }
+            None => None,
+        };
+
+        match self.lfs_service.download(&repository.url, access_token.as_deref(), pointer).await {
+            Ok(bytes) => {
+                let default_encoding = repository.default_encoding.as_deref().and_then(encoding_for_label);
+                let content = decode_text(&bytes, default_encoding).map(ExtractedText::from);
+                if content.is_none() {
+                    debug!("[LFS] Fetched file {} is binary", relative_path);
+                }
+                content
+            }
+            Err(e) => {
+                warn!("[LFS] Failed to fetch {}: {}", relative_path, e);
+                None
+            }
+        }
+    }
+
     /// Process repository files using file system walk (fallback method)
     #[allow(clippy::too_many_arguments)]
     pub async fn process_repository_files_internal(
@@ -424,92 +737,19 @@ impl BranchProcessor {
         gitlab_tracking: Option<(Uuid, usize)>, // (parent_id, _project_start_files_count)
         parent_project_name: Option<&str>,      // Parent repository name for GitLab/GitHub multi-project repos
     ) -> Result<()> {
-        use super::git_tree_walker::MAX_FILE_SIZE;
-        use walkdir::WalkDir;
-
         // For Tantivy-only indexing, we don't need to track file deletions
         // since Tantivy will be rebuilt fresh for each crawl
 
-        let repo_path_owned = repo_path.to_owned();
-        let repo_path_owned2 = repo_path.to_owned();
-
-        // First pass: Count total eligible files for accurate progress reporting (in blocking thread)
-        let total_files = tokio::task::spawn_blocking(move || -> Result<usize> {
-            let mut total_files = 0;
-            for entry in
-                WalkDir::new(&repo_path_owned).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file())
-            {
-                let file_path = entry.path();
-                let relative_path = file_path
-                    .strip_prefix(&repo_path_owned)
-                    .map_err(|e| anyhow!("Failed to get relative path: {}", e))?;
-
-                let relative_path_str = relative_path.to_string_lossy().to_string();
-
-                // Skip hidden files and directories
-                if relative_path_str.starts_with('.') {
-                    continue;
-                }
-
-                // Check file extension
-                if !Self::is_supported_file_static(file_path) {
-                    continue;
-                }
-
-                // Check file size
-                if let Ok(metadata) = file_path.metadata()
-                    && metadata.len() > MAX_FILE_SIZE
-                {
-                    continue;
-                }
-
-                total_files += 1;
-            }
-            Ok(total_files)
-        })
-        .await??;
-
-        info!(
-            "Found {} eligible files to process for branch '{}' in repository {}",
-            total_files, branch_name, repository.name
-        );
-
         // Collect all file paths to process (in blocking thread)
-        let files_to_process = tokio::task::spawn_blocking(move || -> Result<Vec<(std::path::PathBuf, String)>> {
-            let mut files = Vec::new();
-            for entry in
-                WalkDir::new(&repo_path_owned2).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file())
-            {
-                let file_path = entry.path();
-                let relative_path = file_path
-                    .strip_prefix(&repo_path_owned2)
-                    .map_err(|e| anyhow!("Failed to get relative path: {}", e))?;
-
-                let relative_path_str = relative_path.to_string_lossy().to_string();
-
-                // Skip hidden files and directories
-                if relative_path_str.starts_with('.') {
-                    continue;
-                }
-
-                // Check file extension
-                if !Self::is_supported_file_static(file_path) {
-                    continue;
-                }
-
-                // Check file size
-                if let Ok(metadata) = file_path.metadata()
-                    && metadata.len() > MAX_FILE_SIZE
-                {
-                    debug!("Skipping large file: {} ({} bytes)", relative_path_str, metadata.len());
-                    continue;
-                }
-
-                files.push((file_path.to_path_buf(), relative_path_str));
-            }
-            Ok(files)
+        let repo_path_owned = repo_path.to_owned();
+        let rules = self.inclusion_rules_for(repository);
+        let collect_rules = rules.clone();
+        let large_files = LargeFilePolicy::for_repository(repository);
+        let files_to_process = tokio::task::spawn_blocking(move || {
+            FileProcessor::collect_indexable_files(&repo_path_owned, &collect_rules, large_files)
         })
         .await??;
+        let total_files = files_to_process.len();
 
         info!(
             "Collected {} files to process for branch '{}' in repository {}",
@@ -551,6 +791,36 @@ impl BranchProcessor {
                 self.progress_tracker.update_current_project_files(parent_id, project_files_processed).await;
             }
 
+            // Archives: index the files inside them
+            if rules.opens_archive(&relative_path_str) {
+                let result = match tokio::fs::read(&file_path).await {
+                    Ok(bytes) => {
+                        self.file_processor
+                            .process_archive(
+                                repository,
+                                &relative_path_str,
+                                bytes,
+                                branch_name,
+                                parent_project_name,
+                                &rules,
+                            )
+                            .await
+                    }
+                    Err(e) => Err(e.into()),
+                };
+                match result {
+                    Ok(indexed) => {
+                        progress.files_indexed += indexed;
+                        debug!("Indexed {} files from archive {}", indexed, relative_path_str);
+                    }
+                    Err(e) => {
+                        progress.errors.push(format!("Failed to process archive {}: {}", relative_path_str, e));
+                        error!("Error processing archive {}: {}", relative_path_str, e);
+                    }
+                }
+                continue;
+            }
+
             match self
                 .file_processor
                 .process_single_file(
@@ -563,7 +833,18 @@ impl BranchProcessor {
                 )
                 .await
             {
-                Ok(()) => {
+                Ok(FileOutcome::Binary) => {
+                    progress.files_skipped_binary += 1;
+                    self.progress_tracker.record_binary_skipped(repository.id).await;
+                    debug!("Skipped binary file {} in branch '{}'", relative_path_str, branch_name);
+                }
+                Ok(FileOutcome::TooLarge) => {
+                    debug!(
+                        "Skipped file {} in branch '{}' (too large)",
+                        relative_path_str, branch_name
+                    );
+                }
+                Ok(FileOutcome::Indexed) => {
                     progress.files_indexed += 1;
                     debug!(
                         "Successfully indexed file {} in branch '{}' for repository {}",
@@ -597,11 +878,12 @@ impl BranchProcessor {
         self.progress_tracker.set_current_file(repository.id, None).await;
 
         info!(
-            "Finished processing {} files for branch '{}' in repository {} - indexed: {}, errors: {}",
+            "Finished processing {} files for branch '{}' in repository {} - indexed: {}, binary: {}, errors: {}",
             progress.files_processed,
             branch_name,
             repository.name,
             progress.files_indexed,
+            progress.files_skipped_binary,
             progress.errors.len()
         );
 
@@ -609,37 +891,4 @@ impl BranchProcessor {
         // (not after each branch) for better performance
         Ok(())
     }
-
-    /// Check if a file is supported for indexing based on its extension or name
-    fn is_supported_file_static(file_path: &Path) -> bool {
-        use super::file_processing::SUPPORTED_EXTENSIONS;
-
-        if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
-            SUPPORTED_EXTENSIONS.contains(&extension.to_lowercase().as_str())
-        } else {
-            // Support files without extensions that might be scripts or config files
-            if let Some(file_name) = file_path.file_name().and_then(|name| name.to_str()) {
-                matches!(
-                    file_name.to_lowercase().as_str(),
-                    "dockerfile"
-                        | "makefile"
-                        | "rakefile"
-                        | "gemfile"
-                        | "vagrantfile"
-                        | "procfile"
-                        | "readme"
-                        | "license"
-                        | "changelog"
-                        | "authors"
-                        | "contributors"
-                        | "copying"
-                        | "install"
-                        | "news"
-                        | "todo"
-                )
-            } else {
-                false
-            }
-        }
-    }
 }
@@ -0,0 +1,135 @@
+//! Text decoding for crawled files
+//!
+//! File content is transcoded to UTF-8 before indexing:
+//! - a byte order mark (UTF-8, UTF-16LE/BE) decides the encoding
+//! - UTF-16 without BOM is recognized from the distribution of NUL bytes
+//! - valid UTF-8 is used as is
+//! - anything else is decoded with the repository's default encoding when set, otherwise with the
+//!   single-byte or CJK encoding guessed from the content (e.g. Windows-1252, ISO-8859-2, Shift_JIS)
+//!
+//! Content with NUL bytes that is not UTF-16 is binary and is not decoded.
+
+use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+
+/// Number of leading bytes inspected for binary content and UTF-16 detection (same window as Git)
+const SNIFF_LENGTH: usize = 8000;
+
+/// Minimum share of NUL bytes at odd (UTF-16LE) or even (UTF-16BE) positions for UTF-16 without BOM
+const UTF16_NUL_RATIO: f32 = 0.3;
+
+/// Look up an encoding by its WHATWG label (e.g. "windows-1252", "latin1", "shift_jis"), case-insensitive
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// Decode file content to UTF-8 text, or `None` when the content is binary
+pub fn decode_text(bytes: &[u8], default_encoding: Option<&'static Encoding>) -> Option<String> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+        return Some(text.into_owned());
+    }
+
+    if let Some(encoding) = detect_utf16(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(bytes);
+        return Some(text.into_owned());
+    }
+
+    if bytes[..bytes.len().min(SNIFF_LENGTH)].contains(&0) {
+        return None;
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Some(text.to_string());
+    }
+
+    let encoding = default_encoding.unwrap_or_else(|| {
+        let mut detector = EncodingDetector::new(Iso2022JpDetection::Deny);
+        detector.feed(bytes, true);
+        detector.guess(None, Utf8Detection::Deny)
+    });
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    Some(text.into_owned())
+}
+
+/// UTF-16 text without BOM: ASCII characters leave a NUL byte at every other position
+fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(SNIFF_LENGTH) & !1];
+    if sample.is_empty() {
+        return None;
+    }
+
+    let pairs = (sample.len() / 2) as f32;
+    let even_nuls = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_nuls = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+
+    if odd_nuls as f32 / pairs >= UTF16_NUL_RATIO && even_nuls == 0 {
+        Some(UTF_16LE)
+    } else if even_nuls as f32 / pairs >= UTF16_NUL_RATIO && odd_nuls == 0 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_utf8_and_bom() {
+        assert_eq!(decode_text("café".as_bytes(), None).as_deref(), Some("café"));
+        assert_eq!(
+            decode_text(b"\xEF\xBB\xBFclass A {}", None).as_deref(),
+            Some("class A {}")
+        );
+
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(utf16le("Größe"));
+        assert_eq!(decode_text(&bytes, None).as_deref(), Some("Größe"));
+    }
+
+    #[test]
+    fn test_utf16_without_bom() {
+        let bytes = utf16le("public class Main {}");
+        assert_eq!(decode_text(&bytes, None).as_deref(), Some("public class Main {}"));
+
+        let bytes: Vec<u8> = "IDENTIFICATION DIVISION.".encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+        assert_eq!(decode_text(&bytes, None).as_deref(), Some("IDENTIFICATION DIVISION."));
+    }
+
+    #[test]
+    fn test_single_byte_encodings() {
+        // "// Paramètres de la requête" in Windows-1252
+        let bytes = b"// Param\xE8tres de la requ\xEAte\nString r\xE9sum\xE9 = \"\";";
+        assert_eq!(
+            decode_text(bytes, None).as_deref(),
+            Some("// Paramètres de la requête\nString résumé = \"\";")
+        );
+
+        // The repository's default encoding wins over the guess
+        let bytes = b"\xD0\xD2\xC9\xD7\xC5\xD4";
+        let koi8 = encoding_for_label("KOI8-R");
+        assert_eq!(decode_text(bytes, koi8).as_deref(), Some("привет"));
+    }
+
+    #[test]
+    fn test_binary_content() {
+        assert_eq!(decode_text(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01", None), None);
+        assert_eq!(
+            decode_text(b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x03\0>\0", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_encoding_for_label() {
+        assert_eq!(encoding_for_label(" Latin1 ").map(|e| e.name()), Some("windows-1252"));
+        assert_eq!(encoding_for_label("shift_jis").map(|e| e.name()), Some("Shift_JIS"));
+        assert!(encoding_for_label("klingon").is_none());
+    }
+}
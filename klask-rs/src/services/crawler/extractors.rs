@@ -0,0 +1,260 @@
+//! Built-in content extractors
+//!
+//! - Jupyter notebooks (`.ipynb`): the source of code and markdown cells, without outputs and metadata
+//! - Office Open XML (`.docx`, `.pptx`, `.xlsx`) and OpenDocument (`.odt`, `.odp`, `.ods`): the document text
+//! - PDF: the text of each page
+//!
+//! Notebook cells, slides and PDF pages are recorded as sections, so that matches can be located in the document.
+
+use super::file_processing::{ContentExtractor, ExtractedText};
+use anyhow::{Result, anyhow};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use serde_json::Value;
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+
+/// Maximum size of an XML part read from an office document
+const MAX_PART_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The extractors registered by default, in order of precedence
+pub fn builtin_extractors() -> Vec<Arc<dyn ContentExtractor>> {
+    vec![Arc::new(NotebookExtractor), Arc::new(OfficeDocumentExtractor), Arc::new(PdfExtractor)]
+}
+
+/// Jupyter notebooks: code and markdown cells
+pub struct NotebookExtractor;
+
+impl ContentExtractor for NotebookExtractor {
+    fn handles(&self, file_name: &str) -> bool {
+        file_name.ends_with(".ipynb")
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedText> {
+        let notebook: Value = serde_json::from_slice(bytes)?;
+        let cells = notebook.get("cells").and_then(Value::as_array).ok_or_else(|| anyhow!("Notebook has no cells"))?;
+
+        let mut extracted = ExtractedText::default();
+        for (index, cell) in cells.iter().enumerate() {
+            let cell_type = cell.get("cell_type").and_then(Value::as_str).unwrap_or("");
+            if cell_type != "code" && cell_type != "markdown" {
+                continue;
+            }
+            // Cell source is either a string or a list of lines
+            let source = match cell.get("source") {
+                Some(Value::String(source)) => source.clone(),
+                Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+                _ => continue,
+            };
+            extracted.push_section(Some(format!("cell {} ({})", index + 1, cell_type)), &source);
+        }
+        Ok(extracted)
+    }
+}
+
+/// Office Open XML and OpenDocument text, presentations and spreadsheets
+pub struct OfficeDocumentExtractor;
+
+const OFFICE_EXTENSIONS: [&str; 6] = [".docx", ".pptx", ".xlsx", ".odt", ".odp", ".ods"];
+
+impl ContentExtractor for OfficeDocumentExtractor {
+    fn handles(&self, file_name: &str) -> bool {
+        OFFICE_EXTENSIONS.iter().any(|ext| file_name.ends_with(ext))
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedText> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+
+        let mut extracted = ExtractedText::default();
+        if names.iter().any(|name| name == "content.xml") {
+            // OpenDocument
+            let text = xml_text(&read_part(&mut archive, "content.xml")?)?;
+            extracted.push_section(None, &text);
+        } else if names.iter().any(|name| name == "word/document.xml") {
+            let text = xml_text(&read_part(&mut archive, "word/document.xml")?)?;
+            extracted.push_section(None, &text);
+        } else if names.iter().any(|name| name.starts_with("ppt/slides/")) {
+            // Slides in presentation order (slide2.xml before slide10.xml)
+            let mut slides: Vec<(u32, &String)> = names
+                .iter()
+                .filter_map(|name| {
+                    let number = name.strip_prefix("ppt/slides/slide")?.strip_suffix(".xml")?;
+                    Some((number.parse().ok()?, name))
+                })
+                .collect();
+            slides.sort();
+            for (number, name) in slides {
+                let text = xml_text(&read_part(&mut archive, name)?)?;
+                extracted.push_section(Some(format!("slide {}", number)), &text);
+            }
+        } else if names.iter().any(|name| name == "xl/sharedStrings.xml") {
+            // Spreadsheet cells with text are stored once in the shared strings
+            let text = xml_text(&read_part(&mut archive, "xl/sharedStrings.xml")?)?;
+            extracted.push_section(None, &text);
+        } else {
+            return Err(anyhow!("Unknown office document layout"));
+        }
+        Ok(extracted)
+    }
+}
+
+fn read_part(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Vec<u8>> {
+    let mut content = Vec::new();
+    archive.by_name(name)?.take(MAX_PART_SIZE).read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Text of an office XML part, with a line per paragraph, heading, slide line or spreadsheet string
+fn xml_text(xml: &[u8]) -> Result<String> {
+    let mut reader = Reader::from_reader(xml);
+    let mut text = String::new();
+    let mut buf = Vec::new();
+    // Depth inside elements whose text is not document text (field codes, deleted revisions)
+    let mut skipped = 0usize;
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) if matches!(e.local_name().as_ref(), b"instrText" | b"delText") => skipped += 1,
+            Event::End(e) => match e.local_name().as_ref() {
+                b"instrText" | b"delText" => skipped = skipped.saturating_sub(1),
+                b"p" | b"h" | b"si" => text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"tab" => text.push('\t'),
+                b"br" | b"cr" | b"line-break" => text.push('\n'),
+                b"s" => text.push(' '),
+                _ => {}
+            },
+            Event::Text(e) if skipped == 0 => text.push_str(&e.unescape()?),
+            Event::CData(e) if skipped == 0 => text.push_str(&String::from_utf8_lossy(&e)),
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(text)
+}
+
+/// PDF text, page by page
+pub struct PdfExtractor;
+
+impl ContentExtractor for PdfExtractor {
+    fn handles(&self, file_name: &str) -> bool {
+        file_name.ends_with(".pdf")
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedText> {
+        let pages = pdf_extract::extract_text_from_mem_by_pages(bytes).map_err(|e| anyhow!("{}", e))?;
+        let mut extracted = ExtractedText::default();
+        for (index, page) in pages.iter().enumerate() {
+            extracted.push_section(Some(format!("page {}", index + 1)), page.trim());
+        }
+        Ok(extracted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::search::SourceSection;
+    use std::io::Write;
+
+    fn section(line: u32, label: &str) -> SourceSection {
+        SourceSection { line, label: label.to_string() }
+    }
+
+    fn zip_bytes(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_notebook_cells() {
+        let notebook = serde_json::json!({
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Churn model\n", "Training data"]},
+                {"cell_type": "code", "source": "import pandas as pd\ndf = pd.read_csv('churn.csv')",
+                 "outputs": [{"output_type": "stream", "text": ["noise"]}]},
+                {"cell_type": "raw", "source": "raw text"},
+                {"cell_type": "code", "source": []},
+                {"cell_type": "code", "source": "model.fit(df)"}
+            ],
+            "metadata": {"kernelspec": {"name": "python3"}}
+        });
+        let extracted = NotebookExtractor.extract(notebook.to_string().as_bytes()).unwrap();
+        assert_eq!(
+            extracted.text,
+            "# Churn model\nTraining data\n\nimport pandas as pd\ndf = pd.read_csv('churn.csv')\n\nmodel.fit(df)"
+        );
+        assert_eq!(
+            extracted.sections,
+            [section(1, "cell 1 (markdown)"), section(4, "cell 2 (code)"), section(7, "cell 5 (code)")]
+        );
+        assert!(NotebookExtractor.extract(b"{\"nbformat\": 4}").is_err());
+    }
+
+    #[test]
+    fn test_office_documents() {
+        let docx = zip_bytes(&[(
+            "word/document.xml",
+            r#"<w:document xmlns:w="w"><w:body>
+                <w:p><w:r><w:t>Retention</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve">policy &amp; rules</w:t></w:r></w:p>
+                <w:p><w:r><w:instrText>HYPERLINK "x"</w:instrText></w:r><w:r><w:t>Second paragraph</w:t></w:r></w:p>
+            </w:body></w:document>"#,
+        )]);
+        let extracted = OfficeDocumentExtractor.extract(&docx).unwrap();
+        assert!(extracted.text.contains("Retention\tpolicy & rules\n"));
+        assert!(extracted.text.contains("Second paragraph"));
+        assert!(!extracted.text.contains("HYPERLINK"));
+
+        let odt = zip_bytes(&[
+            ("mimetype", "application/vnd.oasis.opendocument.text"),
+            (
+                "content.xml",
+                r#"<office:document-content xmlns:office="o" xmlns:text="t"><office:body><office:text>
+                    <text:h>Runbook</text:h><text:p>Restart<text:s/>the broker</text:p>
+                </office:text></office:body></office:document-content>"#,
+            ),
+        ]);
+        let extracted = OfficeDocumentExtractor.extract(&odt).unwrap();
+        assert!(extracted.text.contains("Runbook\n"));
+        assert!(extracted.text.contains("Restart the broker"));
+
+        let pptx = zip_bytes(&[
+            (
+                "ppt/slides/slide10.xml",
+                r#"<p:sld xmlns:a="a" xmlns:p="p"><a:p><a:r><a:t>Roadmap</a:t></a:r></a:p></p:sld>"#,
+            ),
+            (
+                "ppt/slides/slide2.xml",
+                r#"<p:sld xmlns:a="a" xmlns:p="p"><a:p><a:r><a:t>Agenda</a:t></a:r></a:p></p:sld>"#,
+            ),
+        ]);
+        let extracted = OfficeDocumentExtractor.extract(&pptx).unwrap();
+        assert_eq!(extracted.text, "Agenda\n\nRoadmap");
+        assert_eq!(extracted.sections, [section(1, "slide 2"), section(3, "slide 10")]);
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        assert!(OfficeDocumentExtractor.extract(b"not a zip").is_err());
+        assert!(OfficeDocumentExtractor.extract(&zip_bytes(&[("readme.txt", "hello")])).is_err());
+        assert!(PdfExtractor.extract(b"%PDF-1.4 truncated").is_err());
+    }
+
+    #[test]
+    fn test_handled_files() {
+        let extractors = builtin_extractors();
+        let handled = |name: &str| extractors.iter().filter(|e| e.handles(name)).count();
+        for name in ["analysis.ipynb", "spec.docx", "slides.odp", "manual.pdf"] {
+            assert_eq!(handled(name), 1, "{}", name);
+        }
+        assert_eq!(handled("main.rs"), 0);
+        assert_eq!(handled("lib.jar"), 0);
+    }
+}
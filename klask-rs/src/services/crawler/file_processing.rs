@@ -1,85 +1,118 @@
-use crate::models::{Repository, RepositoryType};
-use crate::services::search::{FileData, SearchService};
-use anyhow::Result;
+use super::archive::{MAX_ARCHIVE_SIZE, extract_entries};
+use super::encoding::{decode_text, encoding_for_label};
+use super::extractors::builtin_extractors;
+use super::ignore_files::IgnoreRules;
+use super::large_files::LargeFilePolicy;
+use crate::models::{FileInclusionRules, Repository, RepositoryType};
+use crate::services::language::detect_language;
+use crate::services::lfs::{LfsPointer, parse_lfs_pointer};
+use crate::services::search::{FileData, SearchService, SourceSection};
+use anyhow::{Result, anyhow};
 use sha2::{Digest, Sha256};
+use std::panic::{AssertUnwindSafe, catch_unwind};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 use uuid::Uuid;
+use walkdir::WalkDir;
 
-/// Supported file extensions for indexing
-pub const SUPPORTED_EXTENSIONS: &[&str] = &[
-    "rs",
-    "py",
-    "js",
-    "ts",
-    "java",
-    "c",
-    "cpp",
-    "h",
-    "hpp",
-    "go",
-    "rb",
-    "php",
-    "cs",
-    "swift",
-    "kt",
-    "scala",
-    "clj",
-    "hs",
-    "ml",
-    "fs",
-    "elm",
-    "dart",
-    "vue",
-    "jsx",
-    "tsx",
-    "html",
-    "css",
-    "scss",
-    "less",
-    "sql",
-    "sh",
-    "bash",
-    "zsh",
-    "fish",
-    "ps1",
-    "bat",
-    "cmd",
-    "dockerfile",
-    "yaml",
-    "yml",
-    "json",
-    "toml",
-    "xml",
-    "md",
-    "txt",
-    "cfg",
-    "conf",
-    "ini",
-    "properties",
-    "gradle",
-    "maven",
-    "pom",
-    "sbt",
-    "cmake",
-    "makefile",
-    "r",
-    "m",
-    "perl",
-    "pl",
-    "lua",
-];
+/// What became of a processed file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOutcome {
+    Indexed,
+    Binary,   // Binary file, or document without text
+    TooLarge, // Above the repository's size limit, which skips large files
+}
+
+/// Extracts the searchable text of a structured document (notebook, office document, PDF...)
+///
+/// A file handled by an extractor is indexed with the text extracted from it instead of its raw content.
+pub trait ContentExtractor: Send + Sync {
+    /// Whether the extractor handles a file, from its lowercase file name
+    fn handles(&self, file_name: &str) -> bool;
+
+    /// Extract the text of a document from its raw content
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedText>;
+}
+
+/// Text of a file to index: its content, or the text extracted from a document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtractedText {
+    pub text: String,
+    /// Where the lines of `text` come from in the document (e.g. notebook cells, PDF pages), when known
+    pub sections: Vec<SourceSection>,
+}
+
+impl ExtractedText {
+    /// Append a part of the document on new lines, separated from the previous part by an empty line
+    pub fn push_section(&mut self, label: Option<String>, text: &str) {
+        let text = text.trim_end();
+        if text.trim().is_empty() {
+            return;
+        }
+        if !self.text.is_empty() {
+            self.text.push_str("\n\n");
+        }
+        if let Some(label) = label {
+            let line = self.text.matches('\n').count() as u32 + 1;
+            self.sections.push(SourceSection { line, label });
+        }
+        self.text.push_str(text);
+    }
+}
+
+impl From<String> for ExtractedText {
+    fn from(text: String) -> Self {
+        Self { text, sections: Vec::new() }
+    }
+}
+
+/// Extract the text of a document, or `None` when the document has no text or cannot be read
+pub fn extract_document(extractor: &dyn ContentExtractor, relative_path: &str, bytes: &[u8]) -> Option<ExtractedText> {
+    // Parsers of untrusted documents may panic on malformed input
+    match catch_unwind(AssertUnwindSafe(|| extractor.extract(bytes))) {
+        Ok(Ok(extracted)) if !extracted.text.trim().is_empty() => Some(extracted),
+        Ok(Ok(_)) => {
+            debug!("No text extracted from {}", relative_path);
+            None
+        }
+        Ok(Err(e)) => {
+            warn!("Failed to extract text from {}: {}", relative_path, e);
+            None
+        }
+        Err(_) => {
+            warn!(
+                "Failed to extract text from {}: the document parser panicked",
+                relative_path
+            );
+            None
+        }
+    }
+}
 
 /// File processing utilities for the crawler
 #[derive(Clone)]
 pub struct FileProcessor {
     search_service: Arc<SearchService>,
+    extractors: Vec<Arc<dyn ContentExtractor>>,
 }
 
 impl FileProcessor {
     pub fn new(search_service: Arc<SearchService>) -> Self {
-        Self { search_service }
+        Self { search_service, extractors: builtin_extractors() }
+    }
+
+    /// Register a content extractor, which takes precedence over the built-in ones
+    #[allow(dead_code)]
+    pub fn with_extractor(mut self, extractor: Arc<dyn ContentExtractor>) -> Self {
+        self.extractors.insert(0, extractor);
+        self
+    }
+
+    /// The content extractor handling a file, if any
+    pub fn extractor_for(&self, relative_path: &str) -> Option<Arc<dyn ContentExtractor>> {
+        let file_name = relative_path.rsplit('/').next().unwrap_or(relative_path).to_lowercase();
+        self.extractors.iter().find(|extractor| extractor.handles(&file_name)).cloned()
     }
 
     /// Generate a deterministic UUID for a file based on repository, specific branch, and path
@@ -92,7 +125,13 @@ impl FileProcessor {
                 // For FileSystem: hash of {repository.url}:{relative_path}
                 format!("{}:{}", repository.url, relative_path)
             }
-            RepositoryType::Git | RepositoryType::GitLab | RepositoryType::GitHub => {
+            RepositoryType::Git
+            | RepositoryType::GitLab
+            | RepositoryType::GitHub
+            | RepositoryType::Bitbucket
+            | RepositoryType::Gitea
+            | RepositoryType::AzureDevOps
+            | RepositoryType::Svn => {
                 // For Git/GitLab/GitHub: hash of {repository.url}:{branch}:{relative_path}
                 format!("{}:{}:{}", repository.url, branch_name, relative_path)
             }
@@ -122,6 +161,12 @@ impl FileProcessor {
     ///
     /// If `provided_content` is Some, it will be used directly instead of reading from disk.
     /// This is useful when reading from Git trees without checking out files.
+    ///
+    /// Returns whether the file was indexed or skipped: binary files and files above the repository's size limit
+    /// (unless it truncates or chunks large files) are skipped, and files that cannot be read from disk are an
+    /// error. Files that are not valid UTF-8 are transcoded with the repository's default encoding, or with the
+    /// encoding guessed from their content.
+    /// Documents handled by a content extractor are indexed with their extracted text.
     pub async fn process_single_file(
         &self,
         repository: &Repository,
@@ -129,75 +174,179 @@ impl FileProcessor {
         relative_path: &str,
         branch_name: &str,
         parent_project_name: Option<&str>,
-        provided_content: Option<String>,
-    ) -> Result<()> {
+        provided_content: Option<ExtractedText>,
+    ) -> Result<FileOutcome> {
+        // Modification time is only known for files read from disk (Git content has none)
+        let mut last_modified = None;
+
         // Read file content - use provided content if available, otherwise read from disk
         let content = if let Some(content) = provided_content {
             debug!(
                 "[GIT READ] Processing file {} in branch '{}' from Git (provided content: {} bytes)",
                 relative_path,
                 branch_name,
-                content.len()
+                content.text.len()
             );
 
-            // Skip binary files or files with invalid UTF-8
-            if content.chars().any(|c| c == '\0') {
+            // Skip binary files
+            if content.text.contains('\0') {
                 debug!(
                     "[GIT READ] Skipping binary file (contains null bytes): {}",
                     relative_path
                 );
-                return Ok(());
+                return Ok(FileOutcome::Binary);
             }
 
-            Some(content)
+            content
         } else {
             debug!(
                 "[DISK READ] Reading file {} in branch '{}' from filesystem",
                 relative_path, branch_name
             );
 
-            // Read from disk
-            match tokio::fs::read_to_string(file_path).await {
-                Ok(content) => {
-                    // Skip binary files or files with invalid UTF-8
-                    if content.chars().any(|c| c == '\0') {
-                        debug!(
-                            "[DISK READ] Skipping binary file (contains null bytes): {}",
-                            relative_path
-                        );
-                        return Ok(());
+            // Read from disk and decode to UTF-8
+            let default_encoding = repository.default_encoding.as_deref().and_then(encoding_for_label);
+            match tokio::fs::read(file_path).await {
+                Ok(bytes) => {
+                    // Working copies checked out without Git LFS hold pointer files
+                    if let Some(pointer) = parse_lfs_pointer(&bytes) {
+                        debug!("[DISK READ] File {} is a Git LFS pointer", relative_path);
+                        return self
+                            .process_lfs_file(
+                                repository,
+                                file_path,
+                                relative_path,
+                                branch_name,
+                                parent_project_name,
+                                &pointer,
+                                None,
+                            )
+                            .await;
                     }
 
+                    let content = if let Some(extractor) = self.extractor_for(relative_path) {
+                        let path = relative_path.to_string();
+                        tokio::task::spawn_blocking(move || extract_document(extractor.as_ref(), &path, &bytes)).await?
+                    } else {
+                        decode_text(&bytes, default_encoding).map(ExtractedText::from)
+                    };
+
+                    // Skip binary files and documents without text
+                    let Some(content) = content else {
+                        debug!("[DISK READ] Skipping binary file: {}", relative_path);
+                        return Ok(FileOutcome::Binary);
+                    };
+
                     debug!(
                         "[DISK READ] Successfully read file {} ({} bytes)",
                         relative_path,
-                        content.len()
+                        content.text.len()
                     );
-                    Some(content)
+                    last_modified = tokio::fs::metadata(file_path)
+                        .await
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|duration| duration.as_secs() as i64);
+                    content
                 }
                 Err(e) => {
-                    debug!(
-                        "[DISK READ] Could not read file as UTF-8: {} - Error: {}",
-                        relative_path, e
-                    );
-                    None
+                    debug!("[DISK READ] Could not read file: {} - Error: {}", relative_path, e);
+                    return Err(anyhow!("Failed to read {}: {}", relative_path, e));
                 }
             }
         };
 
+        self.index_text(
+            repository,
+            file_path,
+            relative_path,
+            branch_name,
+            parent_project_name,
+            content,
+            last_modified,
+            None,
+        )
+        .await
+    }
+
+    /// Index a file tracked by Git LFS, flagged as such: with the content fetched from the LFS server when
+    /// provided, otherwise by its path only (the pointer text is never indexed)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn process_lfs_file(
+        &self,
+        repository: &Repository,
+        file_path: &Path,
+        relative_path: &str,
+        branch_name: &str,
+        parent_project_name: Option<&str>,
+        pointer: &LfsPointer,
+        content: Option<ExtractedText>,
+    ) -> Result<FileOutcome> {
+        debug!(
+            "Indexing Git LFS file {} in branch '{}' (content fetched: {})",
+            relative_path,
+            branch_name,
+            content.is_some()
+        );
+        self.index_text(
+            repository,
+            file_path,
+            relative_path,
+            branch_name,
+            parent_project_name,
+            content.unwrap_or_default(),
+            None,
+            Some(pointer.size),
+        )
+        .await
+    }
+
+    /// Index the text of a file, split into several documents for large files when the repository chunks them.
+    /// `lfs_size` is the size of the content of files tracked by Git LFS.
+    #[allow(clippy::too_many_arguments)]
+    async fn index_text(
+        &self,
+        repository: &Repository,
+        file_path: &Path,
+        relative_path: &str,
+        branch_name: &str,
+        parent_project_name: Option<&str>,
+        content: ExtractedText,
+        last_modified: Option<i64>,
+        lfs_size: Option<u64>,
+    ) -> Result<FileOutcome> {
         let extension = file_path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_string();
 
         let file_name = file_path.file_name().and_then(|name| name.to_str()).unwrap_or("").to_string();
 
-        // Index in Tantivy search engine if content is available
-        if let Some(content) = content {
-            // Generate a deterministic ID for Tantivy indexing to prevent duplicates
-            let file_id = Self::generate_deterministic_file_id(repository, relative_path, branch_name);
-            let version = branch_name.to_string();
+        // Index in Tantivy search engine
+        let ExtractedText { text: content, sections } = content;
+        // Classify the file by name, extension and shebang (e.g. .h as C or C++, Dockerfile)
+        let language = detect_language(&file_name, &content);
+
+        // Files above the repository's size limit are skipped, truncated or split into chunks
+        let parts = LargeFilePolicy::for_repository(repository).split(&content);
+        if parts.is_empty() {
+            debug!("Skipping large file: {} ({} bytes)", relative_path, content.len());
+            return Ok(FileOutcome::TooLarge);
+        }
+
+        let version = branch_name.to_string();
 
-            // For repository: use parent project name if provided (for GitLab/GitHub multi-project repos),
-            // otherwise use repository name (for regular Git repos)
-            let repository_field = parent_project_name.unwrap_or(&repository.name);
+        // For repository: use parent project name if provided (for GitLab/GitHub multi-project repos),
+        // otherwise use repository name (for regular Git repos)
+        let repository_field = parent_project_name.unwrap_or(&repository.name);
+
+        for (index, part) in parts.iter().enumerate() {
+            // Generate a deterministic ID for Tantivy indexing to prevent duplicates
+            // (chunks after the first one get IDs of their own)
+            let file_id = if index == 0 {
+                Self::generate_deterministic_file_id(repository, relative_path, branch_name)
+            } else {
+                let chunk_path = format!("{}#chunk{}", relative_path, index + 1);
+                Self::generate_deterministic_file_id(repository, &chunk_path, branch_name)
+            };
 
             debug!(
                 "Indexing file {} with deterministic ID {} for branch '{}' - repository: {}, project: {}",
@@ -211,12 +360,18 @@ impl FileProcessor {
                     file_id,
                     file_name: &file_name,
                     file_path: relative_path,
-                    content: &content,
+                    content: part.text,
                     repository: repository_field, // Parent repository for mass deletion
                     project: &repository.name,    // Individual project name for facets
                     version: &version,
                     extension: &extension,
-                    size: content.len() as u64, // Calculate size from content length
+                    size: lfs_size.unwrap_or(content.len() as u64), // Size of the whole file, for chunks too
+                    last_modified,
+                    language: Some(language.unwrap_or("")),
+                    sections: &sections,
+                    line_offset: part.line_offset,
+                    is_truncated: part.is_truncated,
+                    is_lfs: lfs_size.is_some(),
                 })
                 .await
             {
@@ -236,71 +391,124 @@ impl FileProcessor {
             }
         }
 
-        Ok(())
+        Ok(FileOutcome::Indexed)
     }
 
-    /// Check if a file is supported for indexing based on its extension or name
-    #[allow(dead_code)]
-    pub fn is_supported_file(file_path: &Path) -> bool {
-        if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
-            SUPPORTED_EXTENSIONS.contains(&extension.to_lowercase().as_str())
-        } else {
-            // Support files without extensions that might be scripts or config files
-            if let Some(file_name) = file_path.file_name().and_then(|name| name.to_str()) {
-                matches!(
-                    file_name.to_lowercase().as_str(),
-                    "dockerfile"
-                        | "makefile"
-                        | "rakefile"
-                        | "gemfile"
-                        | "vagrantfile"
-                        | "procfile"
-                        | "readme"
-                        | "license"
-                        | "changelog"
-                        | "authors"
-                        | "contributors"
-                        | "copying"
-                        | "install"
-                        | "news"
-                        | "todo"
+    /// Index the files inside an archive under virtual paths (`lib/foo.jar!/com/x/Config.properties`)
+    ///
+    /// Entries are filtered with the file inclusion rules, and nested archives are opened when the rules
+    /// open archives. Returns the number of files indexed.
+    pub async fn process_archive(
+        &self,
+        repository: &Repository,
+        relative_path: &str,
+        bytes: Vec<u8>,
+        branch_name: &str,
+        parent_project_name: Option<&str>,
+        rules: &FileInclusionRules,
+    ) -> Result<usize> {
+        let archive_path = relative_path.to_string();
+        let rules = rules.clone();
+        let processor = self.clone();
+        let default_encoding = repository.default_encoding.as_deref().and_then(encoding_for_label);
+        let entries = tokio::task::spawn_blocking(move || -> Result<Vec<(String, Option<ExtractedText>)>> {
+            let entries = extract_entries(&archive_path, &bytes, &|name| rules.is_included(name))?;
+            Ok(entries
+                .into_iter()
+                .map(|entry| {
+                    let content = match processor.extractor_for(&entry.path) {
+                        Some(extractor) => extract_document(extractor.as_ref(), &entry.path, &entry.content),
+                        None => decode_text(&entry.content, default_encoding).map(ExtractedText::from),
+                    };
+                    (entry.path, content)
+                })
+                .collect())
+        })
+        .await??;
+        debug!("Read {} files from archive {}", entries.len(), relative_path);
+
+        let mut indexed = 0;
+        for (path, content) in entries {
+            let Some(content) = content else {
+                debug!("Skipping binary archive entry: {}", path);
+                continue;
+            };
+            if self
+                .process_single_file(
+                    repository,
+                    Path::new(&path),
+                    &path,
+                    branch_name,
+                    parent_project_name,
+                    Some(content),
                 )
-            } else {
-                false
+                .await?
+                == FileOutcome::Indexed
+            {
+                indexed += 1;
             }
         }
+        Ok(indexed)
     }
 
-    /// Collect all supported files from a directory recursively
-    #[allow(dead_code)]
-    pub fn collect_supported_files(repo_path: &Path) -> Result<Vec<PathBuf>> {
-        let mut files = Vec::new();
-        Self::collect_files_recursive(repo_path, &mut files)?;
-        Ok(files.into_iter().filter(|path| Self::is_supported_file(path)).collect())
-    }
+    /// Collect the files of a working directory that a crawl indexes, as (absolute path, relative path) pairs
+    ///
+    /// Hidden top-level entries, files excluded by `.klaskignore`, `.gitattributes` or `.gitignore` and files
+    /// larger than the read limit of the large file policy (`MAX_ARCHIVE_SIZE` for archives) are skipped, and
+    /// the remaining files are filtered with the repository's file inclusion rules.
+    pub fn collect_indexable_files(
+        root: &Path,
+        rules: &FileInclusionRules,
+        large_files: LargeFilePolicy,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let mut entries = Vec::new();
+        let mut ignore_files = Vec::new();
+        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+            let relative_path =
+                entry.path().strip_prefix(root).map_err(|e| anyhow!("Failed to get relative path: {}", e))?;
+            let relative_path_str = relative_path.to_string_lossy().to_string();
 
-    /// Recursively collect files from a directory
-    #[allow(dead_code)]
-    fn collect_files_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
-        for entry in std::fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                // Skip hidden directories and common ignore patterns
-                if let Some(dir_name) = path.file_name().and_then(|n| n.to_str())
-                    && (dir_name.starts_with('.')
-                        || dir_name == "node_modules"
-                        || dir_name == "target"
-                        || dir_name == "__pycache__")
-                {
-                    continue;
+            let is_ignore_file =
+                entry.file_name().to_str().is_some_and(|file_name| IgnoreRules::is_ignore_file(file_name, true));
+            // Ignore files in hidden directories (e.g. `.git/`) do not apply to the tree
+            if is_ignore_file && !(relative_path_str.starts_with('.') && relative_path.components().count() > 1) {
+                match std::fs::read_to_string(entry.path()) {
+                    Ok(content) => ignore_files.push((relative_path_str.clone(), content)),
+                    Err(e) => warn!("Failed to read ignore file {}: {}", relative_path_str, e),
                 }
-                Self::collect_files_recursive(&path, files)?;
-            } else if path.is_file() {
-                files.push(path);
             }
+
+            entries.push((entry.into_path(), relative_path_str));
+        }
+
+        let ignore_rules =
+            IgnoreRules::from_files(ignore_files.iter().map(|(path, content)| (path.as_str(), content.as_str())));
+
+        let mut files = Vec::new();
+        for (file_path, relative_path_str) in entries {
+            // Skip hidden files and directories
+            if relative_path_str.starts_with('.') {
+                continue;
+            }
+
+            if !rules.is_included(&relative_path_str) || ignore_rules.is_ignored(&relative_path_str) {
+                continue;
+            }
+
+            let max_size = if rules.opens_archive(&relative_path_str) {
+                MAX_ARCHIVE_SIZE
+            } else {
+                large_files.read_limit()
+            };
+            if let Ok(metadata) = file_path.metadata()
+                && metadata.len() > max_size
+            {
+                debug!("Skipping large file: {} ({} bytes)", relative_path_str, metadata.len());
+                continue;
+            }
+
+            files.push((file_path, relative_path_str));
         }
-        Ok(())
+        Ok(files)
     }
 }
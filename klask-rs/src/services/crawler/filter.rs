@@ -42,8 +42,8 @@ pub fn matches_pattern(text: &str, pattern: &str) -> bool {
             search_start = part.len();
         } else if i == parts.len() - 1 {
             // Last part: already checked with ends_with
-            // Just verify position
-            if !text[..text.len() - part.len()].ends_with(part) {
+            // Just verify it does not overlap the parts matched before it
+            if text.len() - part.len() < search_start {
                 return false;
             }
         } else {
@@ -60,7 +60,7 @@ pub fn matches_pattern(text: &str, pattern: &str) -> bool {
 
 /// Parse comma-separated values from a string
 /// Trims whitespace and filters out empty entries
-fn parse_list(value: Option<&str>) -> Vec<String> {
+pub fn parse_list(value: Option<&str>) -> Vec<String> {
     value
         .map(|s| s.split(',').map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect())
         .unwrap_or_default()
@@ -287,6 +287,15 @@ mod tests {
         assert_eq!(filtered.len(), 3);
     }
 
+    #[test]
+    fn test_matches_pattern_suffix_and_middle() {
+        assert!(matches_pattern("old-archive", "*-archive"));
+        assert!(matches_pattern("infra/main.tf", "infra/*.tf"));
+        assert!(matches_pattern("v1.0-stable", "v*-stable"));
+        assert!(!matches_pattern("v-stable", "v-*-stable"));
+        assert!(!matches_pattern("infra/main.tfvars", "*.tf"));
+    }
+
     #[test]
     fn test_matches_pattern_all_wildcard() {
         assert!(matches_pattern("anything", "*"));
@@ -1,11 +1,24 @@
+use super::encoding::decode_text;
+use super::review_refs::{REVIEW_REF_PREFIX, is_review_version};
+use crate::services::lfs::{LfsPointer, MAX_POINTER_SIZE, parse_lfs_pointer};
 use anyhow::{Result, anyhow};
+use encoding_rs::Encoding;
 use gix::ObjectId;
 use gix::bstr::ByteSlice;
+use std::collections::{BTreeSet, HashMap};
 use tracing::{debug, info};
 
-/// Maximum file size to process (10MB)
+/// Default maximum file size to process (10MB), see `LargeFilePolicy` for the per-repository limit
 pub const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
 
+/// Content of a blob read as text
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlobText {
+    Text(String),
+    Binary,
+    LfsPointer(LfsPointer), // File tracked by Git LFS, its content is on the LFS server
+}
+
 /// Represents a file entry in a Git tree
 #[derive(Debug, Clone)]
 pub struct GitFileEntry {
@@ -18,7 +31,15 @@ pub struct GitTreeWalker;
 
 impl GitTreeWalker {
     /// Recursively walk a Git tree and collect all file entries
-    pub fn walk_tree(repo: &gix::Repository, tree_id: &ObjectId, base_path: &str) -> Result<Vec<GitFileEntry>> {
+    ///
+    /// Submodules are walked `submodule_depth` levels deep when their pinned commit is in the object
+    /// database (see `GitOperations::fetch_submodules`), their files listed under the submodule path.
+    pub fn walk_tree(
+        repo: &gix::Repository,
+        tree_id: &ObjectId,
+        base_path: &str,
+        submodule_depth: usize,
+    ) -> Result<Vec<GitFileEntry>> {
         let mut files = Vec::new();
         let tree = repo.find_object(*tree_id)?.try_into_tree().map_err(|_| anyhow!("Object is not a tree"))?;
 
@@ -37,53 +58,114 @@ impl GitTreeWalker {
                 files.push(GitFileEntry { path: full_path, oid: entry.oid().to_owned() });
             } else if entry.mode().is_tree() {
                 // It's a directory, recurse
-                let subtree_files = Self::walk_tree(repo, &entry.oid().to_owned(), &full_path)?;
+                let subtree_files = Self::walk_tree(repo, &entry.oid().to_owned(), &full_path, submodule_depth)?;
+                files.extend(subtree_files);
+            } else if entry.mode().is_commit() && submodule_depth > 0 {
+                // It's a submodule, recurse into the tree of its pinned commit if it was fetched
+                let commit_id = entry.oid().to_owned();
+                if !repo.has_object(commit_id) {
+                    debug!("Skipping submodule {}: commit {} was not fetched", full_path, commit_id);
+                    continue;
+                }
+                let commit = repo
+                    .find_object(commit_id)?
+                    .try_into_commit()
+                    .map_err(|_| anyhow!("Submodule entry does not point to a commit"))?;
+                let subtree_files =
+                    Self::walk_tree(repo, &commit.tree_id()?.detach(), &full_path, submodule_depth - 1)?;
                 files.extend(subtree_files);
             }
-            // Skip links, submodules, etc.
+            // Skip links and submodules that are not indexed
         }
 
         Ok(files)
     }
 
-    /// Check if a blob size is within acceptable limits
-    pub fn check_blob_size(repo: &gix::Repository, oid: &ObjectId) -> Result<bool> {
+    /// Paths of the files added, modified or removed between two trees, sorted
+    pub fn changed_paths(
+        repo: &gix::Repository,
+        old_tree_id: &ObjectId,
+        new_tree_id: &ObjectId,
+        submodule_depth: usize,
+    ) -> Result<Vec<String>> {
+        let old_files: HashMap<String, ObjectId> = Self::walk_tree(repo, old_tree_id, "", submodule_depth)?
+            .into_iter()
+            .map(|file| (file.path, file.oid))
+            .collect();
+        let new_files: HashMap<String, ObjectId> = Self::walk_tree(repo, new_tree_id, "", submodule_depth)?
+            .into_iter()
+            .map(|file| (file.path, file.oid))
+            .collect();
+
+        let removed = old_files.keys().filter(|path| !new_files.contains_key(*path));
+        let added_or_modified =
+            new_files.iter().filter(|(path, oid)| old_files.get(*path) != Some(oid)).map(|(path, _)| path);
+        let changed: BTreeSet<&String> = removed.chain(added_or_modified).collect();
+        Ok(changed.into_iter().cloned().collect())
+    }
+
+    /// Check if a blob size is within `max_size` bytes
+    pub fn check_blob_size(repo: &gix::Repository, oid: &ObjectId, max_size: u64) -> Result<bool> {
+        let obj = repo.find_object(*oid)?;
+        Ok(obj.data.len() as u64 <= max_size)
+    }
+
+    /// Read the raw content of a blob
+    pub fn read_blob_bytes(repo: &gix::Repository, oid: &ObjectId) -> Result<Vec<u8>> {
         let obj = repo.find_object(*oid)?;
-        Ok(obj.data.len() as u64 <= MAX_FILE_SIZE)
+        let blob = obj.try_into_blob().map_err(|_| anyhow!("Object is not a blob"))?;
+        Ok(blob.data.to_vec())
     }
 
-    /// Read the content of a blob as a UTF-8 string
-    pub fn read_blob_content(repo: &gix::Repository, oid: &ObjectId) -> Result<Option<String>> {
+    /// Read the content of a blob as text transcoded to UTF-8
+    ///
+    /// Content that is not valid UTF-8 is decoded with `default_encoding` when set, otherwise with the
+    /// encoding guessed from the content. Git LFS pointers are returned as such instead of their text.
+    pub fn read_blob_content(
+        repo: &gix::Repository,
+        oid: &ObjectId,
+        default_encoding: Option<&'static Encoding>,
+    ) -> Result<BlobText> {
         let obj = repo.find_object(*oid)?;
         let blob = obj.try_into_blob().map_err(|_| anyhow!("Object is not a blob"))?;
 
         let blob_size = blob.data.len();
         debug!("[BLOB] Attempting to read blob {} ({} bytes)", oid, blob_size);
 
-        // Try to convert to UTF-8 string
-        match String::from_utf8(blob.data.to_vec()) {
-            Ok(content) => {
+        if let Some(pointer) = parse_lfs_pointer(&blob.data) {
+            debug!(
+                "[BLOB] Blob {} is a Git LFS pointer to {} ({} bytes)",
+                oid, pointer.oid, pointer.size
+            );
+            return Ok(BlobText::LfsPointer(pointer));
+        }
+
+        let content = decode_text(&blob.data, default_encoding);
+        match content {
+            Some(content) => {
                 debug!(
-                    "[BLOB] Successfully converted blob {} to UTF-8 ({} bytes, {} chars)",
+                    "[BLOB] Decoded blob {} ({} bytes, {} chars)",
                     oid,
-                    content.len(),
+                    blob_size,
                     content.chars().count()
                 );
-                Ok(Some(content))
+                Ok(BlobText::Text(content))
             }
-            Err(e) => {
-                debug!(
-                    "[BLOB] Failed to convert blob {} to UTF-8: {} (blob size: {} bytes, valid up to byte {})",
-                    oid,
-                    e,
-                    blob_size,
-                    e.utf8_error().valid_up_to()
-                );
-                Ok(None)
+            None => {
+                debug!("[BLOB] Blob {} is binary ({} bytes)", oid, blob_size);
+                Ok(BlobText::Binary)
             }
         }
     }
 
+    /// Read a blob as a Git LFS pointer, or `None` when it is not one (larger blobs are not read)
+    pub fn read_lfs_pointer(repo: &gix::Repository, oid: &ObjectId) -> Result<Option<LfsPointer>> {
+        if repo.find_header(*oid)?.size() >= MAX_POINTER_SIZE {
+            return Ok(None);
+        }
+        Ok(parse_lfs_pointer(&Self::read_blob_bytes(repo, oid)?))
+    }
+
     /// Get all branches from a gix repository
     pub fn get_all_branches(repo: &gix::Repository) -> Result<Vec<String>> {
         let mut branches = Vec::new();
@@ -114,17 +196,31 @@ impl GitTreeWalker {
         Ok(branches)
     }
 
-    /// Get the tree ID for a specific branch
+    /// Get the tree ID for a specific branch, or for an open merge request / pull request version (`MR!123`)
     pub fn get_branch_tree_id(repo: &gix::Repository, branch_name: &str) -> Result<ObjectId> {
+        let commit_id = Self::get_branch_commit_id(repo, branch_name)?;
+        Self::get_commit_tree_id(repo, &commit_id)
+    }
+
+    /// Get the commit a branch (or an indexed merge request / pull request) points to
+    pub fn get_branch_commit_id(repo: &gix::Repository, branch_name: &str) -> Result<ObjectId> {
         // Try remote branch first (refs/remotes/origin/branch_name)
         let remote_ref = format!("refs/remotes/origin/{}", branch_name);
         let local_ref = format!("refs/heads/{}", branch_name);
 
-        let reference = repo.find_reference(&remote_ref).or_else(|_| repo.find_reference(&local_ref))?;
+        let reference = if is_review_version(branch_name) {
+            repo.find_reference(&format!("{}{}", REVIEW_REF_PREFIX, branch_name))?
+        } else {
+            repo.find_reference(&remote_ref).or_else(|_| repo.find_reference(&local_ref))?
+        };
+
+        Ok(reference.id().detach())
+    }
 
-        let commit_id = reference.id().detach();
+    /// Get the tree of a commit
+    pub fn get_commit_tree_id(repo: &gix::Repository, commit_id: &ObjectId) -> Result<ObjectId> {
         let commit = repo
-            .find_object(commit_id)?
+            .find_object(*commit_id)?
             .try_into_commit()
             .map_err(|_| anyhow!("Reference does not point to a commit"))?;
 
@@ -0,0 +1,58 @@
+use super::filter::filter_projects;
+use super::provider_crawler::{DiscoveredRepository, RepositoryDiscovery};
+use crate::models::Repository;
+use crate::services::gitea::GiteaService;
+use anyhow::Result;
+
+/// Discovery of the repositories of a Gitea or Forgejo repository
+pub struct GiteaDiscovery {
+    service: GiteaService,
+    gitea_url: String, // The repository URL is the address of the Gitea/Forgejo server
+}
+
+impl GiteaDiscovery {
+    pub fn new(repository: &Repository) -> Self {
+        Self { service: GiteaService::new(), gitea_url: repository.url.clone() }
+    }
+}
+
+impl RepositoryDiscovery for GiteaDiscovery {
+    fn provider(&self) -> &'static str {
+        "Gitea"
+    }
+
+    async fn test_token(&self, access_token: &str) -> Result<bool> {
+        self.service.test_token(&self.gitea_url, access_token).await
+    }
+
+    async fn discover(
+        &self,
+        repository: &Repository,
+        access_token: &str,
+        _errors: &mut Vec<String>,
+    ) -> Result<Vec<DiscoveredRepository>> {
+        let repositories = self
+            .service
+            .discover_repositories(&self.gitea_url, access_token, repository.gitea_organization.as_deref())
+            .await?;
+
+        // Apply filtering logic to "owner/name" names: included filters first, then exclusions
+        let filtered_repo_names = filter_projects(
+            repositories.iter().map(|r| r.full_name.clone()).collect(),
+            repository.included_projects.as_deref(),
+            repository.included_projects_patterns.as_deref(),
+            repository.excluded_projects.as_deref(),
+            repository.excluded_projects_patterns.as_deref(),
+        );
+
+        Ok(repositories
+            .into_iter()
+            .filter(|r| filtered_repo_names.contains(&r.full_name))
+            .map(|r| DiscoveredRepository {
+                full_name: r.full_name,
+                clone_url: Some(r.clone_url),
+                default_branch: r.default_branch,
+            })
+            .collect())
+    }
+}
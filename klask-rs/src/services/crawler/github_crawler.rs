@@ -1,318 +1,86 @@
-use super::branch_processor::CrawlProgress;
 use super::filter::filter_repositories;
-use crate::models::{Repository, RepositoryType};
-use crate::repositories::RepositoryRepository;
-use crate::services::encryption::EncryptionService;
+use super::git_operations::GitOperations;
+use super::provider_crawler::{DiscoveredRepository, RepositoryDiscovery};
+use super::review_refs::ReviewRef;
+use crate::models::Repository;
 use crate::services::github::GitHubService;
-use crate::services::progress::ProgressTracker;
-use crate::services::search::SearchService;
-use anyhow::{Result, anyhow};
-use sqlx::{Pool, Postgres};
-use std::path::PathBuf;
-use std::sync::Arc;
-use tokio_util::sync::CancellationToken;
-use tracing::{error, info, warn};
-use uuid::Uuid;
+use anyhow::Result;
+use std::path::Path;
+use tracing::warn;
+
+/// Discovery of the repositories of a GitHub repository, fetching their open pull requests once cloned
+pub struct GitHubDiscovery {
+    service: GitHubService,
+    git_operations: GitOperations,
+}
 
-/// GitHub-specific crawler operations
-pub struct GitHubCrawler {
-    database: Pool<Postgres>,
-    search_service: Arc<SearchService>,
-    progress_tracker: Arc<ProgressTracker>,
-    encryption_service: Arc<EncryptionService>,
-    temp_dir: PathBuf,
+impl GitHubDiscovery {
+    pub fn new(repository: &Repository, git_operations: GitOperations) -> Self {
+        Self { service: GitHubService::for_repository(repository), git_operations }
+    }
 }
 
-impl GitHubCrawler {
-    pub fn new(
-        database: Pool<Postgres>,
-        search_service: Arc<SearchService>,
-        progress_tracker: Arc<ProgressTracker>,
-        encryption_service: Arc<EncryptionService>,
-        temp_dir: PathBuf,
-    ) -> Self {
-        Self { database, search_service, progress_tracker, encryption_service, temp_dir }
+impl RepositoryDiscovery for GitHubDiscovery {
+    fn provider(&self) -> &'static str {
+        "GitHub"
+    }
+
+    async fn test_token(&self, access_token: &str) -> Result<bool> {
+        self.service.test_token(access_token).await
     }
 
-    /// Crawl a GitHub repository by discovering all sub-repositories and cloning them
-    pub async fn crawl_github_repository(
+    async fn discover(
         &self,
         repository: &Repository,
-        cancellation_token: CancellationToken,
-        clone_or_update_fn: impl Fn(
-            &Repository,
-            &std::path::Path,
-        )
-            -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<gix::Repository>> + Send>>
-        + Send
-        + Sync,
-        process_files_fn: impl Fn(
-            &Repository,
-            &std::path::Path,
-            &mut CrawlProgress,
-            &CancellationToken,
-            Uuid,
-            &str,
-        ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-        + Send
-        + Sync,
-        _update_crawl_time_fn: impl Fn(
-            Uuid,
-            Option<i32>,
-        ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-        + Send
-        + Sync,
-        cleanup_token_fn: impl Fn(Uuid) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> + Send + Sync,
-    ) -> Result<()> {
-        let _github_crawl_start_time = std::time::Instant::now();
-        let repo_repo = RepositoryRepository::new(self.database.clone());
-
-        info!("Starting GitHub discovery for repository: {}", repository.name);
-
-        // Mark crawl as started in database
-        repo_repo.start_crawl(repository.id, None).await?;
-
-        // Delete all existing documents for this repository before crawling
-        // This ensures no duplicates when re-crawling
-        match self.search_service.delete_project_documents(&repository.name).await {
-            Ok(deleted_count) => {
-                if deleted_count > 0 {
-                    info!(
-                        "Deleted {} existing documents for GitHub repository {} before crawling",
-                        deleted_count, repository.name
-                    );
-                }
-            }
-            Err(e) => {
-                warn!(
-                    "Failed to delete existing documents for GitHub repository {}: {}",
-                    repository.name, e
-                );
-                // Continue anyway - the upsert should handle duplicates
-            }
-        }
-
-        // Extract and decrypt access token from repository
-        let encrypted_token =
-            repository.access_token.as_ref().ok_or_else(|| anyhow!("GitHub repository missing access token"))?;
-
-        let access_token = self
-            .encryption_service
-            .decrypt(encrypted_token)
-            .map_err(|e| anyhow!("Failed to decrypt GitHub access token: {}", e))?;
-
-        self.progress_tracker.update_status(repository.id, crate::services::progress::CrawlStatus::Cloning).await;
-
-        // Test GitHub token first
-        let github_service = GitHubService::new();
-        info!("Testing GitHub token for repository: {}", repository.name);
-        match github_service.test_token(&access_token).await {
-            Ok(true) => info!("GitHub token is valid"),
-            Ok(false) => {
-                let error_msg = "GitHub token is invalid or expired";
-                error!(
-                    "GitHub token validation failed for repository {}: {}",
-                    repository.name, error_msg
-                );
-                self.progress_tracker.set_error(repository.id, error_msg.to_string()).await;
-                // Mark crawl as failed in database
-                let _ = repo_repo.fail_crawl(repository.id).await;
-                cleanup_token_fn(repository.id).await;
-                return Err(anyhow!(error_msg));
-            }
-            Err(e) => {
-                let error_msg = format!("Failed to test GitHub token: {}", e);
-                error!(
-                    "GitHub token test error for repository {}: {}",
-                    repository.name, error_msg
-                );
-                self.progress_tracker.set_error(repository.id, error_msg.clone()).await;
-                // Mark crawl as failed in database
-                let _ = repo_repo.fail_crawl(repository.id).await;
-                cleanup_token_fn(repository.id).await;
-                return Err(anyhow!(error_msg));
-            }
-        }
-
-        // Discover GitHub repositories
-        info!("Discovering GitHub repositories for repository: {}", repository.name);
+        access_token: &str,
+        _errors: &mut Vec<String>,
+    ) -> Result<Vec<DiscoveredRepository>> {
         let repositories =
-            match github_service.discover_repositories(&access_token, repository.github_namespace.as_deref()).await {
-                Ok(repos) => repos,
-                Err(e) => {
-                    let error_msg = format!("Failed to discover GitHub repositories: {}", e);
-                    error!(
-                        "GitHub discovery error for repository {}: {}",
-                        repository.name, error_msg
-                    );
-                    self.progress_tracker.set_error(repository.id, error_msg.clone()).await;
-                    // Mark crawl as failed in database
-                    let _ = repo_repo.fail_crawl(repository.id).await;
-                    cleanup_token_fn(repository.id).await;
-                    return Err(anyhow!(error_msg));
-                }
-            };
+            self.service.discover_repositories(access_token, repository.github_namespace.as_deref()).await?;
 
-        // Convert GitHub repositories to simple string names for filtering
-        let repo_names: Vec<String> = repositories.iter().map(|r| r.full_name.clone()).collect();
-
-        // Apply filtering logic: included filters first, then exclusions
+        // Apply filtering logic to "owner/name" names: included filters first, then exclusions
         let filtered_repo_names = filter_repositories(
-            repo_names,
+            repositories.iter().map(|r| r.full_name.clone()).collect(),
             repository.included_projects.as_deref(),
             repository.included_projects_patterns.as_deref(),
             repository.github_excluded_repositories.as_deref(),
             repository.github_excluded_patterns.as_deref(),
         );
 
-        // Map back from filtered names to GitHub repository objects
-        let filtered_repositories: Vec<_> =
-            repositories.into_iter().filter(|r| filtered_repo_names.contains(&r.full_name)).collect();
+        Ok(repositories
+            .into_iter()
+            .filter(|r| filtered_repo_names.contains(&r.full_name))
+            .map(|r| DiscoveredRepository {
+                full_name: r.full_name,
+                clone_url: Some(r.clone_url),
+                default_branch: Some(r.default_branch),
+            })
+            .collect())
+    }
 
-        if filtered_repositories.is_empty() {
-            let error_msg = "No accessible GitHub repositories found after filtering";
-            self.progress_tracker.set_error(repository.id, error_msg.to_string()).await;
-            // Mark crawl as failed in database
-            let _ = repo_repo.fail_crawl(repository.id).await;
-            cleanup_token_fn(repository.id).await;
-            return Err(anyhow!(error_msg));
+    /// Fetch the heads of the open pull requests of a cloned repository when pull request indexing is enabled.
+    /// Failures are logged and the repository's branches are still indexed.
+    async fn after_clone(
+        &self,
+        access_token: &str,
+        discovered: &DiscoveredRepository,
+        project_repository: &Repository,
+        repo_path: &Path,
+    ) {
+        if project_repository.index_merge_requests != Some(true) {
+            return;
         }
 
-        info!(
-            "Discovered {} GitHub repositories for repository {} after filtering",
-            filtered_repositories.len(),
-            repository.name
-        );
-
-        // Initialize hierarchical progress tracking for GitHub
-        self.progress_tracker.set_gitlab_projects_total(repository.id, filtered_repositories.len()).await;
-
-        // Create base directory for this GitHub repository
-        let base_repo_path = self.temp_dir.join(format!("{}-{}", repository.name, repository.id));
-        std::fs::create_dir_all(&base_repo_path)?;
-
-        let mut total_files_processed = 0;
-        let mut total_files_indexed = 0;
-        let mut all_errors = Vec::new();
-
-        // Process each discovered repository
-        for (repo_index, github_repo) in filtered_repositories.iter().enumerate() {
-            // Update progress in database before processing each repository
-            repo_repo.update_crawl_progress(repository.id, Some(github_repo.full_name.clone())).await?;
-
-            // Check for cancellation before each repository
-            if cancellation_token.is_cancelled() {
-                self.progress_tracker.cancel_crawl(repository.id).await;
-                cleanup_token_fn(repository.id).await;
-                return Ok(());
-            }
-
-            info!(
-                "Processing GitHub repository {}/{}: {}",
-                repo_index + 1,
-                filtered_repositories.len(),
-                github_repo.full_name
-            );
-
-            // Update current project in progress tracker
-            self.progress_tracker.set_current_gitlab_project(repository.id, Some(github_repo.full_name.clone())).await;
-
-            // Create sub-directory for this repository
-            let repo_path = base_repo_path.join(&github_repo.full_name);
-
-            // Create a temporary repository object for this GitHub repo
-            let temp_repository = Repository {
-                id: repository.id,                   // Use same ID so it's grouped under the same repository
-                name: github_repo.full_name.clone(), // Use full repo name
-                url: github_repo.clone_url.clone(),
-                repository_type: RepositoryType::Git, // Treat as Git for cloning
-                branch: Some(github_repo.default_branch.clone()),
-                enabled: repository.enabled,
-                access_token: repository.access_token.clone(),
-                gitlab_namespace: None,
-                is_group: false,
-                created_at: repository.created_at,
-                updated_at: repository.updated_at,
-                last_crawled: repository.last_crawled,
-                auto_crawl_enabled: repository.auto_crawl_enabled,
-                cron_schedule: repository.cron_schedule.clone(),
-                next_crawl_at: repository.next_crawl_at,
-                crawl_frequency_hours: repository.crawl_frequency_hours,
-                max_crawl_duration_minutes: repository.max_crawl_duration_minutes,
-                last_crawl_duration_seconds: repository.last_crawl_duration_seconds,
-                gitlab_excluded_projects: None,
-                gitlab_excluded_patterns: None,
-                github_namespace: repository.github_namespace.clone(),
-                github_excluded_repositories: repository.github_excluded_repositories.clone(),
-                github_excluded_patterns: repository.github_excluded_patterns.clone(),
-                crawl_state: repository.crawl_state.clone(),
-                last_processed_project: repository.last_processed_project.clone(),
-                crawl_started_at: repository.crawl_started_at,
-                included_branches: repository.included_branches.clone(),
-                included_branches_patterns: repository.included_branches_patterns.clone(),
-                excluded_branches: repository.excluded_branches.clone(),
-                excluded_branches_patterns: repository.excluded_branches_patterns.clone(),
-                included_projects: repository.included_projects.clone(),
-                included_projects_patterns: repository.included_projects_patterns.clone(),
-            };
-
-            // Clone this specific repository
-            match clone_or_update_fn(&temp_repository, &repo_path).await {
-                Ok(_) => {
-                    // Create progress tracker for this repository
-                    let mut repo_progress = CrawlProgress { files_processed: 0, files_indexed: 0, errors: Vec::new() };
-
-                    // Process files in this repository with hierarchical tracking
-                    match process_files_fn(
-                        &temp_repository,
-                        &repo_path,
-                        &mut repo_progress,
-                        &cancellation_token,
-                        repository.id,
-                        &repository.name, // Pass parent repository name
-                    )
-                    .await
-                    {
-                        Ok(()) => {
-                            info!("Successfully processed GitHub repository: {}", github_repo.full_name);
-                            total_files_processed += repo_progress.files_processed;
-                            total_files_indexed += repo_progress.files_indexed;
-                            all_errors.extend(repo_progress.errors);
-                        }
-                        Err(e) => {
-                            let error_msg = format!(
-                                "Failed to process files for repository {}: {}",
-                                github_repo.full_name, e
-                            );
-                            warn!("{}", error_msg);
-                            all_errors.push(error_msg);
-                        }
-                    }
-
-                    // Complete this repository in the tracker
-                    self.progress_tracker.complete_current_gitlab_project(repository.id).await;
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to clone GitHub repository {}: {}", github_repo.full_name, e);
-                    warn!("{}", error_msg);
-                    all_errors.push(error_msg);
-                }
+        let reviews = match self.service.get_open_pull_requests(access_token, &discovered.full_name).await {
+            Ok(numbers) => numbers.into_iter().map(ReviewRef::pull_request).collect(),
+            Err(e) => {
+                // Without the list of open pull requests, none is indexed rather than closed ones
+                warn!("Failed to list open pull requests of {}: {}", discovered.full_name, e);
+                Vec::new()
             }
+        };
+        if let Err(e) = self.git_operations.fetch_review_refs(project_repository, repo_path, &reviews).await {
+            warn!("Failed to fetch pull requests of {}: {}", discovered.full_name, e);
         }
-
-        // Update final progress
-        self.progress_tracker.update_progress(repository.id, total_files_processed, None, total_files_indexed).await;
-
-        if !all_errors.is_empty() {
-            let combined_errors = all_errors.join("; ");
-            self.progress_tracker
-                .set_error(repository.id, format!("Some repositories failed: {}", combined_errors))
-                .await;
-        }
-
-        info!("Completed GitHub repository crawl for: {}", repository.name);
-
-        // Note: Tantivy commit, database updates, and progress completion are handled in CrawlerService::finalize_crawl()
-        Ok(())
     }
 }
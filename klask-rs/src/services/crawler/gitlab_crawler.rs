@@ -1,14 +1,17 @@
 use super::branch_processor::CrawlProgress;
 use super::filter::filter_projects;
-use crate::models::{Repository, RepositoryType};
+use super::git_operations::GitOperations;
+use super::provider_crawler::project_repository;
+use super::review_refs::ReviewRef;
+use crate::models::Repository;
 use crate::repositories::RepositoryRepository;
 use crate::services::encryption::EncryptionService;
-use crate::services::gitlab::GitLabService;
+use crate::services::gitlab::{GitLabProject, GitLabService};
 use crate::services::progress::ProgressTracker;
 use crate::services::search::SearchService;
 use anyhow::{Result, anyhow};
 use sqlx::{Pool, Postgres};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
@@ -20,6 +23,7 @@ pub struct GitLabCrawler {
     search_service: Arc<SearchService>,
     progress_tracker: Arc<ProgressTracker>,
     encryption_service: Arc<EncryptionService>,
+    git_operations: GitOperations,
     temp_dir: PathBuf,
 }
 
@@ -29,9 +33,44 @@ impl GitLabCrawler {
         search_service: Arc<SearchService>,
         progress_tracker: Arc<ProgressTracker>,
         encryption_service: Arc<EncryptionService>,
+        git_operations: GitOperations,
         temp_dir: PathBuf,
     ) -> Self {
-        Self { database, search_service, progress_tracker, encryption_service, temp_dir }
+        Self { database, search_service, progress_tracker, encryption_service, git_operations, temp_dir }
+    }
+
+    /// Fetch the heads of the open merge requests of a cloned project when merge request indexing is enabled.
+    /// Failures are logged and the project's branches are still indexed.
+    async fn fetch_open_merge_requests(
+        &self,
+        gitlab_service: &GitLabService,
+        gitlab_url: &str,
+        access_token: &str,
+        project: &GitLabProject,
+        project_repository: &Repository,
+        project_path: &Path,
+    ) {
+        if project_repository.index_merge_requests != Some(true) {
+            return;
+        }
+
+        let reviews = match gitlab_service.get_open_merge_requests(gitlab_url, access_token, project.id).await {
+            Ok(iids) => iids.into_iter().map(ReviewRef::merge_request).collect(),
+            Err(e) => {
+                // Without the list of open merge requests, none is indexed rather than closed ones
+                warn!(
+                    "Failed to list open merge requests of project {}: {}",
+                    project.path_with_namespace, e
+                );
+                Vec::new()
+            }
+        };
+        if let Err(e) = self.git_operations.fetch_review_refs(project_repository, project_path, &reviews).await {
+            warn!(
+                "Failed to fetch merge requests of project {}: {}",
+                project.path_with_namespace, e
+            );
+        }
     }
 
     /// Crawl a GitLab repository by discovering all sub-projects and cloning them
@@ -233,48 +272,34 @@ impl GitLabCrawler {
             // Create sub-directory for this project
             let project_path = base_repo_path.join(&project.path_with_namespace);
 
-            // Create a temporary repository object for this project
-            let project_repository = Repository {
-                id: repository.id,                         // Use same ID so it's grouped under the same repository
-                name: project.path_with_namespace.clone(), // Use full project path as name
-                url: project.http_url_to_repo.clone(),
-                repository_type: RepositoryType::Git, // Treat as Git for cloning
-                branch: project.default_branch.clone(),
-                enabled: repository.enabled,
-                access_token: repository.access_token.clone(),
-                gitlab_namespace: repository.gitlab_namespace.clone(),
-                is_group: false,
-                created_at: repository.created_at,
-                updated_at: repository.updated_at,
-                last_crawled: repository.last_crawled,
-                auto_crawl_enabled: repository.auto_crawl_enabled,
-                cron_schedule: repository.cron_schedule.clone(),
-                next_crawl_at: repository.next_crawl_at,
-                crawl_frequency_hours: repository.crawl_frequency_hours,
-                max_crawl_duration_minutes: repository.max_crawl_duration_minutes,
-                last_crawl_duration_seconds: repository.last_crawl_duration_seconds,
-                gitlab_excluded_projects: repository.gitlab_excluded_projects.clone(),
-                gitlab_excluded_patterns: repository.gitlab_excluded_patterns.clone(),
-                github_namespace: repository.github_namespace.clone(),
-                github_excluded_repositories: repository.github_excluded_repositories.clone(),
-                github_excluded_patterns: repository.github_excluded_patterns.clone(),
-                crawl_state: repository.crawl_state.clone(),
-                last_processed_project: repository.last_processed_project.clone(),
-                crawl_started_at: repository.crawl_started_at,
-                included_branches: repository.included_branches.clone(),
-                included_branches_patterns: repository.included_branches_patterns.clone(),
-                excluded_branches: repository.excluded_branches.clone(),
-                excluded_branches_patterns: repository.excluded_branches_patterns.clone(),
-                included_projects: repository.included_projects.clone(),
-                included_projects_patterns: repository.included_projects_patterns.clone(),
-            };
+            // Repository object for this project, grouped under the GitLab repository
+            let project_repository = project_repository(
+                repository,
+                &project.path_with_namespace,
+                &project.http_url_to_repo,
+                project.default_branch.clone(),
+            );
 
             // Clone this specific project
             match clone_or_update_fn(&project_repository, &project_path).await {
                 Ok(_) => {
+                    self.fetch_open_merge_requests(
+                        &gitlab_service,
+                        &gitlab_url,
+                        &access_token,
+                        project,
+                        &project_repository,
+                        &project_path,
+                    )
+                    .await;
+
                     // Create progress tracker for this project
-                    let mut project_progress =
-                        CrawlProgress { files_processed: 0, files_indexed: 0, errors: Vec::new() };
+                    let mut project_progress = CrawlProgress {
+                        files_processed: 0,
+                        files_indexed: 0,
+                        files_skipped_binary: 0,
+                        errors: Vec::new(),
+                    };
 
                     // Process files in this project with hierarchical tracking
                     match process_files_fn(
@@ -462,46 +487,32 @@ impl GitLabCrawler {
             // Process the project (same logic as normal crawl)
             let project_path = base_repo_path.join(&project.path_with_namespace);
 
-            let project_repository = Repository {
-                id: repository.id,
-                name: project.path_with_namespace.clone(),
-                url: project.http_url_to_repo.clone(),
-                repository_type: RepositoryType::Git,
-                branch: project.default_branch.clone(),
-                enabled: repository.enabled,
-                access_token: repository.access_token.clone(),
-                gitlab_namespace: repository.gitlab_namespace.clone(),
-                is_group: false,
-                created_at: repository.created_at,
-                updated_at: repository.updated_at,
-                last_crawled: repository.last_crawled,
-                auto_crawl_enabled: repository.auto_crawl_enabled,
-                cron_schedule: repository.cron_schedule.clone(),
-                next_crawl_at: repository.next_crawl_at,
-                crawl_frequency_hours: repository.crawl_frequency_hours,
-                max_crawl_duration_minutes: repository.max_crawl_duration_minutes,
-                last_crawl_duration_seconds: repository.last_crawl_duration_seconds,
-                gitlab_excluded_projects: repository.gitlab_excluded_projects.clone(),
-                gitlab_excluded_patterns: repository.gitlab_excluded_patterns.clone(),
-                github_namespace: repository.github_namespace.clone(),
-                github_excluded_repositories: repository.github_excluded_repositories.clone(),
-                github_excluded_patterns: repository.github_excluded_patterns.clone(),
-                crawl_state: repository.crawl_state.clone(),
-                last_processed_project: repository.last_processed_project.clone(),
-                crawl_started_at: repository.crawl_started_at,
-                included_branches: repository.included_branches.clone(),
-                included_branches_patterns: repository.included_branches_patterns.clone(),
-                excluded_branches: repository.excluded_branches.clone(),
-                excluded_branches_patterns: repository.excluded_branches_patterns.clone(),
-                included_projects: repository.included_projects.clone(),
-                included_projects_patterns: repository.included_projects_patterns.clone(),
-            };
+            let project_repository = project_repository(
+                repository,
+                &project.path_with_namespace,
+                &project.http_url_to_repo,
+                project.default_branch.clone(),
+            );
 
             // Clone and process this project
             match clone_or_update_fn(&project_repository, &project_path).await {
                 Ok(_) => {
-                    let mut project_progress =
-                        CrawlProgress { files_processed: 0, files_indexed: 0, errors: Vec::new() };
+                    self.fetch_open_merge_requests(
+                        &gitlab_service,
+                        &gitlab_url,
+                        &access_token,
+                        project,
+                        &project_repository,
+                        &project_path,
+                    )
+                    .await;
+
+                    let mut project_progress = CrawlProgress {
+                        files_processed: 0,
+                        files_indexed: 0,
+                        files_skipped_binary: 0,
+                        errors: Vec::new(),
+                    };
 
                     match process_files_fn(
                         &project_repository,
@@ -0,0 +1,162 @@
+//! Ignore files read from crawled trees
+//!
+//! - `.klaskignore` (gitignore syntax): files never to index, in any repository
+//! - `.gitattributes`: files marked `linguist-generated`, `linguist-vendored`, `-diff` or `binary`
+//! - `.gitignore`: only honored when walking a working directory (FileSystem repositories), where
+//!   untracked build output lives. Git trees only contain tracked files, so it is not applied there.
+//!
+//! Like Git, ignore files apply to their own directory and below, and deeper files take precedence.
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+use tracing::warn;
+
+pub const KLASKIGNORE: &str = ".klaskignore";
+pub const GITATTRIBUTES: &str = ".gitattributes";
+pub const GITIGNORE: &str = ".gitignore";
+
+/// `.gitattributes` attributes marking files that are not indexed
+const EXCLUDING_ATTRIBUTES: &[&str] = &["linguist-generated", "linguist-vendored"];
+
+/// Ignore rules built from the ignore files of one tree
+#[derive(Default)]
+pub struct IgnoreRules {
+    /// One matcher per ignore file with the directory it applies to, deepest directories first
+    matchers: Vec<(String, Gitignore)>,
+}
+
+impl IgnoreRules {
+    /// Whether a file name is one of the ignore files read by the crawler
+    pub fn is_ignore_file(file_name: &str, include_gitignore: bool) -> bool {
+        file_name == KLASKIGNORE || file_name == GITATTRIBUTES || (include_gitignore && file_name == GITIGNORE)
+    }
+
+    /// Build the rules from the ignore files of a tree, given as (relative path, content) pairs
+    pub fn from_files<'a>(files: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut matchers = Vec::new();
+
+        for (path, content) in files {
+            let path = Path::new(path);
+            let directory = path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+            let lines = match path.file_name().and_then(|name| name.to_str()) {
+                Some(GITATTRIBUTES) => attributes_to_ignore_lines(content),
+                _ => content.lines().map(str::to_string).collect(),
+            };
+
+            let mut builder = GitignoreBuilder::new(&directory);
+            for line in &lines {
+                if let Err(e) = builder.add_line(None, line) {
+                    warn!("Skipping invalid line '{}' in {}: {}", line, path.display(), e);
+                }
+            }
+            match builder.build() {
+                Ok(matcher) if !matcher.is_empty() => matchers.push((directory, matcher)),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to parse {}: {}", path.display(), e),
+            }
+        }
+
+        matchers.sort_by_key(|(directory, _)| std::cmp::Reverse(Path::new(directory).components().count()));
+        Self { matchers }
+    }
+
+    /// Whether the file at `relative_path` (relative to the tree root) is excluded by an ignore file
+    pub fn is_ignored(&self, relative_path: &str) -> bool {
+        let path = Path::new(relative_path);
+        for (directory, matcher) in &self.matchers {
+            if !path.starts_with(directory) {
+                continue;
+            }
+            match matcher.matched_path_or_any_parents(path, false) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+}
+
+/// Translate `.gitattributes` lines into gitignore lines: patterns whose files are not indexed,
+/// and negated patterns for attributes explicitly unset (e.g. `linguist-generated=false`)
+fn attributes_to_ignore_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next() else { continue };
+        let mut excluded = None;
+        for attribute in parts {
+            let (name, value) = match attribute.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (attribute, None),
+            };
+            if attribute == "-diff" || attribute == "binary" {
+                excluded = Some(true);
+            } else if EXCLUDING_ATTRIBUTES.contains(&name) {
+                excluded = Some(!matches!(value, Some("false") | Some("0")));
+            } else if let Some(name) = name.strip_prefix('-').or_else(|| name.strip_prefix('!'))
+                && EXCLUDING_ATTRIBUTES.contains(&name)
+            {
+                excluded = Some(false);
+            }
+        }
+
+        match excluded {
+            Some(true) => lines.push(pattern.to_string()),
+            Some(false) => lines.push(format!("!{}", pattern)),
+            None => {}
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_klaskignore_uses_gitignore_syntax() {
+        let rules = IgnoreRules::from_files([(".klaskignore", "# generated stubs\n*.pb.go\nthird_party/")]);
+        assert!(rules.is_ignored("api/service.pb.go"));
+        assert!(rules.is_ignored("third_party/lib/util.go"));
+        assert!(!rules.is_ignored("api/service.go"));
+    }
+
+    #[test]
+    fn test_gitattributes_linguist_and_diff() {
+        let rules = IgnoreRules::from_files([(
+            ".gitattributes",
+            "*.pb.go linguist-generated=true\nvendor/** linguist-vendored\n*.svg -diff\n*.md linguist-documentation\nvendor/ours/** linguist-vendored=false",
+        )]);
+        assert!(rules.is_ignored("api/service.pb.go"));
+        assert!(rules.is_ignored("vendor/lib/util.go"));
+        assert!(rules.is_ignored("assets/logo.svg"));
+        assert!(!rules.is_ignored("README.md"));
+        assert!(!rules.is_ignored("vendor/ours/patch.go"));
+    }
+
+    #[test]
+    fn test_nested_files_apply_to_their_directory() {
+        let rules = IgnoreRules::from_files([(".klaskignore", "*.json"), ("web/.klaskignore", "dist/\n!config.json")]);
+        assert!(rules.is_ignored("data/fixtures.json"));
+        assert!(rules.is_ignored("web/dist/app.js"));
+        assert!(!rules.is_ignored("web/config.json"), "Deeper files take precedence");
+        assert!(!rules.is_ignored("dist/app.js"));
+    }
+
+    #[test]
+    fn test_is_ignore_file() {
+        assert!(IgnoreRules::is_ignore_file(".klaskignore", false));
+        assert!(IgnoreRules::is_ignore_file(".gitattributes", false));
+        assert!(!IgnoreRules::is_ignore_file(".gitignore", false));
+        assert!(IgnoreRules::is_ignore_file(".gitignore", true));
+    }
+}
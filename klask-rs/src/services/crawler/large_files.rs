@@ -0,0 +1,154 @@
+//! Files above a repository's size limit
+//!
+//! Depending on the repository's `LargeFileMode`, a file larger than the limit is skipped, indexed up to the
+//! limit and flagged as truncated, or indexed as several chunk documents split between lines. Each chunk
+//! records the number of lines before it, so that search results map back to lines of the original file.
+
+use super::git_tree_walker::MAX_FILE_SIZE;
+use crate::models::{LargeFileMode, Repository};
+
+/// Largest file read when large files are truncated or chunked
+pub const MAX_LARGE_FILE_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Size limit and large file handling of a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargeFilePolicy {
+    /// Size limit of a document in bytes
+    pub max_size: u64,
+    pub mode: LargeFileMode,
+}
+
+impl Default for LargeFilePolicy {
+    fn default() -> Self {
+        Self { max_size: MAX_FILE_SIZE, mode: LargeFileMode::Skip }
+    }
+}
+
+/// Part of a file indexed as one document
+#[derive(Debug, PartialEq, Eq)]
+pub struct ContentPart<'a> {
+    pub text: &'a str,
+    /// Number of lines of the file before this part
+    pub line_offset: u32,
+    pub is_truncated: bool,
+}
+
+impl LargeFilePolicy {
+    pub fn for_repository(repository: &Repository) -> Self {
+        let max_size =
+            repository.max_file_size_mb.filter(|mb| *mb > 0).map(|mb| mb as u64 * 1024 * 1024).unwrap_or(MAX_FILE_SIZE);
+        Self { max_size, mode: repository.large_file_mode.unwrap_or_default() }
+    }
+
+    /// Size of the largest file worth reading
+    pub fn read_limit(&self) -> u64 {
+        match self.mode {
+            LargeFileMode::Skip => self.max_size,
+            LargeFileMode::Truncate | LargeFileMode::Chunk => self.max_size.max(MAX_LARGE_FILE_SIZE),
+        }
+    }
+
+    /// Split file content into the parts indexed as documents (none when the file is skipped)
+    pub fn split<'a>(&self, content: &'a str) -> Vec<ContentPart<'a>> {
+        let max_size = self.max_size as usize;
+        if content.len() <= max_size {
+            return vec![ContentPart { text: content, line_offset: 0, is_truncated: false }];
+        }
+
+        match self.mode {
+            LargeFileMode::Skip => Vec::new(),
+            LargeFileMode::Truncate => {
+                let end = split_point(content, max_size);
+                vec![ContentPart { text: &content[..end], line_offset: 0, is_truncated: true }]
+            }
+            LargeFileMode::Chunk => {
+                let mut parts = Vec::new();
+                let mut rest = content;
+                let mut line_offset = 0;
+                while !rest.is_empty() {
+                    let end = split_point(rest, max_size);
+                    let (text, remaining) = rest.split_at(end);
+                    parts.push(ContentPart { text, line_offset, is_truncated: false });
+                    line_offset += text.matches('\n').count() as u32;
+                    rest = remaining;
+                }
+                parts
+            }
+        }
+    }
+}
+
+/// End of the first part of `text` of at most `max_size` bytes: after the last complete line that fits,
+/// or at a character boundary when a single line is longer than the limit
+fn split_point(text: &str, max_size: usize) -> usize {
+    if text.len() <= max_size {
+        return text.len();
+    }
+    let mut end = max_size.max(1);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if end == 0 {
+        // A single character larger than the limit
+        return text.chars().next().map_or(text.len(), char::len_utf8);
+    }
+    match text[..end].rfind('\n') {
+        Some(newline) => newline + 1,
+        None => end,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_size: u64, mode: LargeFileMode) -> LargeFilePolicy {
+        LargeFilePolicy { max_size, mode }
+    }
+
+    #[test]
+    fn test_small_files_are_indexed_whole() {
+        for mode in [LargeFileMode::Skip, LargeFileMode::Truncate, LargeFileMode::Chunk] {
+            let parts = policy(100, mode).split("a\nb\n");
+            assert_eq!(
+                parts,
+                [ContentPart { text: "a\nb\n", line_offset: 0, is_truncated: false }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_skip_and_truncate() {
+        let content = "line one\nline two\nline three\n";
+        assert!(policy(12, LargeFileMode::Skip).split(content).is_empty());
+        assert_eq!(
+            policy(20, LargeFileMode::Truncate).split(content),
+            [ContentPart { text: "line one\nline two\n", line_offset: 0, is_truncated: true }]
+        );
+    }
+
+    #[test]
+    fn test_chunks_split_between_lines() {
+        let content = "line one\nline two\nline three\nfour";
+        let parts = policy(20, LargeFileMode::Chunk).split(content);
+        assert_eq!(
+            parts,
+            [
+                ContentPart { text: "line one\nline two\n", line_offset: 0, is_truncated: false },
+                ContentPart { text: "line three\nfour", line_offset: 2, is_truncated: false },
+            ]
+        );
+
+        // Lines longer than the limit are cut at a character boundary
+        let parts = policy(5, LargeFileMode::Chunk).split("ééééé\nabcdef");
+        let texts: Vec<&str> = parts.iter().map(|part| part.text).collect();
+        assert_eq!(texts, ["éé", "éé", "é\n", "abcde", "f"]);
+        assert_eq!(parts[3].line_offset, 1);
+    }
+
+    #[test]
+    fn test_read_limit() {
+        assert_eq!(LargeFilePolicy::default().read_limit(), MAX_FILE_SIZE);
+        assert_eq!(policy(1024, LargeFileMode::Chunk).read_limit(), MAX_LARGE_FILE_SIZE);
+    }
+}
@@ -1,12 +1,25 @@
 // Module declarations for crawler submodules
+pub mod archive;
+pub mod azure_devops_crawler;
+pub mod bitbucket_crawler;
 pub mod branch_processor;
+pub mod encoding;
+pub mod extractors;
 pub mod file_processing;
 pub mod filter;
 pub mod git_operations;
 pub mod git_tree_walker;
+pub mod gitea_crawler;
 pub mod github_crawler;
 pub mod gitlab_crawler;
+pub mod ignore_files;
+pub mod large_files;
+pub mod provider_crawler;
+pub mod review_refs;
 pub mod service;
+pub mod submodules;
+pub mod svn_crawler;
+pub mod svn_dump;
 
 // Re-export main service and commonly used types
 #[allow(unused_imports)]
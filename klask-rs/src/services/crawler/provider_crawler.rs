@@ -0,0 +1,319 @@
+use super::branch_processor::CrawlProgress;
+use crate::models::{Repository, RepositoryType};
+use crate::repositories::RepositoryRepository;
+use crate::services::encryption::EncryptionService;
+use crate::services::progress::ProgressTracker;
+use crate::services::search::SearchService;
+use anyhow::{Result, anyhow};
+use sqlx::{Pool, Postgres};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Repository found by the discovery of a code hosting provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredRepository {
+    pub full_name: String,              // Indexed as the project name, e.g. `owner/name`
+    pub clone_url: Option<String>,      // HTTP(S) clone URL, None when the provider lists none
+    pub default_branch: Option<String>, // None when the listing does not tell it
+}
+
+/// API of a code hosting provider whose repositories are discovered, then cloned and crawled as Git repositories
+pub trait RepositoryDiscovery: Send + Sync {
+    /// Provider name used in logs and errors, e.g. `Bitbucket`
+    fn provider(&self) -> &'static str;
+
+    /// Whether the provider accepts the access token
+    fn test_token(&self, access_token: &str) -> impl Future<Output = Result<bool>> + Send;
+
+    /// Repositories selected by the project filters of `repository`.
+    /// Failures that leave the other repositories crawlable are pushed to `errors`.
+    fn discover(
+        &self,
+        repository: &Repository,
+        access_token: &str,
+        errors: &mut Vec<String>,
+    ) -> impl Future<Output = Result<Vec<DiscoveredRepository>>> + Send;
+
+    /// Called once a discovered repository is cloned, before its files are processed
+    fn after_clone(
+        &self,
+        _access_token: &str,
+        _discovered: &DiscoveredRepository,
+        _project_repository: &Repository,
+        _repo_path: &Path,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+
+/// Git repository crawled for a project of a GitLab, GitHub or other provider repository,
+/// grouped under the provider repository's id and settings
+pub fn project_repository(repository: &Repository, name: &str, url: &str, branch: Option<String>) -> Repository {
+    Repository {
+        name: name.to_string(),
+        url: url.to_string(),
+        repository_type: RepositoryType::Git, // Treat as Git for cloning
+        branch,
+        is_group: false,
+        ..repository.clone()
+    }
+}
+
+/// Crawl loop shared by the providers whose repositories are found through a `RepositoryDiscovery`
+pub struct ProviderCrawler {
+    database: Pool<Postgres>,
+    search_service: Arc<SearchService>,
+    progress_tracker: Arc<ProgressTracker>,
+    encryption_service: Arc<EncryptionService>,
+    temp_dir: PathBuf,
+}
+
+impl ProviderCrawler {
+    pub fn new(
+        database: Pool<Postgres>,
+        search_service: Arc<SearchService>,
+        progress_tracker: Arc<ProgressTracker>,
+        encryption_service: Arc<EncryptionService>,
+        temp_dir: PathBuf,
+    ) -> Self {
+        Self { database, search_service, progress_tracker, encryption_service, temp_dir }
+    }
+
+    /// Crawl a provider repository by discovering all sub-repositories and cloning them
+    pub async fn crawl_provider_repository(
+        &self,
+        discovery: &impl RepositoryDiscovery,
+        repository: &Repository,
+        cancellation_token: CancellationToken,
+        clone_or_update_fn: impl Fn(
+            &Repository,
+            &std::path::Path,
+        )
+            -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<gix::Repository>> + Send>>
+        + Send
+        + Sync,
+        process_files_fn: impl Fn(
+            &Repository,
+            &std::path::Path,
+            &mut CrawlProgress,
+            &CancellationToken,
+            Uuid,
+            &str,
+        ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
+        + Send
+        + Sync,
+        cleanup_token_fn: impl Fn(Uuid) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> + Send + Sync,
+    ) -> Result<()> {
+        let provider = discovery.provider();
+        let repo_repo = RepositoryRepository::new(self.database.clone());
+
+        info!("Starting {} discovery for repository: {}", provider, repository.name);
+
+        // Mark crawl as started in database
+        repo_repo.start_crawl(repository.id, None).await?;
+
+        // Delete all existing documents for this repository before crawling
+        // This ensures no duplicates when re-crawling
+        match self.search_service.delete_project_documents(&repository.name).await {
+            Ok(deleted_count) => {
+                if deleted_count > 0 {
+                    info!(
+                        "Deleted {} existing documents for {} repository {} before crawling",
+                        deleted_count, provider, repository.name
+                    );
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to delete existing documents for {} repository {}: {}",
+                    provider, repository.name, e
+                );
+                // Continue anyway - the upsert should handle duplicates
+            }
+        }
+
+        // Extract and decrypt access token from repository
+        let encrypted_token =
+            repository.access_token.as_ref().ok_or_else(|| anyhow!("{} repository missing access token", provider))?;
+
+        let access_token = self
+            .encryption_service
+            .decrypt(encrypted_token)
+            .map_err(|e| anyhow!("Failed to decrypt {} access token: {}", provider, e))?;
+
+        self.progress_tracker.update_status(repository.id, crate::services::progress::CrawlStatus::Cloning).await;
+
+        // Test the token first
+        info!("Testing {} token for repository: {}", provider, repository.name);
+        let token_error = match discovery.test_token(&access_token).await {
+            Ok(true) => None,
+            Ok(false) => Some(format!("{} token is invalid or expired", provider)),
+            Err(e) => Some(format!("Failed to test {} token: {}", provider, e)),
+        };
+        if let Some(error_msg) = token_error {
+            error!(
+                "{} token validation failed for repository {}: {}",
+                provider, repository.name, error_msg
+            );
+            self.progress_tracker.set_error(repository.id, error_msg.clone()).await;
+            // Mark crawl as failed in database
+            let _ = repo_repo.fail_crawl(repository.id).await;
+            cleanup_token_fn(repository.id).await;
+            return Err(anyhow!(error_msg));
+        }
+        info!("{} token is valid", provider);
+
+        // Discover the repositories selected by the project filters
+        info!(
+            "Discovering {} repositories for repository: {}",
+            provider, repository.name
+        );
+        let mut all_errors = Vec::new();
+        let filtered_repositories = match discovery.discover(repository, &access_token, &mut all_errors).await {
+            Ok(repos) => repos,
+            Err(e) => {
+                let error_msg = format!("Failed to discover {} repositories: {}", provider, e);
+                error!(
+                    "{} discovery error for repository {}: {}",
+                    provider, repository.name, error_msg
+                );
+                self.progress_tracker.set_error(repository.id, error_msg.clone()).await;
+                // Mark crawl as failed in database
+                let _ = repo_repo.fail_crawl(repository.id).await;
+                cleanup_token_fn(repository.id).await;
+                return Err(anyhow!(error_msg));
+            }
+        };
+
+        if filtered_repositories.is_empty() {
+            let error_msg = format!("No accessible {} repositories found after filtering", provider);
+            self.progress_tracker.set_error(repository.id, error_msg.clone()).await;
+            // Mark crawl as failed in database
+            let _ = repo_repo.fail_crawl(repository.id).await;
+            cleanup_token_fn(repository.id).await;
+            return Err(anyhow!(error_msg));
+        }
+
+        info!(
+            "Discovered {} {} repositories for repository {} after filtering",
+            filtered_repositories.len(),
+            provider,
+            repository.name
+        );
+
+        // Initialize hierarchical progress tracking (projects_processed / current_project)
+        self.progress_tracker.set_gitlab_projects_total(repository.id, filtered_repositories.len()).await;
+
+        // Create base directory for this repository
+        let base_repo_path = self.temp_dir.join(format!("{}-{}", repository.name, repository.id));
+        std::fs::create_dir_all(&base_repo_path)?;
+
+        let mut total_files_processed = 0;
+        let mut total_files_indexed = 0;
+
+        // Process each discovered repository
+        for (repo_index, discovered) in filtered_repositories.iter().enumerate() {
+            let full_name = &discovered.full_name;
+
+            // Update progress in database before processing each repository
+            repo_repo.update_crawl_progress(repository.id, Some(full_name.clone())).await?;
+
+            // Check for cancellation before each repository
+            if cancellation_token.is_cancelled() {
+                self.progress_tracker.cancel_crawl(repository.id).await;
+                cleanup_token_fn(repository.id).await;
+                return Ok(());
+            }
+
+            info!(
+                "Processing {} repository {}/{}: {}",
+                provider,
+                repo_index + 1,
+                filtered_repositories.len(),
+                full_name
+            );
+
+            // Update current project in progress tracker
+            self.progress_tracker.set_current_gitlab_project(repository.id, Some(full_name.clone())).await;
+
+            // Create sub-directory for this repository
+            let repo_path = base_repo_path.join(full_name);
+
+            let Some(clone_url) = &discovered.clone_url else {
+                let error_msg = format!("{} repository {} has no HTTP clone URL", provider, full_name);
+                warn!("{}", error_msg);
+                all_errors.push(error_msg);
+                continue;
+            };
+
+            let temp_repository =
+                project_repository(repository, full_name, clone_url, discovered.default_branch.clone());
+
+            // Clone this specific repository
+            match clone_or_update_fn(&temp_repository, &repo_path).await {
+                Ok(_) => {
+                    discovery.after_clone(&access_token, discovered, &temp_repository, &repo_path).await;
+
+                    // Create progress tracker for this repository
+                    let mut repo_progress = CrawlProgress {
+                        files_processed: 0,
+                        files_indexed: 0,
+                        files_skipped_binary: 0,
+                        errors: Vec::new(),
+                    };
+
+                    // Process files in this repository with hierarchical tracking
+                    match process_files_fn(
+                        &temp_repository,
+                        &repo_path,
+                        &mut repo_progress,
+                        &cancellation_token,
+                        repository.id,
+                        &repository.name, // Pass parent repository name
+                    )
+                    .await
+                    {
+                        Ok(()) => {
+                            info!("Successfully processed {} repository: {}", provider, full_name);
+                            total_files_processed += repo_progress.files_processed;
+                            total_files_indexed += repo_progress.files_indexed;
+                            all_errors.extend(repo_progress.errors);
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to process files for repository {}: {}", full_name, e);
+                            warn!("{}", error_msg);
+                            all_errors.push(error_msg);
+                        }
+                    }
+
+                    // Complete this repository in the tracker
+                    self.progress_tracker.complete_current_gitlab_project(repository.id).await;
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to clone {} repository {}: {}", provider, full_name, e);
+                    warn!("{}", error_msg);
+                    all_errors.push(error_msg);
+                }
+            }
+        }
+
+        // Update final progress
+        self.progress_tracker.update_progress(repository.id, total_files_processed, None, total_files_indexed).await;
+
+        if !all_errors.is_empty() {
+            let combined_errors = all_errors.join("; ");
+            self.progress_tracker
+                .set_error(repository.id, format!("Some repositories failed: {}", combined_errors))
+                .await;
+        }
+
+        info!("Completed {} repository crawl for: {}", provider, repository.name);
+
+        // Note: Tantivy commit, database updates, and progress completion are handled in CrawlerService::finalize_crawl()
+        Ok(())
+    }
+}
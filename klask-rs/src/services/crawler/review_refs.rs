@@ -0,0 +1,57 @@
+use anyhow::{Result, anyhow};
+
+/// Namespace of the clone where the heads of open merge requests / pull requests are fetched, one reference
+/// per indexed version (e.g. `refs/klask/reviews/MR!123`)
+pub const REVIEW_REF_PREFIX: &str = "refs/klask/reviews/";
+
+/// Open merge request or pull request indexed as a virtual version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewRef {
+    pub version: String,    // Version name in the index: `MR!123` or `PR#45`
+    pub source_ref: String, // Reference advertised by the provider for the head of the review
+}
+
+impl ReviewRef {
+    /// Open GitLab merge request, by its project-scoped IID
+    pub fn merge_request(iid: u64) -> Self {
+        Self { version: format!("MR!{}", iid), source_ref: format!("refs/merge-requests/{}/head", iid) }
+    }
+
+    /// Open GitHub pull request, by its number
+    pub fn pull_request(number: u64) -> Self {
+        Self { version: format!("PR#{}", number), source_ref: format!("refs/pull/{}/head", number) }
+    }
+
+    /// Reference of the clone holding the fetched head
+    pub fn local_ref(&self) -> String {
+        format!("{}{}", REVIEW_REF_PREFIX, self.version)
+    }
+
+    /// Refspec fetching the head of the review into the clone
+    pub fn refspec(&self) -> String {
+        format!("+{}:{}", self.source_ref, self.local_ref())
+    }
+}
+
+/// Whether a version name is an indexed merge request or pull request rather than a branch
+pub fn is_review_version(version: &str) -> bool {
+    let number = version.strip_prefix("MR!").or_else(|| version.strip_prefix("PR#"));
+    number.is_some_and(|number| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Versions of the merge requests / pull requests fetched into a clone, sorted
+pub fn fetched_review_versions(repo: &gix::Repository) -> Result<Vec<String>> {
+    let references = repo.references()?;
+    let mut versions = Vec::new();
+    for reference in references.prefixed(REVIEW_REF_PREFIX)? {
+        let reference = reference.map_err(|e| anyhow!("Failed to iterate references: {:?}", e))?;
+        let name = reference.name().as_bstr().to_string();
+        if let Some(version) = name.strip_prefix(REVIEW_REF_PREFIX)
+            && is_review_version(version)
+        {
+            versions.push(version.to_string());
+        }
+    }
+    versions.sort();
+    Ok(versions)
+}
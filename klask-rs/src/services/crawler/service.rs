@@ -1,17 +1,27 @@
+use super::azure_devops_crawler::AzureDevOpsDiscovery;
+use super::bitbucket_crawler::BitbucketDiscovery;
 use super::branch_processor::{BranchProcessor, CrawlProgress};
-use super::file_processing::SUPPORTED_EXTENSIONS;
 use super::git_operations::GitOperations;
-use super::github_crawler::GitHubCrawler;
+use super::git_tree_walker::GitTreeWalker;
+use super::gitea_crawler::GiteaDiscovery;
+use super::github_crawler::GitHubDiscovery;
 use super::gitlab_crawler::GitLabCrawler;
-use crate::models::{Repository, RepositoryType};
-use crate::repositories::RepositoryRepository;
+use super::ignore_files::IgnoreRules;
+use super::provider_crawler::{ProviderCrawler, RepositoryDiscovery, project_repository};
+use super::submodules::MAX_SUBMODULE_DEPTH;
+use super::svn_crawler::SvnCrawler;
+use crate::models::{FileInclusionRules, Repository, RepositoryType, SshDeployKey};
+use crate::repositories::{RepositoryRepository, SearchSettingsRepository};
 use crate::services::encryption::EncryptionService;
 use crate::services::progress::ProgressTracker;
 use crate::services::search::SearchService;
 use anyhow::{Result, anyhow};
+use futures::future::BoxFuture;
+use gix::ObjectId;
+use gix::refs::transaction::PreviousValue;
 use sha2::{Digest, Sha256};
 use sqlx::{Pool, Postgres};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -19,6 +29,24 @@ use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// Project of a GitLab or GitHub repository targeted by a branch crawl
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchCrawlProject {
+    pub name: String,      // Full project path, e.g. `group/project`, indexed as the project name
+    pub clone_url: String, // HTTP(S) or SSH URL of the project
+}
+
+/// Outcome of a branch crawl
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchCrawl {
+    Unchanged,          // The branch did not change since its last branch crawl
+    Incremental(usize), // Only the given number of changed files were re-indexed
+    Full,               // The whole branch was re-indexed
+}
+
+/// Namespace of the clone recording the last commit indexed by a branch crawl, e.g. `refs/klask/indexed/main`
+pub const INDEXED_REF_PREFIX: &str = "refs/klask/indexed/";
+
 /// Main crawler service that orchestrates all crawl operations
 pub struct CrawlerService {
     database: Pool<Postgres>,
@@ -27,11 +55,16 @@ pub struct CrawlerService {
     encryption_service: Arc<EncryptionService>,
     pub temp_dir: PathBuf,
     cancellation_tokens: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
+    // Global file inclusion rules, reloaded from the database when a crawl starts
+    inclusion_rules: Arc<std::sync::RwLock<FileInclusionRules>>,
+    // Shared SSH deploy key, reloaded from the database when a crawl starts
+    ssh_deploy_key: Arc<std::sync::RwLock<Option<SshDeployKey>>>,
     // Specialized crawlers
     git_operations: GitOperations,
     branch_processor: BranchProcessor,
     gitlab_crawler: GitLabCrawler,
-    github_crawler: GitHubCrawler,
+    provider_crawler: ProviderCrawler, // GitHub, Bitbucket, Gitea and Azure DevOps
+    svn_crawler: SvnCrawler,
 }
 
 impl CrawlerService {
@@ -46,22 +79,38 @@ impl CrawlerService {
         std::fs::create_dir_all(&temp_dir).map_err(|e| anyhow!("Failed to create temp directory: {}", e))?;
 
         // Create specialized crawlers
-        let git_operations = GitOperations::new(encryption_service.clone());
-        let branch_processor = BranchProcessor::new(search_service.clone(), progress_tracker.clone());
+        let ssh_deploy_key = Arc::new(std::sync::RwLock::new(None));
+        let git_operations = GitOperations::new(encryption_service.clone(), ssh_deploy_key.clone());
+        let inclusion_rules = Arc::new(std::sync::RwLock::new(FileInclusionRules::default()));
+        let branch_processor = BranchProcessor::new(
+            search_service.clone(),
+            progress_tracker.clone(),
+            inclusion_rules.clone(),
+            encryption_service.clone(),
+        );
         let gitlab_crawler = GitLabCrawler::new(
             database.clone(),
             search_service.clone(),
             progress_tracker.clone(),
             encryption_service.clone(),
+            git_operations.clone(),
             temp_dir.clone(),
         );
-        let github_crawler = GitHubCrawler::new(
+        let provider_crawler = ProviderCrawler::new(
             database.clone(),
             search_service.clone(),
             progress_tracker.clone(),
             encryption_service.clone(),
             temp_dir.clone(),
         );
+        let svn_crawler = SvnCrawler::new(
+            database.clone(),
+            progress_tracker.clone(),
+            encryption_service.clone(),
+            branch_processor.clone(),
+            inclusion_rules.clone(),
+            temp_dir.clone(),
+        );
 
         Ok(Self {
             database,
@@ -70,10 +119,13 @@ impl CrawlerService {
             encryption_service,
             temp_dir,
             cancellation_tokens: Arc::new(RwLock::new(HashMap::new())),
+            inclusion_rules,
+            ssh_deploy_key,
             git_operations,
             branch_processor,
             gitlab_crawler,
-            github_crawler,
+            provider_crawler,
+            svn_crawler,
         })
     }
 
@@ -93,8 +145,14 @@ impl CrawlerService {
                 // For FileSystem: hash of {repository.url}:{relative_path}
                 format!("{}:{}", repository.url, relative_path)
             }
-            RepositoryType::Git | RepositoryType::GitLab | RepositoryType::GitHub => {
-                // For Git/GitLab/GitHub: hash of {repository.url}:{branch}:{relative_path}
+            RepositoryType::Git
+            | RepositoryType::GitLab
+            | RepositoryType::GitHub
+            | RepositoryType::Bitbucket
+            | RepositoryType::Gitea
+            | RepositoryType::AzureDevOps
+            | RepositoryType::Svn => {
+                // For Git, Subversion and the hosted providers: hash of {repository.url}:{branch}:{relative_path}
                 format!("{}:{}:{}", repository.url, branch_name, relative_path)
             }
         };
@@ -127,6 +185,9 @@ impl CrawlerService {
         // Mark crawl as started in database
         repo_repo.start_crawl(repository.id, None).await?;
 
+        self.reload_inclusion_rules().await;
+        self.reload_ssh_deploy_key().await;
+
         // Delete all existing documents for this repository/project before crawling
         // This ensures no duplicates when re-crawling
         match self.search_service.delete_project_documents(&repository.name).await {
@@ -166,7 +227,8 @@ impl CrawlerService {
         }
 
         // Initialize progress for all repository types
-        let mut progress = CrawlProgress { files_processed: 0, files_indexed: 0, errors: Vec::new() };
+        let mut progress =
+            CrawlProgress { files_processed: 0, files_indexed: 0, files_skipped_binary: 0, errors: Vec::new() };
 
         let repo_path_git = match repository.repository_type {
             RepositoryType::Git => {
@@ -245,61 +307,36 @@ impl CrawlerService {
                     .update_status(repository.id, crate::services::progress::CrawlStatus::Indexing)
                     .await;
             }
+            RepositoryType::Svn => {
+                // For Subversion repositories, export trunk, branches and tags and process each of them
+                if let Err(e) =
+                    self.svn_crawler.crawl_svn_repository(repository, &mut progress, &cancellation_token).await
+                {
+                    let error_msg = format!("Failed to crawl Subversion repository: {}", e);
+                    error!("Crawl error for repository {}: {}", repository.name, error_msg);
+                    self.progress_tracker.set_error(repository.id, error_msg.clone()).await;
+                    // Mark crawl as failed in database
+                    let _ = repo_repo.fail_crawl(repository.id).await;
+                    self.cleanup_cancellation_token(repository.id).await;
+                    return Err(anyhow!(error_msg));
+                }
+
+                // Check for cancellation before indexing
+                if cancellation_token.is_cancelled() {
+                    self.progress_tracker.cancel_crawl(repository.id).await;
+                    self.cleanup_cancellation_token(repository.id).await;
+                    return Ok(());
+                }
+
+                // Update status to indexing
+                self.progress_tracker
+                    .update_status(repository.id, crate::services::progress::CrawlStatus::Indexing)
+                    .await;
+            }
             RepositoryType::GitLab => {
                 // For GitLab repositories, discover and clone all sub-projects
-                // Create closures for GitLab crawler callbacks
-                let clone_or_update_fn = |repo: &Repository, path: &Path| {
-                    let repo = repo.clone();
-                    let path = path.to_owned();
-                    let git_ops = self.git_operations.clone();
-                    Box::pin(async move { git_ops.clone_or_update_repository(&repo, &path).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = Result<gix::Repository>> + Send>>
-                };
-
-                let process_files_fn = |repo: &Repository,
-                                        path: &Path,
-                                        progress: &mut CrawlProgress,
-                                        token: &CancellationToken,
-                                        parent_id: Uuid,
-                                        parent_name: &str| {
-                    let repo = repo.clone();
-                    let path = path.to_owned();
-                    let mut progress_clone = CrawlProgress {
-                        files_processed: progress.files_processed,
-                        files_indexed: progress.files_indexed,
-                        errors: progress.errors.clone(),
-                    };
-                    let token = token.clone();
-                    let parent_name = parent_name.to_owned();
-                    let branch_processor = self.branch_processor.clone();
-
-                    Box::pin(async move {
-                        let project_start_files = progress_clone.files_processed;
-                        branch_processor
-                            .process_all_branches_with_tracking(
-                                &repo,
-                                &path,
-                                &mut progress_clone,
-                                &token,
-                                parent_id,
-                                project_start_files,
-                                &parent_name,
-                            )
-                            .await
-                    }) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-                };
-
-                let update_crawl_time_fn = |repo_id: Uuid, duration: Option<i32>| {
-                    let service = self.clone();
-                    Box::pin(async move { service.update_repository_crawl_time(repo_id, duration).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-                };
-
-                let cleanup_token_fn = |repo_id: Uuid| {
-                    let service = self.clone();
-                    Box::pin(async move { service.cleanup_cancellation_token(repo_id).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
-                };
+                let (clone_or_update_fn, process_files_fn, update_crawl_time_fn, cleanup_token_fn) =
+                    self.project_crawl_callbacks();
 
                 self.gitlab_crawler
                     .crawl_gitlab_repository(
@@ -314,69 +351,22 @@ impl CrawlerService {
             }
             RepositoryType::GitHub => {
                 // For GitHub repositories, discover and clone all sub-repositories
-                // Create closures for GitHub crawler callbacks
-                let clone_or_update_fn = |repo: &Repository, path: &Path| {
-                    let repo = repo.clone();
-                    let path = path.to_owned();
-                    let git_ops = self.git_operations.clone();
-                    Box::pin(async move { git_ops.clone_or_update_repository(&repo, &path).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = Result<gix::Repository>> + Send>>
-                };
-
-                let process_files_fn = |repo: &Repository,
-                                        path: &Path,
-                                        progress: &mut CrawlProgress,
-                                        token: &CancellationToken,
-                                        parent_id: Uuid,
-                                        parent_name: &str| {
-                    let repo = repo.clone();
-                    let path = path.to_owned();
-                    let mut progress_clone = CrawlProgress {
-                        files_processed: progress.files_processed,
-                        files_indexed: progress.files_indexed,
-                        errors: progress.errors.clone(),
-                    };
-                    let token = token.clone();
-                    let parent_name = parent_name.to_owned();
-                    let branch_processor = self.branch_processor.clone();
-
-                    Box::pin(async move {
-                        let project_start_files = progress_clone.files_processed;
-                        branch_processor
-                            .process_all_branches_with_tracking(
-                                &repo,
-                                &path,
-                                &mut progress_clone,
-                                &token,
-                                parent_id,
-                                project_start_files,
-                                &parent_name,
-                            )
-                            .await
-                    }) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-                };
-
-                let update_crawl_time_fn = |repo_id: Uuid, duration: Option<i32>| {
-                    let service = self.clone();
-                    Box::pin(async move { service.update_repository_crawl_time(repo_id, duration).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-                };
-
-                let cleanup_token_fn = |repo_id: Uuid| {
-                    let service = self.clone();
-                    Box::pin(async move { service.cleanup_cancellation_token(repo_id).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
-                };
-
-                self.github_crawler
-                    .crawl_github_repository(
-                        repository,
-                        cancellation_token,
-                        clone_or_update_fn,
-                        process_files_fn,
-                        update_crawl_time_fn,
-                        cleanup_token_fn,
-                    )
+                let discovery = GitHubDiscovery::new(repository, self.git_operations.clone());
+                self.crawl_provider_repository(&discovery, repository, cancellation_token).await?;
+            }
+            RepositoryType::Bitbucket => {
+                // For Bitbucket repositories, discover and clone the repositories of its projects
+                self.crawl_provider_repository(&BitbucketDiscovery::new(repository), repository, cancellation_token)
+                    .await?;
+            }
+            RepositoryType::Gitea => {
+                // For Gitea repositories, discover and clone the repositories of the organization or token owner
+                self.crawl_provider_repository(&GiteaDiscovery::new(repository), repository, cancellation_token)
+                    .await?;
+            }
+            RepositoryType::AzureDevOps => {
+                // For Azure DevOps organizations, discover and clone the repositories of the selected projects
+                self.crawl_provider_repository(&AzureDevOpsDiscovery::new(repository), repository, cancellation_token)
                     .await?;
             }
         };
@@ -385,10 +375,11 @@ impl CrawlerService {
         self.finalize_crawl(repository, crawl_start_time).await?;
 
         info!(
-            "Crawl completed for repository: {}. Files processed: {}, Files indexed: {}, Errors: {}",
+            "Crawl completed for repository: {}. Files processed: {}, Files indexed: {}, Binary files skipped: {}, Errors: {}",
             repository.name,
             progress.files_processed,
             progress.files_indexed,
+            progress.files_skipped_binary,
             progress.errors.len()
         );
 
@@ -399,6 +390,105 @@ impl CrawlerService {
         Ok(())
     }
 
+    /// Crawl a GitHub, Bitbucket, Gitea or Azure DevOps repository with the shared provider crawl loop
+    async fn crawl_provider_repository(
+        &self,
+        discovery: &impl RepositoryDiscovery,
+        repository: &Repository,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let (clone_or_update_fn, process_files_fn, _, cleanup_token_fn) = self.project_crawl_callbacks();
+
+        self.provider_crawler
+            .crawl_provider_repository(
+                discovery,
+                repository,
+                cancellation_token,
+                clone_or_update_fn,
+                process_files_fn,
+                cleanup_token_fn,
+            )
+            .await
+    }
+
+    /// Callbacks through which the GitLab and provider crawlers clone their projects, process their files,
+    /// update the crawl time and release the cancellation token
+    #[allow(clippy::type_complexity)]
+    fn project_crawl_callbacks(
+        &self,
+    ) -> (
+        impl Fn(&Repository, &Path) -> BoxFuture<'static, Result<gix::Repository>> + Send + Sync,
+        impl Fn(&Repository, &Path, &mut CrawlProgress, &CancellationToken, Uuid, &str) -> BoxFuture<'static, Result<()>>
+        + Send
+        + Sync,
+        impl Fn(Uuid, Option<i32>) -> BoxFuture<'static, Result<()>> + Send + Sync,
+        impl Fn(Uuid) -> BoxFuture<'static, ()> + Send + Sync,
+    ) {
+        let git_ops = self.git_operations.clone();
+        let clone_or_update_fn = move |repo: &Repository, path: &Path| {
+            let repo = repo.clone();
+            let path = path.to_owned();
+            let git_ops = git_ops.clone();
+            Box::pin(async move { git_ops.clone_or_update_repository(&repo, &path).await })
+                as BoxFuture<'static, Result<gix::Repository>>
+        };
+
+        let branch_processor = self.branch_processor.clone();
+        let process_files_fn = move |repo: &Repository,
+                                     path: &Path,
+                                     progress: &mut CrawlProgress,
+                                     token: &CancellationToken,
+                                     parent_id: Uuid,
+                                     parent_name: &str| {
+            let repo = repo.clone();
+            let path = path.to_owned();
+            let mut progress_clone = CrawlProgress {
+                files_processed: progress.files_processed,
+                files_indexed: progress.files_indexed,
+                files_skipped_binary: progress.files_skipped_binary,
+                errors: progress.errors.clone(),
+            };
+            let token = token.clone();
+            let parent_name = parent_name.to_owned();
+            let branch_processor = branch_processor.clone();
+
+            Box::pin(async move {
+                let project_start_files = progress_clone.files_processed;
+                branch_processor
+                    .process_all_branches_with_tracking(
+                        &repo,
+                        &path,
+                        &mut progress_clone,
+                        &token,
+                        parent_id,
+                        project_start_files,
+                        &parent_name,
+                    )
+                    .await
+            }) as BoxFuture<'static, Result<()>>
+        };
+
+        let service = self.clone();
+        let update_crawl_time_fn = move |repo_id: Uuid, duration: Option<i32>| {
+            let service = service.clone();
+            Box::pin(async move { service.update_repository_crawl_time(repo_id, duration).await })
+                as BoxFuture<'static, Result<()>>
+        };
+
+        let service = self.clone();
+        let cleanup_token_fn = move |repo_id: Uuid| {
+            let service = service.clone();
+            Box::pin(async move { service.cleanup_cancellation_token(repo_id).await }) as BoxFuture<'static, ()>
+        };
+
+        (
+            clone_or_update_fn,
+            process_files_fn,
+            update_crawl_time_fn,
+            cleanup_token_fn,
+        )
+    }
+
     /// Process repository files using the branch processor
     pub async fn process_repository_files(
         &self,
@@ -429,40 +519,212 @@ impl CrawlerService {
         }
     }
 
-    /// Check if a file is supported for indexing based on its extension or name
-    #[allow(dead_code)]
-    pub fn is_supported_file(&self, file_path: &Path) -> bool {
-        Self::is_supported_file_static(file_path)
+    /// Crawl a single branch of a Git repository, or of one project of a GitLab / GitHub repository, after a push
+    ///
+    /// The last commit indexed by a branch crawl is kept under `INDEXED_REF_PREFIX` in the clone: only the files
+    /// changed since that commit are re-indexed, unless an ignore file changed. The branch is fully re-indexed
+    /// otherwise.
+    pub async fn crawl_branch(
+        &self,
+        repository: &Repository,
+        project: Option<&BranchCrawlProject>,
+        branch: &str,
+    ) -> Result<BranchCrawl> {
+        let (target, repo_path) = self.branch_crawl_target(repository, project)?;
+        let parent_project_name = project.map(|_| repository.name.as_str());
+        let repository_field = parent_project_name.unwrap_or(&target.name);
+        let cancellation_token = self.start_branch_crawl(repository).await?;
+
+        let result = async {
+            self.reload_inclusion_rules().await;
+            self.reload_ssh_deploy_key().await;
+            self.git_operations.clone_or_update_repository(&target, &repo_path).await?;
+
+            let submodule_depth = if target.index_submodules == Some(true) { MAX_SUBMODULE_DEPTH } else { 0 };
+            let indexed_ref = format!("{}{}", INDEXED_REF_PREFIX, branch);
+            let (commit_id, changed_paths) = {
+                let repo_path = repo_path.clone();
+                let branch = branch.to_string();
+                let indexed_ref = indexed_ref.clone();
+                tokio::task::spawn_blocking(move || -> Result<(ObjectId, Option<Vec<String>>)> {
+                    let git_repo = gix::open(&repo_path)?;
+                    let commit_id = GitTreeWalker::get_branch_commit_id(&git_repo, &branch)?;
+                    let Ok(indexed) = git_repo.find_reference(&indexed_ref) else {
+                        return Ok((commit_id, None));
+                    };
+                    let old_tree_id = GitTreeWalker::get_commit_tree_id(&git_repo, &indexed.id().detach())?;
+                    let new_tree_id = GitTreeWalker::get_commit_tree_id(&git_repo, &commit_id)?;
+                    let changed = GitTreeWalker::changed_paths(&git_repo, &old_tree_id, &new_tree_id, submodule_depth)?;
+                    Ok((commit_id, Some(changed)))
+                })
+                .await??
+            };
+
+            let mut progress =
+                CrawlProgress { files_processed: 0, files_indexed: 0, files_skipped_binary: 0, errors: Vec::new() };
+            // Documents are written over the indexed ones, and those not written again (deleted or excluded files)
+            // are only removed once the whole branch or all changed files have been indexed
+            self.search_service.track_version(repository_field, &target.name, branch);
+            let (crawl, replaced_paths) = match changed_paths {
+                Some(changed_paths) if changed_paths.is_empty() => (BranchCrawl::Unchanged, Some(changed_paths)),
+                // A changed ignore file can include or exclude any file of the branch
+                Some(changed_paths)
+                    if !changed_paths
+                        .iter()
+                        .any(|path| IgnoreRules::is_ignore_file(path.rsplit('/').next().unwrap_or(path), false)) =>
+                {
+                    let paths: HashSet<String> = changed_paths.iter().cloned().collect();
+                    self.branch_processor
+                        .process_branch_paths(
+                            &target,
+                            &repo_path,
+                            branch,
+                            &paths,
+                            &mut progress,
+                            &cancellation_token,
+                            parent_project_name,
+                        )
+                        .await?;
+                    (BranchCrawl::Incremental(changed_paths.len()), Some(changed_paths))
+                }
+                _ => {
+                    self.branch_processor
+                        .process_branch_from_tree(
+                            &target,
+                            &repo_path,
+                            branch,
+                            &mut progress,
+                            &cancellation_token,
+                            None,
+                            parent_project_name,
+                        )
+                        .await?;
+                    (BranchCrawl::Full, None)
+                }
+            };
+            if cancellation_token.is_cancelled() {
+                return Err(anyhow!("Crawl of branch '{}' of {} was cancelled", branch, target.name));
+            }
+
+            self.search_service
+                .replace_version_documents(repository_field, &target.name, branch, replaced_paths.as_deref())
+                .await?;
+            let repo_path = repo_path.clone();
+            tokio::task::spawn_blocking(move || -> Result<()> {
+                let git_repo = gix::open(&repo_path)?;
+                git_repo.reference(
+                    indexed_ref.as_str(),
+                    commit_id,
+                    PreviousValue::Any,
+                    "klask: branch indexed",
+                )?;
+                Ok(())
+            })
+            .await??;
+
+            if !progress.errors.is_empty() {
+                warn!("Branch crawl errors: {:?}", progress.errors);
+            }
+            info!(
+                "Crawled branch '{}' of {}: {:?}, {} files indexed",
+                branch, target.name, crawl, progress.files_indexed
+            );
+            Ok(crawl)
+        }
+        .await;
+
+        // Left over by a failed crawl, whose documents are kept until the next crawl of the branch
+        self.search_service.untrack_version(repository_field, &target.name, branch);
+        self.cleanup_cancellation_token(repository.id).await;
+        result
     }
 
-    /// Static version of is_supported_file for use without instance
-    pub fn is_supported_file_static(file_path: &Path) -> bool {
-        if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
-            SUPPORTED_EXTENSIONS.contains(&extension.to_lowercase().as_str())
-        } else {
-            // Support files without extensions that might be scripts or config files
-            if let Some(file_name) = file_path.file_name().and_then(|name| name.to_str()) {
-                matches!(
-                    file_name.to_lowercase().as_str(),
-                    "dockerfile"
-                        | "makefile"
-                        | "rakefile"
-                        | "gemfile"
-                        | "vagrantfile"
-                        | "procfile"
-                        | "readme"
-                        | "license"
-                        | "changelog"
-                        | "authors"
-                        | "contributors"
-                        | "copying"
-                        | "install"
-                        | "news"
-                        | "todo"
-                )
-            } else {
-                false
+    /// Remove the documents of a deleted branch of a Git repository, or of one project of a GitLab / GitHub repository
+    pub async fn delete_branch(
+        &self,
+        repository: &Repository,
+        project: Option<&BranchCrawlProject>,
+        branch: &str,
+    ) -> Result<u64> {
+        let (_, repo_path) = self.branch_crawl_target(repository, project)?;
+        let project_name = project.map(|project| project.name.as_str()).unwrap_or(&repository.name);
+        let _cancellation_token = self.start_branch_crawl(repository).await?;
+
+        let result = async {
+            let deleted =
+                self.search_service.delete_version_documents(&repository.name, project_name, branch, None).await?;
+
+            // A branch pushed again is crawled in full, not against the commit indexed before its removal
+            if repo_path.exists() {
+                let indexed_ref = format!("{}{}", INDEXED_REF_PREFIX, branch);
+                tokio::task::spawn_blocking(move || -> Result<()> {
+                    let git_repo = gix::open(&repo_path)?;
+                    if let Ok(reference) = git_repo.find_reference(&indexed_ref) {
+                        reference.delete()?;
+                    }
+                    Ok(())
+                })
+                .await??;
+            }
+
+            info!(
+                "Deleted {} documents of removed branch '{}' of {}",
+                deleted, branch, project_name
+            );
+            Ok(deleted)
+        }
+        .await;
+
+        self.cleanup_cancellation_token(repository.id).await;
+        result
+    }
+
+    /// Repository cloned for a branch crawl and its clone path, the same as those of a full crawl
+    fn branch_crawl_target(
+        &self,
+        repository: &Repository,
+        project: Option<&BranchCrawlProject>,
+    ) -> Result<(Repository, PathBuf)> {
+        let base_path = self.temp_dir.join(format!("{}-{}", repository.name, repository.id));
+        match (&repository.repository_type, project) {
+            (RepositoryType::Git, None) => Ok((repository.clone(), base_path)),
+            (RepositoryType::GitLab | RepositoryType::GitHub, Some(project)) => {
+                let target =
+                    project_repository(repository, &project.name, &project.clone_url, repository.branch.clone());
+                Ok((target, base_path.join(&project.name)))
             }
+            (repository_type, _) => Err(anyhow!(
+                "Branch crawls are not supported for {:?} repository {}",
+                repository_type,
+                repository.name
+            )),
+        }
+    }
+
+    /// Register the cancellation token of a branch crawl, unless the repository is already being crawled
+    async fn start_branch_crawl(&self, repository: &Repository) -> Result<CancellationToken> {
+        let mut tokens = self.cancellation_tokens.write().await;
+        if tokens.contains_key(&repository.id) {
+            return Err(anyhow!("Repository {} is already being crawled", repository.name));
+        }
+        let cancellation_token = CancellationToken::new();
+        tokens.insert(repository.id, cancellation_token.clone());
+        Ok(cancellation_token)
+    }
+
+    /// Reload the global file inclusion rules so that changes apply to the next crawl
+    async fn reload_inclusion_rules(&self) {
+        match SearchSettingsRepository::new(self.database.clone()).get_file_inclusion_rules().await {
+            Ok(rules) => *self.inclusion_rules.write().unwrap_or_else(|e| e.into_inner()) = rules,
+            Err(e) => warn!("Failed to load file inclusion rules, keeping current rules: {}", e),
+        }
+    }
+
+    /// Reload the shared SSH deploy key so that changes apply to the next crawl
+    async fn reload_ssh_deploy_key(&self) {
+        match SearchSettingsRepository::new(self.database.clone()).get_ssh_deploy_key().await {
+            Ok(deploy_key) => *self.ssh_deploy_key.write().unwrap_or_else(|e| e.into_inner()) = deploy_key,
+            Err(e) => warn!("Failed to load SSH deploy key, keeping current key: {}", e),
         }
     }
 
@@ -557,6 +819,39 @@ impl CrawlerService {
         Ok(())
     }
 
+    /// Crawl every enabled repository, one after the other, to fill a search index recreated empty.
+    /// Repositories already being crawled are skipped, as that crawl fills the new index. Fails when a
+    /// repository could not be crawled.
+    pub async fn reindex_all_repositories(&self) -> Result<()> {
+        let repositories = RepositoryRepository::new(self.database.clone()).list_repositories().await?;
+        let repositories: Vec<Repository> = repositories.into_iter().filter(|repository| repository.enabled).collect();
+        info!(
+            "Re-indexing {} repositories into the new search index",
+            repositories.len()
+        );
+
+        let mut failed = 0;
+        for repository in repositories {
+            if self.is_crawling(repository.id).await {
+                info!(
+                    "Repository {} is already being crawled, not re-indexing it",
+                    repository.name
+                );
+                continue;
+            }
+            if let Err(e) = self.crawl_repository(&repository).await {
+                error!("Failed to re-index repository {}: {}", repository.name, e);
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            return Err(anyhow!("{} repositories could not be re-indexed", failed));
+        }
+        info!("Finished re-indexing repositories into the new search index");
+        Ok(())
+    }
+
     /// Resume a repository crawl from where it left off
     pub async fn resume_repository_crawl(&self, repository: &Repository) -> Result<()> {
         let crawl_start_time = std::time::Instant::now();
@@ -566,61 +861,13 @@ impl CrawlerService {
             repository.name, repository.last_processed_project
         );
 
+        self.reload_inclusion_rules().await;
+        self.reload_ssh_deploy_key().await;
+
         match repository.repository_type {
             RepositoryType::GitLab => {
-                // Create closures for GitLab crawler callbacks
-                let clone_or_update_fn = |repo: &Repository, path: &Path| {
-                    let repo = repo.clone();
-                    let path = path.to_owned();
-                    let git_ops = self.git_operations.clone();
-                    Box::pin(async move { git_ops.clone_or_update_repository(&repo, &path).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = Result<gix::Repository>> + Send>>
-                };
-
-                let process_files_fn = |repo: &Repository,
-                                        path: &Path,
-                                        progress: &mut CrawlProgress,
-                                        token: &CancellationToken,
-                                        parent_id: Uuid,
-                                        parent_name: &str| {
-                    let repo = repo.clone();
-                    let path = path.to_owned();
-                    let mut progress_clone = CrawlProgress {
-                        files_processed: progress.files_processed,
-                        files_indexed: progress.files_indexed,
-                        errors: progress.errors.clone(),
-                    };
-                    let token = token.clone();
-                    let parent_name = parent_name.to_owned();
-                    let branch_processor = self.branch_processor.clone();
-
-                    Box::pin(async move {
-                        let project_start_files = progress_clone.files_processed;
-                        branch_processor
-                            .process_all_branches_with_tracking(
-                                &repo,
-                                &path,
-                                &mut progress_clone,
-                                &token,
-                                parent_id,
-                                project_start_files,
-                                &parent_name,
-                            )
-                            .await
-                    }) as std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-                };
-
-                let update_crawl_time_fn = |repo_id: Uuid, duration: Option<i32>| {
-                    let service = self.clone();
-                    Box::pin(async move { service.update_repository_crawl_time(repo_id, duration).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
-                };
-
-                let cleanup_token_fn = |repo_id: Uuid| {
-                    let service = self.clone();
-                    Box::pin(async move { service.cleanup_cancellation_token(repo_id).await })
-                        as std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>
-                };
+                let (clone_or_update_fn, process_files_fn, update_crawl_time_fn, cleanup_token_fn) =
+                    self.project_crawl_callbacks();
 
                 self.gitlab_crawler
                     .resume_gitlab_repository_crawl(
@@ -635,12 +882,18 @@ impl CrawlerService {
                 // Finalize the crawl: commit Tantivy index and update database
                 self.finalize_crawl(repository, crawl_start_time).await
             }
-            RepositoryType::Git | RepositoryType::FileSystem | RepositoryType::GitHub => {
-                // For Git, FileSystem, and GitHub, just restart the entire crawl
-                // since tracking at project level doesn't apply (or not yet implemented for GitHub)
+            RepositoryType::Git
+            | RepositoryType::FileSystem
+            | RepositoryType::GitHub
+            | RepositoryType::Bitbucket
+            | RepositoryType::Gitea
+            | RepositoryType::AzureDevOps
+            | RepositoryType::Svn => {
+                // For Git, FileSystem, Subversion and the providers other than GitLab, just restart the entire crawl
+                // since tracking at project level doesn't apply (or not yet implemented for them)
                 info!(
-                    "Git/FileSystem/GitHub repository, restarting entire crawl: {}",
-                    repository.name
+                    "{:?} repository, restarting entire crawl: {}",
+                    repository.repository_type, repository.name
                 );
                 self.crawl_repository(repository).await
             }
@@ -719,22 +972,38 @@ impl Clone for CrawlerService {
             encryption_service: self.encryption_service.clone(),
             temp_dir: self.temp_dir.clone(),
             cancellation_tokens: self.cancellation_tokens.clone(),
-            git_operations: GitOperations::new(self.encryption_service.clone()),
-            branch_processor: BranchProcessor::new(self.search_service.clone(), self.progress_tracker.clone()),
+            inclusion_rules: self.inclusion_rules.clone(),
+            ssh_deploy_key: self.ssh_deploy_key.clone(),
+            git_operations: GitOperations::new(self.encryption_service.clone(), self.ssh_deploy_key.clone()),
+            branch_processor: BranchProcessor::new(
+                self.search_service.clone(),
+                self.progress_tracker.clone(),
+                self.inclusion_rules.clone(),
+                self.encryption_service.clone(),
+            ),
             gitlab_crawler: GitLabCrawler::new(
                 self.database.clone(),
                 self.search_service.clone(),
                 self.progress_tracker.clone(),
                 self.encryption_service.clone(),
+                self.git_operations.clone(),
                 self.temp_dir.clone(),
             ),
-            github_crawler: GitHubCrawler::new(
+            provider_crawler: ProviderCrawler::new(
                 self.database.clone(),
                 self.search_service.clone(),
                 self.progress_tracker.clone(),
                 self.encryption_service.clone(),
                 self.temp_dir.clone(),
             ),
+            svn_crawler: SvnCrawler::new(
+                self.database.clone(),
+                self.progress_tracker.clone(),
+                self.encryption_service.clone(),
+                self.branch_processor.clone(),
+                self.inclusion_rules.clone(),
+                self.temp_dir.clone(),
+            ),
         }
     }
 }
@@ -0,0 +1,140 @@
+use anyhow::{Result, anyhow};
+use gix::ObjectId;
+use gix::bstr::ByteSlice;
+use sha2::{Digest, Sha256};
+
+/// Levels of nested submodules fetched and indexed below a repository
+pub const MAX_SUBMODULE_DEPTH: usize = 3;
+
+/// Submodule declared in a `.gitmodules` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submodule {
+    pub name: String,
+    pub path: String,
+    pub url: String,
+}
+
+/// Submodules declared in the content of a `.gitmodules` file; entries without a path or URL are ignored
+pub fn parse_gitmodules(content: &[u8]) -> Result<Vec<Submodule>> {
+    let content = content.to_str().map_err(|_| anyhow!("Invalid UTF-8 in .gitmodules"))?;
+    let config = gix::config::File::try_from(content).map_err(|e| anyhow!("Invalid .gitmodules: {}", e))?;
+
+    let mut submodules = Vec::new();
+    for section in config.sections_by_name("submodule").into_iter().flatten() {
+        let Some(name) = section.header().subsection_name() else {
+            continue;
+        };
+        let (Some(path), Some(url)) = (section.value("path"), section.value("url")) else {
+            continue;
+        };
+        submodules.push(Submodule {
+            name: name.to_string(),
+            path: path.to_string().trim_matches('/').to_string(),
+            url: url.to_string(),
+        });
+    }
+    Ok(submodules)
+}
+
+/// Submodules of a tree with the commit each one is pinned to
+pub fn pinned_submodules(repo: &gix::Repository, tree_id: &ObjectId) -> Result<Vec<(Submodule, ObjectId)>> {
+    let tree = repo.find_object(*tree_id)?.try_into_tree().map_err(|_| anyhow!("Object is not a tree"))?;
+
+    let Some(gitmodules) = tree.lookup_entry_by_path(".gitmodules")? else {
+        return Ok(Vec::new());
+    };
+    let content = gitmodules.object()?.detach().data;
+
+    let mut pinned = Vec::new();
+    for submodule in parse_gitmodules(&content)? {
+        // A declared submodule is only pinned when the tree has a commit entry at its path
+        match tree.lookup_entry_by_path(&submodule.path)? {
+            Some(entry) if entry.mode().is_commit() => pinned.push((submodule, entry.id().detach())),
+            _ => {}
+        }
+    }
+    Ok(pinned)
+}
+
+/// Resolve a submodule URL relative to the URL of the repository declaring it (`../lib.git`, `./lib.git`)
+pub fn resolve_submodule_url(url: &str, parent_url: &str) -> String {
+    if !url.starts_with("./") && !url.starts_with("../") {
+        return url.to_string();
+    }
+
+    // The path of the parent URL starts after the host (`scheme://host/`) or after `host:` (scp-like)
+    let parent = parent_url.trim_end_matches('/');
+    let path_start = match parent.find("://") {
+        Some(scheme_end) => parent[scheme_end + 3..].find('/').map(|i| scheme_end + 3 + i + 1),
+        None => parent.find(':').map(|i| i + 1),
+    }
+    .unwrap_or(if parent.starts_with('/') { 1 } else { 0 });
+
+    let (prefix, path) = parent.split_at(path_start.min(parent.len()));
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut rest = url;
+    loop {
+        if let Some(stripped) = rest.strip_prefix("./") {
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("../") {
+            segments.pop();
+            rest = stripped;
+        } else {
+            break;
+        }
+    }
+    segments.extend(rest.split('/').filter(|s| !s.is_empty()));
+
+    let separator = if prefix.is_empty() || prefix.ends_with('/') || prefix.ends_with(':') {
+        ""
+    } else {
+        "/"
+    };
+    format!("{}{}{}", prefix, separator, segments.join("/"))
+}
+
+/// Host of a Git URL (`https://host/...`, `ssh://user@host:22/...`, `user@host:path`); `None` for local paths
+pub fn url_host(url: &str) -> Option<String> {
+    let authority = match url.split_once("://") {
+        Some((scheme, _)) if scheme.eq_ignore_ascii_case("file") => return None,
+        Some((_, rest)) => rest.split('/').next()?,
+        None if crate::services::ssh::is_ssh_url(url) => url.split(':').next()?,
+        None => return None,
+    };
+    let host = authority.rsplit('@').next()?;
+    let host = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Whether a submodule URL may be fetched: HTTPS, SSH or scp-like URLs of a remote host only. `.gitmodules`
+/// comes from the crawled repository, so local paths and `file://` URLs would expose other repositories of the
+/// Klask host (like Git's `protocol.file.allow=user` for submodules).
+pub fn is_fetchable_submodule_url(url: &str) -> bool {
+    // Hosts starting with '-' would be read as options of the ssh command
+    if url_host(url).is_none_or(|host| host.starts_with('-')) {
+        return false;
+    }
+    match url.split_once("://") {
+        Some((scheme, _)) => matches!(
+            scheme.to_ascii_lowercase().as_str(),
+            "https" | "ssh" | "git+ssh" | "ssh+git"
+        ),
+        // `<transport>::<address>` selects a remote helper, not an SSH host
+        None => !url.contains("::") && crate::services::ssh::is_ssh_url(url),
+    }
+}
+
+/// Whether a submodule is hosted with its parent repository, and may be fetched with its credentials
+pub fn same_host(url: &str, parent_url: &str) -> bool {
+    matches!((url_host(url), url_host(parent_url)), (Some(host), Some(parent_host)) if host == parent_host)
+}
+
+/// Reference namespace receiving the branches fetched from a submodule URL
+pub fn submodule_ref_prefix(url: &str) -> String {
+    let digest = Sha256::digest(url.as_bytes());
+    let hash: String = digest.iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+    format!("refs/klask/submodules/{}", hash)
+}
@@ -0,0 +1,353 @@
+use super::archive::MAX_ARCHIVE_SIZE;
+use super::branch_processor::{BranchProcessor, CrawlProgress};
+use super::filter::filter_branches;
+use super::ignore_files::IgnoreRules;
+use super::large_files::LargeFilePolicy;
+use super::svn_dump::SvnDump;
+use crate::models::{FileInclusionRules, Repository};
+use crate::repositories::RepositoryRepository;
+use crate::services::encryption::EncryptionService;
+use crate::services::progress::{CrawlStatus, ProgressTracker};
+use crate::services::svn::{SvnEntry, SvnLocation, SvnNodeKind, SvnService, dump_local_repository};
+use anyhow::{Result, anyhow};
+use sqlx::{Pool, Postgres};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// Directory of a Subversion repository indexed as a version: trunk, a branch or a tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvnVersion {
+    pub name: String, // "trunk", "branches/<name>" or "tags/<name>"
+    pub path: String, // Path in the repository ("" when the repository has no standard layout)
+    pub revision: u64,
+}
+
+/// Result of exporting a version to disk
+#[derive(Debug, Clone, Copy)]
+pub struct SvnExport {
+    pub files: usize,
+    pub revision: u64, // Last revision that changed the version
+}
+
+/// Subversion repository read over HTTP/WebDAV or from a dump
+pub enum SvnSource {
+    WebDav {
+        service: SvnService,
+        url: String,
+        revision: Option<u64>, // Revision every read is pinned to, the youngest one when unset
+        credentials: Option<String>,
+    },
+    Dump(Arc<SvnDump>),
+}
+
+impl SvnSource {
+    pub fn web_dav(url: &str, credentials: Option<String>) -> Self {
+        Self::WebDav {
+            service: SvnService::new(),
+            url: url.trim_end_matches('/').to_string(),
+            revision: None,
+            credentials,
+        }
+    }
+
+    /// Read a dump file (blocking)
+    pub fn dump(path: &Path) -> Result<Self> {
+        Ok(Self::Dump(Arc::new(SvnDump::open(path)?)))
+    }
+
+    /// Youngest revision of the repository
+    pub async fn youngest_revision(&self) -> Result<u64> {
+        match self {
+            Self::WebDav { service, url, credentials, .. } => {
+                Ok(service.stat(url, credentials.as_deref()).await?.revision)
+            }
+            Self::Dump(dump) => Ok(dump.youngest_revision()),
+        }
+    }
+
+    /// Read versions and files at `revision`, so that commits made during a crawl are not half indexed.
+    /// A dump is already a snapshot of a single revision.
+    pub fn pin_revision(&mut self, revision: u64) {
+        if let Self::WebDav { revision: pinned, .. } = self {
+            *pinned = Some(revision);
+        }
+    }
+
+    /// Trunk, branches and tags of the repository, or its root named `default_name`
+    /// when it has no trunk/branches/tags layout
+    pub async fn versions(&self, default_name: &str) -> Result<Vec<SvnVersion>> {
+        let root = self.list_dir("").await?;
+        let layout_dir =
+            |name: &str| root.iter().find(|entry| entry.kind == SvnNodeKind::Dir && entry.name == name).cloned();
+
+        let mut versions = Vec::new();
+        if let Some(trunk) = layout_dir("trunk") {
+            versions.push(SvnVersion {
+                name: "trunk".to_string(),
+                path: "trunk".to_string(),
+                revision: trunk.revision,
+            });
+        }
+        for container in ["branches", "tags"] {
+            if layout_dir(container).is_none() {
+                continue;
+            }
+            for entry in self.list_dir(container).await? {
+                if entry.kind == SvnNodeKind::Dir {
+                    let path = format!("{}/{}", container, entry.name);
+                    versions.push(SvnVersion { name: path.clone(), path, revision: entry.revision });
+                }
+            }
+        }
+
+        if versions.is_empty() && layout_dir("branches").is_none() && layout_dir("tags").is_none() {
+            let revision = root.iter().map(|entry| entry.revision).max().unwrap_or(0);
+            versions.push(SvnVersion { name: default_name.to_string(), path: String::new(), revision });
+        }
+
+        Ok(versions)
+    }
+
+    /// Write the files of a version accepted by `exportable(relative path, size)` under `destination`,
+    /// with the date of the revision that last changed them as modification time
+    pub async fn export_version(
+        &self,
+        version: &SvnVersion,
+        destination: &Path,
+        exportable: impl Fn(&str, u64) -> bool,
+        cancellation_token: &CancellationToken,
+    ) -> Result<SvnExport> {
+        let mut export = SvnExport { files: 0, revision: version.revision };
+        let mut directories = vec![String::new()];
+
+        while let Some(directory) = directories.pop() {
+            if cancellation_token.is_cancelled() {
+                return Ok(export);
+            }
+
+            for entry in self.list_dir(&join_path(&version.path, &directory)).await? {
+                export.revision = export.revision.max(entry.revision);
+                let relative_path = join_path(&directory, &entry.name);
+
+                if entry.kind == SvnNodeKind::Dir {
+                    directories.push(relative_path);
+                    continue;
+                }
+                if !exportable(&relative_path, entry.size) {
+                    continue;
+                }
+
+                let content = self.read_file(&join_path(&version.path, &relative_path)).await?;
+                let target = destination.join(&relative_path);
+                if let Some(parent) = target.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&target, content).await?;
+                if let Some(modified) = entry.last_modified {
+                    std::fs::File::options().write(true).open(&target)?.set_modified(modified.into())?;
+                }
+                export.files += 1;
+            }
+        }
+
+        Ok(export)
+    }
+
+    async fn list_dir(&self, path: &str) -> Result<Vec<SvnEntry>> {
+        match self {
+            Self::WebDav { service, url, revision, credentials } => {
+                service.list_directory(&entry_url(url, path), *revision, credentials.as_deref()).await
+            }
+            Self::Dump(dump) => Ok(dump.list_dir(path)),
+        }
+    }
+
+    async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::WebDav { service, url, revision, credentials } => {
+                service.get_file(&entry_url(url, path), *revision, credentials.as_deref()).await
+            }
+            Self::Dump(dump) => {
+                let dump = dump.clone();
+                let path = path.to_string();
+                tokio::task::spawn_blocking(move || dump.read_file(&path)).await?
+            }
+        }
+    }
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    match (parent.is_empty(), name.is_empty()) {
+        (true, _) => name.to_string(),
+        (_, true) => parent.to_string(),
+        _ => format!("{}/{}", parent, name),
+    }
+}
+
+/// URL of a path of the repository, with every segment percent-encoded
+fn entry_url(base_url: &str, path: &str) -> String {
+    let segments: Vec<String> =
+        path.split('/').filter(|s| !s.is_empty()).map(|s| urlencoding::encode(s).into_owned()).collect();
+    if segments.is_empty() {
+        base_url.to_string()
+    } else {
+        format!("{}/{}", base_url, segments.join("/"))
+    }
+}
+
+/// Subversion-specific crawler operations
+pub struct SvnCrawler {
+    database: Pool<Postgres>,
+    progress_tracker: Arc<ProgressTracker>,
+    encryption_service: Arc<EncryptionService>,
+    branch_processor: BranchProcessor,
+    inclusion_rules: Arc<std::sync::RwLock<FileInclusionRules>>,
+    temp_dir: PathBuf,
+}
+
+impl SvnCrawler {
+    pub fn new(
+        database: Pool<Postgres>,
+        progress_tracker: Arc<ProgressTracker>,
+        encryption_service: Arc<EncryptionService>,
+        branch_processor: BranchProcessor,
+        inclusion_rules: Arc<std::sync::RwLock<FileInclusionRules>>,
+        temp_dir: PathBuf,
+    ) -> Self {
+        Self { database, progress_tracker, encryption_service, branch_processor, inclusion_rules, temp_dir }
+    }
+
+    /// Crawl a Subversion repository: export trunk, branches and tags one at a time and process their files
+    pub async fn crawl_svn_repository(
+        &self,
+        repository: &Repository,
+        progress: &mut CrawlProgress,
+        cancellation_token: &CancellationToken,
+    ) -> Result<()> {
+        let repo_repo = RepositoryRepository::new(self.database.clone());
+        let base_path = self.temp_dir.join(format!("{}-{}", repository.name, repository.id));
+        let dump_path = self.temp_dir.join(format!("{}-{}.svndump", repository.name, repository.id));
+
+        self.progress_tracker.update_status(repository.id, CrawlStatus::Cloning).await;
+
+        let mut source = match SvnLocation::parse(&repository.url)? {
+            SvnLocation::Http(url) => {
+                let credentials = match &repository.access_token {
+                    Some(encrypted_token) => Some(
+                        self.encryption_service
+                            .decrypt(encrypted_token)
+                            .map_err(|e| anyhow!("Failed to decrypt Subversion credentials: {}", e))?,
+                    ),
+                    None => None,
+                };
+                SvnSource::web_dav(&url, credentials)
+            }
+            SvnLocation::Local(path) => {
+                info!("Dumping local Subversion repository {:?}", path);
+                dump_local_repository(&path, &dump_path).await?;
+                let dump_path = dump_path.clone();
+                tokio::task::spawn_blocking(move || SvnSource::dump(&dump_path)).await??
+            }
+            SvnLocation::Dump(path) => tokio::task::spawn_blocking(move || SvnSource::dump(&path)).await??,
+        };
+
+        let youngest_revision = source.youngest_revision().await?;
+        source.pin_revision(youngest_revision);
+        let default_version = repository.branch.as_deref().unwrap_or("trunk");
+        let versions = source.versions(default_version).await?;
+
+        // Apply branch filtering to trunk, branch and tag names
+        let version_names = filter_branches(
+            versions.iter().map(|version| version.name.clone()).collect(),
+            repository.included_branches.as_deref(),
+            repository.included_branches_patterns.as_deref(),
+            repository.excluded_branches.as_deref(),
+            repository.excluded_branches_patterns.as_deref(),
+        );
+        let mut selected: Vec<&SvnVersion> =
+            versions.iter().filter(|version| version_names.contains(&version.name)).collect();
+        if selected.is_empty() {
+            info!("No branches matched the filter criteria, using default branch");
+            selected = versions.iter().filter(|version| version.name == default_version).collect();
+        }
+
+        info!(
+            "Found {} versions in Subversion repository {} at revision {}, {} will be processed: {:?}",
+            versions.len(),
+            repository.name,
+            youngest_revision,
+            selected.len(),
+            selected.iter().map(|version| &version.name).collect::<Vec<_>>()
+        );
+
+        let rules = self.inclusion_rules.read().unwrap_or_else(|e| e.into_inner()).for_repository(repository);
+        let read_limit = LargeFilePolicy::for_repository(repository).read_limit();
+        let exportable = |path: &str, size: u64| {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            if IgnoreRules::is_ignore_file(file_name, true) {
+                return true;
+            }
+            let max_size = if rules.opens_archive(path) { MAX_ARCHIVE_SIZE } else { read_limit };
+            rules.is_included(path) && size <= max_size
+        };
+
+        for version in selected {
+            if cancellation_token.is_cancelled() {
+                break;
+            }
+
+            // Each version is exported fresh and removed once processed
+            let version_path = base_path.join(&version.name);
+            if version_path.exists() {
+                tokio::fs::remove_dir_all(&version_path).await?;
+            }
+            tokio::fs::create_dir_all(&version_path).await?;
+
+            self.progress_tracker.update_status(repository.id, CrawlStatus::Cloning).await;
+            let export = match source.export_version(version, &version_path, &exportable, cancellation_token).await {
+                Ok(export) => export,
+                Err(e) => {
+                    warn!(
+                        "Failed to export '{}' of Subversion repository {}: {}",
+                        version.name, repository.name, e
+                    );
+                    progress.errors.push(format!("Branch '{}': {}", version.name, e));
+                    continue;
+                }
+            };
+            info!(
+                "Exported {} files of '{}' (last changed in r{}) for repository {}",
+                export.files, version.name, export.revision, repository.name
+            );
+
+            self.progress_tracker.update_status(repository.id, CrawlStatus::Processing).await;
+            self.branch_processor
+                .process_repository_files_internal(
+                    repository,
+                    &version_path,
+                    &version.name,
+                    progress,
+                    cancellation_token,
+                    None,
+                    None, // No parent project name for Subversion repos
+                )
+                .await?;
+
+            if let Err(e) = tokio::fs::remove_dir_all(&version_path).await {
+                debug!("Failed to remove exported files in {:?}: {}", version_path, e);
+            }
+        }
+
+        if dump_path.exists() {
+            let _ = tokio::fs::remove_file(&dump_path).await;
+        }
+
+        if !cancellation_token.is_cancelled() {
+            repo_repo.update_svn_revision(repository.id, youngest_revision as i64).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+use crate::services::svn::{SvnEntry, SvnNodeKind};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Node of the youngest tree of a Subversion dump
+#[derive(Debug, Clone)]
+pub struct SvnDumpNode {
+    pub kind: SvnNodeKind,
+    pub revision: u64, // Revision that last changed the node (or copied it)
+    pub date: Option<DateTime<Utc>>,
+    text: Option<TextRange>,
+}
+
+/// Location of a file's full text in the dump file
+#[derive(Debug, Clone, Copy)]
+struct TextRange {
+    offset: u64,
+    length: u64,
+}
+
+/// Revision properties of the revision being read
+struct RevisionInfo {
+    number: u64,
+    date: Option<DateTime<Utc>>,
+}
+
+/// Youngest tree of a repository read from a file written by `svnadmin dump`.
+/// File contents stay in the dump file and are read on demand.
+pub struct SvnDump {
+    path: PathBuf,
+    youngest_revision: u64,
+    nodes: BTreeMap<String, SvnDumpNode>,
+}
+
+impl SvnDump {
+    /// Read the records of a dump file (format 2, without deltas)
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).map_err(|e| anyhow!("Failed to open Subversion dump {:?}: {}", path, e))?;
+        let mut reader = BufReader::new(file);
+
+        // Every state of every path, in revision order, to resolve copies from older revisions
+        let mut history: BTreeMap<String, Vec<(u64, Option<SvnDumpNode>)>> = BTreeMap::new();
+        let mut revision = RevisionInfo { number: 0, date: None };
+
+        while let Some(headers) = read_headers(&mut reader)? {
+            let content_start = reader.stream_position()?;
+            let content_length = header_u64(&headers, "Content-length")?.unwrap_or(0);
+            let prop_length = header_u64(&headers, "Prop-content-length")?.unwrap_or(0);
+            let text_length = header_u64(&headers, "Text-content-length")?;
+
+            if let Some(number) = header_u64(&headers, "Revision-number")? {
+                let mut props = vec![0; prop_length as usize];
+                reader.read_exact(&mut props)?;
+                let props = parse_props(&props);
+                let date = props
+                    .get("svn:date")
+                    .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                    .map(|date| date.with_timezone(&Utc));
+                revision = RevisionInfo { number, date };
+            } else if let Some(node_path) = headers.get("Node-path") {
+                if headers.get("Text-delta").is_some_and(|v| v == "true") {
+                    return Err(anyhow!(
+                        "Subversion dumps with deltas are not supported, create the dump without --deltas"
+                    ));
+                }
+                let text = text_length.map(|length| TextRange { offset: content_start + prop_length, length });
+                let node_path = node_path.trim_matches('/');
+                apply_node(&mut history, &revision, node_path, &headers, text)?;
+                touch_ancestors(&mut history, &revision, node_path);
+            }
+
+            reader.seek(SeekFrom::Start(content_start + content_length))?;
+        }
+
+        let nodes = history
+            .into_iter()
+            .filter_map(|(path, states)| states.into_iter().last().and_then(|(_, node)| node).map(|node| (path, node)))
+            .collect();
+
+        Ok(Self { path: path.to_owned(), youngest_revision: revision.number, nodes })
+    }
+
+    pub fn youngest_revision(&self) -> u64 {
+        self.youngest_revision
+    }
+
+    /// Entries of the directory at `dir` (`""` for the repository root)
+    pub fn list_dir(&self, dir: &str) -> Vec<SvnEntry> {
+        let dir = dir.trim_matches('/');
+        let prefix = if dir.is_empty() { String::new() } else { format!("{}/", dir) };
+
+        self.nodes
+            .range(prefix.clone()..)
+            .take_while(|(path, _)| path.starts_with(&prefix))
+            .filter(|(path, _)| !path[prefix.len()..].is_empty() && !path[prefix.len()..].contains('/'))
+            .map(|(path, node)| SvnEntry {
+                name: path[prefix.len()..].to_string(),
+                kind: node.kind,
+                revision: node.revision,
+                last_modified: node.date,
+                size: node.text.map(|text| text.length).unwrap_or(0),
+            })
+            .collect()
+    }
+
+    /// Content of the file at `path`
+    pub fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        let node = self
+            .nodes
+            .get(path.trim_matches('/'))
+            .filter(|node| node.kind == SvnNodeKind::File)
+            .ok_or_else(|| anyhow!("No file {} in Subversion dump", path))?;
+
+        let Some(text) = node.text else {
+            return Ok(Vec::new());
+        };
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(text.offset))?;
+        let mut content = vec![0; text.length as usize];
+        file.read_exact(&mut content)?;
+        Ok(content)
+    }
+}
+
+/// Read the header block of the next record; `None` at the end of the dump
+fn read_headers(reader: &mut impl BufRead) -> Result<Option<HashMap<String, String>>> {
+    let mut headers = HashMap::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(if headers.is_empty() { None } else { Some(headers) });
+        }
+
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            // Blank lines separate records; the first one after headers ends the block
+            if headers.is_empty() {
+                continue;
+            }
+            return Ok(Some(headers));
+        }
+
+        let (name, value) =
+            trimmed.split_once(": ").ok_or_else(|| anyhow!("Invalid Subversion dump header: {}", trimmed))?;
+        headers.insert(name.to_string(), value.to_string());
+    }
+}
+
+fn header_u64(headers: &HashMap<String, String>, name: &str) -> Result<Option<u64>> {
+    headers
+        .get(name)
+        .map(|value| value.parse().map_err(|_| anyhow!("Invalid {} in Subversion dump: {}", name, value)))
+        .transpose()
+}
+
+/// Parse a property block (`K <len>` / `V <len>` pairs ending with `PROPS-END`)
+fn parse_props(data: &[u8]) -> HashMap<String, String> {
+    let mut props = HashMap::new();
+    let mut rest = data;
+
+    let next_value = |rest: &mut &[u8]| -> Option<String> {
+        let newline = rest.iter().position(|&b| b == b'\n')?;
+        let length: usize = std::str::from_utf8(&rest[2..newline]).ok()?.parse().ok()?;
+        let value = rest.get(newline + 1..newline + 1 + length)?;
+        let value = String::from_utf8_lossy(value).into_owned();
+        *rest = rest.get(newline + 2 + length..).unwrap_or_default();
+        Some(value)
+    };
+
+    while rest.starts_with(b"K ") {
+        let Some(key) = next_value(&mut rest) else { break };
+        if !rest.starts_with(b"V ") {
+            break;
+        }
+        let Some(value) = next_value(&mut rest) else { break };
+        props.insert(key, value);
+    }
+
+    props
+}
+
+/// State of `path` at `revision`
+fn state_at<'a>(
+    history: &'a BTreeMap<String, Vec<(u64, Option<SvnDumpNode>)>>,
+    path: &str,
+    revision: u64,
+) -> Option<&'a SvnDumpNode> {
+    history.get(path)?.iter().rev().find(|(rev, _)| *rev <= revision).and_then(|(_, node)| node.as_ref())
+}
+
+/// Paths of `path` and everything below it
+fn subtree(history: &BTreeMap<String, Vec<(u64, Option<SvnDumpNode>)>>, path: &str) -> Vec<String> {
+    history
+        .range(path.to_string()..)
+        .take_while(|(key, _)| key.starts_with(path))
+        .filter(|(key, _)| path.is_empty() || key.len() == path.len() || key.as_bytes()[path.len()] == b'/')
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+fn apply_node(
+    history: &mut BTreeMap<String, Vec<(u64, Option<SvnDumpNode>)>>,
+    revision: &RevisionInfo,
+    path: &str,
+    headers: &HashMap<String, String>,
+    text: Option<TextRange>,
+) -> Result<()> {
+    let action = headers.get("Node-action").map(String::as_str).unwrap_or("change");
+
+    if action == "delete" || action == "replace" {
+        for key in subtree(history, path) {
+            if state_at(history, &key, revision.number).is_some() {
+                history.entry(key).or_default().push((revision.number, None));
+            }
+        }
+        if action == "delete" {
+            return Ok(());
+        }
+    }
+
+    let current = state_at(history, path, revision.number).cloned();
+    let kind = match headers.get("Node-kind").map(String::as_str) {
+        Some("dir") => SvnNodeKind::Dir,
+        Some("file") => SvnNodeKind::File,
+        _ => current.as_ref().map(|node| node.kind).ok_or_else(|| anyhow!("Unknown node kind for {}", path))?,
+    };
+
+    // Copies bring the copied subtree as it was in the source revision
+    let mut copied_text = None;
+    if let (Some(from_path), Some(from_revision)) = (
+        headers.get("Node-copyfrom-path"),
+        header_u64(headers, "Node-copyfrom-rev")?,
+    ) {
+        let from_path = from_path.trim_matches('/');
+        let copied: Vec<(String, SvnDumpNode)> = subtree(history, from_path)
+            .into_iter()
+            .filter_map(|key| {
+                let node = state_at(history, &key, from_revision)?.clone();
+                Some((format!("{}{}", path, &key[from_path.len()..]), node))
+            })
+            .collect();
+
+        for (key, node) in copied {
+            if key == path {
+                copied_text = node.text;
+                continue;
+            }
+            let node = SvnDumpNode { revision: revision.number, date: revision.date, ..node };
+            history.entry(key).or_default().push((revision.number, Some(node)));
+        }
+    }
+
+    let text =
+        text.or(copied_text).or_else(|| if action == "change" { current.and_then(|node| node.text) } else { None });
+    let node = SvnDumpNode { kind, revision: revision.number, date: revision.date, text };
+    history.entry(path.to_string()).or_default().push((revision.number, Some(node)));
+
+    Ok(())
+}
+
+/// Directories change in every revision that changes something below them
+fn touch_ancestors(
+    history: &mut BTreeMap<String, Vec<(u64, Option<SvnDumpNode>)>>,
+    revision: &RevisionInfo,
+    path: &str,
+) {
+    let mut ancestor = path;
+    while let Some((parent, _)) = ancestor.rsplit_once('/') {
+        ancestor = parent;
+        let Some(node) = state_at(history, parent, revision.number).filter(|node| node.revision < revision.number)
+        else {
+            continue;
+        };
+        let node = SvnDumpNode { revision: revision.number, date: revision.date, ..node.clone() };
+        history.entry(parent.to_string()).or_default().push((revision.number, Some(node)));
+    }
+}
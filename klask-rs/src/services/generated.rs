@@ -0,0 +1,124 @@
+//! Generated and minified file detection.
+//!
+//! Generated files (lockfiles, source maps, minified assets, files carrying a "do not edit" header)
+//! are still indexed, but flagged so that searches can leave them out by default.
+
+/// Lockfiles written by package managers (lowercase file names)
+const LOCKFILE_NAMES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lock",
+    "cargo.lock",
+    "gemfile.lock",
+    "poetry.lock",
+    "pipfile.lock",
+    "uv.lock",
+    "composer.lock",
+    "go.sum",
+    "mix.lock",
+    "pubspec.lock",
+    "podfile.lock",
+    "packages.lock.json",
+    "flake.lock",
+    "gradle.lockfile",
+];
+
+/// Header markers of generated code, looked up (case-insensitive) in the first lines of a file
+const GENERATED_MARKERS: &[&str] =
+    &["do not edit", "@generated", "code generated by", "auto-generated", "autogenerated", "automatically generated"];
+
+/// Number of leading lines searched for generated markers
+const HEADER_LINES: usize = 10;
+
+/// Extensions of minifiable assets, checked for very long lines
+const MINIFIABLE_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "css"];
+
+/// Average line length above which a script or stylesheet is considered minified
+const MINIFIED_AVERAGE_LINE_LENGTH: usize = 300;
+
+/// Line length above which a script or stylesheet is considered minified
+const MINIFIED_MAX_LINE_LENGTH: usize = 2000;
+
+/// Whether a file is generated code, from its name and content
+pub fn is_generated(file_name: &str, content: &str) -> bool {
+    let name = file_name.rsplit('/').next().unwrap_or(file_name).to_lowercase();
+
+    if LOCKFILE_NAMES.contains(&name.as_str()) {
+        return true;
+    }
+    if name.ends_with(".map") || name.contains(".min.") {
+        return true;
+    }
+    if has_generated_header(content) {
+        return true;
+    }
+
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+    MINIFIABLE_EXTENSIONS.contains(&extension) && is_minified(content)
+}
+
+/// Whether one of the first lines carries a generated-code marker
+fn has_generated_header(content: &str) -> bool {
+    content.lines().take(HEADER_LINES).any(|line| {
+        let line = line.to_lowercase();
+        GENERATED_MARKERS.iter().any(|marker| line.contains(marker))
+    })
+}
+
+/// Minified code is made of a few very long lines
+fn is_minified(content: &str) -> bool {
+    let (mut lines, mut total, mut longest) = (0, 0, 0);
+    for line in content.lines() {
+        lines += 1;
+        total += line.len();
+        longest = longest.max(line.len());
+    }
+    lines > 0 && (longest > MINIFIED_MAX_LINE_LENGTH || total / lines > MINIFIED_AVERAGE_LINE_LENGTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockfiles_and_source_maps() {
+        assert!(is_generated("web/package-lock.json", "{}"));
+        assert!(is_generated("Cargo.lock", "[[package]]"));
+        assert!(is_generated("go.sum", ""));
+        assert!(is_generated("dist/app.js.map", "{\"version\":3}"));
+        assert!(is_generated("static/vendor.min.js", "var a=1;"));
+        assert!(is_generated("static/theme.min.css", "a{color:red}"));
+        assert!(!is_generated("package.json", "{\"name\": \"web\"}"));
+    }
+
+    #[test]
+    fn test_generated_headers() {
+        assert!(is_generated(
+            "api/service.pb.go",
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api"
+        ));
+        assert!(is_generated("schema.rs", "// @generated by diesel\nuse diesel::*;"));
+        assert!(is_generated(
+            "Resource.java",
+            "/*\n * This file was automatically generated\n */"
+        ));
+        assert!(!is_generated("main.rs", "fn main() {}"));
+
+        let late_marker = format!("{}// DO NOT EDIT", "fn f() {}\n".repeat(HEADER_LINES));
+        assert!(!is_generated("lib.rs", &late_marker), "Only the header is searched");
+    }
+
+    #[test]
+    fn test_minified_assets() {
+        let minified = "function a(b){return b+1}".repeat(100);
+        assert!(is_generated("bundle.js", &minified));
+        assert!(is_generated("styles.css", &"a{color:red}".repeat(200)));
+        assert!(
+            !is_generated("data.txt", &minified),
+            "Only scripts and stylesheets are checked"
+        );
+        assert!(!is_generated("app.js", "function add(a, b) {\n  return a + b;\n}\n"));
+    }
+}
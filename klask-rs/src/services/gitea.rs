@@ -0,0 +1,170 @@
+use anyhow::{Context, Result, anyhow};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+/// Client for the REST API of Gitea and Forgejo (`/api/v1`)
+#[derive(Debug, Clone)]
+pub struct GiteaService {
+    client: Client,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiteaRepository {
+    pub id: i64,
+    pub name: String,
+    pub full_name: String,
+    pub description: Option<String>,
+    pub default_branch: Option<String>,
+    pub clone_url: String,
+    pub ssh_url: String,
+    pub html_url: String,
+    pub private: bool,
+    pub archived: bool,
+    #[serde(default)]
+    pub empty: bool,
+    pub owner: GiteaOwner,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiteaOwner {
+    pub login: String,
+}
+
+impl Default for GiteaService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GiteaService {
+    pub fn new() -> Self {
+        let accept_invalid_certs =
+            std::env::var("KLASK_GITEA_ACCEPT_INVALID_CERTS").map(|v| v.to_lowercase() == "true").unwrap_or(false);
+
+        let mut builder = Client::builder().user_agent("klask-rs/2.0").timeout(std::time::Duration::from_secs(30));
+
+        if accept_invalid_certs {
+            tracing::warn!(
+                "Gitea client configured to accept invalid certificates (KLASK_GITEA_ACCEPT_INVALID_CERTS=true)"
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Self { client: builder.build().unwrap_or_else(|_| Client::new()) }
+    }
+
+    /// Discover the repositories of an organization, or those accessible to the token owner
+    pub async fn discover_repositories(
+        &self,
+        gitea_url: &str,
+        access_token: &str,
+        organization: Option<&str>,
+    ) -> Result<Vec<GiteaRepository>> {
+        // Treat empty string as None (token owner's repositories)
+        let organization = organization.filter(|s| !s.is_empty());
+
+        let endpoint = match organization {
+            Some(org) => format!(
+                "{}/api/v1/orgs/{}/repos",
+                gitea_url.trim_end_matches('/'),
+                urlencoding::encode(org)
+            ),
+            None => format!("{}/api/v1/user/repos", gitea_url.trim_end_matches('/')),
+        };
+
+        let mut repositories = Vec::new();
+        let mut page = 1;
+        let limit = 50; // Default maximum page size of Gitea instances
+
+        loop {
+            let url = format!("{}?page={}&limit={}", endpoint, page, limit);
+
+            tracing::debug!("Making Gitea API request to: {}", url);
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("token {}", access_token))
+                .header("Accept", "application/json")
+                .send()
+                .await
+                .context("Failed to fetch Gitea repositories")?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let error_body = response.text().await.unwrap_or_default();
+                tracing::error!(
+                    "Gitea API request failed - URL: {}, Status: {}, Body: {}",
+                    url,
+                    status,
+                    error_body
+                );
+                return Err(anyhow!("Gitea API error: {} - {}", status, error_body));
+            }
+
+            let page_repos: Vec<GiteaRepository> =
+                response.json().await.context("Failed to parse Gitea repositories response")?;
+
+            if page_repos.is_empty() {
+                break;
+            }
+
+            repositories.extend(page_repos);
+            page += 1;
+        }
+
+        // Filter out archived and empty repositories (nothing to clone in the latter)
+        let initial_count = repositories.len();
+        repositories.retain(|r| !r.archived && !r.empty);
+        let skipped_count = initial_count - repositories.len();
+        if skipped_count > 0 {
+            tracing::info!(
+                "Filtered out {} archived or empty Gitea repositories (kept {} active)",
+                skipped_count,
+                repositories.len()
+            );
+        }
+
+        Ok(repositories)
+    }
+
+    /// Test if the access token is valid
+    pub async fn test_token(&self, gitea_url: &str, access_token: &str) -> Result<bool> {
+        let url = format!("{}/api/v1/user", gitea_url.trim_end_matches('/'));
+
+        tracing::debug!("Testing Gitea token with URL: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("token {}", access_token))
+            .header("Accept", "application/json")
+            .send()
+            .await;
+
+        match response {
+            Ok(resp) => {
+                let status = resp.status();
+                tracing::info!("Gitea token test status: {}", status);
+
+                if status.is_success() {
+                    let user_info: serde_json::Value =
+                        resp.json().await.context("Failed to parse Gitea user response")?;
+                    tracing::info!(
+                        "Gitea token valid for user: {}",
+                        user_info["login"].as_str().unwrap_or("unknown")
+                    );
+                    Ok(true)
+                } else {
+                    let error_body = resp.text().await.unwrap_or_default();
+                    tracing::error!("Gitea token test failed: {} - {}", status, error_body);
+                    Ok(false)
+                }
+            }
+            Err(e) => {
+                tracing::error!("Gitea API request failed for URL: {}", url);
+                tracing::error!("Request error: {}", e);
+                Err(anyhow!("Network error: {} (URL: {})", e, url))
+            }
+        }
+    }
+}
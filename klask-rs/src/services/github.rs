@@ -1,11 +1,17 @@
+use crate::models::Repository;
 use anyhow::{Context, Result, anyhow};
 use reqwest::Client;
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
+/// REST API base for repositories hosted on github.com
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
 #[derive(Debug, Clone)]
 pub struct GitHubService {
     #[allow(dead_code)]
     client: Client,
+    api_url: String,
     #[allow(dead_code)]
     excluded_repositories: Vec<String>,
     #[allow(dead_code)]
@@ -41,8 +47,10 @@ impl Default for GitHubService {
 }
 
 impl GitHubService {
-    /// Check GitHub API rate limit from response headers and log warnings/errors
-    fn check_github_rate_limit(response: &reqwest::Response) {
+    /// Check GitHub API rate limit from response headers and log warnings/errors.
+    /// Returns true when the limit is exhausted. GitHub Enterprise Server instances with
+    /// rate limiting disabled send no headers, which counts as not limited.
+    fn check_github_rate_limit(response: &reqwest::Response) -> bool {
         if let Some(remaining) = response.headers().get("x-ratelimit-remaining")
             && let Ok(remaining_str) = remaining.to_str()
             && let Ok(remaining_count) = remaining_str.parse::<i32>()
@@ -56,10 +64,12 @@ impl GitHubService {
                 {
                     tracing::error!("Rate limit will reset at timestamp: {}", reset_str);
                 }
+                return true;
             } else if remaining_count < 100 {
                 tracing::warn!("GitHub rate limit low: {} requests remaining", remaining_count);
             }
         }
+        false
     }
 
     pub fn new() -> Self {
@@ -104,7 +114,34 @@ impl GitHubService {
             );
         }
 
-        Self { client: builder.build().unwrap_or_else(|_| Client::new()), excluded_repositories, excluded_patterns }
+        Self {
+            client: builder.build().unwrap_or_else(|_| Client::new()),
+            api_url: DEFAULT_GITHUB_API_URL.to_string(),
+            excluded_repositories,
+            excluded_patterns,
+        }
+    }
+
+    /// Point the service at another GitHub host, e.g. a GitHub Enterprise Server instance.
+    /// See [`github_api_base`] for the accepted URL forms.
+    pub fn with_api_url(mut self, url: &str) -> Self {
+        self.api_url = github_api_base(url);
+        if self.api_url != DEFAULT_GITHUB_API_URL {
+            tracing::info!("Using GitHub Enterprise API at {}", self.api_url);
+        }
+        self
+    }
+
+    /// Service reaching the API of a stored repository: its `github_url`, or github.com when unset.
+    /// The repository `url` is never used, as it may hold a clone URL.
+    pub fn for_repository(repository: &Repository) -> Self {
+        Self::new().with_api_url(repository.github_url.as_deref().unwrap_or_default())
+    }
+
+    /// REST API base URL used for every request, without trailing slash
+    #[allow(dead_code)]
+    pub fn api_url(&self) -> &str {
+        &self.api_url
     }
 
     /// Discover all accessible repositories for the authenticated user
@@ -151,8 +188,8 @@ impl GitHubService {
 
         loop {
             let url = format!(
-                "https://api.github.com/user/repos?affiliation=owner,organization_member&per_page={}&page={}",
-                per_page, page
+                "{}/user/repos?affiliation=owner,organization_member&per_page={}&page={}",
+                self.api_url, per_page, page
             );
 
             tracing::debug!("Making GitHub API request to: {}", url);
@@ -168,9 +205,12 @@ impl GitHubService {
             let status = response.status();
 
             // Check rate limit before processing response
-            Self::check_github_rate_limit(&response);
+            let rate_limited = Self::check_github_rate_limit(&response);
 
             if !status.is_success() {
+                if rate_limited {
+                    return Err(anyhow!("GitHub API rate limit exceeded for {}", self.api_url));
+                }
                 let error_body = response.text().await.unwrap_or_default();
                 tracing::error!(
                     "GitHub API request failed - URL: {}, Status: {}, Body: {}",
@@ -198,6 +238,61 @@ impl GitHubService {
         Ok(repositories)
     }
 
+    /// Numbers of the open pull requests of a repository (`owner/name`)
+    pub async fn get_open_pull_requests(&self, access_token: &str, full_name: &str) -> Result<Vec<u64>> {
+        #[derive(Deserialize)]
+        struct PullRequest {
+            number: u64,
+        }
+
+        let mut numbers = Vec::new();
+        let mut page = 1;
+        let per_page = 100;
+
+        loop {
+            let url = format!(
+                "{}/repos/{}/pulls?state=open&per_page={}&page={}",
+                self.api_url, full_name, per_page, page
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", access_token))
+                .header("Accept", "application/vnd.github.v3+json")
+                .send()
+                .await
+                .context("Failed to fetch GitHub pull requests")?;
+
+            let status = response.status();
+            let rate_limited = Self::check_github_rate_limit(&response);
+
+            if !status.is_success() {
+                if rate_limited {
+                    return Err(anyhow!("GitHub API rate limit exceeded for {}", self.api_url));
+                }
+                let error_body = response.text().await.unwrap_or_default();
+                return Err(anyhow!("GitHub API error: {} - {}", status, error_body));
+            }
+
+            let page_pulls: Vec<PullRequest> =
+                response.json().await.context("Failed to parse GitHub pull requests response")?;
+
+            if page_pulls.is_empty() {
+                break;
+            }
+
+            let is_last_page = page_pulls.len() < per_page;
+            numbers.extend(page_pulls.into_iter().map(|pull| pull.number));
+            if is_last_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(numbers)
+    }
+
     /// Fetch repositories for a specific organization
     #[allow(dead_code)]
     async fn fetch_org_repositories(&self, access_token: &str, org: &str) -> Result<Vec<GitHubRepository>> {
@@ -207,8 +302,8 @@ impl GitHubService {
 
         loop {
             let url = format!(
-                "https://api.github.com/orgs/{}/repos?per_page={}&page={}",
-                org, per_page, page
+                "{}/orgs/{}/repos?per_page={}&page={}",
+                self.api_url, org, per_page, page
             );
 
             tracing::debug!("Making GitHub API request to: {}", url);
@@ -224,9 +319,12 @@ impl GitHubService {
             let status = response.status();
 
             // Check rate limit before processing response
-            Self::check_github_rate_limit(&response);
+            let rate_limited = Self::check_github_rate_limit(&response);
 
             if !status.is_success() {
+                if rate_limited {
+                    return Err(anyhow!("GitHub API rate limit exceeded for {}", self.api_url));
+                }
                 let error_body = response.text().await.unwrap_or_default();
                 tracing::error!(
                     "GitHub API request failed - URL: {}, Status: {}, Body: {}",
@@ -254,13 +352,13 @@ impl GitHubService {
     /// Test if the access token is valid
     #[allow(dead_code)]
     pub async fn test_token(&self, access_token: &str) -> Result<bool> {
-        let url = "https://api.github.com/user";
+        let url = format!("{}/user", self.api_url);
 
         tracing::debug!("Testing GitHub token with URL: {}", url);
 
         let response = self
             .client
-            .get(url)
+            .get(&url)
             .header("Authorization", format!("Bearer {}", access_token))
             .header("Accept", "application/vnd.github.v3+json")
             .send()
@@ -271,8 +369,10 @@ impl GitHubService {
                 let status = resp.status();
                 tracing::info!("GitHub token test status: {}", status);
 
-                // Check rate limit
-                Self::check_github_rate_limit(&resp);
+                // Check rate limit; an exhausted limit says nothing about the token itself
+                if Self::check_github_rate_limit(&resp) && !status.is_success() {
+                    return Err(anyhow!("GitHub API rate limit exceeded for {}", self.api_url));
+                }
 
                 if status.is_success() {
                     let user_info: serde_json::Value =
@@ -416,6 +516,36 @@ impl GitHubService {
     }
 }
 
+/// Resolve the REST API base URL for a github.com or GitHub Enterprise Server address.
+///
+/// Empty values and github.com hosts map to `https://api.github.com`. An Enterprise host can be
+/// given as its web address (`https://github.example.com`) or with the `/api/v3` path; both
+/// resolve to `https://github.example.com/api/v3`.
+pub fn github_api_base(url: &str) -> String {
+    let trimmed = url.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        return DEFAULT_GITHUB_API_URL.to_string();
+    }
+
+    let Ok(parsed) = Url::parse(trimmed) else {
+        return trimmed.to_string();
+    };
+    let Some(host) = parsed.host_str() else {
+        return trimmed.to_string();
+    };
+    if ["github.com", "www.github.com", "api.github.com"].iter().any(|h| host.eq_ignore_ascii_case(h)) {
+        return DEFAULT_GITHUB_API_URL.to_string();
+    }
+
+    // Keep any reverse-proxy prefix in front of /api/v3, drop anything after it
+    let origin = parsed.origin().ascii_serialization();
+    let path = parsed.path();
+    match path.find("/api/v3") {
+        Some(pos) => format!("{}{}", origin, &path[..pos + "/api/v3".len()]),
+        None => format!("{}/api/v3", origin),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,7 +568,12 @@ mod tests {
 
     #[test]
     fn test_pattern_matching() {
-        let service = GitHubService { client: Client::new(), excluded_repositories: vec![], excluded_patterns: vec![] };
+        let service = GitHubService {
+            client: Client::new(),
+            api_url: DEFAULT_GITHUB_API_URL.to_string(),
+            excluded_repositories: vec![],
+            excluded_patterns: vec![],
+        };
 
         // Exact match
         assert!(service.matches_pattern("user/project", "user/project"));
@@ -473,6 +608,7 @@ mod tests {
     fn test_should_exclude_repository_exact_match() {
         let service = GitHubService {
             client: Client::new(),
+            api_url: DEFAULT_GITHUB_API_URL.to_string(),
             excluded_repositories: vec!["user/large-project".to_string(), "org/archive".to_string()],
             excluded_patterns: vec![],
         };
@@ -490,6 +626,7 @@ mod tests {
     fn test_should_exclude_repository_pattern_match() {
         let service = GitHubService {
             client: Client::new(),
+            api_url: DEFAULT_GITHUB_API_URL.to_string(),
             excluded_repositories: vec![],
             excluded_patterns: vec!["*-archive".to_string(), "test/*".to_string(), "*/large-*".to_string()],
         };
@@ -511,6 +648,7 @@ mod tests {
     fn test_filter_excluded_repositories() {
         let service = GitHubService {
             client: Client::new(),
+            api_url: DEFAULT_GITHUB_API_URL.to_string(),
             excluded_repositories: vec!["user/exclude-me".to_string()],
             excluded_patterns: vec!["*-archive".to_string()],
         };
@@ -531,7 +669,12 @@ mod tests {
 
     #[test]
     fn test_empty_exclusion_config() {
-        let service = GitHubService { client: Client::new(), excluded_repositories: vec![], excluded_patterns: vec![] };
+        let service = GitHubService {
+            client: Client::new(),
+            api_url: DEFAULT_GITHUB_API_URL.to_string(),
+            excluded_repositories: vec![],
+            excluded_patterns: vec![],
+        };
 
         let repositories =
             vec![create_test_repository("project1", "user", 1), create_test_repository("project2", "user", 2)];
@@ -544,6 +687,7 @@ mod tests {
     fn test_exclude_all_repositories() {
         let service = GitHubService {
             client: Client::new(),
+            api_url: DEFAULT_GITHUB_API_URL.to_string(),
             excluded_repositories: vec![],
             excluded_patterns: vec!["*".to_string()],
         };
@@ -554,4 +698,23 @@ mod tests {
         let filtered = service.filter_excluded_repositories(repositories);
         assert_eq!(filtered.len(), 0);
     }
+
+    #[test]
+    fn test_github_api_base() {
+        assert_eq!(github_api_base(""), DEFAULT_GITHUB_API_URL);
+        assert_eq!(github_api_base("https://api.github.com/"), DEFAULT_GITHUB_API_URL);
+        assert_eq!(github_api_base("https://github.com/my-org"), DEFAULT_GITHUB_API_URL);
+        assert_eq!(
+            github_api_base("https://github.example.com"),
+            "https://github.example.com/api/v3"
+        );
+        assert_eq!(
+            github_api_base("https://github.example.com/api/v3/"),
+            "https://github.example.com/api/v3"
+        );
+        assert_eq!(
+            github_api_base("https://proxy.example.com:8443/ghe/api/v3/orgs"),
+            "https://proxy.example.com:8443/ghe/api/v3"
+        );
+    }
 }
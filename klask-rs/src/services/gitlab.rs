@@ -219,6 +219,65 @@ impl GitLabService {
         Ok(project)
     }
 
+    /// IIDs of the open merge requests of a project
+    pub async fn get_open_merge_requests(
+        &self,
+        gitlab_url: &str,
+        access_token: &str,
+        project_id: i64,
+    ) -> Result<Vec<u64>> {
+        #[derive(Deserialize)]
+        struct MergeRequest {
+            iid: u64,
+        }
+
+        let mut iids = Vec::new();
+        let mut page = 1;
+        let per_page = 100;
+
+        loop {
+            let url = format!(
+                "{}/api/v4/projects/{}/merge_requests?state=opened&page={}&per_page={}",
+                gitlab_url.trim_end_matches('/'),
+                project_id,
+                page,
+                per_page
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", access_token))
+                .send()
+                .await
+                .context("Failed to fetch GitLab merge requests")?;
+
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!(
+                    "GitLab API error: {} - {}",
+                    response.status(),
+                    response.text().await.unwrap_or_default()
+                ));
+            }
+
+            let page_merge_requests: Vec<MergeRequest> =
+                response.json().await.context("Failed to parse GitLab merge requests response")?;
+
+            if page_merge_requests.is_empty() {
+                break;
+            }
+
+            let is_last_page = page_merge_requests.len() < per_page;
+            iids.extend(page_merge_requests.into_iter().map(|merge_request| merge_request.iid));
+            if is_last_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(iids)
+    }
+
     /// Check if a project should be excluded from crawling
     #[allow(dead_code)]
     pub fn should_exclude_project(&self, project: &GitLabProject) -> bool {
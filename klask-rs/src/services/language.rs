@@ -0,0 +1,296 @@
+//! Language detection for indexed files.
+//!
+//! Files are classified into a canonical, lowercase language id (e.g. `rust`, `cpp`, `objective-c`)
+//! from their file name, their extension and, when those are ambiguous or missing, their content
+//! (shebang line and a few language markers).
+
+/// Languages recognized from the whole file name (lowercase), for files without a meaningful extension
+const FILE_NAME_LANGUAGES: &[(&str, &str)] = &[
+    ("dockerfile", "dockerfile"),
+    ("containerfile", "dockerfile"),
+    ("makefile", "makefile"),
+    ("gnumakefile", "makefile"),
+    ("cmakelists.txt", "cmake"),
+    ("rakefile", "ruby"),
+    ("gemfile", "ruby"),
+    ("podfile", "ruby"),
+    ("vagrantfile", "ruby"),
+    ("jenkinsfile", "groovy"),
+];
+
+/// Languages by file extension (lowercase), for unambiguous extensions
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("py", "python"),
+    ("pyw", "python"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("jsx", "javascript"),
+    ("ts", "typescript"),
+    ("mts", "typescript"),
+    ("tsx", "typescript"),
+    ("java", "java"),
+    ("c", "c"),
+    ("cc", "cpp"),
+    ("cpp", "cpp"),
+    ("cxx", "cpp"),
+    ("hh", "cpp"),
+    ("hpp", "cpp"),
+    ("hxx", "cpp"),
+    ("mm", "objective-c"),
+    ("go", "go"),
+    ("rb", "ruby"),
+    ("php", "php"),
+    ("cs", "csharp"),
+    ("swift", "swift"),
+    ("kt", "kotlin"),
+    ("kts", "kotlin"),
+    ("scala", "scala"),
+    ("sbt", "scala"),
+    ("clj", "clojure"),
+    ("cljs", "clojure"),
+    ("cljc", "clojure"),
+    ("hs", "haskell"),
+    ("ml", "ocaml"),
+    ("mli", "ocaml"),
+    ("fs", "fsharp"),
+    ("fsx", "fsharp"),
+    ("elm", "elm"),
+    ("dart", "dart"),
+    ("vue", "vue"),
+    ("svelte", "svelte"),
+    ("html", "html"),
+    ("htm", "html"),
+    ("css", "css"),
+    ("scss", "scss"),
+    ("less", "less"),
+    ("sql", "sql"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("ksh", "shell"),
+    ("fish", "fish"),
+    ("ps1", "powershell"),
+    ("psm1", "powershell"),
+    ("bat", "batch"),
+    ("cmd", "batch"),
+    ("dockerfile", "dockerfile"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("json", "json"),
+    ("toml", "toml"),
+    ("xml", "xml"),
+    ("pom", "xml"),
+    ("md", "markdown"),
+    ("markdown", "markdown"),
+    ("txt", "text"),
+    ("cfg", "ini"),
+    ("conf", "ini"),
+    ("ini", "ini"),
+    ("properties", "properties"),
+    ("gradle", "groovy"),
+    ("groovy", "groovy"),
+    ("cmake", "cmake"),
+    ("mk", "makefile"),
+    ("makefile", "makefile"),
+    ("r", "r"),
+    ("pl", "perl"),
+    ("pm", "perl"),
+    ("perl", "perl"),
+    ("lua", "lua"),
+    ("proto", "protobuf"),
+    ("tf", "terraform"),
+    ("graphql", "graphql"),
+    ("jl", "julia"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+    ("erl", "erlang"),
+    ("zig", "zig"),
+    ("bzl", "starlark"),
+    ("bazel", "starlark"),
+];
+
+/// Interpreters found in shebang lines (version suffixes such as `python3.12` are stripped)
+const INTERPRETER_LANGUAGES: &[(&str, &str)] = &[
+    ("python", "python"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("ksh", "shell"),
+    ("dash", "shell"),
+    ("fish", "fish"),
+    ("node", "javascript"),
+    ("nodejs", "javascript"),
+    ("deno", "typescript"),
+    ("ts-node", "typescript"),
+    ("ruby", "ruby"),
+    ("perl", "perl"),
+    ("php", "php"),
+    ("lua", "lua"),
+    ("pwsh", "powershell"),
+    ("rscript", "r"),
+];
+
+/// Alternative names accepted in language filters, mapped to canonical language ids
+const LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("c++", "cpp"),
+    ("cxx", "cpp"),
+    ("c#", "csharp"),
+    ("cs", "csharp"),
+    ("f#", "fsharp"),
+    ("js", "javascript"),
+    ("node", "javascript"),
+    ("ts", "typescript"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("kt", "kotlin"),
+    ("golang", "go"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("objc", "objective-c"),
+    ("obj-c", "objective-c"),
+    ("objectivec", "objective-c"),
+    ("yml", "yaml"),
+    ("md", "markdown"),
+    ("docker", "dockerfile"),
+    ("make", "makefile"),
+    ("pwsh", "powershell"),
+];
+
+fn lookup(table: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, language)| *language)
+}
+
+/// Detect the language of a file from its name and content.
+///
+/// Returns `None` when the file cannot be classified.
+pub fn detect_language(file_name: &str, content: &str) -> Option<&'static str> {
+    let name = file_name.rsplit('/').next().unwrap_or(file_name).to_lowercase();
+
+    if let Some(language) = lookup(FILE_NAME_LANGUAGES, &name) {
+        return Some(language);
+    }
+    if name.starts_with("dockerfile.") || name.ends_with(".dockerfile") {
+        return Some("dockerfile");
+    }
+
+    let extension = name.rsplit_once('.').map(|(stem, ext)| if stem.is_empty() { "" } else { ext }).unwrap_or("");
+    match extension {
+        "h" => return Some(classify_header(content)),
+        "m" => return Some(classify_m_file(content)),
+        _ => {}
+    }
+
+    lookup(EXTENSION_LANGUAGES, extension).or_else(|| shebang_language(content))
+}
+
+/// Normalize a user-supplied language name (case and common aliases) to a canonical language id
+pub fn canonical_language(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    lookup(LANGUAGE_ALIASES, &name).map(str::to_string).unwrap_or(name)
+}
+
+/// `.h` headers are shared by C, C++ and Objective-C
+fn classify_header(content: &str) -> &'static str {
+    if ["@interface", "@protocol", "#import"].iter().any(|marker| content.contains(marker)) {
+        "objective-c"
+    } else if ["namespace ", "template <", "template<", "std::", "public:", "private:", "class "]
+        .iter()
+        .any(|marker| content.contains(marker))
+    {
+        "cpp"
+    } else {
+        "c"
+    }
+}
+
+/// `.m` files are either Objective-C or MATLAB
+fn classify_m_file(content: &str) -> &'static str {
+    if ["@interface", "@implementation", "#import", "@end"].iter().any(|marker| content.contains(marker)) {
+        "objective-c"
+    } else {
+        "matlab"
+    }
+}
+
+/// Language of a script from its `#!` line, e.g. `#!/usr/bin/env python3`
+fn shebang_language(content: &str) -> Option<&'static str> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-'))?;
+    }
+
+    let interpreter = interpreter.to_lowercase();
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    lookup(INTERPRETER_LANGUAGES, interpreter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_by_extension_and_file_name() {
+        assert_eq!(detect_language("main.rs", ""), Some("rust"));
+        assert_eq!(detect_language("src/App.TSX", ""), Some("typescript"));
+        assert_eq!(detect_language("Dockerfile", ""), Some("dockerfile"));
+        assert_eq!(detect_language("Dockerfile.prod", ""), Some("dockerfile"));
+        assert_eq!(detect_language("Makefile", ""), Some("makefile"));
+        assert_eq!(detect_language("CMakeLists.txt", ""), Some("cmake"));
+        assert_eq!(detect_language("notes.unknown", ""), None);
+        assert_eq!(detect_language(".bashrc", ""), None);
+    }
+
+    #[test]
+    fn test_disambiguate_headers() {
+        assert_eq!(detect_language("list.h", "struct list { int n; };"), Some("c"));
+        assert_eq!(detect_language("list.h", "namespace util { class List; }"), Some("cpp"));
+        assert_eq!(
+            detect_language("View.h", "#import <UIKit/UIKit.h>\n@interface View"),
+            Some("objective-c")
+        );
+    }
+
+    #[test]
+    fn test_disambiguate_m_files() {
+        assert_eq!(
+            detect_language("View.m", "#import \"View.h\"\n@implementation View\n@end"),
+            Some("objective-c")
+        );
+        assert_eq!(
+            detect_language("solve.m", "% Solve the system\nfunction x = solve(A, b)\nend"),
+            Some("matlab")
+        );
+    }
+
+    #[test]
+    fn test_detect_by_shebang() {
+        assert_eq!(detect_language("deploy", "#!/bin/bash\nset -e"), Some("shell"));
+        assert_eq!(
+            detect_language("manage", "#!/usr/bin/env python3\nimport sys"),
+            Some("python")
+        );
+        assert_eq!(
+            detect_language("tool", "#!/usr/bin/env -S node --no-warnings\n"),
+            Some("javascript")
+        );
+        assert_eq!(
+            detect_language("server", "#!/usr/local/bin/python3.12\n"),
+            Some("python")
+        );
+        assert_eq!(detect_language("data", "plain text"), None);
+    }
+
+    #[test]
+    fn test_canonical_language_aliases() {
+        assert_eq!(canonical_language("C++"), "cpp");
+        assert_eq!(canonical_language(" Rust "), "rust");
+        assert_eq!(canonical_language("objc"), "objective-c");
+        assert_eq!(canonical_language("golang"), "go");
+    }
+}
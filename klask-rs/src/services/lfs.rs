@@ -0,0 +1,240 @@
+//! Git LFS pointers and downloads through the LFS batch API.
+//!
+//! Files tracked by Git LFS are committed as small pointer files naming the SHA-256 and size of their
+//! content, which is stored on the LFS server of the repository.
+
+use crate::services::ssh::is_ssh_url;
+use anyhow::{Context, Result, anyhow};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Pointer files are smaller than this, larger blobs are never parsed as pointers
+pub const MAX_POINTER_SIZE: u64 = 1024;
+
+const LFS_MEDIA_TYPE: &str = "application/vnd.git-lfs+json";
+
+/// Content of a Git LFS pointer file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsPointer {
+    pub oid: String, // SHA-256 of the content, lowercase hex
+    pub size: u64,   // Size of the content in bytes
+}
+
+/// Parse a Git LFS pointer (`version https://git-lfs.github.com/spec/v1`, `oid sha256:...`, `size ...`)
+pub fn parse_lfs_pointer(content: &[u8]) -> Option<LfsPointer> {
+    if content.len() as u64 >= MAX_POINTER_SIZE {
+        return None;
+    }
+    let content = std::str::from_utf8(content).ok()?;
+    let mut lines = content.lines();
+
+    // The version line comes first; early pointers used the hawser spec
+    let version = lines.next()?.strip_prefix("version ")?;
+    if !version.starts_with("https://git-lfs.github.com/spec/") && version != "https://hawser.github.com/spec/v1" {
+        return None;
+    }
+
+    let (mut oid, mut size) = (None, None);
+    for line in lines.filter(|line| !line.is_empty()) {
+        let (key, value) = line.split_once(' ')?;
+        match key {
+            "oid" => {
+                let hash = value.strip_prefix("sha256:")?;
+                if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return None;
+                }
+                oid = Some(hash.to_ascii_lowercase());
+            }
+            "size" => size = Some(value.parse().ok()?),
+            _ => {} // Extensions (ext-0-...)
+        }
+    }
+
+    Some(LfsPointer { oid: oid?, size: size? })
+}
+
+/// LFS server of a Git remote, following the Git LFS default (`<repository>.git/info/lfs` over HTTPS for SSH
+/// remotes), with the user name of the remote URL if any. `None` for local repositories.
+pub fn lfs_endpoint(repository_url: &str) -> Option<(Url, Option<String>)> {
+    let repository_url = repository_url.trim().trim_end_matches('/');
+    let (host, path) = match repository_url.split_once("://") {
+        Some((scheme, _)) if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") => {
+            let mut url = Url::parse(repository_url).ok()?;
+            let username = Some(url.username().to_string()).filter(|username| !username.is_empty());
+            url.set_username("").ok()?;
+            url.set_password(None).ok()?;
+            let path = with_git_suffix(url.path().trim_end_matches('/'));
+            url.set_path(&format!("{}/info/lfs", path));
+            return Some((url, username));
+        }
+        Some((_, rest)) if is_ssh_url(repository_url) => {
+            let (authority, path) = rest.split_once('/')?;
+            (authority.rsplit('@').next()?.split(':').next()?, path)
+        }
+        None if is_ssh_url(repository_url) => {
+            let (authority, path) = repository_url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        }
+        _ => return None,
+    };
+
+    let url = Url::parse(&format!(
+        "https://{}/{}/info/lfs",
+        host,
+        with_git_suffix(path.trim_matches('/'))
+    ))
+    .ok()?;
+    Some((url, None))
+}
+
+fn with_git_suffix(path: &str) -> String {
+    if path.ends_with(".git") { path.to_string() } else { format!("{}.git", path) }
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResponse {
+    objects: Vec<BatchObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchObject {
+    oid: String,
+    actions: Option<BatchActions>,
+    error: Option<BatchError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchActions {
+    download: Option<BatchAction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchAction {
+    href: String,
+    #[serde(default)]
+    header: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchError {
+    code: u16,
+    message: String,
+}
+
+/// Client downloading LFS objects with the basic transfer of the batch API
+#[derive(Debug, Clone)]
+pub struct LfsService {
+    client: Client,
+}
+
+impl Default for LfsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LfsService {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .user_agent("klask-rs/2.0")
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self { client }
+    }
+
+    /// Download the content of a pointer from the LFS server of a repository, checking its size and SHA-256.
+    ///
+    /// The access token is sent like for Git over HTTPS: as the password of the remote URL's user, or of `oauth2`.
+    pub async fn download(
+        &self,
+        repository_url: &str,
+        access_token: Option<&str>,
+        pointer: &LfsPointer,
+    ) -> Result<Vec<u8>> {
+        let (endpoint, username) =
+            lfs_endpoint(repository_url).ok_or_else(|| anyhow!("No LFS server for {}", repository_url))?;
+        let username = username.unwrap_or_else(|| "oauth2".to_string());
+
+        let mut request = self
+            .client
+            .post(format!("{}/objects/batch", endpoint))
+            .header("Accept", LFS_MEDIA_TYPE)
+            .header("Content-Type", LFS_MEDIA_TYPE)
+            .body(
+                json!({
+                    "operation": "download",
+                    "transfers": ["basic"],
+                    "objects": [{ "oid": pointer.oid, "size": pointer.size }]
+                })
+                .to_string(),
+            );
+        if let Some(token) = access_token {
+            request = request.basic_auth(&username, Some(token));
+        }
+
+        let response = request.send().await.context("LFS batch request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("LFS batch API error {}: {}", status, body));
+        }
+        let batch: BatchResponse = response.json().await.context("Invalid LFS batch response")?;
+
+        let object = batch
+            .objects
+            .into_iter()
+            .find(|object| object.oid == pointer.oid)
+            .ok_or_else(|| anyhow!("LFS object {} missing from the batch response", pointer.oid))?;
+        if let Some(error) = object.error {
+            return Err(anyhow!(
+                "LFS object {}: {} ({})",
+                pointer.oid,
+                error.message,
+                error.code
+            ));
+        }
+        let action = object
+            .actions
+            .and_then(|actions| actions.download)
+            .ok_or_else(|| anyhow!("LFS object {} has no download action", pointer.oid))?;
+
+        let mut request = self.client.get(&action.href);
+        let has_authorization = action.header.keys().any(|name| name.eq_ignore_ascii_case("authorization"));
+        for (name, value) in &action.header {
+            request = request.header(name, value);
+        }
+        // Objects served by the LFS server itself need the same credentials, other stores get signed URLs
+        let same_host = Url::parse(&action.href).ok().is_some_and(|href| href.host_str() == endpoint.host_str());
+        if let Some(token) = access_token
+            && !has_authorization
+            && same_host
+        {
+            request = request.basic_auth(&username, Some(token));
+        }
+
+        let response = request.send().await.context("LFS download failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("LFS download of {} failed with {}", pointer.oid, status));
+        }
+        let content = response.bytes().await.context("LFS download failed")?.to_vec();
+
+        if content.len() as u64 != pointer.size {
+            return Err(anyhow!(
+                "LFS object {} has {} bytes instead of {}",
+                pointer.oid,
+                content.len(),
+                pointer.size
+            ));
+        }
+        let digest: String = Sha256::digest(&content).iter().map(|byte| format!("{:02x}", byte)).collect();
+        if digest != pointer.oid {
+            return Err(anyhow!("LFS object {} does not match its SHA-256", pointer.oid));
+        }
+        Ok(content)
+    }
+}
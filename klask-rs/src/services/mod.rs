@@ -1,12 +1,21 @@
+pub mod azure_devops;
+pub mod bitbucket;
 pub mod crawler;
 pub mod encryption;
+pub mod generated;
+pub mod gitea;
 pub mod github;
 pub mod gitlab;
+pub mod language;
+pub mod lfs;
 pub mod progress;
 pub mod scheduler;
 pub mod search;
 pub mod search_metrics;
 pub mod seeding;
+pub mod ssh;
+pub mod svn;
 pub mod tantivy_config;
+pub mod webhooks;
 
 pub use search::*;
@@ -26,6 +26,7 @@ pub struct CrawlProgressInfo {
     pub files_processed: usize,
     pub files_total: Option<usize>,
     pub files_indexed: usize,
+    pub files_skipped_binary: usize, // Binary files found and not indexed
     pub current_file: Option<String>,
     pub error_message: Option<String>,
     pub started_at: DateTime<Utc>,
@@ -51,6 +52,7 @@ impl CrawlProgressInfo {
             files_processed: 0,
             files_total: None,
             files_indexed: 0,
+            files_skipped_binary: 0,
             current_file: None,
             error_message: None,
             started_at: now,
@@ -148,6 +150,15 @@ impl ProgressTracker {
         }
     }
 
+    /// Count a binary file skipped by the crawl
+    pub async fn record_binary_skipped(&self, repository_id: Uuid) {
+        let mut map = self.progress_map.write().await;
+        if let Some(progress) = map.get_mut(&repository_id) {
+            progress.files_skipped_binary += 1;
+            progress.updated_at = Utc::now();
+        }
+    }
+
     pub async fn set_current_file(&self, repository_id: Uuid, file_path: Option<String>) {
         let mut map = self.progress_map.write().await;
         if let Some(progress) = map.get_mut(&repository_id) {
@@ -1,26 +1,42 @@
+use crate::models::RankingConfig;
+use crate::services::language::canonical_language;
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use tantivy::collector::{Count, TopDocs};
 use tantivy::directory::MmapDirectory;
-use tantivy::query::{BooleanQuery, QueryParser, RegexQuery, TermQuery};
+use tantivy::query::{BooleanQuery, MoreLikeThisQuery, QueryParser, RegexQuery, TermQuery};
 use tantivy::schema::{
-    FAST, Field, IndexRecordOption, STORED, STRING, Schema, TEXT, TextFieldIndexing, TextOptions, Value,
+    FAST, Facet, FacetOptions, Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TEXT, TextFieldIndexing,
+    TextOptions, Value,
 };
 use tantivy::snippet::SnippetGenerator;
 use tantivy::{Index, IndexReader, IndexWriter, Term, doc};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use tracing::{debug, warn};
+use tracing::{debug, error, warn};
 
 // Search timeout: maximum time allowed for a single search query (30 seconds)
 // This prevents heavy regex queries (e.g., .*pattern) from blocking other requests
 const SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
 
+// More-like-this tuning: a term must appear in the source file and at least one other file,
+// and we keep the most distinctive terms only so the query stays fast on large indices
+const SIMILAR_MIN_DOC_FREQUENCY: u64 = 2;
+const SIMILAR_MIN_TERM_FREQUENCY: usize = 1;
+const SIMILAR_MIN_WORD_LENGTH: usize = 3;
+const SIMILAR_MAX_QUERY_TERMS: usize = 50;
+
+// Maximum number of directory facet values returned for one level of the path tree
+const DIRECTORY_FACET_LIMIT: usize = 200;
+
+// Maximum number of files listed for one directory when browsing a repository tree
+const TREE_FILES_LIMIT: usize = 1000;
+
 const SIZE_BUCKETS: &[(&str, Option<u64>, Option<u64>)] = &[
     ("< 1 KB", None, Some(1024)),
     ("1 KB - 10 KB", Some(1024), Some(10 * 1024)),
@@ -64,7 +80,7 @@ fn build_regex_pattern(pattern: &str, flags: Option<&str>) -> String {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FileData<'a> {
     pub file_id: Uuid,
     pub file_name: &'a str,
@@ -74,7 +90,20 @@ pub struct FileData<'a> {
     pub project: &'a str,    // Individual project name (for GitLab/GitHub, same as repository for simple Git repos)
     pub version: &'a str,
     pub extension: &'a str,
-    pub size: u64, // File content size in bytes
+    pub size: u64,                     // File content size in bytes
+    pub last_modified: Option<i64>,    // Unix timestamp in seconds (None when unknown, sorted after dated files)
+    pub language: Option<&'a str>,     // Canonical language id (None detects it from the file name and content)
+    pub sections: &'a [SourceSection], // Parts of the text extracted from a document (empty for plain text files)
+    pub line_offset: u32,              // Lines of the file before this document, for chunks of large files
+    pub is_truncated: bool,            // Only the beginning of a file larger than the size limit is indexed
+    pub is_lfs: bool,                  // Tracked by Git LFS: only the path is indexed unless the content was fetched
+}
+
+/// Part of the text extracted from a document, e.g. a notebook cell or a PDF page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceSection {
+    pub line: u32, // 1-based line of the extracted text where the part starts
+    pub label: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,13 +112,47 @@ pub struct SearchResult {
     pub doc_address: String, // Format: "segment_ord:doc_id"
     pub file_name: String,
     pub file_path: String,
-    pub content_snippet: String,
+    pub content_snippet: String, // Plain text fragment (full content when fetching a single file)
+    pub highlights: Vec<HighlightRange>,
+    pub fragment_start_line: Option<u32>, // 1-based line of the fragment's first character
     pub repository: String,
     pub project: String,
     pub version: String,
     pub extension: String,
+    pub language: String,   // Canonical language id, empty when unknown
+    pub is_generated: bool, // Lockfile, source map, minified asset or file with a generated-code header
     pub score: f32,
     pub line_number: Option<u32>,
+    pub source_location: Option<String>, // Part of an extracted document containing the match, e.g. "cell 3"
+    pub line_offset: u32,                // Lines of the file before this chunk (0 unless large files are chunked)
+    pub is_truncated: bool,              // Only the beginning of the file is indexed
+    pub is_lfs: bool,                    // Tracked by Git LFS
+    pub size: u64,
+    pub last_modified: Option<i64>, // Unix timestamp in seconds
+}
+
+/// Highlighted match inside a snippet fragment, relative to the fragment start (end exclusive)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRange {
+    pub start: usize, // Byte offsets
+    pub end: usize,
+    pub char_start: usize, // Char offsets
+    pub char_end: usize,
+}
+
+/// Snippet of a document's content, as attached to a `SearchResult`
+struct ContentSnippet {
+    fragment: String,
+    highlights: Vec<HighlightRange>,
+    fragment_start_line: Option<u32>,
+    line_number: Option<u32>,
+}
+
+impl ContentSnippet {
+    /// The whole content as a single unhighlighted fragment
+    fn full(content: String) -> Self {
+        Self { fragment: content, highlights: Vec::new(), fragment_start_line: Some(1), line_number: None }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +160,7 @@ pub struct SearchResultsWithTotal {
     pub results: Vec<SearchResult>,
     pub total: u64,
     pub facets: Option<SearchFacets>,
+    pub next_cursor: Option<String>, // Pass as search_after to get the next page (None on the last page)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,7 +169,64 @@ pub struct SearchFacets {
     pub projects: Vec<(String, u64)>,
     pub versions: Vec<(String, u64)>,
     pub extensions: Vec<(String, u64)>,
+    pub languages: Vec<(String, u64)>,
     pub size_ranges: Vec<(String, u64)>,
+    pub directories: Vec<(String, u64)>, // Direct subdirectories of the path filter (or of the root)
+    pub generated: u64,                  // Matches in generated files (counted even when they are excluded)
+}
+
+/// One level of a repository tree, built from the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryTree {
+    pub path: String,
+    pub directories: Vec<TreeDirectory>,
+    pub files: Vec<TreeFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeDirectory {
+    pub name: String,
+    pub path: String,
+    pub file_count: u64, // Number of indexed files below this directory (recursively)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeFile {
+    pub file_id: Uuid,
+    pub doc_address: String,
+    pub name: String,
+    pub path: String,
+    pub project: String,
+    pub version: String,
+    pub size: u64,
+}
+
+/// Field used to order search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    Score,      // Relevance (default)
+    Path,       // File path, then project and version
+    Repository, // Repository, then file path and version
+    Size,       // File size in bytes
+    Date,       // Last modification time
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortField {
+    /// Order used when none is requested: alphabetical for names, largest/newest first otherwise
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            SortField::Path | SortField::Repository => SortOrder::Asc,
+            SortField::Score | SortField::Size | SortField::Date => SortOrder::Desc,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -115,14 +236,20 @@ pub struct SearchQuery {
     pub project_filter: Option<String>,
     pub version_filter: Option<String>,
     pub extension_filter: Option<String>,
-    pub min_size: Option<u64>, // Minimum file size in bytes
-    pub max_size: Option<u64>, // Maximum file size in bytes
+    pub language_filter: Option<String>, // Language ids or aliases; `lang:` terms in the query are added to it
+    pub min_size: Option<u64>,           // Minimum file size in bytes
+    pub max_size: Option<u64>,           // Maximum file size in bytes
+    pub path_filter: Option<String>,     // Directory prefix, e.g. "src/api" (matches files below it)
+    pub sort_by: Option<SortField>,      // None sorts by relevance
+    pub sort_order: Option<SortOrder>,   // None uses the sort field's default order
+    pub search_after: Option<String>,    // Cursor from a previous page's next_cursor (same query and sort)
     pub limit: usize,
     pub offset: usize,
     pub include_facets: bool,
     pub fuzzy_search: bool,          // Enable fuzzy search (1 char edit distance) - default: false
     pub regex_search: bool,          // Enable regex search (pattern matching) - default: false
     pub regex_flags: Option<String>, // Regex flags: "i" (case-insensitive), "m" (multiline), "s" (dotall), or combinations like "ims"
+    pub include_generated: bool,     // Include generated files (lockfiles, minified assets...) - default: false
 }
 
 impl SearchQuery {
@@ -135,17 +262,69 @@ impl SearchQuery {
             project_filter: None,
             version_filter: None,
             extension_filter: None,
+            language_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
+        }
+    }
+
+    /// Sort field and order actually applied (relevance is always descending)
+    pub fn effective_sort(&self) -> (SortField, SortOrder) {
+        match self.sort_by.unwrap_or(SortField::Score) {
+            SortField::Score => (SortField::Score, SortOrder::Desc),
+            sort_by => (sort_by, self.sort_order.unwrap_or(sort_by.default_order())),
         }
     }
 
+    /// Check that the search_after cursor is well-formed and belongs to this query's sort
+    pub fn validate_cursor(&self) -> Result<()> {
+        if let Some(ref cursor) = self.search_after {
+            let cursor = SearchCursor::decode(cursor)?;
+            if (cursor.sort_by, cursor.sort_order) != self.effective_sort() {
+                return Err(anyhow!("search_after cursor was created for a different sort"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Move `lang:<name>` terms from the query text into the language filter (not in regex mode)
+    pub fn extract_inline_filters(mut self) -> Self {
+        if self.regex_search {
+            return self;
+        }
+
+        let mut languages: Vec<String> = Vec::new();
+        let mut terms = Vec::new();
+        for term in self.query.split_whitespace() {
+            match term.get(..5).filter(|prefix| prefix.eq_ignore_ascii_case("lang:")) {
+                Some(_) if term.len() > 5 => languages.push(term[5..].to_string()),
+                _ => terms.push(term),
+            }
+        }
+        if languages.is_empty() {
+            return self;
+        }
+
+        let remaining = terms.join(" ");
+        self.query = if remaining.is_empty() { "*".to_string() } else { remaining };
+        if let Some(ref existing) = self.language_filter {
+            languages.insert(0, existing.clone());
+        }
+        self.language_filter = Some(languages.join(","));
+        self
+    }
+
     /// Set fuzzy search option
     #[allow(dead_code)]
     pub fn with_fuzzy(mut self, fuzzy: bool) -> Self {
@@ -161,6 +340,45 @@ impl SearchQuery {
     }
 }
 
+/// Parameters for a "more like this" lookup.
+///
+/// The source document is resolved from `doc_address` first, then from `file_id`.
+#[derive(Debug, Clone, Default)]
+pub struct SimilarQuery {
+    pub file_id: Option<Uuid>,
+    pub doc_address: Option<String>,
+    pub exclude_same_path: bool, // Skip the same path in the same project (i.e. the file on other branches)
+    pub limit: usize,
+}
+
+/// Why a document matches a search (or not), and how its score is computed
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchExplanation {
+    pub doc_address: String,
+    pub file_path: String,
+    pub project: String,
+    pub version: String,
+    pub matches: bool,        // Whether the document matches the query and its filters
+    pub parsed_query: String, // Executed query, after fuzzy/regex/filter composition
+    pub explanation: Option<serde_json::Value>, // Tantivy score explanation tree (None if no match)
+    pub matched_fields: Vec<String>, // Fields matching the text query on their own
+    pub bm25_score: Option<f32>,
+    pub ranking_multiplier: f32, // Default-branch, path demotion and shallow-path adjustments
+    pub final_score: Option<f32>,
+}
+
+/// Parameters for browsing one directory level of an indexed repository
+#[derive(Debug, Clone, Default)]
+pub struct TreeQuery {
+    pub repository: String,
+    pub project: Option<String>, // Restrict to one project of the repository (e.g. a GitLab sub-project)
+    pub version: String,
+    pub path: String, // Directory to list ("" for the root)
+}
+
+/// Scope of a re-indexed version: (repository, project, version)
+type VersionKey = (String, String, String);
+
 #[derive(Clone)]
 pub struct SearchService {
     index: Index,
@@ -169,6 +387,12 @@ pub struct SearchService {
     schema: Schema,
     fields: SearchFields,
     index_dir: std::path::PathBuf,
+    outdated_index_dir: Option<std::path::PathBuf>, // Where an index with an outdated schema was moved at startup
+    ranking: Arc<std::sync::RwLock<RankingConfig>>,
+    // Default branch of each project (document `project` field), from its repository or its crawl
+    default_branches: Arc<std::sync::RwLock<HashMap<String, String>>>,
+    // IDs of the documents written to the versions being re-indexed
+    tracked_versions: Arc<std::sync::Mutex<HashMap<VersionKey, HashSet<Uuid>>>>,
 }
 
 #[derive(Clone)]
@@ -184,6 +408,16 @@ struct SearchFields {
     size: Field,          // File content size in bytes
     file_name_raw: Field, // Non-tokenized file_name for regex search
     file_path_raw: Field, // Non-tokenized file_path for regex search
+    path_facet: Field,    // Hierarchical facet of the parent directory, e.g. /src/api/admin
+    directory: Field,     // Parent directory as an exact value, e.g. "src/api/admin" ("" for the root)
+    last_modified: Field, // Unix timestamp in seconds, for date sorting
+    language: Field,
+    is_generated: Field,
+    sections: Field, // JSON list of `SourceSection`, for text extracted from documents
+    line_offset: Field,
+    is_truncated: Field,
+    is_lfs: Field,
+    file_key: Field, // First 8 bytes of file_id, the tie-breaker of every sort order
 }
 
 impl SearchService {
@@ -196,7 +430,40 @@ impl SearchService {
 
         // Use MmapDirectory with open_or_create - the elegant Tantivy way
         let mmap_directory = MmapDirectory::open(&index_dir)?;
-        let index = Index::open_or_create(mmap_directory, schema.clone())?;
+        let mut outdated_index_dir = None;
+        let index = match Index::open_or_create(mmap_directory, schema.clone()) {
+            Ok(index) => index,
+            Err(tantivy::TantivyError::SchemaError(e)) => {
+                // The schema changed since the index was built (new or modified fields).
+                // Tantivy cannot migrate an index: keep the old one aside and start from an empty one,
+                // which callers fill by re-crawling every repository (see `outdated_index_dir`).
+                let index_name = index_dir.as_ref().file_name().map(|name| name.to_string_lossy().to_string());
+                let backup_dir = index_dir.as_ref().with_file_name(format!(
+                    "{}.outdated-{}",
+                    index_name.as_deref().unwrap_or("index"),
+                    chrono::Utc::now().format("%Y%m%d%H%M%S")
+                ));
+                std::fs::rename(&index_dir, &backup_dir).map_err(|rename_error| {
+                    anyhow!(
+                        "Search index at {:?} has an outdated schema ({}) and could not be moved to {:?}: {}",
+                        index_dir.as_ref(),
+                        e,
+                        backup_dir,
+                        rename_error
+                    )
+                })?;
+                error!(
+                    "Search index at {:?} has an outdated schema ({}): moved it to {:?} and created an empty index, every repository must be re-crawled",
+                    index_dir.as_ref(),
+                    e,
+                    backup_dir
+                );
+                std::fs::create_dir_all(&index_dir)?;
+                outdated_index_dir = Some(backup_dir);
+                Index::create_in_dir(&index_dir, schema.clone())?
+            }
+            Err(e) => return Err(e.into()),
+        };
 
         let reader = index.reader()?;
 
@@ -231,14 +498,26 @@ impl SearchService {
             Arc::new(RwLock::new(index.writer(memory_bytes)?))
         };
 
-        Ok(Self { index, reader, writer, schema, fields, index_dir: index_dir.as_ref().to_path_buf() })
+        Ok(Self {
+            index,
+            reader,
+            writer,
+            schema,
+            fields,
+            index_dir: index_dir.as_ref().to_path_buf(),
+            outdated_index_dir,
+            ranking: Arc::new(std::sync::RwLock::new(RankingConfig::default())),
+            default_branches: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            tracked_versions: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        })
     }
 
     fn build_schema() -> Schema {
         let mut schema_builder = Schema::builder();
 
         // File metadata fields
-        schema_builder.add_text_field("file_id", TEXT | STORED | FAST);
+        // file_id must not be tokenized so it can be used for exact lookups and upsert deletions
+        schema_builder.add_text_field("file_id", STRING | STORED | FAST);
         schema_builder.add_text_field("file_name", TEXT | STORED);
         schema_builder.add_text_field("file_path", TEXT | STORED);
 
@@ -250,6 +529,8 @@ impl SearchService {
         schema_builder.add_text_field("project", STRING | STORED | FAST);
         schema_builder.add_text_field("version", STRING | STORED | FAST);
         schema_builder.add_text_field("extension", STRING | STORED | FAST);
+        schema_builder.add_text_field("language", STRING | STORED | FAST);
+        schema_builder.add_bool_field("is_generated", INDEXED | STORED | FAST);
 
         // Size field for filtering by file content size (in bytes)
         schema_builder.add_u64_field("size", FAST | STORED);
@@ -260,11 +541,30 @@ impl SearchService {
             .set_indexing_options(
                 TextFieldIndexing::default().set_tokenizer("raw").set_index_option(IndexRecordOption::Basic),
             )
-            .set_stored();
+            .set_stored()
+            .set_fast(Some("raw"));
 
         schema_builder.add_text_field("file_name_raw", raw_text_options.clone());
         schema_builder.add_text_field("file_path_raw", raw_text_options);
 
+        // Directory fields for path-tree facets and repository browsing
+        schema_builder.add_facet_field("path_facet", FacetOptions::default());
+        schema_builder.add_text_field("directory", STRING | STORED | FAST);
+
+        // Modification time for sorting by date
+        schema_builder.add_i64_field("last_modified", FAST | STORED);
+        schema_builder.add_u64_field("file_key", FAST);
+
+        // Parts of the text extracted from documents (notebook cells, PDF pages), to locate matches
+        schema_builder.add_text_field("sections", STORED);
+
+        // Large files indexed in chunks (line offset of the chunk) or truncated
+        schema_builder.add_u64_field("line_offset", STORED);
+        schema_builder.add_bool_field("is_truncated", INDEXED | STORED);
+
+        // Files tracked by Git LFS, indexed by path only unless their content was fetched
+        schema_builder.add_bool_field("is_lfs", INDEXED | STORED);
+
         schema_builder.build()
     }
 
@@ -281,14 +581,29 @@ impl SearchService {
             size: schema.get_field("size").expect("size field should exist"),
             file_name_raw: schema.get_field("file_name_raw").expect("file_name_raw field should exist"),
             file_path_raw: schema.get_field("file_path_raw").expect("file_path_raw field should exist"),
+            path_facet: schema.get_field("path_facet").expect("path_facet field should exist"),
+            directory: schema.get_field("directory").expect("directory field should exist"),
+            last_modified: schema.get_field("last_modified").expect("last_modified field should exist"),
+            file_key: schema.get_field("file_key").expect("file_key field should exist"),
+            language: schema.get_field("language").expect("language field should exist"),
+            is_generated: schema.get_field("is_generated").expect("is_generated field should exist"),
+            sections: schema.get_field("sections").expect("sections field should exist"),
+            line_offset: schema.get_field("line_offset").expect("line_offset field should exist"),
+            is_truncated: schema.get_field("is_truncated").expect("is_truncated field should exist"),
+            is_lfs: schema.get_field("is_lfs").expect("is_lfs field should exist"),
         }
     }
 
-    #[allow(dead_code)]
-    pub async fn index_file(&self, file_data: FileData<'_>) -> Result<()> {
-        let writer = self.writer.write().await;
+    /// Build the Tantivy document for a file
+    fn build_document(&self, file_data: &FileData<'_>) -> tantivy::TantivyDocument {
+        let directory = parent_directory(file_data.file_path);
+        let facet = directory_facet(&directory);
+        let language = file_data
+            .language
+            .or_else(|| crate::services::language::detect_language(file_data.file_name, file_data.content))
+            .unwrap_or("");
 
-        let doc = doc!(
+        let mut doc = doc!(
             self.fields.file_id => file_data.file_id.to_string(),
             self.fields.file_name => file_data.file_name,
             self.fields.file_path => file_data.file_path,
@@ -300,7 +615,32 @@ impl SearchService {
             self.fields.size => file_data.size,
             self.fields.file_name_raw => file_data.file_name,
             self.fields.file_path_raw => file_data.file_path,
+            self.fields.directory => directory,
+            self.fields.file_key => file_key(file_data.file_id),
+            self.fields.language => language,
+            self.fields.is_generated => crate::services::generated::is_generated(file_data.file_name, file_data.content),
+            self.fields.line_offset => file_data.line_offset as u64,
+            self.fields.is_truncated => file_data.is_truncated,
+            self.fields.is_lfs => file_data.is_lfs,
         );
+        doc.add_facet(self.fields.path_facet, facet);
+        if let Some(last_modified) = file_data.last_modified {
+            doc.add_i64(self.fields.last_modified, last_modified);
+        }
+        if !file_data.sections.is_empty()
+            && let Ok(sections) = serde_json::to_string(file_data.sections)
+        {
+            doc.add_text(self.fields.sections, sections);
+        }
+
+        doc
+    }
+
+    #[allow(dead_code)]
+    pub async fn index_file(&self, file_data: FileData<'_>) -> Result<()> {
+        let writer = self.writer.write().await;
+
+        let doc = self.build_document(&file_data);
 
         writer.add_document(doc)?;
         Ok(())
@@ -324,24 +664,41 @@ impl SearchService {
         );
 
         // Add the new document
-        let doc = doc!(
-            self.fields.file_id => file_id_str,
-            self.fields.file_name => file_data.file_name,
-            self.fields.file_path => file_data.file_path,
-            self.fields.content => file_data.content,
-            self.fields.repository => file_data.repository,
-            self.fields.project => file_data.project,
-            self.fields.version => file_data.version,
-            self.fields.extension => file_data.extension,
-            self.fields.size => file_data.size,
-            self.fields.file_name_raw => file_data.file_name,
-            self.fields.file_path_raw => file_data.file_path,
-        );
+        let doc = self.build_document(&file_data);
 
         writer.add_document(doc)?;
+
+        let mut tracked_versions = self.tracked_versions.lock().unwrap_or_else(|e| e.into_inner());
+        if !tracked_versions.is_empty() {
+            let key = (
+                file_data.repository.to_string(),
+                file_data.project.to_string(),
+                file_data.version.to_string(),
+            );
+            if let Some(file_ids) = tracked_versions.get_mut(&key) {
+                file_ids.insert(file_data.file_id);
+            }
+        }
         Ok(())
     }
 
+    /// Start recording the IDs of the documents written to a version, for `replace_version_documents`
+    pub fn track_version(&self, repository: &str, project: &str, version: &str) {
+        self.tracked_versions.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            (repository.to_string(), project.to_string(), version.to_string()),
+            HashSet::new(),
+        );
+    }
+
+    /// Stop recording the documents written to a version, returning the IDs recorded since `track_version`
+    pub fn untrack_version(&self, repository: &str, project: &str, version: &str) -> HashSet<Uuid> {
+        self.tracked_versions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(repository.to_string(), project.to_string(), version.to_string()))
+            .unwrap_or_default()
+    }
+
     /// Check if a document exists by file_id (lightweight check)
     #[allow(dead_code)]
     pub async fn document_exists(&self, file_id: Uuid) -> Result<bool> {
@@ -406,6 +763,107 @@ impl SearchService {
         Ok(count_before)
     }
 
+    /// Delete the documents of one version (branch) of a project, or only those of `file_paths` in it
+    /// (with their chunks, and the files indexed from inside them when they are archives)
+    pub async fn delete_version_documents(
+        &self,
+        repository: &str,
+        project: &str,
+        version: &str,
+        file_paths: Option<&[String]>,
+    ) -> Result<u64> {
+        let Some(query) = self.version_query(repository, project, version, file_paths)? else {
+            return Ok(0);
+        };
+        self.delete_and_commit(query, repository, project, version).await
+    }
+
+    /// Commit the documents written to a re-indexed version since `track_version`, deleting in the same commit
+    /// the other documents of the version (or of `file_paths` in it), which were not written again
+    pub async fn replace_version_documents(
+        &self,
+        repository: &str,
+        project: &str,
+        version: &str,
+        file_paths: Option<&[String]>,
+    ) -> Result<u64> {
+        use tantivy::query::{Occur, Query, TermSetQuery};
+
+        let written = self.untrack_version(repository, project, version);
+        let Some(query) = self.version_query(repository, project, version, file_paths)? else {
+            self.commit().await?;
+            return Ok(0);
+        };
+        // Deletions only apply to documents added before them, the written ones are excluded explicitly
+        let written = TermSetQuery::new(
+            written.iter().map(|file_id| Term::from_field_text(self.fields.file_id, &file_id.to_string())),
+        );
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, Box::new(query) as Box<dyn Query>),
+            (Occur::MustNot, Box::new(written)),
+        ]);
+        self.delete_and_commit(query, repository, project, version).await
+    }
+
+    /// Query matching the documents of a version, or of `file_paths` in it. `None` when `file_paths` is empty.
+    fn version_query(
+        &self,
+        repository: &str,
+        project: &str,
+        version: &str,
+        file_paths: Option<&[String]>,
+    ) -> Result<Option<BooleanQuery>> {
+        use tantivy::query::{Occur, Query};
+
+        let term_query = |field: Field, value: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(field, value),
+                IndexRecordOption::Basic,
+            ))
+        };
+        let mut clauses = vec![
+            (Occur::Must, term_query(self.fields.repository, repository)),
+            (Occur::Must, term_query(self.fields.project, project)),
+            (Occur::Must, term_query(self.fields.version, version)),
+        ];
+        if let Some(file_paths) = file_paths {
+            if file_paths.is_empty() {
+                return Ok(None);
+            }
+            let mut paths: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+            for file_path in file_paths {
+                // Archive entries are indexed under `<archive>!/<entry>`
+                let pattern = format!("{}(!/.*)?", regex::escape(file_path));
+                paths.push((
+                    Occur::Should,
+                    Box::new(RegexQuery::from_pattern(&pattern, self.fields.file_path_raw)?),
+                ));
+            }
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(paths))));
+        }
+        Ok(Some(BooleanQuery::new(clauses)))
+    }
+
+    async fn delete_and_commit(
+        &self,
+        query: BooleanQuery,
+        repository: &str,
+        project: &str,
+        version: &str,
+    ) -> Result<u64> {
+        let mut writer = self.writer.write().await;
+        let count = self.reader.searcher().search(&query, &Count)? as u64;
+        let _ = writer.delete_query(Box::new(query));
+        writer.commit()?;
+        self.reader.reload()?;
+
+        debug!(
+            "Deleted {} documents of version '{}' of project '{}' in repository '{}'",
+            count, version, project, repository
+        );
+        Ok(count)
+    }
+
     /// Update project name for all documents (used when repository is renamed)
     pub async fn update_project_name(&self, old_project: &str, new_project: &str) -> Result<u64> {
         let searcher = self.reader.searcher();
@@ -430,24 +888,42 @@ impl SearchService {
                 let version = doc.get_first(self.fields.version).and_then(|v| v.as_str()).unwrap_or_default();
                 let extension = doc.get_first(self.fields.extension).and_then(|v| v.as_str()).unwrap_or_default();
                 let size = doc.get_first(self.fields.size).and_then(|v| v.as_u64()).unwrap_or(content.len() as u64);
+                let last_modified = doc.get_first(self.fields.last_modified).and_then(|v| v.as_i64());
+                let language = doc.get_first(self.fields.language).and_then(|v| v.as_str());
+                let sections = stored_sections(&doc, self.fields.sections);
+                let line_offset = doc.get_first(self.fields.line_offset).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+                let is_truncated = doc.get_first(self.fields.is_truncated).and_then(|v| v.as_bool()).unwrap_or(false);
+                let is_lfs = doc.get_first(self.fields.is_lfs).and_then(|v| v.as_bool()).unwrap_or(false);
 
                 // Extract repository or use new_project as default
                 let repository = doc.get_first(self.fields.repository).and_then(|v| v.as_str()).unwrap_or(new_project);
 
+                let Ok(file_id) = Uuid::parse_str(file_id) else {
+                    warn!(
+                        "Skipping document with invalid file_id '{}' while renaming project",
+                        file_id
+                    );
+                    continue;
+                };
+
                 // Create new document with updated project name
-                let new_doc = doc!(
-                    self.fields.file_id => file_id,
-                    self.fields.file_name => file_name,
-                    self.fields.file_path => file_path,
-                    self.fields.content => content,
-                    self.fields.repository => repository,
-                    self.fields.project => new_project,
-                    self.fields.version => version,
-                    self.fields.extension => extension,
-                    self.fields.size => size,
-                    self.fields.file_name_raw => file_name,
-                    self.fields.file_path_raw => file_path,
-                );
+                let new_doc = self.build_document(&FileData {
+                    file_id,
+                    file_name,
+                    file_path,
+                    content,
+                    repository,
+                    project: new_project,
+                    version,
+                    extension,
+                    size,
+                    last_modified,
+                    language,
+                    sections: &sections,
+                    line_offset,
+                    is_truncated,
+                    is_lfs,
+                });
 
                 writer.add_document(new_doc)?;
             }
@@ -488,6 +964,8 @@ impl SearchService {
     }
 
     pub async fn search(&self, search_query: SearchQuery) -> Result<SearchResultsWithTotal> {
+        let search_query = search_query.extract_inline_filters();
+
         // Detect inefficient regex patterns (.*prefix or .*) that cause full index scans
         if search_query.regex_search {
             let pattern = search_query.query.trim();
@@ -517,10 +995,13 @@ impl SearchService {
         }
     }
 
-    // Blocking search implementation - runs in a dedicated thread pool
-    fn search_blocking(&self, search_query: SearchQuery) -> Result<SearchResultsWithTotal> {
-        let searcher = self.reader.searcher();
-
+    /// Build the query executed by a search: the text query (regex, fuzzy or normal mode)
+    /// combined with all filters
+    fn build_final_query(
+        &self,
+        search_query: &SearchQuery,
+        ranking: &RankingConfig,
+    ) -> Result<Box<dyn tantivy::query::Query>> {
         // Notes on search modes:
         // - regex_search and fuzzy_search are mutually exclusive (regex takes priority)
         // - RegexQuery may be slower than QueryParser, especially for complex patterns
@@ -605,6 +1086,11 @@ impl SearchService {
                 query_parser.set_field_fuzzy(self.fields.file_path, true, 1, true);
             }
 
+            // Field boosts from the ranking configuration (e.g. favor file names over content)
+            query_parser.set_field_boost(self.fields.content, ranking.content_boost);
+            query_parser.set_field_boost(self.fields.file_name, ranking.file_name_boost);
+            query_parser.set_field_boost(self.fields.file_path, ranking.file_path_boost);
+
             // Parse the main query
             // If parsing fails (e.g., invalid syntax like "string[abc]"), use EmptyQuery (no results)
             // This happens when user types incomplete regex patterns without activating regex mode
@@ -621,55 +1107,6 @@ impl SearchService {
             }
         };
 
-        // Create a separate query for snippet highlighting
-        // (SnippetGenerator doesn't work well with FuzzyTermQuery or RegexQuery - tantivy issue #867)
-        // For regex mode, extract simple terms from the pattern for snippet generation
-        let base_query_for_snippet = if search_query.regex_search {
-            // For regex, extract alphanumeric terms from pattern for highlighting
-            let simple_terms = extract_simple_terms_from_regex(&search_query.query);
-
-            if !simple_terms.is_empty() {
-                // Try to parse extracted terms with OR for better highlighting
-                let terms_query = simple_terms.join(" OR ");
-                let query_parser_for_snippet = QueryParser::for_index(
-                    &self.index,
-                    vec![self.fields.content, self.fields.file_name, self.fields.file_path],
-                );
-
-                match query_parser_for_snippet.parse_query(&terms_query) {
-                    Ok(q) => {
-                        debug!("Using extracted terms for snippet: {}", terms_query);
-                        q
-                    }
-                    Err(_) => {
-                        // Fallback if extraction fails
-                        debug!("Failed to parse extracted terms, using AllQuery");
-                        Box::new(tantivy::query::AllQuery)
-                    }
-                }
-            } else {
-                // No terms extracted - use AllQuery
-                debug!("No simple terms extracted from regex pattern, using AllQuery");
-                Box::new(tantivy::query::AllQuery)
-            }
-        } else {
-            // For normal/fuzzy search, use the original query as-is
-            let query_parser_for_snippet = QueryParser::for_index(
-                &self.index,
-                vec![self.fields.content, self.fields.file_name, self.fields.file_path],
-            );
-            query_parser_for_snippet
-                .parse_query(&search_query.query)
-                .unwrap_or_else(|_| Box::new(tantivy::query::AllQuery))
-        };
-
-        // Create snippet generator once for the entire search (using non-fuzzy query)
-        let snippet_generator = if search_query.limit > 0 {
-            Some(self.create_snippet_generator(&searcher, &*base_query_for_snippet)?)
-        } else {
-            None
-        };
-
         // Build filter queries if filters are provided
         let mut filter_queries = Vec::new();
 
@@ -765,6 +1202,20 @@ impl SearchService {
             }
         }
 
+        // Handle language filters (supports comma-separated multi-select and aliases)
+        if let Some(language_filter) = &search_query.language_filter {
+            let mut language_clauses = Vec::new();
+            for language_value in language_filter.split(',').filter(|s| !s.trim().is_empty()) {
+                let term = Term::from_field_text(self.fields.language, &canonical_language(language_value));
+                let term_query = Box::new(TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic))
+                    as Box<dyn tantivy::query::Query>;
+                language_clauses.push((tantivy::query::Occur::Should, term_query));
+            }
+            if !language_clauses.is_empty() {
+                filter_queries.push(Box::new(BooleanQuery::new(language_clauses)) as Box<dyn tantivy::query::Query>);
+            }
+        }
+
         // Handle size filters (range queries)
         if search_query.min_size.is_some() || search_query.max_size.is_some() {
             use std::ops::Bound;
@@ -782,29 +1233,130 @@ impl SearchService {
             filter_queries.push(Box::new(size_range_query) as Box<dyn tantivy::query::Query>);
         }
 
+        // Handle path filter (the facet term matches the whole subtree)
+        if let Some(path_filter) = &search_query.path_filter {
+            let directory = normalize_directory(path_filter);
+            if !directory.is_empty() {
+                let term = Term::from_facet(self.fields.path_facet, &directory_facet(&directory));
+                filter_queries.push(
+                    Box::new(TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic))
+                        as Box<dyn tantivy::query::Query>,
+                );
+            }
+        }
+
+        // Generated files are excluded unless the search includes them
+        let generated_exclusion = (!search_query.include_generated).then(|| {
+            let term = Term::from_field_bool(self.fields.is_generated, true);
+            Box::new(TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic)) as Box<dyn tantivy::query::Query>
+        });
+
         // Combine base query with filters using BooleanQuery if we have filters
-        let final_query: Box<dyn tantivy::query::Query> = if !filter_queries.is_empty() {
+        let final_query: Box<dyn tantivy::query::Query> = if !filter_queries.is_empty() || generated_exclusion.is_some()
+        {
             let mut clauses = vec![(tantivy::query::Occur::Must, base_query)];
             for filter in filter_queries {
                 clauses.push((tantivy::query::Occur::Must, filter));
             }
+            if let Some(exclusion) = generated_exclusion {
+                clauses.push((tantivy::query::Occur::MustNot, exclusion));
+            }
             Box::new(BooleanQuery::new(clauses))
         } else {
             base_query
         };
 
+        Ok(final_query)
+    }
+
+    // Blocking search implementation - runs in a dedicated thread pool
+    fn search_blocking(&self, search_query: SearchQuery) -> Result<SearchResultsWithTotal> {
+        let searcher = self.reader.searcher();
+        let ranking = self.ranking_config();
+
+        let final_query = self.build_final_query(&search_query, &ranking)?;
+
+        // Create a separate query for snippet highlighting
+        // (SnippetGenerator doesn't work well with FuzzyTermQuery or RegexQuery - tantivy issue #867)
+        // For regex mode, extract simple terms from the pattern for snippet generation
+        let base_query_for_snippet = if search_query.regex_search {
+            // For regex, extract alphanumeric terms from pattern for highlighting
+            let simple_terms = extract_simple_terms_from_regex(&search_query.query);
+
+            if !simple_terms.is_empty() {
+                // Try to parse extracted terms with OR for better highlighting
+                let terms_query = simple_terms.join(" OR ");
+                let query_parser_for_snippet = QueryParser::for_index(
+                    &self.index,
+                    vec![self.fields.content, self.fields.file_name, self.fields.file_path],
+                );
+
+                match query_parser_for_snippet.parse_query(&terms_query) {
+                    Ok(q) => {
+                        debug!("Using extracted terms for snippet: {}", terms_query);
+                        q
+                    }
+                    Err(_) => {
+                        // Fallback if extraction fails
+                        debug!("Failed to parse extracted terms, using AllQuery");
+                        Box::new(tantivy::query::AllQuery)
+                    }
+                }
+            } else {
+                // No terms extracted - use AllQuery
+                debug!("No simple terms extracted from regex pattern, using AllQuery");
+                Box::new(tantivy::query::AllQuery)
+            }
+        } else {
+            // For normal/fuzzy search, use the original query as-is
+            let query_parser_for_snippet = QueryParser::for_index(
+                &self.index,
+                vec![self.fields.content, self.fields.file_name, self.fields.file_path],
+            );
+            query_parser_for_snippet
+                .parse_query(&search_query.query)
+                .unwrap_or_else(|_| Box::new(tantivy::query::AllQuery))
+        };
+
+        // Create snippet generator once for the entire search (using non-fuzzy query)
+        let snippet_generator = if search_query.limit > 0 {
+            Some(self.create_snippet_generator(&searcher, &*base_query_for_snippet)?)
+        } else {
+            None
+        };
+
         // For performance with large indices, use Count collector for total
         let total = searcher.search(&final_query, &Count)? as u64;
 
         // Ensure limit is at least 1 to avoid Tantivy panic
         let effective_limit = if search_query.limit == 0 { 1 } else { search_query.limit };
 
-        // Execute search with pagination
-        let top_docs = searcher.search(
+        // Execute search with pagination: by relevance (BM25 adjusted with the ranking configuration)
+        // or by fast-field values when a sort field is requested, resuming after the cursor if given
+        search_query.validate_cursor()?;
+        let (sort_by, sort_order) = search_query.effective_sort();
+        let after = search_query.search_after.as_deref().map(SearchCursor::decode).transpose()?.map(|c| c.key);
+        let default_branches = Arc::new(self.default_branches.read().unwrap_or_else(|e| e.into_inner()).clone());
+        let (top_docs, last_key) = top_docs_page(
+            &searcher,
             &final_query,
-            &TopDocs::with_limit(effective_limit).and_offset(search_query.offset),
+            ranking,
+            default_branches,
+            sort_by,
+            sort_order,
+            effective_limit,
+            search_query.offset,
+            after,
         )?;
 
+        // A full page may be followed by more hits
+        let next_cursor = match last_key {
+            Some(key) if search_query.limit > 0 && top_docs.len() == search_query.limit => {
+                Some(SearchCursor { sort_by, sort_order, key }.encode()?)
+            }
+            _ => None,
+        };
+
         let mut results = Vec::new();
 
         // Only process results if limit > 0 (for facets-only searches, we don't need results)
@@ -812,57 +1364,14 @@ impl SearchService {
             for (score, doc_address) in top_docs {
                 let retrieved_doc = searcher.doc::<tantivy::TantivyDocument>(doc_address)?;
 
-                let file_id_str = retrieved_doc
-                    .get_first(self.fields.file_id)
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| anyhow!("Missing file_id in search result"))?;
-
-                let file_id = Uuid::parse_str(file_id_str)
-                    .map_err(|_| anyhow!("Invalid UUID format in file_id: {}", file_id_str))?;
-
-                let file_name =
-                    retrieved_doc.get_first(self.fields.file_name).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let file_path =
-                    retrieved_doc.get_first(self.fields.file_path).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let repository =
-                    retrieved_doc.get_first(self.fields.repository).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let project =
-                    retrieved_doc.get_first(self.fields.project).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let version =
-                    retrieved_doc.get_first(self.fields.version).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let extension =
-                    retrieved_doc.get_first(self.fields.extension).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                // No need for post-query filtering anymore since we're using query-time filters
-
                 // Generate content snippet and extract line number
-                let (content_snippet, line_number) = if let Some(ref generator) = snippet_generator {
+                let snippet = if let Some(ref generator) = snippet_generator {
                     self.generate_optimized_snippet(generator, &search_query.query, &retrieved_doc)?
                 } else {
-                    ("".to_string(), None)
+                    ContentSnippet::full(String::new())
                 };
 
-                // Format DocAddress as "segment_ord:doc_id"
-                let doc_address_str = format!("{}:{}", doc_address.segment_ord, doc_address.doc_id);
-
-                results.push(SearchResult {
-                    file_id,
-                    doc_address: doc_address_str,
-                    file_name,
-                    file_path,
-                    content_snippet,
-                    repository,
-                    project,
-                    version,
-                    extension,
-                    score,
-                    line_number,
-                });
+                results.push(self.search_result_from_doc(&retrieved_doc, doc_address, score, snippet)?);
             }
         }
 
@@ -873,7 +1382,7 @@ impl SearchService {
             None
         };
 
-        Ok(SearchResultsWithTotal { results, total, facets })
+        Ok(SearchResultsWithTotal { results, total, facets, next_cursor })
     }
 
     fn create_snippet_generator(
@@ -891,11 +1400,11 @@ impl SearchService {
         generator: &SnippetGenerator,
         query: &str,
         doc: &tantivy::TantivyDocument,
-    ) -> Result<(String, Option<u32>)> {
-        // Generate the snippet with HTML highlighting using Tantivy's SnippetGenerator
+    ) -> Result<ContentSnippet> {
+        // Generate the snippet and its highlighted ranges using Tantivy's SnippetGenerator
         // The snippet generator uses the non-fuzzy query, so it can properly highlight matches
         let snippet = generator.snippet_from_doc(doc);
-        let highlighted_html = snippet.to_html();
+        let content = doc.get_first(self.fields.content).and_then(|v| v.as_str()).unwrap_or("");
 
         // Extract clean search terms from query for line number calculation (remove fuzzy/wildcard chars)
         let clean_terms: Vec<String> = query
@@ -910,36 +1419,29 @@ impl SearchService {
             .collect();
 
         // If snippet is empty (no matches found by generator), fallback to raw content excerpt
-        if highlighted_html.trim().is_empty()
-            && let Some(content) = doc.get_first(self.fields.content).and_then(|v| v.as_str())
-        {
-            // Return first 400 chars without HTML highlighting (better than nothing)
+        if snippet.fragment().trim().is_empty() {
+            // Return first 400 chars without highlighting (better than nothing)
             // Use chars() to properly handle UTF-8 multi-byte characters (e.g., é, ü, 中)
-            let excerpt = if content.chars().count() > 400 {
-                let truncated: String = content.chars().take(400).collect();
-                format!("{}...", truncated.replace('<', "&lt;").replace('>', "&gt;"))
-            } else {
-                content.replace('<', "&lt;").replace('>', "&gt;")
-            };
-            return Ok((excerpt, None));
+            let excerpt: String = content.chars().take(400).collect();
+            return Ok(ContentSnippet::full(excerpt));
         }
 
         // For line number, use a simple approach to avoid scanning the entire content
-        let line_number = if let Some(content) = doc.get_first(self.fields.content).and_then(|v| v.as_str()) {
-            if let Some(first_term) = clean_terms.first() {
-                // Search for first term (case-insensitive)
-                content
-                    .to_lowercase()
-                    .find(first_term)
-                    .map(|pos| content[..pos].chars().filter(|&c| c == '\n').count() as u32 + 1)
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-
-        Ok((highlighted_html, line_number))
+        let line_number = clean_terms.first().and_then(|first_term| {
+            // Search for first term (case-insensitive)
+            find_case_insensitive(content, first_term).map(|pos| line_at(content, pos))
+        });
+
+        // Tantivy does not expose the fragment offset, but the fragment is a verbatim slice of the content
+        let fragment = snippet.fragment();
+        let fragment_start_line = content.find(fragment).map(|pos| line_at(content, pos));
+
+        Ok(ContentSnippet {
+            fragment: fragment.to_string(),
+            highlights: highlight_ranges(fragment, snippet.highlighted()),
+            fragment_start_line,
+            line_number,
+        })
     }
 
     #[allow(dead_code)]
@@ -970,64 +1472,22 @@ impl SearchService {
     }
 
     pub async fn get_file_by_doc_address(&self, doc_address_str: &str) -> Result<Option<SearchResult>> {
-        // Parse "segment_ord:doc_id" format
-        let parts: Vec<&str> = doc_address_str.split(':').collect();
-        if parts.len() != 2 {
-            return Err(anyhow!("Invalid doc_address format, expected 'segment_ord:doc_id'"));
-        }
-
-        let segment_ord: u32 =
-            parts[0].parse().map_err(|_| anyhow!("Invalid segment_ord in doc_address: {}", parts[0]))?;
-        let doc_id: u32 = parts[1].parse().map_err(|_| anyhow!("Invalid doc_id in doc_address: {}", parts[1]))?;
-
-        let doc_address = tantivy::DocAddress::new(segment_ord, doc_id);
+        let doc_address = parse_doc_address(doc_address_str)?;
         let searcher = self.reader.searcher();
 
         // Try to get the document directly using DocAddress
         match searcher.doc::<tantivy::TantivyDocument>(doc_address) {
             Ok(retrieved_doc) => {
-                let file_id_str = retrieved_doc
-                    .get_first(self.fields.file_id)
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| anyhow!("Missing file_id in document"))?;
-
-                let file_id = Uuid::parse_str(file_id_str)
-                    .map_err(|_| anyhow!("Invalid UUID format in file_id: {}", file_id_str))?;
-
-                let file_name =
-                    retrieved_doc.get_first(self.fields.file_name).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let file_path =
-                    retrieved_doc.get_first(self.fields.file_path).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
+                // Return full content instead of snippet
                 let content =
                     retrieved_doc.get_first(self.fields.content).and_then(|v| v.as_str()).unwrap_or("").to_string();
 
-                let repository =
-                    retrieved_doc.get_first(self.fields.repository).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let project =
-                    retrieved_doc.get_first(self.fields.project).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let version =
-                    retrieved_doc.get_first(self.fields.version).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                let extension =
-                    retrieved_doc.get_first(self.fields.extension).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-                Ok(Some(SearchResult {
-                    file_id,
-                    doc_address: doc_address_str.to_string(),
-                    file_name,
-                    file_path,
-                    content_snippet: content,
-                    repository,
-                    project,
-                    version,
-                    extension,
-                    score: 1.0,
-                    line_number: None,
-                }))
+                Ok(Some(self.search_result_from_doc(
+                    &retrieved_doc,
+                    doc_address,
+                    1.0,
+                    ContentSnippet::full(content),
+                )?))
             }
             Err(_) => {
                 // Document not found at this address
@@ -1053,43 +1513,16 @@ impl SearchService {
 
             debug!("Found matching document for file_id: {}", file_id);
 
-            let file_name =
-                retrieved_doc.get_first(self.fields.file_name).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-            let file_path =
-                retrieved_doc.get_first(self.fields.file_path).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
+            // Return full content instead of snippet
             let content =
                 retrieved_doc.get_first(self.fields.content).and_then(|v| v.as_str()).unwrap_or("").to_string();
 
-            let repository =
-                retrieved_doc.get_first(self.fields.repository).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-            let project =
-                retrieved_doc.get_first(self.fields.project).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-            let version =
-                retrieved_doc.get_first(self.fields.version).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-            let extension =
-                retrieved_doc.get_first(self.fields.extension).and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-            // Format DocAddress as "segment_ord:doc_id"
-            let doc_address_str = format!("{}:{}", doc_address.segment_ord, doc_address.doc_id);
-
-            return Ok(Some(SearchResult {
-                file_id,
-                doc_address: doc_address_str,
-                file_name,
-                file_path,
-                content_snippet: content, // Return full content instead of snippet
-                repository,
-                project,
-                version,
-                extension,
-                score: *score,
-                line_number: None,
-            }));
+            return Ok(Some(self.search_result_from_doc(
+                &retrieved_doc,
+                *doc_address,
+                *score,
+                ContentSnippet::full(content),
+            )?));
         }
 
         // If no matching document found
@@ -1097,6 +1530,416 @@ impl SearchService {
         Ok(None)
     }
 
+    /// Find files with similar content to a given document, across all repositories.
+    ///
+    /// Uses Tantivy's `MoreLikeThisQuery` on the content field of the source document.
+    /// The source document itself is never returned. Returns `None` if the source
+    /// document cannot be found.
+    pub async fn find_similar(&self, similar_query: SimilarQuery) -> Result<Option<SearchResultsWithTotal>> {
+        let service = self.clone();
+        let similar_future = tokio::task::spawn_blocking(move || service.find_similar_blocking(similar_query));
+
+        match tokio::time::timeout(SEARCH_TIMEOUT, similar_future).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(anyhow!("Similar files thread panicked: {}", e)),
+            Err(_) => Err(anyhow!(
+                "Similar files timeout: query took longer than {} seconds",
+                SEARCH_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    fn find_similar_blocking(&self, similar_query: SimilarQuery) -> Result<Option<SearchResultsWithTotal>> {
+        use tantivy::query::Occur;
+
+        let searcher = self.reader.searcher();
+
+        // Resolve the source document
+        let source_address = if let Some(ref doc_address_str) = similar_query.doc_address {
+            Some(parse_doc_address(doc_address_str)?)
+        } else if let Some(file_id) = similar_query.file_id {
+            let term = Term::from_field_text(self.fields.file_id, &file_id.to_string());
+            let query = TermQuery::new(term, IndexRecordOption::Basic);
+            searcher.search(&query, &TopDocs::with_limit(1))?.first().map(|(_, address)| *address)
+        } else {
+            return Err(anyhow!("Either file_id or doc_address is required"));
+        };
+
+        let Some(source_address) = source_address else {
+            return Ok(None);
+        };
+        let Ok(source_doc) = searcher.doc::<tantivy::TantivyDocument>(source_address) else {
+            return Ok(None);
+        };
+
+        let source_file_id = source_doc.get_first(self.fields.file_id).and_then(|v| v.as_str()).unwrap_or("");
+        let source_project = source_doc.get_first(self.fields.project).and_then(|v| v.as_str()).unwrap_or("");
+        let source_path = source_doc.get_first(self.fields.file_path).and_then(|v| v.as_str()).unwrap_or("");
+        let source_content = source_doc.get_first(self.fields.content).and_then(|v| v.as_str()).unwrap_or("");
+
+        debug!(
+            "Finding files similar to '{}' (project='{}', file_id='{}')",
+            source_path, source_project, source_file_id
+        );
+
+        // Only use the content field: the stored metadata fields (repository, version, ...)
+        // would otherwise make files of the same repository look similar
+        let mlt_query = MoreLikeThisQuery::builder()
+            .with_min_doc_frequency(SIMILAR_MIN_DOC_FREQUENCY)
+            .with_min_term_frequency(SIMILAR_MIN_TERM_FREQUENCY)
+            .with_min_word_length(SIMILAR_MIN_WORD_LENGTH)
+            .with_max_query_terms(SIMILAR_MAX_QUERY_TERMS)
+            .with_document_fields(vec![(
+                self.fields.content,
+                vec![tantivy::schema::OwnedValue::Str(source_content.to_string())],
+            )]);
+
+        let mut clauses: Vec<(Occur, Box<dyn tantivy::query::Query>)> = vec![(Occur::Must, Box::new(mlt_query))];
+
+        // Never return the source document itself
+        clauses.push((
+            Occur::MustNot,
+            Box::new(TermQuery::new(
+                Term::from_field_text(self.fields.file_id, source_file_id),
+                IndexRecordOption::Basic,
+            )),
+        ));
+
+        // Optionally skip the same file on other branches of the same project
+        if similar_query.exclude_same_path {
+            let same_path_query = BooleanQuery::new(vec![
+                (
+                    Occur::Must,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(self.fields.project, source_project),
+                        IndexRecordOption::Basic,
+                    )) as Box<dyn tantivy::query::Query>,
+                ),
+                (
+                    Occur::Must,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(self.fields.file_path_raw, source_path),
+                        IndexRecordOption::Basic,
+                    )) as Box<dyn tantivy::query::Query>,
+                ),
+            ]);
+            clauses.push((Occur::MustNot, Box::new(same_path_query)));
+        }
+
+        let final_query = BooleanQuery::new(clauses);
+
+        // MoreLikeThisQuery requires scoring, so count together with TopDocs instead of a bare Count
+        let effective_limit = if similar_query.limit == 0 { 1 } else { similar_query.limit };
+        let (top_docs, total) = searcher.search(&final_query, &(TopDocs::with_limit(effective_limit), Count))?;
+
+        // MoreLikeThisQuery has no highlightable terms, so snippets fall back to the file excerpt
+        let snippet_generator = self.create_snippet_generator(&searcher, &final_query)?;
+
+        let mut results = Vec::new();
+        for (score, doc_address) in top_docs {
+            let retrieved_doc = searcher.doc::<tantivy::TantivyDocument>(doc_address)?;
+            let snippet = self.generate_optimized_snippet(&snippet_generator, "", &retrieved_doc)?;
+            results.push(self.search_result_from_doc(&retrieved_doc, doc_address, score, snippet)?);
+        }
+
+        Ok(Some(SearchResultsWithTotal {
+            results,
+            total: total as u64,
+            facets: None,
+            next_cursor: None,
+        }))
+    }
+
+    /// Explain how a search scores one document, for ranking and query parser debugging
+    pub async fn explain(&self, search_query: SearchQuery, doc_address: String) -> Result<Option<SearchExplanation>> {
+        let search_query = search_query.extract_inline_filters();
+        let service = self.clone();
+        let explain_future = tokio::task::spawn_blocking(move || service.explain_blocking(&search_query, &doc_address));
+
+        match tokio::time::timeout(SEARCH_TIMEOUT, explain_future).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(anyhow!("Explain thread panicked: {}", e)),
+            Err(_) => Err(anyhow!(
+                "Explain timeout: query took longer than {} seconds",
+                SEARCH_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    fn explain_blocking(&self, search_query: &SearchQuery, doc_address_str: &str) -> Result<Option<SearchExplanation>> {
+        let doc_address = parse_doc_address(doc_address_str)?;
+        let searcher = self.reader.searcher();
+        let ranking = self.ranking_config();
+
+        let Ok(doc) = searcher.doc::<tantivy::TantivyDocument>(doc_address) else {
+            return Ok(None);
+        };
+        let get_text = |field: Field| doc.get_first(field).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        let (file_path, version) = (get_text(self.fields.file_path), get_text(self.fields.version));
+        let default_branch = self.default_branch(&get_text(self.fields.project));
+
+        // Tantivy's explain can panic on non-matching documents for some composite queries,
+        // so only explain queries that match this document, pinned to it by file_id
+        let document_term = Term::from_field_text(self.fields.file_id, &get_text(self.fields.file_id));
+        let matches_document = |query: &dyn tantivy::query::Query| -> bool {
+            let pinned = BooleanQuery::new(vec![
+                (tantivy::query::Occur::Must, query.box_clone()),
+                (
+                    tantivy::query::Occur::Must,
+                    Box::new(TermQuery::new(document_term.clone(), IndexRecordOption::Basic)),
+                ),
+            ]);
+            searcher.search(&pinned, &Count).is_ok_and(|count| count > 0)
+        };
+
+        let final_query = self.build_final_query(search_query, &ranking)?;
+        let explanation = if matches_document(final_query.as_ref()) {
+            Some(final_query.explain(&searcher, doc_address)?)
+        } else {
+            None
+        };
+        let bm25_score = explanation.as_ref().map(|e| e.value());
+        let ranking_multiplier = ranking.score_multiplier(&version, default_branch.as_deref(), &file_path);
+
+        // Check each searchable field on its own against the text query (filters excluded)
+        let field_candidates = if search_query.regex_search {
+            [
+                ("file_name", self.fields.file_name_raw),
+                ("file_path", self.fields.file_path_raw),
+                ("content", self.fields.content),
+            ]
+        } else {
+            [
+                ("file_name", self.fields.file_name),
+                ("file_path", self.fields.file_path),
+                ("content", self.fields.content),
+            ]
+        };
+        let mut matched_fields = Vec::new();
+        for (name, field) in field_candidates {
+            let field_query: Option<Box<dyn tantivy::query::Query>> = if search_query.regex_search {
+                let pattern = build_regex_pattern(&search_query.query, search_query.regex_flags.as_deref());
+                RegexQuery::from_pattern(&pattern, field).ok().map(|q| Box::new(q) as Box<dyn tantivy::query::Query>)
+            } else {
+                let mut parser = QueryParser::for_index(&self.index, vec![field]);
+                if search_query.fuzzy_search {
+                    parser.set_field_fuzzy(field, true, 1, true);
+                }
+                parser.parse_query(&search_query.query).ok()
+            };
+
+            if field_query.is_some_and(|q| matches_document(q.as_ref())) {
+                matched_fields.push(name.to_string());
+            }
+        }
+
+        Ok(Some(SearchExplanation {
+            doc_address: doc_address_str.to_string(),
+            project: get_text(self.fields.project),
+            matches: explanation.is_some(),
+            parsed_query: format!("{:?}", final_query),
+            explanation: explanation.map(|e| serde_json::to_value(&e)).transpose()?,
+            matched_fields,
+            bm25_score,
+            ranking_multiplier,
+            final_score: bm25_score.map(|score| score * ranking_multiplier),
+            file_path,
+            version,
+        }))
+    }
+
+    /// Current ranking configuration
+    pub fn ranking_config(&self) -> RankingConfig {
+        self.ranking.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Where the index was moved when it had an outdated schema at startup. The new index starts empty,
+    /// so every repository must be crawled again.
+    pub fn outdated_index_dir(&self) -> Option<&Path> {
+        self.outdated_index_dir.as_deref()
+    }
+
+    /// Delete the index moved aside at startup, once every repository was crawled again into the new index
+    pub fn remove_outdated_index(&self) -> Result<()> {
+        if let Some(outdated_index_dir) = &self.outdated_index_dir {
+            std::fs::remove_dir_all(outdated_index_dir)
+                .map_err(|e| anyhow!("Failed to delete {:?}: {}", outdated_index_dir, e))?;
+            tracing::info!("Deleted the previous search index at {:?}", outdated_index_dir);
+        }
+        Ok(())
+    }
+
+    /// Record the default branch of a project, boosted by the ranking. Projects without one fall back to
+    /// the default branch names of the ranking configuration.
+    pub fn set_default_branch(&self, project: &str, branch: Option<&str>) {
+        let mut default_branches = self.default_branches.write().unwrap_or_else(|e| e.into_inner());
+        match branch.map(str::trim).filter(|branch| !branch.is_empty()) {
+            Some(branch) => default_branches.insert(project.to_string(), branch.to_string()),
+            None => default_branches.remove(project),
+        };
+    }
+
+    /// Default branch of a project, when known
+    pub fn default_branch(&self, project: &str) -> Option<String> {
+        self.default_branches.read().unwrap_or_else(|e| e.into_inner()).get(project).cloned()
+    }
+
+    /// Replace the ranking configuration used by subsequent searches
+    pub fn set_ranking_config(&self, config: RankingConfig) {
+        *self.ranking.write().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    /// List the subdirectories and files of one directory of an indexed repository
+    pub async fn browse_tree(&self, tree_query: TreeQuery) -> Result<RepositoryTree> {
+        let service = self.clone();
+        let tree_future = tokio::task::spawn_blocking(move || service.browse_tree_blocking(tree_query));
+
+        match tokio::time::timeout(SEARCH_TIMEOUT, tree_future).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(anyhow!("Tree browsing thread panicked: {}", e)),
+            Err(_) => Err(anyhow!(
+                "Tree browsing timeout: query took longer than {} seconds",
+                SEARCH_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    fn browse_tree_blocking(&self, tree_query: TreeQuery) -> Result<RepositoryTree> {
+        use tantivy::collector::FacetCollector;
+        use tantivy::query::Occur;
+
+        let searcher = self.reader.searcher();
+        let directory = normalize_directory(&tree_query.path);
+        let parent = directory_facet(&directory);
+
+        let term_query =
+            |term: Term| Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn tantivy::query::Query>;
+        let scope_clauses = || {
+            let mut clauses = vec![
+                (
+                    Occur::Must,
+                    term_query(Term::from_field_text(self.fields.repository, &tree_query.repository)),
+                ),
+                (
+                    Occur::Must,
+                    term_query(Term::from_field_text(self.fields.version, &tree_query.version)),
+                ),
+            ];
+            if let Some(ref project) = tree_query.project {
+                clauses.push((
+                    Occur::Must,
+                    term_query(Term::from_field_text(self.fields.project, project)),
+                ));
+            }
+            clauses
+        };
+
+        // Subdirectories: children of the requested directory in the path facet
+        let mut directory_clauses = scope_clauses();
+        directory_clauses.push((
+            Occur::Must,
+            term_query(Term::from_facet(self.fields.path_facet, &parent)),
+        ));
+        let mut collector = FacetCollector::for_field("path_facet");
+        collector.add_facet(parent.clone());
+        let counts = searcher.search(&BooleanQuery::new(directory_clauses), &collector)?;
+
+        let mut directories: Vec<TreeDirectory> = counts
+            .get(parent)
+            .map(|(facet, file_count)| {
+                let path = facet_to_directory(facet);
+                let name = path.rsplit('/').next().unwrap_or_default().to_string();
+                TreeDirectory { name, path, file_count }
+            })
+            .collect();
+        directories.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Files: documents whose parent directory is exactly the requested one
+        let mut file_clauses = scope_clauses();
+        file_clauses.push((
+            Occur::Must,
+            term_query(Term::from_field_text(self.fields.directory, &directory)),
+        ));
+        let top_docs = searcher.search(&BooleanQuery::new(file_clauses), &TopDocs::with_limit(TREE_FILES_LIMIT))?;
+
+        let mut files = Vec::with_capacity(top_docs.len());
+        for (_score, doc_address) in top_docs {
+            let doc: tantivy::TantivyDocument = searcher.doc(doc_address)?;
+            let result = self.search_result_from_doc(&doc, doc_address, 0.0, ContentSnippet::full(String::new()))?;
+            let size = doc.get_first(self.fields.size).and_then(|v| v.as_u64()).unwrap_or(0);
+
+            files.push(TreeFile {
+                file_id: result.file_id,
+                doc_address: result.doc_address,
+                name: result.file_name,
+                path: result.file_path,
+                project: result.project,
+                version: result.version,
+                size,
+            });
+        }
+        files.sort_by(|a, b| a.name.cmp(&b.name).then(a.project.cmp(&b.project)));
+
+        Ok(RepositoryTree { path: directory, directories, files })
+    }
+
+    /// Build a `SearchResult` from a stored document
+    fn search_result_from_doc(
+        &self,
+        doc: &tantivy::TantivyDocument,
+        doc_address: tantivy::DocAddress,
+        score: f32,
+        snippet: ContentSnippet,
+    ) -> Result<SearchResult> {
+        let file_id_str = doc
+            .get_first(self.fields.file_id)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing file_id in document"))?;
+
+        let file_id =
+            Uuid::parse_str(file_id_str).map_err(|_| anyhow!("Invalid UUID format in file_id: {}", file_id_str))?;
+
+        let get_text = |field: Field| doc.get_first(field).and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+        // Lines of chunks of large files are numbered from the start of the file
+        let line_offset = doc.get_first(self.fields.line_offset).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let line_number = snippet.line_number.map(|line| line + line_offset);
+        let fragment_start_line = snippet.fragment_start_line.map(|line| line + line_offset);
+
+        // Extracted documents: the part containing the match (or the fragment)
+        let source_location = line_number.or(fragment_start_line).and_then(|line| {
+            stored_sections(doc, self.fields.sections)
+                .into_iter()
+                .take_while(|section| section.line <= line)
+                .last()
+                .map(|section| section.label)
+        });
+
+        Ok(SearchResult {
+            file_id,
+            doc_address: format!("{}:{}", doc_address.segment_ord, doc_address.doc_id),
+            file_name: get_text(self.fields.file_name),
+            file_path: get_text(self.fields.file_path),
+            content_snippet: snippet.fragment,
+            highlights: snippet.highlights,
+            fragment_start_line,
+            repository: get_text(self.fields.repository),
+            project: get_text(self.fields.project),
+            version: get_text(self.fields.version),
+            extension: get_text(self.fields.extension),
+            language: get_text(self.fields.language),
+            is_generated: doc.get_first(self.fields.is_generated).and_then(|v| v.as_bool()).unwrap_or(false),
+            score,
+            line_number,
+            source_location,
+            line_offset,
+            is_truncated: doc.get_first(self.fields.is_truncated).and_then(|v| v.as_bool()).unwrap_or(false),
+            is_lfs: doc.get_first(self.fields.is_lfs).and_then(|v| v.as_bool()).unwrap_or(false),
+            size: doc.get_first(self.fields.size).and_then(|v| v.as_u64()).unwrap_or(0),
+            last_modified: doc.get_first(self.fields.last_modified).and_then(|v| v.as_i64()),
+        })
+    }
+
     pub fn get_document_count(&self) -> Result<u64> {
         // Reload the reader to see the latest changes
         self.reader.reload()?;
@@ -1168,6 +2011,7 @@ impl SearchService {
         let mut project_counts: HashMap<String, u64> = HashMap::new();
         let mut version_counts: HashMap<String, u64> = HashMap::new();
         let mut extension_counts: HashMap<String, u64> = HashMap::new();
+        let mut language_counts: HashMap<String, u64> = HashMap::new();
 
         // Count facets from all documents in the index
         for (_score, doc_address) in top_docs {
@@ -1202,6 +2046,13 @@ impl SearchService {
             {
                 *extension_counts.entry(extension.to_string()).or_insert(0) += 1;
             }
+
+            // Count language facets
+            if let Some(language) = doc.get_first(self.fields.language).and_then(|v| v.as_str())
+                && !language.is_empty()
+            {
+                *language_counts.entry(language.to_string()).or_insert(0) += 1;
+            }
         }
 
         // Sort and limit to top 50 each for UI performance
@@ -1221,10 +2072,23 @@ impl SearchService {
         extensions.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
         extensions.truncate(50);
 
+        let mut languages: Vec<(String, u64)> = language_counts.into_iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        languages.truncate(50);
+
         // For size ranges in legacy method, return empty since this is not commonly used
         let size_ranges = Vec::new();
 
-        Ok(SearchFacets { repositories, projects, versions, extensions, size_ranges })
+        Ok(SearchFacets {
+            repositories,
+            projects,
+            versions,
+            extensions,
+            languages,
+            size_ranges,
+            directories: Vec::new(),
+            generated: 0,
+        })
     }
 
     /// Collect facets using Tantivy native aggregations API
@@ -1232,19 +2096,55 @@ impl SearchService {
     fn collect_facets_from_search_results(
         &self,
         searcher: &tantivy::Searcher,
-        _query: &dyn tantivy::query::Query,
+        final_query: &dyn tantivy::query::Query,
         search_query: &SearchQuery,
     ) -> Result<SearchFacets> {
         use tantivy::aggregation::AggregationCollector;
         use tantivy::aggregation::agg_req::Aggregations;
         use tantivy::aggregation::agg_result::AggregationResults;
+        use tantivy::collector::FacetCollector;
         use tantivy::query::{AllQuery, BooleanQuery, Occur, QueryParser, TermQuery};
 
+        // The path filter restricts every facet, like the text query does
+        let path_directory = search_query.path_filter.as_deref().map(normalize_directory).unwrap_or_default();
+        let path_query = (!path_directory.is_empty()).then(|| {
+            TermQuery::new(
+                Term::from_facet(self.fields.path_facet, &directory_facet(&path_directory)),
+                tantivy::schema::IndexRecordOption::Basic,
+            )
+        });
+
+        // Language filter (values are normalized to canonical language ids)
+        let language_query = search_query.language_filter.as_deref().and_then(|language_filter| {
+            let language_clauses: Vec<(Occur, Box<dyn tantivy::query::Query>)> = language_filter
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|language| {
+                    let term = Term::from_field_text(self.fields.language, &canonical_language(language));
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic))
+                            as Box<dyn tantivy::query::Query>,
+                    )
+                })
+                .collect();
+            (!language_clauses.is_empty()).then(|| BooleanQuery::from(language_clauses))
+        });
+
+        // Generated files are left out of every facet unless the search includes them
+        let generated_query = TermQuery::new(
+            Term::from_field_bool(self.fields.is_generated, true),
+            tantivy::schema::IndexRecordOption::Basic,
+        );
+        let exclude_generated = !search_query.include_generated;
+
         // Helper to build query with specific filters
         let build_query_with_filters = |include_repository: bool,
                                         include_project: bool,
                                         include_version: bool,
-                                        include_extension: bool|
+                                        include_extension: bool,
+                                        include_language: bool,
+                                        include_generated_filter: bool|
          -> Result<Box<dyn tantivy::query::Query>> {
             let mut clauses = vec![];
 
@@ -1398,6 +2298,28 @@ impl SearchService {
                 }
             }
 
+            // Add language filter if requested
+            if include_language && let Some(ref language_query) = language_query {
+                clauses.push((
+                    Occur::Must,
+                    Box::new(language_query.clone()) as Box<dyn tantivy::query::Query>,
+                ));
+            }
+
+            if let Some(ref path_query) = path_query {
+                clauses.push((
+                    Occur::Must,
+                    Box::new(path_query.clone()) as Box<dyn tantivy::query::Query>,
+                ));
+            }
+
+            if include_generated_filter && exclude_generated {
+                clauses.push((
+                    Occur::MustNot,
+                    Box::new(generated_query.clone()) as Box<dyn tantivy::query::Query>,
+                ));
+            }
+
             if clauses.len() == 1 {
                 Ok(clauses.into_iter().next().unwrap().1)
             } else {
@@ -1410,10 +2332,11 @@ impl SearchService {
         // - Project facets: apply repository, version & extension filters (but not project filter)
         // - Version facets: apply repository, project & extension filters (but not version filter)
         // - Extension facets: apply repository, project & version filters (but not extension filter)
+        // - Language facets: apply all other filters (but not language filter)
 
         // Calculate repository facets (with project, version & extension filters)
         let repository_facets = {
-            let query = build_query_with_filters(false, true, true, true)?;
+            let query = build_query_with_filters(false, true, true, true, true, true)?;
 
             // Build aggregation request using JSON
             let agg_req: Aggregations = serde_json::from_value(serde_json::json!({
@@ -1445,7 +2368,7 @@ impl SearchService {
 
         // Calculate project facets (with repository, version & extension filters)
         let project_facets = {
-            let query = build_query_with_filters(true, false, true, true)?;
+            let query = build_query_with_filters(true, false, true, true, true, true)?;
 
             // Build aggregation request using JSON
             let agg_req: Aggregations = serde_json::from_value(serde_json::json!({
@@ -1477,7 +2400,7 @@ impl SearchService {
 
         // Calculate version facets (with repository, project & extension filters)
         let version_facets = {
-            let query = build_query_with_filters(true, true, false, true)?;
+            let query = build_query_with_filters(true, true, false, true, true, true)?;
 
             let agg_req: Aggregations = serde_json::from_value(serde_json::json!({
                 "version_terms": {
@@ -1507,7 +2430,7 @@ impl SearchService {
 
         // Calculate extension facets (with repository, project & version filters)
         let extension_facets = {
-            let query = build_query_with_filters(true, true, true, false)?;
+            let query = build_query_with_filters(true, true, true, false, true, true)?;
 
             let agg_req: Aggregations = serde_json::from_value(serde_json::json!({
                 "extension_terms": {
@@ -1535,7 +2458,40 @@ impl SearchService {
             facets
         };
 
-        // Calculate size range facets (with repository, project, version & extension filters, but NOT size filter)
+        // Calculate language facets (with repository, project, version & extension filters)
+        let language_facets = {
+            let query = build_query_with_filters(true, true, true, true, false, true)?;
+
+            let agg_req: Aggregations = serde_json::from_value(serde_json::json!({
+                "language_terms": {
+                    "terms": {
+                        "field": "language",
+                        "size": 1000
+                    }
+                }
+            }))?;
+
+            let collector = AggregationCollector::from_aggs(agg_req, Default::default());
+            let agg_res: AggregationResults = searcher.search(&*query, &collector)?;
+
+            let mut facets = Vec::new();
+            if let Some(tantivy::aggregation::agg_result::AggregationResult::BucketResult(
+                tantivy::aggregation::agg_result::BucketResult::Terms { buckets, .. },
+            )) = agg_res.0.get("language_terms")
+            {
+                for entry in buckets {
+                    // Files of unknown language are indexed with an empty language
+                    if let tantivy::aggregation::Key::Str(term) = &entry.key
+                        && !term.is_empty()
+                    {
+                        facets.push((term.to_string(), entry.doc_count));
+                    }
+                }
+            }
+            facets
+        };
+
+        // Calculate size range facets (with repository, project, version, extension & language filters, but NOT size filter)
         let size_range_facets = {
             // For size ranges, we should NOT include the size filter from the search query
             let mut size_clauses = vec![];
@@ -1861,6 +2817,30 @@ impl SearchService {
                     }
                 }
 
+                // Add language filter if present
+                if let Some(ref language_query) = language_query {
+                    query_clauses.push((
+                        Occur::Must,
+                        Box::new(language_query.clone()) as Box<dyn tantivy::query::Query>,
+                    ));
+                }
+
+                // Add path filter if present
+                if let Some(ref path_query) = path_query {
+                    query_clauses.push((
+                        Occur::Must,
+                        Box::new(path_query.clone()) as Box<dyn tantivy::query::Query>,
+                    ));
+                }
+
+                // Leave generated files out unless they are included
+                if exclude_generated {
+                    query_clauses.push((
+                        Occur::MustNot,
+                        Box::new(generated_query.clone()) as Box<dyn tantivy::query::Query>,
+                    ));
+                }
+
                 // Add the size range query
                 query_clauses.push((Occur::Must, Box::new(range_query) as Box<dyn tantivy::query::Query>));
 
@@ -1889,12 +2869,42 @@ impl SearchService {
             size_facets
         };
 
+        // Count matches in generated files (with all filters), whether or not the search includes them
+        let generated_count = {
+            let query = build_query_with_filters(true, true, true, true, true, false)?;
+            let generated_only = BooleanQuery::from(vec![
+                (Occur::Must, query),
+                (
+                    Occur::Must,
+                    Box::new(generated_query.clone()) as Box<dyn tantivy::query::Query>,
+                ),
+            ]);
+            searcher.search(&generated_only, &Count)? as u64
+        };
+
+        // Calculate directory facets (children of the path filter, with all filters applied)
+        let directory_facets = {
+            let parent = directory_facet(&path_directory);
+            let mut collector = FacetCollector::for_field("path_facet");
+            collector.add_facet(parent.clone());
+            let counts = searcher.search(final_query, &collector)?;
+
+            let mut facets: Vec<(String, u64)> =
+                counts.get(parent).map(|(facet, count)| (facet_to_directory(facet), count)).collect();
+            facets.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            facets.truncate(DIRECTORY_FACET_LIMIT);
+            facets
+        };
+
         Ok(SearchFacets {
             repositories: repository_facets,
             projects: project_facets,
             versions: version_facets,
             extensions: extension_facets,
+            languages: language_facets,
             size_ranges: size_range_facets,
+            directories: directory_facets,
+            generated: generated_count,
         })
     }
 
@@ -1941,6 +2951,12 @@ impl SearchService {
             version,
             extension,
             size: content.len() as u64, // Calculate size from content length
+            last_modified: None,
+            language: None,
+            sections: &[],
+            line_offset: 0,
+            is_truncated: false,
+            is_lfs: false,
         };
 
         // This is sync, so we need to use a runtime block
@@ -2109,6 +3125,366 @@ impl SearchService {
     }
 }
 
+/// 1-based line number of a byte offset in `content`
+fn line_at(content: &str, byte_offset: usize) -> u32 {
+    let mut end = byte_offset.min(content.len());
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    content[..end].matches('\n').count() as u32 + 1
+}
+
+/// Byte offset in `content` of the first case-insensitive match of the lowercase `needle`.
+///
+/// Lowercasing can change the byte length of a character (e.g. `İ`), so the search runs over
+/// the original text instead of a lowercased copy whose offsets would not line up.
+fn find_case_insensitive(content: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    content.char_indices().map(|(pos, _)| pos).find(|&pos| {
+        let mut haystack = content[pos..].chars().flat_map(char::to_lowercase);
+        needle.chars().all(|expected| haystack.next() == Some(expected))
+    })
+}
+
+/// Convert Tantivy's highlighted byte ranges into byte and char ranges within `fragment`
+fn highlight_ranges(fragment: &str, highlighted: &[std::ops::Range<usize>]) -> Vec<HighlightRange> {
+    highlighted
+        .iter()
+        .filter(|range| fragment.is_char_boundary(range.start) && fragment.is_char_boundary(range.end))
+        .map(|range| {
+            let char_start = fragment[..range.start].chars().count();
+            HighlightRange {
+                start: range.start,
+                end: range.end,
+                char_start,
+                char_end: char_start + fragment[range.start..range.end].chars().count(),
+            }
+        })
+        .collect()
+}
+
+/// Parse a doc address in the "segment_ord:doc_id" format used by search results
+fn parse_doc_address(doc_address_str: &str) -> Result<tantivy::DocAddress> {
+    let parts: Vec<&str> = doc_address_str.split(':').collect();
+    if parts.len() != 2 {
+        return Err(anyhow!("Invalid doc_address format, expected 'segment_ord:doc_id'"));
+    }
+
+    let segment_ord: u32 = parts[0].parse().map_err(|_| anyhow!("Invalid segment_ord in doc_address: {}", parts[0]))?;
+    let doc_id: u32 = parts[1].parse().map_err(|_| anyhow!("Invalid doc_id in doc_address: {}", parts[1]))?;
+
+    Ok(tantivy::DocAddress::new(segment_ord, doc_id))
+}
+
+/// Relevance sort key: adjusted score, then file key as tie-breaker
+type ScoreKey = (tantivy::Score, u64);
+
+/// Build a score tweaker applying the ranking multipliers from the project, version and file_path_raw fast fields.
+///
+/// Multipliers are cached per term ordinal, so each distinct project version and path is only evaluated once per
+/// segment.
+/// Documents at or before the `after` cursor position get no key, which ranks them below every other hit.
+fn ranking_tweaker(
+    ranking: RankingConfig,
+    default_branches: Arc<HashMap<String, String>>,
+    after: Option<ScoreKey>,
+) -> impl Fn(&tantivy::SegmentReader) -> Box<dyn FnMut(tantivy::DocId, tantivy::Score) -> Option<ScoreKey>> + Send + Sync
+{
+    move |segment_reader: &tantivy::SegmentReader| {
+        let ranking = ranking.clone();
+        let default_branches = default_branches.clone();
+        let fast_fields = segment_reader.fast_fields();
+        let project_column = fast_fields.str("project").ok().flatten();
+        let version_column = fast_fields.str("version").ok().flatten();
+        let path_column = fast_fields.str("file_path_raw").ok().flatten();
+        let file_key_column = fast_fields.u64("file_key").ok();
+        let mut version_cache: HashMap<(Option<u64>, u64), f32> = HashMap::new();
+        let mut path_cache: HashMap<u64, f32> = HashMap::new();
+
+        Box::new(move |doc: tantivy::DocId, score: tantivy::Score| {
+            let mut multiplier = 1.0;
+            let mut buffer = String::new();
+
+            if let Some(column) = &version_column
+                && let Some(ord) = column.term_ords(doc).next()
+            {
+                let project_ord = project_column.as_ref().and_then(|column| column.term_ords(doc).next());
+                multiplier *= *version_cache.entry((project_ord, ord)).or_insert_with(|| {
+                    let default_branch = project_ord.and_then(|project_ord| {
+                        let project_column = project_column.as_ref()?;
+                        let _ = project_column.ord_to_str(project_ord, &mut buffer);
+                        default_branches.get(buffer.as_str()).cloned()
+                    });
+                    buffer.clear();
+                    let _ = column.ord_to_str(ord, &mut buffer);
+                    ranking.version_multiplier(&buffer, default_branch.as_deref())
+                });
+            }
+
+            if let Some(column) = &path_column
+                && let Some(ord) = column.term_ords(doc).next()
+            {
+                multiplier *= *path_cache.entry(ord).or_insert_with(|| {
+                    buffer.clear();
+                    let _ = column.ord_to_str(ord, &mut buffer);
+                    ranking.path_multiplier(&buffer)
+                });
+            }
+
+            let key = (
+                score * multiplier,
+                file_key_column.as_ref().and_then(|c| c.first(doc)).unwrap_or(0),
+            );
+            if after.is_some_and(|after| key >= after) { None } else { Some(key) }
+        })
+    }
+}
+
+/// One page of top documents with the sort key of its last hit (the next search_after position)
+type TopDocsPage = (Vec<(f32, tantivy::DocAddress)>, Option<serde_json::Value>);
+
+/// Top documents for one page, ordered by relevance or by fast-field values.
+///
+/// Every key ends with the file key, so documents with equal values keep a stable order across
+/// pages and index merges. Hits sorted by a field carry a score of 0.
+#[allow(clippy::too_many_arguments)]
+fn top_docs_page(
+    searcher: &tantivy::Searcher,
+    query: &dyn tantivy::query::Query,
+    ranking: RankingConfig,
+    default_branches: Arc<HashMap<String, String>>,
+    sort_by: SortField,
+    sort_order: SortOrder,
+    limit: usize,
+    offset: usize,
+    after: Option<serde_json::Value>,
+) -> Result<TopDocsPage> {
+    fn str_column(segment_reader: &tantivy::SegmentReader, field: &str) -> Option<tantivy::columnar::StrColumn> {
+        segment_reader.fast_fields().str(field).ok().flatten()
+    }
+    fn file_key(column: Option<&tantivy::columnar::Column<u64>>, doc: tantivy::DocId) -> u64 {
+        column.and_then(|c| c.first(doc)).unwrap_or(0)
+    }
+
+    match sort_by {
+        SortField::Score => {
+            let after: Option<ScoreKey> = after.map(serde_json::from_value).transpose()?;
+            let collector = TopDocs::with_limit(limit).and_offset(offset).tweak_score(ranking_tweaker(
+                ranking,
+                default_branches,
+                after,
+            ));
+            let hits: Vec<(ScoreKey, tantivy::DocAddress)> = searcher
+                .search(query, &collector)?
+                .into_iter()
+                .filter_map(|(key, doc_address)| key.map(|key| (key, doc_address)))
+                .collect();
+
+            let last_key = hits.last().map(|(key, _)| serde_json::to_value(key)).transpose()?;
+            Ok((
+                hits.into_iter().map(|((score, _), doc_address)| (score, doc_address)).collect(),
+                last_key,
+            ))
+        }
+        SortField::Path => keyed_page(searcher, query, sort_order, limit, offset, after, |reader| {
+            let (path, project, version) = (
+                str_column(reader, "file_path_raw"),
+                str_column(reader, "project"),
+                str_column(reader, "version"),
+            );
+            let key = reader.fast_fields().u64("file_key").ok();
+            move |doc| {
+                (
+                    str_value(path.as_ref(), doc),
+                    str_value(project.as_ref(), doc),
+                    str_value(version.as_ref(), doc),
+                    file_key(key.as_ref(), doc),
+                )
+            }
+        }),
+        SortField::Repository => keyed_page(searcher, query, sort_order, limit, offset, after, |reader| {
+            let (repository, path, version) = (
+                str_column(reader, "repository"),
+                str_column(reader, "file_path_raw"),
+                str_column(reader, "version"),
+            );
+            let key = reader.fast_fields().u64("file_key").ok();
+            move |doc| {
+                (
+                    str_value(repository.as_ref(), doc),
+                    str_value(path.as_ref(), doc),
+                    str_value(version.as_ref(), doc),
+                    file_key(key.as_ref(), doc),
+                )
+            }
+        }),
+        SortField::Size => keyed_page(searcher, query, sort_order, limit, offset, after, |reader| {
+            let size = reader.fast_fields().u64("size").ok();
+            let key = reader.fast_fields().u64("file_key").ok();
+            move |doc| {
+                (
+                    size.as_ref().and_then(|c| c.first(doc)).unwrap_or(0),
+                    file_key(key.as_ref(), doc),
+                )
+            }
+        }),
+        SortField::Date => {
+            // Files without a known modification time come last in both orders
+            let undated = match sort_order {
+                SortOrder::Desc => i64::MIN,
+                SortOrder::Asc => i64::MAX,
+            };
+            keyed_page(searcher, query, sort_order, limit, offset, after, move |reader| {
+                let last_modified = reader.fast_fields().i64("last_modified").ok();
+                let key = reader.fast_fields().u64("file_key").ok();
+                move |doc| {
+                    (
+                        last_modified.as_ref().and_then(|c| c.first(doc)).unwrap_or(undated),
+                        file_key(key.as_ref(), doc),
+                    )
+                }
+            })
+        }
+    }
+}
+
+/// Run a top-K collection keyed by `segment_key`, skipping hits at or before the `after` key.
+///
+/// Skipped hits get no key, which ranks them below every other hit, and are dropped from the page.
+fn keyed_page<K, F, S>(
+    searcher: &tantivy::Searcher,
+    query: &dyn tantivy::query::Query,
+    sort_order: SortOrder,
+    limit: usize,
+    offset: usize,
+    after: Option<serde_json::Value>,
+    segment_key: F,
+) -> Result<TopDocsPage>
+where
+    K: PartialOrd + Clone + Send + Sync + Serialize + serde::de::DeserializeOwned + 'static,
+    F: Fn(&tantivy::SegmentReader) -> S + Send + Sync + 'static,
+    S: FnMut(tantivy::DocId) -> K + 'static,
+{
+    let after: Option<K> = after.map(serde_json::from_value).transpose()?;
+    let top_docs = TopDocs::with_limit(limit).and_offset(offset);
+
+    let hits: Vec<(K, tantivy::DocAddress)> = match sort_order {
+        SortOrder::Desc => searcher
+            .search(
+                query,
+                &top_docs.custom_score(move |reader: &tantivy::SegmentReader| {
+                    let mut key = segment_key(reader);
+                    let after = after.clone();
+                    move |doc| {
+                        let key = key(doc);
+                        if after.as_ref().is_some_and(|after| key >= *after) { None } else { Some(key) }
+                    }
+                }),
+            )?
+            .into_iter()
+            .filter_map(|(key, doc_address)| key.map(|key| (key, doc_address)))
+            .collect(),
+        SortOrder::Asc => searcher
+            .search(
+                query,
+                &top_docs.custom_score(move |reader: &tantivy::SegmentReader| {
+                    let mut key = segment_key(reader);
+                    let after = after.clone();
+                    move |doc| {
+                        let key = key(doc);
+                        if after.as_ref().is_some_and(|after| key <= *after) {
+                            None
+                        } else {
+                            Some(std::cmp::Reverse(key))
+                        }
+                    }
+                }),
+            )?
+            .into_iter()
+            .filter_map(|(key, doc_address)| key.map(|std::cmp::Reverse(key)| (key, doc_address)))
+            .collect(),
+    };
+
+    let last_key = hits.last().map(|(key, _)| serde_json::to_value(key)).transpose()?;
+    Ok((
+        hits.into_iter().map(|(_, doc_address)| (0.0, doc_address)).collect(),
+        last_key,
+    ))
+}
+
+/// Opaque search_after cursor: the sort it belongs to and the key of the last hit of a page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SearchCursor {
+    sort_by: SortField,
+    sort_order: SortOrder,
+    key: serde_json::Value,
+}
+
+impl SearchCursor {
+    fn encode(&self) -> Result<String> {
+        use base64::Engine;
+        Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(self)?))
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| anyhow!("Invalid search_after cursor"))?;
+        serde_json::from_slice(&bytes).map_err(|_| anyhow!("Invalid search_after cursor"))
+    }
+}
+
+/// First value of a string fast field for a document ("" when missing)
+fn str_value(column: Option<&tantivy::columnar::StrColumn>, doc: tantivy::DocId) -> String {
+    let mut value = String::new();
+    if let Some(column) = column
+        && let Some(ord) = column.term_ords(doc).next()
+    {
+        let _ = column.ord_to_str(ord, &mut value);
+    }
+    value
+}
+
+/// Stable numeric key of a file, used to break ties between equal sort values
+fn file_key(file_id: Uuid) -> u64 {
+    u64::from_be_bytes(file_id.as_bytes()[..8].try_into().expect("UUIDs are 16 bytes"))
+}
+
+/// Parts of the text extracted from a document, as stored with it (empty for plain text files)
+fn stored_sections(doc: &tantivy::TantivyDocument, field: Field) -> Vec<SourceSection> {
+    doc.get_first(field)
+        .and_then(|v| v.as_str())
+        .and_then(|sections| serde_json::from_str(sections).ok())
+        .unwrap_or_default()
+}
+
+/// Normalize a directory path: no leading/trailing slashes, no empty or "." components
+fn normalize_directory(path: &str) -> String {
+    path.split('/').filter(|c| !c.is_empty() && *c != ".").collect::<Vec<_>>().join("/")
+}
+
+/// Directory containing a file, normalized ("" for files at the repository root)
+fn parent_directory(file_path: &str) -> String {
+    normalize_directory(file_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or(""))
+}
+
+/// Facet for a normalized directory ("" maps to the root facet)
+fn directory_facet(directory: &str) -> Facet {
+    if directory.is_empty() {
+        Facet::root()
+    } else {
+        Facet::from_path(directory.split('/'))
+    }
+}
+
+/// Convert a directory facet back to its path form ("/src/api" -> "src/api")
+fn facet_to_directory(facet: &Facet) -> String {
+    facet.to_path().join("/")
+}
+
 /// Extract simple alphanumeric terms from regex pattern for snippet highlighting
 ///
 /// This function helps generate better snippets for regex searches by extracting
@@ -218,6 +218,26 @@ impl SeedingService {
                 excluded_branches_patterns: None,
                 included_projects: None,
                 included_projects_patterns: None,
+                included_extensions: None,
+                included_file_names: None,
+                included_path_patterns: None,
+                excluded_path_patterns: None,
+                default_encoding: None,
+                index_archives: None,
+                max_file_size_mb: None,
+                large_file_mode: None,
+                excluded_projects: None,
+                excluded_projects_patterns: None,
+                bitbucket_project_key: None,
+                gitea_organization: None,
+                svn_revision: None,
+                ssh_private_key: None,
+                ssh_known_hosts: None,
+                index_submodules: None,
+                fetch_lfs_objects: None,
+                index_merge_requests: None,
+                webhook_secret: None,
+                github_url: None,
             },
             Repository {
                 id: Uuid::new_v4(),
@@ -252,6 +272,26 @@ impl SeedingService {
                 excluded_branches_patterns: None,
                 included_projects: None,
                 included_projects_patterns: None,
+                included_extensions: None,
+                included_file_names: None,
+                included_path_patterns: None,
+                excluded_path_patterns: None,
+                default_encoding: None,
+                index_archives: None,
+                max_file_size_mb: None,
+                large_file_mode: None,
+                excluded_projects: None,
+                excluded_projects_patterns: None,
+                bitbucket_project_key: None,
+                gitea_organization: None,
+                svn_revision: None,
+                ssh_private_key: None,
+                ssh_known_hosts: None,
+                index_submodules: None,
+                fetch_lfs_objects: None,
+                index_merge_requests: None,
+                webhook_secret: None,
+                github_url: None,
             },
             Repository {
                 id: Uuid::new_v4(),
@@ -286,6 +326,26 @@ impl SeedingService {
                 excluded_branches_patterns: None,
                 included_projects: None,
                 included_projects_patterns: None,
+                included_extensions: None,
+                included_file_names: None,
+                included_path_patterns: None,
+                excluded_path_patterns: None,
+                default_encoding: None,
+                index_archives: None,
+                max_file_size_mb: None,
+                large_file_mode: None,
+                excluded_projects: None,
+                excluded_projects_patterns: None,
+                bitbucket_project_key: None,
+                gitea_organization: None,
+                svn_revision: None,
+                ssh_private_key: None,
+                ssh_known_hosts: None,
+                index_submodules: None,
+                fetch_lfs_objects: None,
+                index_merge_requests: None,
+                webhook_secret: None,
+                github_url: None,
             },
             Repository {
                 id: Uuid::new_v4(),
@@ -320,6 +380,26 @@ impl SeedingService {
                 excluded_branches_patterns: None,
                 included_projects: None,
                 included_projects_patterns: None,
+                included_extensions: None,
+                included_file_names: None,
+                included_path_patterns: None,
+                excluded_path_patterns: None,
+                default_encoding: None,
+                index_archives: None,
+                max_file_size_mb: None,
+                large_file_mode: None,
+                excluded_projects: None,
+                excluded_projects_patterns: None,
+                bitbucket_project_key: None,
+                gitea_organization: None,
+                svn_revision: None,
+                ssh_private_key: None,
+                ssh_known_hosts: None,
+                index_submodules: None,
+                fetch_lfs_objects: None,
+                index_merge_requests: None,
+                webhook_secret: None,
+                github_url: None,
             },
             Repository {
                 id: Uuid::new_v4(),
@@ -354,6 +434,26 @@ impl SeedingService {
                 excluded_branches_patterns: None,
                 included_projects: None,
                 included_projects_patterns: None,
+                included_extensions: None,
+                included_file_names: None,
+                included_path_patterns: None,
+                excluded_path_patterns: None,
+                default_encoding: None,
+                index_archives: None,
+                max_file_size_mb: None,
+                large_file_mode: None,
+                excluded_projects: None,
+                excluded_projects_patterns: None,
+                bitbucket_project_key: None,
+                gitea_organization: None,
+                svn_revision: None,
+                ssh_private_key: None,
+                ssh_known_hosts: None,
+                index_submodules: None,
+                fetch_lfs_objects: None,
+                index_merge_requests: None,
+                webhook_secret: None,
+                github_url: None,
             },
         ];
 
@@ -0,0 +1,162 @@
+//! SSH authentication for Git repositories cloned over `ssh://` or `git@host:path` URLs.
+//!
+//! gix runs the `ssh` program for these URLs. The private key and the pinned host keys are written
+//! to a private directory for the duration of a clone or fetch, and handed to `ssh` through
+//! `core.sshCommand`.
+
+use anyhow::{Result, anyhow};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Whether a Git URL is fetched over SSH: `ssh://`, `git+ssh://` or the scp-like `[user@]host:path`
+pub fn is_ssh_url(url: &str) -> bool {
+    let url = url.trim();
+    if let Some((scheme, _)) = url.split_once("://") {
+        return matches!(scheme.to_ascii_lowercase().as_str(), "ssh" | "git+ssh" | "ssh+git");
+    }
+
+    // scp-like syntax: a host before the first ':', which comes before any '/'.
+    // Single letters are Windows drive letters, not hosts.
+    match url.split_once(':') {
+        Some((host, path)) => host.len() > 1 && !host.contains('/') && !path.is_empty(),
+        None => false,
+    }
+}
+
+/// Check that a key looks like an unencrypted private key in OpenSSH or PEM format
+pub fn validate_private_key(private_key: &str) -> Result<()> {
+    let private_key = private_key.trim();
+    if !private_key.starts_with("-----BEGIN ") || !private_key.ends_with("PRIVATE KEY-----") {
+        return Err(anyhow!(
+            "SSH private key must be in OpenSSH or PEM format (-----BEGIN ... PRIVATE KEY-----)"
+        ));
+    }
+    if private_key.contains("Proc-Type: 4,ENCRYPTED") {
+        return Err(anyhow!("SSH private keys protected by a passphrase are not supported"));
+    }
+    Ok(())
+}
+
+/// Check that every line of a known_hosts document has host patterns, a key type and a key
+pub fn validate_known_hosts(known_hosts: &str) -> Result<()> {
+    for line in known_hosts.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        // Marker lines (@cert-authority, @revoked) have one more field
+        let fields = line.split_whitespace().count();
+        let required = if line.starts_with('@') { 4 } else { 3 };
+        if fields < required {
+            return Err(anyhow!("Invalid known_hosts line: {}", line));
+        }
+    }
+    Ok(())
+}
+
+/// Decrypted SSH private key, with the host keys the connection may accept
+#[derive(Clone)]
+pub struct SshCredentials {
+    pub private_key: Option<String>, // None to let ssh use its own keys
+    pub known_hosts: Option<String>, // None to check host keys against the system known_hosts files
+}
+
+/// Key and known_hosts files written for one Git operation, removed when dropped
+pub struct SshSession {
+    directory: PathBuf,
+    command: String,
+}
+
+impl SshSession {
+    pub fn new(credentials: &SshCredentials) -> Result<Self> {
+        if let Some(private_key) = &credentials.private_key {
+            validate_private_key(private_key)?;
+        }
+
+        let directory = std::env::temp_dir().join(format!("klask-ssh-{}", Uuid::new_v4()));
+        create_private_directory(&directory)?;
+        let mut session = Self { directory, command: String::new() };
+
+        let mut command = "ssh".to_string();
+        if let Some(private_key) = &credentials.private_key {
+            let key_path = session.directory.join("id_key");
+            write_private_file(&key_path, private_key)?;
+            command.push_str(&format!(" -i {} -o IdentitiesOnly=yes", shell_quote(&key_path)?));
+        }
+
+        // Never prompt: unknown or changed host keys and rejected keys fail the connection
+        command.push_str(" -o BatchMode=yes -o StrictHostKeyChecking=yes");
+        if let Some(known_hosts) = credentials.known_hosts.as_deref().filter(|hosts| !hosts.trim().is_empty()) {
+            validate_known_hosts(known_hosts)?;
+            let known_hosts_path = session.directory.join("known_hosts");
+            write_private_file(&known_hosts_path, known_hosts)?;
+            command.push_str(&format!(
+                " -o UserKnownHostsFile={} -o GlobalKnownHostsFile=/dev/null",
+                shell_quote(&known_hosts_path)?
+            ));
+        }
+
+        session.command = command;
+        Ok(session)
+    }
+
+    /// Command run by gix for SSH connections
+    #[allow(dead_code)]
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// gix configuration overrides making clones and fetches use this session's key
+    pub fn config_overrides(&self) -> Vec<String> {
+        vec![format!("core.sshCommand={}", self.command), "ssh.variant=ssh".to_string()]
+    }
+
+    /// Private directory holding the key and known_hosts files
+    #[allow(dead_code)]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+}
+
+impl Drop for SshSession {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.directory) {
+            tracing::warn!("Failed to remove SSH key directory {:?}: {}", self.directory, e);
+        }
+    }
+}
+
+fn create_private_directory(path: &Path) -> Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(path).map_err(|e| anyhow!("Failed to create SSH key directory {:?}: {}", path, e))
+}
+
+/// Write a file readable by its owner only, with normalized line endings (ssh rejects keys without a final newline)
+fn write_private_file(path: &Path, content: &str) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let content = format!("{}\n", content.replace("\r\n", "\n").trim());
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| anyhow!("Failed to write SSH file {:?}: {}", path, e))
+}
+
+/// Single-quote a path for the shell running `core.sshCommand`
+fn shell_quote(path: &Path) -> Result<String> {
+    let path = path.to_str().ok_or_else(|| anyhow!("SSH key path {:?} is not valid UTF-8", path))?;
+    // Git configuration values give backslashes a meaning, and quotes would end the shell quoting
+    if path.contains(['\'', '\\', '"']) {
+        return Err(anyhow!("SSH key path {} contains quotes or backslashes", path));
+    }
+    Ok(format!("'{}'", path))
+}
@@ -0,0 +1,282 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use std::path::{Path, PathBuf};
+
+/// Properties requested from mod_dav_svn for each entry of a directory
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<propfind xmlns="DAV:"><prop><resourcetype/><version-name/><getlastmodified/><getcontentlength/></prop></propfind>"#;
+
+/// Where a Subversion repository is read from, given by the repository URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SvnLocation {
+    /// Repository served over HTTP/WebDAV (`https://svn.example.com/repos/project`)
+    Http(String),
+    /// Local repository (`file:///var/svn/project`), read through `svnadmin dump`
+    Local(PathBuf),
+    /// File written by `svnadmin dump` (a local path, or a `file://` URL of a file)
+    Dump(PathBuf),
+}
+
+impl SvnLocation {
+    pub fn parse(url: &str) -> Result<Self> {
+        let url = url.trim();
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Ok(Self::Http(url.trim_end_matches('/').to_string()));
+        }
+        if url.starts_with("svn://") || url.starts_with("svn+ssh://") {
+            return Err(anyhow!(
+                "svn:// and svn+ssh:// URLs are not supported, use the HTTP address of the repository or a dump of it"
+            ));
+        }
+
+        match url.strip_prefix("file://") {
+            Some(path) if Path::new(path).is_dir() => Ok(Self::Local(PathBuf::from(path))),
+            Some(path) => Ok(Self::Dump(PathBuf::from(path))),
+            None => Ok(Self::Dump(PathBuf::from(url))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvnNodeKind {
+    File,
+    Dir,
+}
+
+/// Entry of a Subversion directory
+#[derive(Debug, Clone)]
+pub struct SvnEntry {
+    pub name: String,
+    pub kind: SvnNodeKind,
+    pub revision: u64,                        // Revision that last changed the entry
+    pub last_modified: Option<DateTime<Utc>>, // Date of that revision
+    pub size: u64,                            // 0 for directories
+}
+
+/// Client for Subversion repositories served over HTTP/WebDAV (mod_dav_svn)
+#[derive(Debug, Clone)]
+pub struct SvnService {
+    client: Client,
+}
+
+impl Default for SvnService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvnService {
+    pub fn new() -> Self {
+        let accept_invalid_certs =
+            std::env::var("KLASK_SVN_ACCEPT_INVALID_CERTS").map(|v| v.to_lowercase() == "true").unwrap_or(false);
+
+        let mut builder = Client::builder().user_agent("klask-rs/2.0").timeout(std::time::Duration::from_secs(60));
+
+        if accept_invalid_certs {
+            tracing::warn!(
+                "Subversion client configured to accept invalid certificates (KLASK_SVN_ACCEPT_INVALID_CERTS=true)"
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Self { client: builder.build().unwrap_or_else(|_| Client::new()) }
+    }
+
+    /// List the entries of a directory, at `revision` or the youngest revision
+    pub async fn list_directory(
+        &self,
+        url: &str,
+        revision: Option<u64>,
+        credentials: Option<&str>,
+    ) -> Result<Vec<SvnEntry>> {
+        let directory_url = format!("{}/", url.trim_end_matches('/'));
+        let directory_path = href_path(&directory_url);
+
+        let entries = self.propfind(&directory_url, "1", revision, credentials).await?;
+        Ok(entries.into_iter().filter(|(href, _)| *href != directory_path).map(|(_, entry)| entry).collect())
+    }
+
+    /// Entry of the file or directory at `url`, at the youngest revision
+    pub async fn stat(&self, url: &str, credentials: Option<&str>) -> Result<SvnEntry> {
+        self.propfind(url, "0", None, credentials)
+            .await?
+            .into_iter()
+            .next()
+            .map(|(_, entry)| entry)
+            .ok_or_else(|| anyhow!("Empty Subversion response for {}", url))
+    }
+
+    /// Content of the file at `url`, at `revision` or the youngest revision
+    pub async fn get_file(&self, url: &str, revision: Option<u64>, credentials: Option<&str>) -> Result<Vec<u8>> {
+        let response = authorize(at_revision(self.client.get(url), revision), credentials).send().await?;
+
+        let status = response.status();
+        if status != StatusCode::OK {
+            return Err(anyhow!("Subversion request failed - URL: {}, Status: {}", url, status));
+        }
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Entries of a PROPFIND response, with their decoded and `/`-trimmed paths
+    async fn propfind(
+        &self,
+        url: &str,
+        depth: &str,
+        revision: Option<u64>,
+        credentials: Option<&str>,
+    ) -> Result<Vec<(String, SvnEntry)>> {
+        tracing::debug!("Making Subversion PROPFIND request (depth {}) to: {}", depth, url);
+        let method = Method::from_bytes(b"PROPFIND").expect("PROPFIND is a valid HTTP method");
+        let request = self
+            .client
+            .request(method, url)
+            .header("Depth", depth)
+            .header("Content-Type", "text/xml; charset=utf-8")
+            .body(PROPFIND_BODY);
+
+        let response = authorize(at_revision(request, revision), credentials).send().await?;
+
+        let status = response.status();
+        if status != StatusCode::MULTI_STATUS {
+            let error_body = response.text().await.unwrap_or_default();
+            tracing::error!(
+                "Subversion request failed - URL: {}, Status: {}, Body: {}",
+                url,
+                status,
+                error_body
+            );
+            return Err(match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    anyhow!("Subversion authentication failed for {}: {}", url, status)
+                }
+                _ => anyhow!("Subversion request failed for {}: {}", url, status),
+            });
+        }
+
+        let body = response.text().await?;
+        parse_multistatus(&body).with_context(|| format!("Failed to parse Subversion response for {}", url))
+    }
+}
+
+/// mod_dav_svn resolves the request URL at the revision given by the DeltaV `Label` header
+fn at_revision(request: RequestBuilder, revision: Option<u64>) -> RequestBuilder {
+    match revision {
+        Some(revision) => request.header("Label", revision.to_string()),
+        None => request,
+    }
+}
+
+/// Credentials are `username:password` for HTTP basic authentication, or a bare password or token
+fn authorize(request: RequestBuilder, credentials: Option<&str>) -> RequestBuilder {
+    match credentials.filter(|c| !c.is_empty()) {
+        Some(credentials) => match credentials.split_once(':') {
+            Some((username, password)) => request.basic_auth(username, Some(password)),
+            None => request.basic_auth("", Some(credentials)),
+        },
+        None => request,
+    }
+}
+
+/// Decoded path of a URL or href, without its trailing `/`
+fn href_path(href: &str) -> String {
+    let path = match reqwest::Url::parse(href) {
+        Ok(url) => url.path().to_string(),
+        Err(_) => href.to_string(),
+    };
+    let decoded = urlencoding::decode(&path).map(|p| p.into_owned()).unwrap_or(path);
+    decoded.trim_end_matches('/').to_string()
+}
+
+/// Parse a WebDAV multistatus document into (path, entry) pairs
+fn parse_multistatus(body: &str) -> Result<Vec<(String, SvnEntry)>> {
+    let mut reader = Reader::from_str(body);
+    reader.config_mut().trim_text(true);
+
+    let mut entries = Vec::new();
+    let mut element = Vec::new();
+    let mut href = String::new();
+    let mut entry = None::<SvnEntry>;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = e.local_name().as_ref().to_vec();
+                if name == b"response" {
+                    href.clear();
+                    entry = Some(SvnEntry {
+                        name: String::new(),
+                        kind: SvnNodeKind::File,
+                        revision: 0,
+                        last_modified: None,
+                        size: 0,
+                    });
+                }
+                element = name;
+            }
+            Event::Empty(e) => {
+                if e.local_name().as_ref() == b"collection"
+                    && let Some(entry) = entry.as_mut()
+                {
+                    entry.kind = SvnNodeKind::Dir;
+                }
+            }
+            Event::Text(text) => {
+                let text = text.unescape()?;
+                let Some(entry) = entry.as_mut() else { continue };
+                match element.as_slice() {
+                    b"href" => href = href_path(&text),
+                    b"version-name" => entry.revision = text.trim().parse().unwrap_or(0),
+                    b"getcontentlength" => entry.size = text.trim().parse().unwrap_or(0),
+                    b"getlastmodified" => {
+                        entry.last_modified =
+                            DateTime::parse_from_rfc2822(text.trim()).ok().map(|date| date.with_timezone(&Utc))
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) => {
+                if e.local_name().as_ref() == b"response"
+                    && let Some(mut entry) = entry.take()
+                {
+                    entry.name = href.rsplit('/').next().unwrap_or_default().to_string();
+                    entries.push((href.clone(), entry));
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Write a dump of the local repository at `repository_path` to `dump_path` with `svnadmin dump`
+pub async fn dump_local_repository(repository_path: &Path, dump_path: &Path) -> Result<()> {
+    let dump_file = std::fs::File::create(dump_path)
+        .map_err(|e| anyhow!("Failed to create Subversion dump file {:?}: {}", dump_path, e))?;
+
+    let output = tokio::process::Command::new("svnadmin")
+        .arg("dump")
+        .arg("--quiet")
+        .arg(repository_path)
+        .stdout(dump_file)
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| anyhow!("Failed to run svnadmin, required to read file:// repositories: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "svnadmin dump failed for {:?}: {}",
+            repository_path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,593 @@
+use crate::models::{Repository, RepositoryType};
+use crate::repositories::RepositoryRepository;
+use crate::services::crawler::CrawlerService;
+use crate::services::crawler::filter::{filter_branches, filter_projects, filter_repositories};
+use crate::services::crawler::service::{BranchCrawl, BranchCrawlProject};
+use crate::services::crawler::submodules::url_host;
+use crate::services::encryption::EncryptionService;
+use crate::services::github::{DEFAULT_GITHUB_API_URL, github_api_base};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// Number of deliveries kept for troubleshooting
+pub const MAX_RECENT_DELIVERIES: usize = 100;
+/// Default delay before crawling a pushed branch, reset by each new push to it
+pub const DEFAULT_DEBOUNCE_SECONDS: u64 = 10;
+
+const ZERO_COMMIT: &str = "0000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookProvider {
+    GitLab,
+    GitHub,
+    Generic,
+}
+
+/// Push event parsed from a webhook payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushEvent {
+    pub project: String, // Full path of the pushed project, or the repository name or URL of generic webhooks
+    pub urls: Vec<String>, // Clone and web URLs of the pushed project
+    pub clone_url: Option<String>,
+    pub git_ref: String,
+    pub deleted: bool,
+}
+
+#[derive(Deserialize)]
+struct GitLabPushPayload {
+    #[serde(rename = "ref")]
+    git_ref: String,
+    after: Option<String>,
+    project: GitLabPushProject,
+}
+
+#[derive(Deserialize)]
+struct GitLabPushProject {
+    path_with_namespace: String,
+    git_http_url: Option<String>,
+    git_ssh_url: Option<String>,
+    web_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitHubPushPayload {
+    #[serde(rename = "ref")]
+    git_ref: String,
+    #[serde(default)]
+    deleted: bool,
+    repository: GitHubPushRepository,
+}
+
+#[derive(Deserialize)]
+struct GitHubPushRepository {
+    full_name: String,
+    clone_url: Option<String>,
+    ssh_url: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GenericPushPayload {
+    repository: String,
+    #[serde(rename = "ref")]
+    git_ref: Option<String>,
+    branch: Option<String>,
+    #[serde(default)]
+    deleted: bool,
+}
+
+impl PushEvent {
+    /// Parse a GitLab `Push Hook` payload
+    pub fn from_gitlab(body: &[u8]) -> Result<Self> {
+        let payload: GitLabPushPayload = serde_json::from_slice(body)?;
+        let project = payload.project;
+        Ok(Self {
+            urls: [&project.git_http_url, &project.git_ssh_url, &project.web_url]
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect(),
+            clone_url: project.git_http_url,
+            project: project.path_with_namespace,
+            git_ref: payload.git_ref,
+            deleted: payload.after.as_deref() == Some(ZERO_COMMIT),
+        })
+    }
+
+    /// Parse a GitHub `push` event payload
+    pub fn from_github(body: &[u8]) -> Result<Self> {
+        let payload: GitHubPushPayload = serde_json::from_slice(body)?;
+        let repository = payload.repository;
+        Ok(Self {
+            urls: [&repository.clone_url, &repository.ssh_url, &repository.html_url]
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect(),
+            clone_url: repository.clone_url,
+            project: repository.full_name,
+            git_ref: payload.git_ref,
+            deleted: payload.deleted,
+        })
+    }
+
+    /// Parse a generic payload: `{"repository": "<name or URL>", "branch": "main"}`, the branch can be given as
+    /// `"ref": "refs/heads/main"` instead
+    pub fn from_generic(body: &[u8]) -> Result<Self> {
+        let payload: GenericPushPayload = serde_json::from_slice(body)?;
+        let git_ref = match (payload.git_ref, payload.branch) {
+            (Some(git_ref), _) => git_ref,
+            (None, Some(branch)) => format!("refs/heads/{}", branch),
+            (None, None) => anyhow::bail!("Missing branch or ref"),
+        };
+        Ok(Self {
+            urls: vec![payload.repository.clone()],
+            clone_url: None,
+            project: payload.repository,
+            git_ref,
+            deleted: payload.deleted,
+        })
+    }
+
+    /// Pushed branch; `None` for tags and other references
+    pub fn branch(&self) -> Option<&str> {
+        self.git_ref.strip_prefix("refs/heads/").filter(|branch| !branch.is_empty())
+    }
+}
+
+/// Repository targeted by a push, with the pushed project for GitLab and GitHub repositories
+#[derive(Debug, Clone)]
+pub struct PushTarget {
+    pub repository: Repository,
+    pub project: Option<BranchCrawlProject>,
+}
+
+impl PushTarget {
+    /// Whether only the pushed branch can be crawled, other repositories are fully crawled
+    pub fn crawls_branch(&self) -> bool {
+        matches!(
+            (&self.repository.repository_type, &self.project),
+            (RepositoryType::Git, None) | (RepositoryType::GitLab | RepositoryType::GitHub, Some(_))
+        )
+    }
+}
+
+/// `host/path` form of a Git URL, without scheme, credentials, port and `.git` suffix, to compare the HTTP and SSH
+/// URLs of a project; local paths are kept as is
+pub fn normalize_git_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let Some(host) = url_host(url) else {
+        return url.to_string();
+    };
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map(|(_, path)| path).unwrap_or(""),
+        None => url.split_once(':').map(|(_, path)| path).unwrap_or(""),
+    };
+    format!("{}/{}", host, path.trim_matches('/')).to_lowercase()
+}
+
+/// Host of the web URLs of a GitHub repository (github.com or the Enterprise host)
+fn github_web_host(url: &str) -> Option<String> {
+    let api_base = github_api_base(url);
+    if api_base == DEFAULT_GITHUB_API_URL {
+        Some("github.com".to_string())
+    } else {
+        url_host(&api_base)
+    }
+}
+
+/// Repositories targeted by a push event, before verifying their webhook secret
+pub fn match_push_targets(
+    provider: WebhookProvider,
+    event: &PushEvent,
+    repositories: &[Repository],
+) -> Vec<PushTarget> {
+    let event_urls: Vec<String> = event.urls.iter().map(|url| normalize_git_url(url)).collect();
+    let event_host = event.urls.iter().find_map(|url| url_host(url));
+    let project = || BranchCrawlProject {
+        name: event.project.clone(),
+        clone_url: event.clone_url.clone().unwrap_or_else(|| event.urls[0].clone()),
+    };
+
+    let mut targets = Vec::new();
+    for repository in repositories.iter().filter(|repository| repository.enabled) {
+        let target_project = match (&repository.repository_type, provider) {
+            (RepositoryType::Git, _) if event_urls.contains(&normalize_git_url(&repository.url)) => None,
+            (RepositoryType::GitLab, WebhookProvider::GitLab) => {
+                let in_namespace = repository.gitlab_namespace.as_deref().is_none_or(|namespace| {
+                    event.project.strip_prefix(namespace).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                });
+                let included = !filter_projects(
+                    vec![event.project.clone()],
+                    repository.included_projects.as_deref(),
+                    repository.included_projects_patterns.as_deref(),
+                    repository.gitlab_excluded_projects.as_deref(),
+                    repository.gitlab_excluded_patterns.as_deref(),
+                )
+                .is_empty();
+                if event_host.is_none() || url_host(&repository.url) != event_host || !in_namespace || !included {
+                    continue;
+                }
+                Some(project())
+            }
+            (RepositoryType::GitHub, WebhookProvider::GitHub) => {
+                let owner = event.project.split('/').next().unwrap_or_default();
+                let in_namespace = repository
+                    .github_namespace
+                    .as_deref()
+                    .is_none_or(|namespace| namespace.eq_ignore_ascii_case(owner));
+                let included = !filter_repositories(
+                    vec![event.project.clone()],
+                    repository.included_projects.as_deref(),
+                    repository.included_projects_patterns.as_deref(),
+                    repository.github_excluded_repositories.as_deref(),
+                    repository.github_excluded_patterns.as_deref(),
+                )
+                .is_empty();
+                if event_host.is_none()
+                    || github_web_host(repository.github_url.as_deref().unwrap_or_default()) != event_host
+                    || !in_namespace
+                    || !included
+                {
+                    continue;
+                }
+                Some(project())
+            }
+            // Generic webhooks can name any repository, those that are not plain Git repositories are fully crawled
+            (_, WebhookProvider::Generic) if repository.name == event.project => None,
+            _ => continue,
+        };
+        targets.push(PushTarget { repository: repository.clone(), project: target_project });
+    }
+    targets
+}
+
+/// Whether a pushed branch is indexed according to the branch filters of the repository
+pub fn is_indexed_branch(repository: &Repository, branch: &str) -> bool {
+    !filter_branches(
+        vec![branch.to_string()],
+        repository.included_branches.as_deref(),
+        repository.included_branches_patterns.as_deref(),
+        repository.excluded_branches.as_deref(),
+        repository.excluded_branches_patterns.as_deref(),
+    )
+    .is_empty()
+}
+
+/// `sha256=<hex>` HMAC-SHA256 signature of a payload, as sent in the `X-Hub-Signature-256` header
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Verify a GitLab `X-Gitlab-Token` header, or a `sha256=<hex>` HMAC signature of GitHub and generic webhooks
+pub fn verify_credential(provider: WebhookProvider, secret: &str, body: &[u8], credential: &str) -> bool {
+    match provider {
+        WebhookProvider::GitLab => constant_time_eq(secret.as_bytes(), credential.as_bytes()),
+        WebhookProvider::GitHub | WebhookProvider::Generic => constant_time_eq(
+            sign_payload(secret, body).as_bytes(),
+            credential.trim().to_ascii_lowercase().as_bytes(),
+        ),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Outcome of a delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Queued,       // A crawl of the pushed branch was scheduled
+    Ignored,      // Not a push to an indexed branch
+    Invalid,      // The payload could not be parsed
+    Unauthorized, // No repository matching the pushed project has a secret verifying the token or signature
+    Failed,       // The repositories could not be loaded
+}
+
+/// Delivery received by a webhook endpoint, kept for troubleshooting
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: Option<String>, // Delivery ID sent by the provider (X-Gitlab-Event-UUID, X-GitHub-Delivery)
+    pub provider: WebhookProvider,
+    pub event: Option<String>,
+    #[serde(rename = "receivedAt")]
+    pub received_at: DateTime<Utc>,
+    pub project: Option<String>,
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+    pub status: DeliveryStatus,
+    pub message: String,
+    #[serde(rename = "repositoryIds")]
+    pub repository_ids: Vec<Uuid>,
+}
+
+/// Webhook request, with the headers identifying and authenticating it
+#[derive(Debug, Clone)]
+pub struct WebhookRequest {
+    pub provider: WebhookProvider,
+    pub delivery_id: Option<String>,
+    pub event: Option<String>,
+    pub credential: Option<String>, // Token or signature header
+}
+
+/// Pushes debounced together: those to one branch, or any push for targets crawled in full
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DebounceKey {
+    repository_id: Uuid,
+    project: Option<String>,
+    branch: Option<String>, // None when the whole repository is crawled
+}
+
+struct PendingCrawl {
+    deadline: Instant,
+    target: PushTarget,
+    branch: String, // Last pushed branch
+    deleted: bool,
+}
+
+/// Receives push webhooks and schedules debounced crawls of the pushed branches
+pub struct WebhookService {
+    database: Pool<Postgres>,
+    crawler_service: Arc<CrawlerService>,
+    encryption_service: Arc<EncryptionService>,
+    debounce: Duration,
+    pending: Arc<Mutex<HashMap<DebounceKey, PendingCrawl>>>,
+    deliveries: Arc<RwLock<VecDeque<WebhookDelivery>>>,
+}
+
+impl WebhookService {
+    pub fn new(
+        database: Pool<Postgres>,
+        crawler_service: Arc<CrawlerService>,
+        encryption_service: Arc<EncryptionService>,
+    ) -> Self {
+        let debounce_seconds = std::env::var("KLASK_WEBHOOK_DEBOUNCE_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_DEBOUNCE_SECONDS);
+        Self::with_debounce(
+            database,
+            crawler_service,
+            encryption_service,
+            Duration::from_secs(debounce_seconds),
+        )
+    }
+
+    pub fn with_debounce(
+        database: Pool<Postgres>,
+        crawler_service: Arc<CrawlerService>,
+        encryption_service: Arc<EncryptionService>,
+        debounce: Duration,
+    ) -> Self {
+        Self {
+            database,
+            crawler_service,
+            encryption_service,
+            debounce,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            deliveries: Arc::new(RwLock::new(VecDeque::new())),
+        }
+    }
+
+    /// Handle a delivery: verify it against the matching repositories and schedule the crawl of the pushed branch
+    pub async fn handle_delivery(&self, request: WebhookRequest, body: &[u8]) -> WebhookDelivery {
+        let mut delivery = WebhookDelivery {
+            id: request.delivery_id.clone(),
+            provider: request.provider,
+            event: request.event.clone(),
+            received_at: Utc::now(),
+            project: None,
+            git_ref: None,
+            status: DeliveryStatus::Ignored,
+            message: String::new(),
+            repository_ids: Vec::new(),
+        };
+        let (status, message) = self.process_delivery(&request, body, &mut delivery).await;
+        delivery.status = status;
+        delivery.message = message;
+
+        match status {
+            DeliveryStatus::Queued | DeliveryStatus::Ignored => info!(
+                "Webhook delivery {:?} from {:?} for {:?} {:?}: {:?}, {}",
+                delivery.id, delivery.provider, delivery.project, delivery.git_ref, status, delivery.message
+            ),
+            _ => warn!(
+                "Webhook delivery {:?} from {:?} for {:?} {:?} rejected: {:?}, {}",
+                delivery.id, delivery.provider, delivery.project, delivery.git_ref, status, delivery.message
+            ),
+        }
+
+        // Only authenticated deliveries are kept, so anonymous requests cannot flush the history
+        if delivery.repository_ids.is_empty() {
+            return delivery;
+        }
+        let mut deliveries = self.deliveries.write().await;
+        if deliveries.len() == MAX_RECENT_DELIVERIES {
+            deliveries.pop_front();
+        }
+        deliveries.push_back(delivery.clone());
+        delivery
+    }
+
+    /// Most recent deliveries, newest first
+    pub async fn recent_deliveries(&self) -> Vec<WebhookDelivery> {
+        self.deliveries.read().await.iter().rev().cloned().collect()
+    }
+
+    async fn process_delivery(
+        &self,
+        request: &WebhookRequest,
+        body: &[u8],
+        delivery: &mut WebhookDelivery,
+    ) -> (DeliveryStatus, String) {
+        let parsed = match (request.provider, request.event.as_deref()) {
+            (WebhookProvider::GitLab, Some("Push Hook") | None) => PushEvent::from_gitlab(body),
+            (WebhookProvider::GitHub, Some("push") | None) => PushEvent::from_github(body),
+            (WebhookProvider::Generic, _) => PushEvent::from_generic(body),
+            (_, Some(event)) => return (DeliveryStatus::Ignored, format!("Event '{}' is not handled", event)),
+        };
+        let event = match parsed {
+            Ok(event) => event,
+            Err(e) => return (DeliveryStatus::Invalid, format!("Invalid payload: {}", e)),
+        };
+        delivery.project = Some(event.project.clone());
+        delivery.git_ref = Some(event.git_ref.clone());
+
+        let repositories = match RepositoryRepository::new(self.database.clone()).list_repositories().await {
+            Ok(repositories) => repositories,
+            Err(e) => {
+                error!("Failed to load repositories for webhook: {}", e);
+                return (DeliveryStatus::Failed, "Failed to load repositories".to_string());
+            }
+        };
+        // Unknown projects are rejected like bad credentials, so senders cannot probe which projects are indexed
+        let candidates = match_push_targets(request.provider, &event, &repositories);
+        if candidates.is_empty() {
+            debug!("No repository matches pushed project {}", event.project);
+        }
+
+        let credential = request.credential.as_deref().unwrap_or_default();
+        let targets: Vec<PushTarget> = candidates
+            .into_iter()
+            .filter(|target| {
+                let secret = target
+                    .repository
+                    .webhook_secret
+                    .as_deref()
+                    .and_then(|encrypted| self.encryption_service.decrypt(encrypted).ok());
+                secret.is_some_and(|secret| verify_credential(request.provider, &secret, body, credential))
+            })
+            .collect();
+        if targets.is_empty() {
+            return (DeliveryStatus::Unauthorized, "Invalid token or signature".to_string());
+        }
+        delivery.repository_ids = targets.iter().map(|target| target.repository.id).collect();
+
+        let Some(branch) = event.branch() else {
+            return (DeliveryStatus::Ignored, "Only branch pushes are handled".to_string());
+        };
+        let targets: Vec<PushTarget> =
+            targets.into_iter().filter(|target| is_indexed_branch(&target.repository, branch)).collect();
+        if targets.is_empty() {
+            return (DeliveryStatus::Ignored, format!("Branch '{}' is not indexed", branch));
+        }
+
+        for target in &targets {
+            self.schedule(target.clone(), branch, event.deleted).await;
+        }
+        let action = if event.deleted { "Removal" } else { "Crawl" };
+        (
+            DeliveryStatus::Queued,
+            format!(
+                "{} of branch '{}' queued for {} repositories",
+                action,
+                branch,
+                targets.len()
+            ),
+        )
+    }
+
+    /// Schedule the crawl of a branch after the debounce delay, postponed by each new push to the same branch
+    async fn schedule(&self, target: PushTarget, branch: &str, deleted: bool) {
+        // Targets without branch crawls are crawled in full once, whichever branches were pushed
+        let key = if target.crawls_branch() {
+            DebounceKey {
+                repository_id: target.repository.id,
+                project: target.project.as_ref().map(|project| project.name.clone()),
+                branch: Some(branch.to_string()),
+            }
+        } else {
+            DebounceKey { repository_id: target.repository.id, project: None, branch: None }
+        };
+        let branch = branch.to_string();
+        let deadline = Instant::now() + self.debounce;
+
+        let mut pending = self.pending.lock().await;
+        if let Some(crawl) = pending.get_mut(&key) {
+            debug!(
+                "Postponing the pending crawl of branch '{}' of {}",
+                branch, target.repository.name
+            );
+            *crawl = PendingCrawl { deadline, target, branch, deleted };
+            return;
+        }
+        pending.insert(key.clone(), PendingCrawl { deadline, target, branch, deleted });
+        drop(pending);
+
+        let pending = self.pending.clone();
+        let crawler_service = self.crawler_service.clone();
+        let debounce = self.debounce;
+        tokio::spawn(async move {
+            let crawl = loop {
+                let deadline = match pending.lock().await.get(&key) {
+                    Some(crawl) => crawl.deadline,
+                    None => return,
+                };
+                tokio::time::sleep_until(deadline).await;
+
+                let mut pending_crawls = pending.lock().await;
+                let Some(crawl) = pending_crawls.get_mut(&key) else {
+                    return;
+                };
+                if crawl.deadline > Instant::now() {
+                    continue;
+                }
+                // Wait for the running crawl of the repository to finish
+                if crawler_service.is_crawling(key.repository_id).await {
+                    crawl.deadline = Instant::now() + debounce;
+                    continue;
+                }
+                break pending_crawls.remove(&key).expect("pending crawl is present");
+            };
+            run_crawl(&crawler_service, crawl).await;
+        });
+    }
+}
+
+async fn run_crawl(crawler_service: &CrawlerService, crawl: PendingCrawl) {
+    let PendingCrawl { target, branch, deleted, .. } = crawl;
+    let branch = branch.as_str();
+    let repository = &target.repository;
+    let result = if !target.crawls_branch() {
+        crawler_service.crawl_repository(repository).await.map(|_| "full crawl".to_string())
+    } else if deleted {
+        crawler_service
+            .delete_branch(repository, target.project.as_ref(), branch)
+            .await
+            .map(|count| format!("{} documents removed", count))
+    } else {
+        crawler_service.crawl_branch(repository, target.project.as_ref(), branch).await.map(|crawl| match crawl {
+            BranchCrawl::Unchanged => "unchanged".to_string(),
+            BranchCrawl::Incremental(count) => format!("{} changed files re-indexed", count),
+            BranchCrawl::Full => "branch re-indexed".to_string(),
+        })
+    };
+    match result {
+        Ok(outcome) => info!(
+            "Webhook crawl of branch '{}' of {}: {}",
+            branch, repository.name, outcome
+        ),
+        Err(e) => error!(
+            "Webhook crawl of branch '{}' of {} failed: {}",
+            branch, repository.name, e
+        ),
+    }
+}
@@ -10,6 +10,7 @@ use klask_rs::{
     services::seeding::SeedingStats,
     services::{
         crawler::CrawlerService, encryption::EncryptionService, progress::ProgressTracker, search::SearchService,
+        webhooks::WebhookService,
     },
 };
 use serde_json::Value;
@@ -46,12 +47,19 @@ async fn setup_test_server() -> Result<(TestServer, AppState)> {
         .expect("Failed to create crawler service"),
     );
 
+    let webhook_service = Arc::new(WebhookService::new(
+        database.pool().clone(),
+        crawler_service.clone(),
+        Arc::new(EncryptionService::new("test-encryption-key-32bytes").unwrap()),
+    ));
+
     let app_state = AppState {
         database,
         search_service: Arc::new(search_service),
         crawler_service,
         progress_tracker,
         scheduler_service: None,
+        webhook_service,
         jwt_service,
         config,
         crawl_tasks: Arc::new(RwLock::new(HashMap::new())),
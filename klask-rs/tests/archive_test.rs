@@ -0,0 +1,81 @@
+mod common;
+
+#[cfg(test)]
+mod archive_tests {
+    use crate::common;
+    use klask_rs::models::{FileInclusionRules, Repository};
+    use klask_rs::services::crawler::file_processing::FileProcessor;
+    use klask_rs::services::crawler::large_files::LargeFilePolicy;
+    use klask_rs::services::search::{SearchQuery, SearchService};
+    use serde_json::json;
+    use std::fs;
+    use std::io::{Cursor, Write};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn repository(url: &str, index_archives: Option<bool>) -> Repository {
+        common::repository(json!({
+            "name": "legacy",
+            "url": url,
+            "repositoryType": "FileSystem",
+            "indexArchives": index_archives
+        }))
+    }
+
+    fn jar_bytes() -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in
+            [("com/x/Config.properties", "datasource.timeout=30"), ("com/x/Config.class", "\u{0}\u{0}binary")]
+        {
+            writer.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_archives_are_collected_when_enabled() {
+        let root = TempDir::new().unwrap();
+        fs::create_dir(root.path().join("lib")).unwrap();
+        fs::write(root.path().join("lib/foo.jar"), jar_bytes()).unwrap();
+        fs::write(root.path().join("README.md"), "# Legacy").unwrap();
+
+        let rules = FileInclusionRules::default();
+        let paths = |rules: &FileInclusionRules| -> Vec<String> {
+            FileProcessor::collect_indexable_files(root.path(), rules, LargeFilePolicy::default())
+                .unwrap()
+                .into_iter()
+                .map(|(_, relative_path)| relative_path)
+                .collect()
+        };
+        assert_eq!(paths(&rules), ["README.md"]);
+
+        let enabled = rules.for_repository(&repository(&root.path().to_string_lossy(), Some(true)));
+        let mut collected = paths(&enabled);
+        collected.sort();
+        assert_eq!(collected, ["README.md", "lib/foo.jar"]);
+    }
+
+    #[tokio::test]
+    async fn test_archive_entries_are_indexed_under_virtual_paths() {
+        let index_dir = TempDir::new().unwrap();
+        let service = Arc::new(SearchService::new(index_dir.path().join("index")).unwrap());
+        let repository = repository("/srv/legacy", Some(true));
+        let rules = FileInclusionRules::default().for_repository(&repository);
+
+        let indexed = FileProcessor::new(service.clone())
+            .process_archive(&repository, "lib/foo.jar", jar_bytes(), "main", None, &rules)
+            .await
+            .unwrap();
+        assert_eq!(indexed, 1);
+        service.commit().await.unwrap();
+
+        let results = service
+            .search(SearchQuery { query: "timeout".to_string(), limit: 10, ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(results.results.len(), 1);
+        assert_eq!(results.results[0].file_path, "lib/foo.jar!/com/x/Config.properties");
+        assert_eq!(results.results[0].file_name, "Config.properties");
+    }
+}
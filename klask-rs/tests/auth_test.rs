@@ -2,6 +2,7 @@ use axum::http::StatusCode;
 use axum_test::TestServer;
 use klask_rs::services::{
     SearchService, crawler::CrawlerService, encryption::EncryptionService, progress::ProgressTracker,
+    webhooks::WebhookService,
 };
 use klask_rs::{Database, config::AppConfig};
 use klask_rs::{
@@ -68,12 +69,19 @@ async fn create_test_app_state() -> AppState {
         .expect("Failed to create crawler service"),
     );
 
+    let webhook_service = Arc::new(WebhookService::new(
+        database.pool().clone(),
+        crawler_service.clone(),
+        Arc::new(EncryptionService::new("test-encryption-key-32bytes").unwrap()),
+    ));
+
     AppState {
         database,
         search_service: shared_search_service,
         crawler_service,
         progress_tracker,
         scheduler_service: None,
+        webhook_service,
         jwt_service,
         config,
         crawl_tasks: Arc::new(RwLock::new(HashMap::new())),
@@ -295,12 +303,19 @@ async fn create_test_app_state_with_registration(allow_registration: bool) -> Ap
         .expect("Failed to create crawler service"),
     );
 
+    let webhook_service = Arc::new(WebhookService::new(
+        database.pool().clone(),
+        crawler_service.clone(),
+        Arc::new(EncryptionService::new("test-encryption-key-32bytes").unwrap()),
+    ));
+
     AppState {
         database,
         search_service: shared_search_service,
         crawler_service,
         progress_tracker,
         scheduler_service: None,
+        webhook_service,
         jwt_service,
         config,
         crawl_tasks: Arc::new(RwLock::new(HashMap::new())),
@@ -323,7 +338,7 @@ async fn test_allow_registration_true_via_config() {
         allow_registration: true,
     };
 
-    assert_eq!(config.allow_registration, true);
+    assert!(config.allow_registration);
 }
 
 #[tokio::test]
@@ -335,7 +350,7 @@ async fn test_allow_registration_false_via_config() {
         allow_registration: false,
     };
 
-    assert_eq!(config.allow_registration, false);
+    assert!(!config.allow_registration);
 }
 
 // ============================================================================
@@ -0,0 +1,141 @@
+use httpmock::prelude::*;
+use klask_rs::models::RepositoryType;
+use klask_rs::services::azure_devops::AzureDevOpsService;
+use klask_rs::services::crawler::filter::filter_projects;
+use serde_json::json;
+
+#[cfg(test)]
+mod azure_devops_tests {
+    use super::*;
+
+    // Personal access tokens are sent as the password of an empty user name
+    const PAT_AUTHORIZATION: &str = "Basic OnBhdC10b2tlbg==";
+
+    fn azure_repository(server: &MockServer, project: &str, name: &str, disabled: bool) -> serde_json::Value {
+        json!({
+            "id": format!("{}-{}", project, name),
+            "name": name,
+            "url": server.url(format!("/tfs/DefaultCollection/_apis/git/repositories/{}", name)),
+            "project": { "id": format!("{}-id", project), "name": project, "state": "wellFormed" },
+            "defaultBranch": "refs/heads/main",
+            "remoteUrl": server.url(format!("/tfs/DefaultCollection/{}/_git/{}", project, name)),
+            "webUrl": server.url(format!("/tfs/DefaultCollection/{}/_git/{}", project, name)),
+            "isDisabled": disabled
+        })
+    }
+
+    #[test]
+    fn test_azure_devops_repository_type_serialization() {
+        assert_eq!(
+            serde_json::to_value(RepositoryType::AzureDevOps).unwrap(),
+            json!("AzureDevOps")
+        );
+        let parsed: RepositoryType = serde_json::from_value(json!("AzureDevOps")).unwrap();
+        assert!(matches!(parsed, RepositoryType::AzureDevOps));
+    }
+
+    #[tokio::test]
+    async fn test_discover_projects_and_repositories() {
+        let server = MockServer::start_async().await;
+        let projects = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/tfs/DefaultCollection/_apis/projects")
+                    .query_param("$skip", "0")
+                    .header("Authorization", PAT_AUTHORIZATION);
+                then.status(200).json_body(json!({
+                    "count": 2,
+                    "value": [
+                        { "id": "payments-id", "name": "Payments", "state": "wellFormed" },
+                        { "id": "sandbox-id", "name": "Sandbox", "state": "wellFormed" }
+                    ]
+                }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/tfs/DefaultCollection/Payments/_apis/git/repositories");
+                then.status(200).json_body(json!({
+                    "count": 2,
+                    "value": [
+                        azure_repository(&server, "Payments", "api", false),
+                        azure_repository(&server, "Payments", "frozen", true)
+                    ]
+                }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/tfs/DefaultCollection/Sandbox/_apis/git/repositories");
+                then.status(200)
+                    .json_body(json!({ "count": 1, "value": [azure_repository(&server, "Sandbox", "spike", false)] }));
+            })
+            .await;
+
+        let service = AzureDevOpsService::new();
+        let collection_url = server.url("/tfs/DefaultCollection");
+
+        let project_names: Vec<String> =
+            service.list_projects(&collection_url, "pat-token").await.unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(project_names, vec!["Payments", "Sandbox"]);
+        projects.assert_async().await;
+
+        // Project filters are applied to project names before listing repositories
+        assert_eq!(
+            filter_projects(project_names, None, None, None, Some("Sand*")),
+            vec!["Payments"]
+        );
+
+        // Disabled repositories are dropped
+        let repositories = service.discover_repositories(&collection_url, "pat-token", None).await.unwrap();
+        let names: Vec<String> = repositories.iter().map(|r| r.full_name()).collect();
+        assert_eq!(names, vec!["Payments/api", "Sandbox/spike"]);
+        assert_eq!(repositories[0].default_branch_name().as_deref(), Some("main"));
+        assert_eq!(
+            repositories[0].remote_url,
+            server.url("/tfs/DefaultCollection/Payments/_git/api")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_discover_single_project() {
+        let server = MockServer::start_async().await;
+        let repositories_mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/my-org/Payments/_apis/git/repositories");
+                then.status(200)
+                    .json_body(json!({ "count": 1, "value": [azure_repository(&server, "Payments", "api", false)] }));
+            })
+            .await;
+
+        let service = AzureDevOpsService::new();
+        let repositories =
+            service.discover_repositories(&server.url("/my-org/"), "pat-token", Some("Payments")).await.unwrap();
+
+        assert_eq!(repositories.len(), 1);
+        repositories_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_token_validation() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/good/_apis/projects");
+                then.status(200).json_body(json!({ "count": 0, "value": [] }));
+            })
+            .await;
+        // Azure DevOps answers rejected tokens with a sign-in page
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/bad/_apis/projects");
+                then.status(203).header("content-type", "text/html").body("<html>Sign In</html>");
+            })
+            .await;
+
+        let service = AzureDevOpsService::new();
+        assert!(service.test_token(&server.url("/good"), "pat-token").await.unwrap());
+        assert!(!service.test_token(&server.url("/bad"), "pat-token").await.unwrap());
+        assert!(service.discover_repositories(&server.url("/bad"), "pat-token", None).await.is_err());
+    }
+}
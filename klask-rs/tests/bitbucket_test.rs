@@ -0,0 +1,216 @@
+mod common;
+
+use httpmock::prelude::*;
+use klask_rs::models::RepositoryType;
+use klask_rs::services::bitbucket::BitbucketService;
+use klask_rs::services::crawler::bitbucket_crawler::BitbucketDiscovery;
+use klask_rs::services::crawler::filter::filter_projects;
+use klask_rs::services::crawler::provider_crawler::{RepositoryDiscovery, project_repository};
+use serde_json::json;
+
+#[cfg(test)]
+mod bitbucket_tests {
+    use super::*;
+
+    fn bitbucket_repository(server: &MockServer, project: &str, slug: &str, archived: bool) -> serde_json::Value {
+        json!({
+            "slug": slug,
+            "id": 1,
+            "name": slug,
+            "scmId": "git",
+            "state": "AVAILABLE",
+            "archived": archived,
+            "project": { "key": project, "id": 1, "name": project },
+            "links": {
+                "clone": [
+                    { "href": format!("ssh://git@{}:7999/{}/{}.git", server.host(), project.to_lowercase(), slug), "name": "ssh" },
+                    { "href": server.url(format!("/scm/{}/{}.git", project.to_lowercase(), slug)), "name": "http" }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_bitbucket_repository_type_serialization() {
+        assert_eq!(
+            serde_json::to_value(RepositoryType::Bitbucket).unwrap(),
+            json!("Bitbucket")
+        );
+        let parsed: RepositoryType = serde_json::from_value(json!("Bitbucket")).unwrap();
+        assert!(matches!(parsed, RepositoryType::Bitbucket));
+    }
+
+    #[tokio::test]
+    async fn test_discover_project_repositories_follows_pages() {
+        let server = MockServer::start_async().await;
+        let first_page = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/rest/api/1.0/projects/PLAT/repos")
+                    .query_param("start", "0")
+                    .header("Authorization", "Bearer bb-token");
+                then.status(200).json_body(json!({
+                    "size": 2,
+                    "limit": 100,
+                    "start": 0,
+                    "isLastPage": false,
+                    "nextPageStart": 2,
+                    "values": [
+                        bitbucket_repository(&server, "PLAT", "api", false),
+                        bitbucket_repository(&server, "PLAT", "old-api", true)
+                    ]
+                }));
+            })
+            .await;
+        let second_page = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects/PLAT/repos").query_param("start", "2");
+                then.status(200).json_body(json!({
+                    "size": 1,
+                    "limit": 100,
+                    "start": 2,
+                    "isLastPage": true,
+                    "values": [bitbucket_repository(&server, "PLAT", "web", false)]
+                }));
+            })
+            .await;
+
+        let service = BitbucketService::new();
+        let repositories = service.discover_repositories(&server.base_url(), "bb-token", Some("PLAT")).await.unwrap();
+
+        // Archived repositories are dropped
+        let names: Vec<String> = repositories.iter().map(|r| r.full_name()).collect();
+        assert_eq!(names, vec!["PLAT/api", "PLAT/web"]);
+        assert_eq!(
+            repositories[0].http_clone_url(),
+            Some(server.url("/scm/plat/api.git").as_str())
+        );
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discover_all_projects_and_filter() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects");
+                then.status(200).json_body(json!({
+                    "isLastPage": true,
+                    "values": [
+                        { "key": "PLAT", "id": 1, "name": "Platform" },
+                        { "key": "ARCHIVE", "id": 2, "name": "Archive" }
+                    ]
+                }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects/PLAT/repos");
+                then.status(200).json_body(json!({
+                    "isLastPage": true,
+                    "values": [
+                        bitbucket_repository(&server, "PLAT", "api", false),
+                        bitbucket_repository(&server, "PLAT", "scratch", false)
+                    ]
+                }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects/ARCHIVE/repos");
+                then.status(200).json_body(json!({
+                    "isLastPage": true,
+                    "values": [bitbucket_repository(&server, "ARCHIVE", "legacy", false)]
+                }));
+            })
+            .await;
+
+        let service = BitbucketService::new();
+        let repositories = service.discover_repositories(&server.base_url(), "bb-token", None).await.unwrap();
+        let names: Vec<String> = repositories.iter().map(|r| r.full_name()).collect();
+        assert_eq!(names, vec!["PLAT/api", "PLAT/scratch", "ARCHIVE/legacy"]);
+
+        // The same include/exclude rules as GitLab projects apply to "PROJECT/slug" names
+        let filtered = filter_projects(names, None, None, Some("PLAT/scratch"), Some("ARCHIVE/*"));
+        assert_eq!(filtered, vec!["PLAT/api"]);
+    }
+
+    #[tokio::test]
+    async fn test_token_validation() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects").header("Authorization", "Bearer good");
+                then.status(200).json_body(json!({ "isLastPage": true, "values": [] }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects").header("Authorization", "Bearer bad");
+                then.status(401).json_body(json!({ "errors": [{ "message": "Authentication failed" }] }));
+            })
+            .await;
+
+        let service = BitbucketService::new();
+        assert!(service.test_token(&server.base_url(), "good").await.unwrap());
+        assert!(!service.test_token(&server.base_url(), "bad").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_discovery_error_is_reported() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects/NOPE/repos");
+                then.status(404).json_body(json!({ "errors": [{ "message": "Project NOPE does not exist." }] }));
+            })
+            .await;
+
+        let service = BitbucketService::new();
+        let error = service.discover_repositories(&server.base_url(), "bb-token", Some("NOPE")).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("404"));
+    }
+
+    #[tokio::test]
+    async fn test_discovery_filters_repositories_cloned_as_git() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/rest/api/1.0/projects/PLAT/repos");
+                then.status(200).json_body(json!({
+                    "isLastPage": true,
+                    "values": [
+                        bitbucket_repository(&server, "PLAT", "api", false),
+                        bitbucket_repository(&server, "PLAT", "scratch", false)
+                    ]
+                }));
+            })
+            .await;
+
+        let repository = common::repository(json!({
+            "name": "bitbucket",
+            "url": server.base_url(),
+            "repositoryType": "Bitbucket",
+            "bitbucketProjectKey": "PLAT",
+            "excludedProjects": "PLAT/scratch",
+            "defaultEncoding": "windows-1252"
+        }));
+        let discovery = BitbucketDiscovery::new(&repository);
+        let discovered = discovery.discover(&repository, "bb-token", &mut Vec::new()).await.unwrap();
+        assert_eq!(discovered.len(), 1);
+        assert_eq!(discovered[0].full_name, "PLAT/api");
+        let clone_url = discovered[0].clone_url.as_deref().unwrap();
+        assert_eq!(clone_url, server.url("/scm/plat/api.git"));
+
+        // Each repository is cloned as Git under the Bitbucket repository's id and settings
+        let project = project_repository(&repository, &discovered[0].full_name, clone_url, None);
+        assert!(matches!(project.repository_type, RepositoryType::Git));
+        assert_eq!(
+            (project.id, project.name.as_str(), project.url.as_str()),
+            (repository.id, "PLAT/api", clone_url)
+        );
+        assert_eq!(project.default_encoding.as_deref(), Some("windows-1252"));
+    }
+}
@@ -0,0 +1,19 @@
+use klask_rs::models::Repository;
+use serde_json::{Value, json};
+
+/// Enabled Git repository with a new id, built from its API representation with the fields of `overrides` replaced
+pub fn repository(overrides: Value) -> Repository {
+    let mut repository = json!({
+        "id": uuid::Uuid::new_v4(),
+        "name": "repository",
+        "url": "",
+        "repositoryType": "Git",
+        "enabled": true,
+        "isGroup": false,
+        "createdAt": "2025-01-01T00:00:00Z",
+        "updatedAt": "2025-01-01T00:00:00Z",
+        "autoCrawlEnabled": false
+    });
+    repository.as_object_mut().unwrap().extend(overrides.as_object().expect("overrides are a JSON object").clone());
+    serde_json::from_value(repository).unwrap()
+}
@@ -1,11 +1,13 @@
 use klask_rs::services::{SearchQuery, SearchService};
 use std::time::Instant;
+use tempfile::TempDir;
 use tokio::time::Duration;
 
 #[tokio::test]
 async fn test_concurrent_queries_run_in_parallel() {
     // Create search service with test data
-    let search_service = SearchService::new("test_index_concurrent_search").unwrap();
+    let index_dir = TempDir::new().unwrap();
+    let search_service = SearchService::new(index_dir.path()).unwrap();
 
     // Index some test files
     for i in 0..100 {
@@ -21,6 +23,7 @@ async fn test_concurrent_queries_run_in_parallel() {
                 version: "main",
                 extension: "rs",
                 size: 1024,
+                ..Default::default()
             })
             .await
             .unwrap();
@@ -39,11 +42,18 @@ async fn test_concurrent_queries_run_in_parallel() {
         extension_filter: None,
         min_size: None,
         max_size: None,
+        path_filter: None,
+        sort_by: None,
+        sort_order: None,
+        search_after: None,
+        language_filter: None,
         limit: 10,
         offset: 0,
         include_facets: false,
         fuzzy_search: false,
         regex_search: false,
+        regex_flags: None,
+        include_generated: false,
     };
     let simple_result = search_service.search(simple_query).await;
     let simple_duration = start.elapsed();
@@ -67,11 +77,18 @@ async fn test_concurrent_queries_run_in_parallel() {
         extension_filter: None,
         min_size: None,
         max_size: None,
+        path_filter: None,
+        sort_by: None,
+        sort_order: None,
+        search_after: None,
+        language_filter: None,
         limit: 10,
         offset: 0,
         include_facets: false,
         fuzzy_search: false,
         regex_search: true,
+        regex_flags: None,
+        include_generated: false,
     };
 
     let simple_query2 = SearchQuery {
@@ -82,11 +99,18 @@ async fn test_concurrent_queries_run_in_parallel() {
         extension_filter: None,
         min_size: None,
         max_size: None,
+        path_filter: None,
+        sort_by: None,
+        sort_order: None,
+        search_after: None,
+        language_filter: None,
         limit: 10,
         offset: 0,
         include_facets: false,
         fuzzy_search: false,
         regex_search: false,
+        regex_flags: None,
+        include_generated: false,
     };
 
     let start = Instant::now();
@@ -116,7 +140,8 @@ async fn test_concurrent_queries_run_in_parallel() {
 
 #[tokio::test]
 async fn test_inefficient_regex_pattern_warning() {
-    let search_service = SearchService::new("test_index_regex_warning").unwrap();
+    let index_dir = TempDir::new().unwrap();
+    let search_service = SearchService::new(index_dir.path()).unwrap();
 
     // Index a test file
     let file_id = uuid::Uuid::new_v4();
@@ -131,6 +156,7 @@ async fn test_inefficient_regex_pattern_warning() {
             version: "main",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         })
         .await
         .unwrap();
@@ -146,11 +172,18 @@ async fn test_inefficient_regex_pattern_warning() {
         extension_filter: None,
         min_size: None,
         max_size: None,
+        path_filter: None,
+        sort_by: None,
+        sort_order: None,
+        search_after: None,
+        language_filter: None,
         limit: 10,
         offset: 0,
         include_facets: false,
         fuzzy_search: false,
         regex_search: true,
+        regex_flags: None,
+        include_generated: false,
     };
 
     let result = search_service.search(query).await;
@@ -163,7 +196,8 @@ async fn test_inefficient_regex_pattern_warning() {
 async fn test_search_timeout() {
     // This test is informational - it demonstrates that the timeout exists
     // We can't easily create a query that times out reliably without a huge index
-    let search_service = SearchService::new("test_index_timeout").unwrap();
+    let index_dir = TempDir::new().unwrap();
+    let search_service = SearchService::new(index_dir.path()).unwrap();
 
     // Index a single file
     let file_id = uuid::Uuid::new_v4();
@@ -178,6 +212,7 @@ async fn test_search_timeout() {
             version: "main",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         })
         .await
         .unwrap();
@@ -193,11 +228,18 @@ async fn test_search_timeout() {
         extension_filter: None,
         min_size: None,
         max_size: None,
+        path_filter: None,
+        sort_by: None,
+        sort_order: None,
+        search_after: None,
+        language_filter: None,
         limit: 10,
         offset: 0,
         include_facets: false,
         fuzzy_search: false,
         regex_search: false,
+        regex_flags: None,
+        include_generated: false,
     };
 
     let start = Instant::now();
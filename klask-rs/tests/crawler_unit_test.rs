@@ -4,7 +4,7 @@ use std::path::Path;
 
 #[tokio::test]
 async fn test_crawl_progress_initialization() -> Result<()> {
-    let progress = CrawlProgress { files_processed: 0, files_indexed: 0, errors: Vec::new() };
+    let progress = CrawlProgress { files_processed: 0, files_indexed: 0, files_skipped_binary: 0, errors: Vec::new() };
 
     assert_eq!(progress.files_processed, 0);
     assert_eq!(progress.files_indexed, 0);
@@ -225,7 +225,8 @@ async fn test_file_size_limit_constant() -> Result<()> {
 
 #[tokio::test]
 async fn test_error_accumulation() -> Result<()> {
-    let mut progress = CrawlProgress { files_processed: 0, files_indexed: 0, errors: Vec::new() };
+    let mut progress =
+        CrawlProgress { files_processed: 0, files_indexed: 0, files_skipped_binary: 0, errors: Vec::new() };
 
     // Simulate processing files with some errors
     progress.files_processed += 1;
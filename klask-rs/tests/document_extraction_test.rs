@@ -0,0 +1,156 @@
+mod common;
+
+#[cfg(test)]
+mod document_extraction_tests {
+    use crate::common;
+    use anyhow::Result;
+    use klask_rs::models::Repository;
+    use klask_rs::services::crawler::file_processing::{ContentExtractor, ExtractedText, FileOutcome, FileProcessor};
+    use klask_rs::services::search::{SearchQuery, SearchResult, SearchService};
+    use serde_json::json;
+    use std::fs;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn repository(url: &str) -> Repository {
+        common::repository(json!({ "name": "notebooks", "url": url, "repositoryType": "FileSystem" }))
+    }
+
+    /// Minimal PDF with one page of text per entry
+    fn pdf_bytes(pages: &[&str]) -> Vec<u8> {
+        let page_count = pages.len();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..page_count).map(|i| format!("{} 0 R", 4 + 2 * i)).collect::<Vec<_>>().join(" "),
+                page_count
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+        for (i, text) in pages.iter().enumerate() {
+            let stream = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                5 + 2 * i
+            ));
+            objects.push(format!("<< /Length {} >>\nstream\n{}\nendstream", stream.len(), stream));
+        }
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).into_bytes());
+        }
+        let xref = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets {
+            pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+        }
+        pdf.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .into_bytes(),
+        );
+        pdf
+    }
+
+    async fn search(service: &SearchService, query: &str) -> Vec<SearchResult> {
+        service.search(SearchQuery { query: query.to_string(), limit: 10, ..Default::default() }).await.unwrap().results
+    }
+
+    #[tokio::test]
+    async fn test_notebooks_and_pdfs_are_indexed_with_their_text() {
+        let index_dir = TempDir::new().unwrap();
+        let service = Arc::new(SearchService::new(index_dir.path().join("index")).unwrap());
+        let root = TempDir::new().unwrap();
+        let repository = repository(&root.path().to_string_lossy());
+        let processor = FileProcessor::new(service.clone());
+
+        let notebook = serde_json::json!({
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Churn model"]},
+                {"cell_type": "code", "source": ["import pandas as pd\n", "df = pd.read_csv('churn.csv')"],
+                 "execution_count": 1, "outputs": [{"output_type": "stream", "text": ["3000 rows"]}]}
+            ],
+            "metadata": {"kernelspec": {"display_name": "Python 3"}}
+        });
+        fs::write(root.path().join("churn.ipynb"), notebook.to_string()).unwrap();
+        fs::write(
+            root.path().join("manual.pdf"),
+            pdf_bytes(&["Installation guide", "Rollback procedure"]),
+        )
+        .unwrap();
+
+        for path in ["churn.ipynb", "manual.pdf"] {
+            let indexed = processor
+                .process_single_file(&repository, &root.path().join(path), path, "main", None, None)
+                .await
+                .unwrap();
+            assert_eq!(indexed, FileOutcome::Indexed, "{}", path);
+        }
+        service.commit().await.unwrap();
+
+        let results = search(&service, "read_csv").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_path, "churn.ipynb");
+        assert_eq!(results[0].line_number, Some(4));
+        assert_eq!(results[0].source_location.as_deref(), Some("cell 2 (code)"));
+
+        // Outputs and notebook metadata are not indexed
+        assert!(search(&service, "rows").await.is_empty());
+        assert!(search(&service, "kernelspec").await.is_empty());
+
+        let results = search(&service, "rollback").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].extension, "pdf");
+        assert_eq!(results[0].source_location.as_deref(), Some("page 2"));
+    }
+
+    struct ShoutingExtractor;
+
+    impl ContentExtractor for ShoutingExtractor {
+        fn handles(&self, file_name: &str) -> bool {
+            file_name.ends_with(".txt")
+        }
+
+        fn extract(&self, bytes: &[u8]) -> Result<ExtractedText> {
+            Ok(String::from_utf8_lossy(bytes).to_uppercase().into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_extractor() {
+        let index_dir = TempDir::new().unwrap();
+        let service = Arc::new(SearchService::new(index_dir.path().join("index")).unwrap());
+        let root = TempDir::new().unwrap();
+        let repository = repository(&root.path().to_string_lossy());
+        let processor = FileProcessor::new(service.clone()).with_extractor(Arc::new(ShoutingExtractor));
+
+        fs::write(root.path().join("notes.txt"), "quiet words").unwrap();
+        assert_eq!(
+            processor
+                .process_single_file(
+                    &repository,
+                    &root.path().join("notes.txt"),
+                    "notes.txt",
+                    "main",
+                    None,
+                    None
+                )
+                .await
+                .unwrap(),
+            FileOutcome::Indexed
+        );
+        service.commit().await.unwrap();
+
+        let results = search(&service, "quiet").await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content_snippet.contains("QUIET WORDS"));
+        assert_eq!(results[0].source_location, None);
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+#[cfg(test)]
+mod encoding_tests {
+    use crate::common;
+    use klask_rs::models::Repository;
+    use klask_rs::services::crawler::file_processing::{FileOutcome, FileProcessor};
+    use klask_rs::services::search::{SearchQuery, SearchService};
+    use serde_json::json;
+    use std::fs;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn repository(url: &str, default_encoding: Option<&str>) -> Repository {
+        common::repository(json!({
+            "name": "legacy",
+            "url": url,
+            "repositoryType": "FileSystem",
+            "defaultEncoding": default_encoding
+        }))
+    }
+
+    async fn index_file(
+        service: &Arc<SearchService>,
+        repository: &Repository,
+        root: &TempDir,
+        path: &str,
+        bytes: &[u8],
+    ) -> bool {
+        let file_path = root.path().join(path);
+        fs::write(&file_path, bytes).unwrap();
+        FileProcessor::new(service.clone())
+            .process_single_file(repository, &file_path, path, "main", None, None)
+            .await
+            .unwrap()
+            == FileOutcome::Indexed
+    }
+
+    async fn search(service: &SearchService, query: &str) -> Vec<String> {
+        let results =
+            service.search(SearchQuery { query: query.to_string(), limit: 10, ..Default::default() }).await.unwrap();
+        results.results.into_iter().map(|r| r.content_snippet).collect()
+    }
+
+    #[tokio::test]
+    async fn test_legacy_encodings_are_transcoded() {
+        let index_dir = TempDir::new().unwrap();
+        let service = Arc::new(SearchService::new(index_dir.path().join("index")).unwrap());
+        let root = TempDir::new().unwrap();
+        let repository = repository(&root.path().to_string_lossy(), None);
+
+        // Windows-1252 Java source and UTF-16LE (with BOM) text
+        let java = b"// Gestion des p\xE9riodes comptables\npublic class Periode {}";
+        assert!(index_file(&service, &repository, &root, "Periode.java", java).await);
+        let mut notes = vec![0xFF, 0xFE];
+        notes.extend("Überweisung notes".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        assert!(index_file(&service, &repository, &root, "notes.txt", &notes).await);
+        service.commit().await.unwrap();
+
+        let snippets = search(&service, "périodes").await;
+        assert_eq!(snippets.len(), 1);
+        assert!(snippets[0].contains("Gestion des périodes comptables"));
+        assert_eq!(search(&service, "überweisung").await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_repository_default_encoding_and_binary_files() {
+        let index_dir = TempDir::new().unwrap();
+        let service = Arc::new(SearchService::new(index_dir.path().join("index")).unwrap());
+        let root = TempDir::new().unwrap();
+        let repository = repository(&root.path().to_string_lossy(), Some("ibm866"));
+
+        // "ПРОГРАММА" in IBM866 (DOS Cyrillic), which the content alone does not identify reliably
+        let cobol = b"* \x8F\x90\x8E\x83\x90\x80\x8C\x8C\x80\n       IDENTIFICATION DIVISION.";
+        assert!(index_file(&service, &repository, &root, "MAIN.cbl", cobol).await);
+
+        // Binary files are skipped, and reported as such
+        let binary = b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x03\0>\0";
+        assert!(!index_file(&service, &repository, &root, "tool.txt", binary).await);
+        service.commit().await.unwrap();
+
+        assert_eq!(search(&service, "программа").await.len(), 1);
+        assert_eq!(service.get_document_count().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_files_are_not_indexed() {
+        let index_dir = TempDir::new().unwrap();
+        let service = Arc::new(SearchService::new(index_dir.path().join("index")).unwrap());
+        let root = TempDir::new().unwrap();
+        let repository = repository(&root.path().to_string_lossy(), None);
+
+        // Removed between the directory walk and the read
+        let result = FileProcessor::new(service.clone())
+            .process_single_file(
+                &repository,
+                &root.path().join("gone.txt"),
+                "gone.txt",
+                "main",
+                None,
+                None,
+            )
+            .await;
+        assert!(result.unwrap_err().to_string().contains("gone.txt"));
+        service.commit().await.unwrap();
+        assert_eq!(service.get_document_count().unwrap(), 0);
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod file_inclusion_tests {
+    use klask_rs::models::FileInclusionRules;
+    use klask_rs::services::crawler::file_processing::FileProcessor;
+    use klask_rs::services::crawler::large_files::LargeFilePolicy;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_tree() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        for (path, content) in [
+            ("src/main.rs", "fn main() {}"),
+            ("infra/main.tf", "resource \"aws_s3_bucket\" \"b\" {}"),
+            ("api/service.proto", "syntax = \"proto3\";"),
+            ("build.gradle.kts", "plugins { kotlin(\"jvm\") }"),
+            ("deploy/prod.env", "PORT=8080"),
+            ("vendor/lib/util.go", "package util"),
+            ("assets/logo.png", "PNG"),
+            (".github/workflows/ci.yml", "on: push"),
+        ] {
+            let file_path = temp_dir.path().join(path);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(file_path, content).unwrap();
+        }
+        temp_dir
+    }
+
+    fn collect(temp_dir: &TempDir, rules: &FileInclusionRules) -> Vec<String> {
+        let mut files: Vec<String> =
+            FileProcessor::collect_indexable_files(temp_dir.path(), rules, LargeFilePolicy::default())
+                .unwrap()
+                .into_iter()
+                .map(|(_, relative_path)| relative_path)
+                .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_default_rules_index_infrastructure_files() {
+        let temp_dir = create_tree();
+        let files = collect(&temp_dir, &FileInclusionRules::default());
+
+        assert_eq!(
+            files,
+            ["api/service.proto", "build.gradle.kts", "infra/main.tf", "src/main.rs", "vendor/lib/util.go"]
+        );
+    }
+
+    #[test]
+    fn test_custom_rules_add_and_exclude_paths() {
+        let temp_dir = create_tree();
+        let rules = FileInclusionRules {
+            path_patterns: vec!["deploy/*.env".to_string()],
+            excluded_path_patterns: vec!["vendor/*".to_string()],
+            ..Default::default()
+        };
+        let files = collect(&temp_dir, &rules);
+
+        assert!(files.contains(&"deploy/prod.env".to_string()));
+        assert!(!files.iter().any(|f| f.starts_with("vendor/")));
+        assert!(
+            !files.iter().any(|f| f.starts_with(".github/")),
+            "Hidden directories are never indexed"
+        );
+    }
+
+    #[test]
+    fn test_partial_rules_fall_back_to_defaults() {
+        let rules: FileInclusionRules = serde_json::from_str(r#"{"extensions": ["rs"]}"#).unwrap();
+
+        assert_eq!(rules.extensions, ["rs"]);
+        assert_eq!(rules.file_names, FileInclusionRules::default().file_names);
+        assert!(rules.is_included("Dockerfile"));
+        assert!(!rules.is_included("main.py"));
+    }
+}
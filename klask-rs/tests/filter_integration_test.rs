@@ -1,9 +1,9 @@
-/// Integration tests for the filter system
-/// Tests the integration of filtering logic with crawlers (GitLab and GitHub)
-///
-/// Note: These tests focus on the filtering logic integration without requiring
-/// actual database or network connections. They validate the behavior of filters
-/// when applied to discovered projects/repositories during crawling.
+//! Integration tests for the filter system
+//! Tests the integration of filtering logic with crawlers (GitLab and GitHub)
+//!
+//! Note: These tests focus on the filtering logic integration without requiring
+//! actual database or network connections. They validate the behavior of filters
+//! when applied to discovered projects/repositories during crawling.
 
 // Test utilities for creating mock data structures
 /// Mock GitLab project structure
@@ -820,6 +820,6 @@ mod edge_case_tests {
             true
         };
 
-        items.into_iter().filter(|item| pattern.map_or(true, |p| matches_pattern(item, p))).collect()
+        items.into_iter().filter(|item| pattern.is_none_or(|p| matches_pattern(item, p))).collect()
     }
 }
@@ -0,0 +1,139 @@
+use httpmock::prelude::*;
+use klask_rs::models::RepositoryType;
+use klask_rs::services::gitea::GiteaService;
+use serde_json::json;
+
+#[cfg(test)]
+mod gitea_tests {
+    use super::*;
+
+    fn gitea_repository(server: &MockServer, owner: &str, name: &str, id: i64) -> serde_json::Value {
+        json!({
+            "id": id,
+            "name": name,
+            "full_name": format!("{}/{}", owner, name),
+            "description": "",
+            "default_branch": "main",
+            "clone_url": server.url(format!("/{}/{}.git", owner, name)),
+            "ssh_url": format!("git@{}:{}/{}.git", server.host(), owner, name),
+            "html_url": server.url(format!("/{}/{}", owner, name)),
+            "private": false,
+            "archived": false,
+            "empty": false,
+            "mirror": false,
+            "owner": { "id": 1, "login": owner }
+        })
+    }
+
+    #[test]
+    fn test_gitea_repository_type_serialization() {
+        assert_eq!(serde_json::to_value(RepositoryType::Gitea).unwrap(), json!("Gitea"));
+        let parsed: RepositoryType = serde_json::from_value(json!("Gitea")).unwrap();
+        assert!(matches!(parsed, RepositoryType::Gitea));
+    }
+
+    #[tokio::test]
+    async fn test_discover_organization_repositories() {
+        let server = MockServer::start_async().await;
+        let mut archived = gitea_repository(&server, "platform", "old", 2);
+        archived["archived"] = json!(true);
+        let mut empty = gitea_repository(&server, "platform", "new", 3);
+        empty["empty"] = json!(true);
+
+        let first_page = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/api/v1/orgs/platform/repos")
+                    .query_param("page", "1")
+                    .header("Authorization", "token gitea-token");
+                then.status(200).json_body(json!([
+                    gitea_repository(&server, "platform", "api", 1),
+                    archived,
+                    empty
+                ]));
+            })
+            .await;
+        let second_page = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/orgs/platform/repos").query_param("page", "2");
+                then.status(200).json_body(json!([]));
+            })
+            .await;
+
+        let service = GiteaService::new();
+        let repositories =
+            service.discover_repositories(&server.base_url(), "gitea-token", Some("platform")).await.unwrap();
+
+        // Archived and empty repositories are dropped
+        assert_eq!(repositories.len(), 1);
+        assert_eq!(repositories[0].full_name, "platform/api");
+        assert_eq!(repositories[0].clone_url, server.url("/platform/api.git"));
+        assert_eq!(repositories[0].default_branch.as_deref(), Some("main"));
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discover_user_repositories_without_organization() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/user/repos").query_param("page", "1");
+                then.status(200).json_body(json!([
+                    gitea_repository(&server, "alice", "dotfiles", 1),
+                    gitea_repository(&server, "platform", "api", 2)
+                ]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/user/repos").query_param("page", "2");
+                then.status(200).json_body(json!([]));
+            })
+            .await;
+
+        let service = GiteaService::new();
+        let repositories = service.discover_repositories(&server.base_url(), "gitea-token", Some("")).await.unwrap();
+
+        let names: Vec<&str> = repositories.iter().map(|r| r.full_name.as_str()).collect();
+        assert_eq!(names, vec!["alice/dotfiles", "platform/api"]);
+    }
+
+    #[tokio::test]
+    async fn test_token_validation() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/user").header("Authorization", "token good");
+                then.status(200).json_body(json!({ "id": 1, "login": "alice" }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/user").header("Authorization", "token bad");
+                then.status(401).json_body(json!({ "message": "user does not exist" }));
+            })
+            .await;
+
+        let service = GiteaService::new();
+        assert!(service.test_token(&server.base_url(), "good").await.unwrap());
+        assert!(!service.test_token(&server.base_url(), "bad").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_discovery_error_is_reported() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v1/orgs/missing/repos");
+                then.status(404).json_body(json!({ "message": "GetOrgByName" }));
+            })
+            .await;
+
+        let service = GiteaService::new();
+        let error =
+            service.discover_repositories(&server.base_url(), "gitea-token", Some("missing")).await.unwrap_err();
+        assert!(error.to_string().contains("404"));
+    }
+}
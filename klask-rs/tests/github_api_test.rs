@@ -149,6 +149,26 @@ async fn test_github_repository_model_fields() {
         included_branches_patterns: None,
         included_projects: None,
         included_projects_patterns: None,
+        included_extensions: None,
+        included_file_names: None,
+        included_path_patterns: None,
+        excluded_path_patterns: None,
+        default_encoding: None,
+        index_archives: None,
+        max_file_size_mb: None,
+        large_file_mode: None,
+        excluded_projects: None,
+        excluded_projects_patterns: None,
+        bitbucket_project_key: None,
+        gitea_organization: None,
+        svn_revision: None,
+        ssh_private_key: None,
+        ssh_known_hosts: None,
+        index_submodules: None,
+        fetch_lfs_objects: None,
+        index_merge_requests: None,
+        webhook_secret: None,
+        github_url: None,
     };
 
     // Verify GitHub fields are set correctly
@@ -0,0 +1,201 @@
+mod common;
+
+use httpmock::prelude::*;
+use klask_rs::services::github::{DEFAULT_GITHUB_API_URL, GitHubService};
+use serde_json::json;
+
+#[cfg(test)]
+mod github_enterprise_tests {
+    use super::*;
+
+    fn enterprise_repository(
+        server: &MockServer,
+        owner: &str,
+        name: &str,
+        id: i64,
+        archived: bool,
+    ) -> serde_json::Value {
+        json!({
+            "id": id,
+            "name": name,
+            "full_name": format!("{}/{}", owner, name),
+            "description": null,
+            "default_branch": "main",
+            "clone_url": server.url(format!("/{}/{}.git", owner, name)),
+            "ssh_url": format!("git@{}:{}/{}.git", server.host(), owner, name),
+            "html_url": server.url(format!("/{}/{}", owner, name)),
+            "private": true,
+            "archived": archived,
+            "owner": { "login": owner, "type": "Organization" }
+        })
+    }
+
+    #[test]
+    fn test_enterprise_url_resolves_to_api_v3() {
+        let service = GitHubService::new();
+        assert_eq!(service.api_url(), DEFAULT_GITHUB_API_URL);
+
+        let service = GitHubService::new().with_api_url("https://github.example.com/");
+        assert_eq!(service.api_url(), "https://github.example.com/api/v3");
+
+        let service = GitHubService::new().with_api_url("https://github.example.com/api/v3");
+        assert_eq!(service.api_url(), "https://github.example.com/api/v3");
+    }
+
+    #[tokio::test]
+    async fn test_token_against_enterprise_server() {
+        let server = MockServer::start_async().await;
+        let user = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/user").header("Authorization", "Bearer ghe-token");
+                then.status(200).json_body(json!({ "login": "octocat" }));
+            })
+            .await;
+
+        let service = GitHubService::new().with_api_url(&server.base_url());
+        assert!(service.test_token("ghe-token").await.unwrap());
+        user.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_against_enterprise_server() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/user");
+                then.status(401).json_body(json!({ "message": "Bad credentials" }));
+            })
+            .await;
+
+        let service = GitHubService::new().with_api_url(&server.base_url());
+        assert!(!service.test_token("expired").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_discover_org_repositories_pages_through_enterprise_api() {
+        let server = MockServer::start_async().await;
+        let first_page = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/orgs/platform/repos").query_param("page", "1");
+                then.status(200).header("x-ratelimit-remaining", "4999").json_body(json!([
+                    enterprise_repository(&server, "platform", "api", 1, false),
+                    enterprise_repository(&server, "platform", "legacy", 2, true),
+                ]));
+            })
+            .await;
+        let second_page = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/orgs/platform/repos").query_param("page", "2");
+                then.status(200).json_body(json!([enterprise_repository(&server, "platform", "web", 3, false)]));
+            })
+            .await;
+        let last_page = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/orgs/platform/repos").query_param("page", "3");
+                then.status(200).json_body(json!([]));
+            })
+            .await;
+
+        let service = GitHubService::new().with_api_url(&server.url("/api/v3"));
+        let repositories = service.discover_repositories("ghe-token", Some("platform")).await.unwrap();
+
+        // Archived repositories are dropped, clone URLs point at the Enterprise host
+        let names: Vec<&str> = repositories.iter().map(|r| r.full_name.as_str()).collect();
+        assert_eq!(names, vec!["platform/api", "platform/web"]);
+        assert_eq!(repositories[0].clone_url, server.url("/platform/api.git"));
+
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+        last_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discover_user_repositories_on_enterprise_server() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/api/v3/user/repos")
+                    .query_param("affiliation", "owner,organization_member")
+                    .query_param("page", "1");
+                then.status(200).json_body(json!([enterprise_repository(&server, "octocat", "dotfiles", 7, false)]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/user/repos").query_param("page", "2");
+                then.status(200).json_body(json!([]));
+            })
+            .await;
+
+        let service = GitHubService::new().with_api_url(&server.base_url());
+        let repositories = service.discover_repositories("ghe-token", None).await.unwrap();
+
+        assert_eq!(repositories.len(), 1);
+        assert_eq!(repositories[0].full_name, "octocat/dotfiles");
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_rate_limit_is_reported_as_error() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/user");
+                then.status(403)
+                    .header("x-ratelimit-remaining", "0")
+                    .header("x-ratelimit-reset", "1700000000")
+                    .json_body(json!({ "message": "API rate limit exceeded" }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/orgs/platform/repos");
+                then.status(403)
+                    .header("x-ratelimit-remaining", "0")
+                    .json_body(json!({ "message": "API rate limit exceeded" }));
+            })
+            .await;
+
+        let service = GitHubService::new().with_api_url(&server.base_url());
+
+        let token_error = service.test_token("ghe-token").await.unwrap_err();
+        assert!(token_error.to_string().contains("rate limit"));
+
+        let discover_error = service.discover_repositories("ghe-token", Some("platform")).await.unwrap_err();
+        assert!(discover_error.to_string().contains("rate limit"));
+    }
+
+    #[test]
+    fn test_repository_under_clone_url_uses_github_com() {
+        // The url of a repository is never read as the API address
+        for url in ["git@github.com:acme/app.git", "https://github.example.com/acme/app.git", ""] {
+            let repository = common::repository(json!({ "name": "acme", "url": url, "repositoryType": "GitHub" }));
+            assert_eq!(
+                GitHubService::for_repository(&repository).api_url(),
+                DEFAULT_GITHUB_API_URL
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repository_github_url_reaches_enterprise_server() {
+        let server = MockServer::start_async().await;
+        let user = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/user").header("Authorization", "Bearer ghe-token");
+                then.status(200).json_body(json!({ "login": "octocat" }));
+            })
+            .await;
+
+        let repository = common::repository(json!({
+            "name": "acme",
+            "url": "git@github.com:acme/app.git",
+            "repositoryType": "GitHub",
+            "githubUrl": server.base_url()
+        }));
+        let service = GitHubService::for_repository(&repository);
+        assert_eq!(service.api_url(), format!("{}/api/v3", server.base_url()));
+        assert!(service.test_token("ghe-token").await.unwrap());
+        user.assert_async().await;
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod ignore_files_tests {
+    use klask_rs::models::FileInclusionRules;
+    use klask_rs::services::crawler::file_processing::FileProcessor;
+    use klask_rs::services::crawler::large_files::LargeFilePolicy;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_tree(files: &[(&str, &str)]) -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        for (path, content) in files {
+            let file_path = temp_dir.path().join(path);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(file_path, content).unwrap();
+        }
+        temp_dir
+    }
+
+    fn collect(temp_dir: &TempDir) -> Vec<String> {
+        let mut files: Vec<String> = FileProcessor::collect_indexable_files(
+            temp_dir.path(),
+            &FileInclusionRules::default(),
+            LargeFilePolicy::default(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|(_, relative_path)| relative_path)
+        .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_filesystem_walk_honors_ignore_files() {
+        let temp_dir = create_tree(&[
+            (".gitignore", "node_modules/\n*.log.txt"),
+            (".klaskignore", "docs/generated/"),
+            (
+                ".gitattributes",
+                "*.pb.go linguist-generated\nthird_party/** linguist-vendored\n",
+            ),
+            ("src/main.go", "package main"),
+            ("src/service.pb.go", "// Code generated by protoc-gen-go. DO NOT EDIT."),
+            ("third_party/lib/util.go", "package lib"),
+            ("node_modules/left-pad/index.js", "module.exports = leftPad"),
+            ("docs/generated/api.md", "# API"),
+            ("docs/guide.md", "# Guide"),
+            ("build.log.txt", "ok"),
+        ]);
+
+        assert_eq!(collect(&temp_dir), ["docs/guide.md", "src/main.go"]);
+    }
+
+    #[test]
+    fn test_nested_ignore_file_overrides_parent() {
+        let temp_dir = create_tree(&[
+            (".klaskignore", "*.json"),
+            ("web/.klaskignore", "!package.json"),
+            ("web/package.json", "{}"),
+            ("web/data/fixtures.json", "{}"),
+            ("data/seed.json", "{}"),
+        ]);
+
+        assert_eq!(collect(&temp_dir), ["web/package.json"]);
+    }
+
+    #[test]
+    fn test_ignore_files_in_hidden_directories_are_not_applied() {
+        let temp_dir = create_tree(&[(".github/.klaskignore", "*.rs"), ("src/lib.rs", "pub fn run() {}")]);
+
+        assert_eq!(collect(&temp_dir), ["src/lib.rs"]);
+    }
+}
@@ -0,0 +1,102 @@
+mod common;
+
+#[cfg(test)]
+mod large_files_tests {
+    use crate::common;
+    use klask_rs::models::Repository;
+    use klask_rs::services::crawler::file_processing::{FileOutcome, FileProcessor};
+    use klask_rs::services::search::{SearchQuery, SearchResult, SearchService};
+    use serde_json::json;
+    use std::fs;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn repository(url: &str, large_file_mode: &str) -> Repository {
+        common::repository(json!({
+            "name": "dumps",
+            "url": url,
+            "repositoryType": "FileSystem",
+            "maxFileSizeMb": 1,
+            "largeFileMode": large_file_mode
+        }))
+    }
+
+    /// SQL dump of about 1.6 MB, with `firstmarker` on line 10 and `lastmarker` on line 25000
+    fn dump() -> String {
+        (1..=25_000)
+            .map(|line| match line {
+                10 => "INSERT INTO audit VALUES ('firstmarker');\n".to_string(),
+                25_000 => "INSERT INTO audit VALUES ('lastmarker');\n".to_string(),
+                _ => format!(
+                    "INSERT INTO events VALUES ({}, 'filler row with padding text');\n",
+                    line
+                ),
+            })
+            .collect()
+    }
+
+    async fn index_dump(mode: &str) -> (Arc<SearchService>, TempDir, FileOutcome) {
+        let index_dir = TempDir::new().unwrap();
+        let service = Arc::new(SearchService::new(index_dir.path().join("index")).unwrap());
+        let root = TempDir::new().unwrap();
+        fs::write(root.path().join("dump.sql"), dump()).unwrap();
+
+        let repository = repository(&root.path().to_string_lossy(), mode);
+        let outcome = FileProcessor::new(service.clone())
+            .process_single_file(
+                &repository,
+                &root.path().join("dump.sql"),
+                "dump.sql",
+                "main",
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        service.commit().await.unwrap();
+        (service, index_dir, outcome)
+    }
+
+    async fn search(service: &SearchService, query: &str) -> Vec<SearchResult> {
+        service.search(SearchQuery { query: query.to_string(), limit: 10, ..Default::default() }).await.unwrap().results
+    }
+
+    #[tokio::test]
+    async fn test_large_files_are_skipped_by_default() {
+        let (service, _index_dir, outcome) = index_dump("skip").await;
+        assert_eq!(outcome, FileOutcome::TooLarge);
+        assert_eq!(service.get_document_count().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_large_files() {
+        let (service, _index_dir, outcome) = index_dump("truncate").await;
+        assert_eq!(outcome, FileOutcome::Indexed);
+        assert_eq!(service.get_document_count().unwrap(), 1);
+
+        let results = search(&service, "firstmarker").await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_truncated);
+        assert_eq!(results[0].line_number, Some(10));
+        assert!(search(&service, "lastmarker").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_chunked_large_files_map_to_file_lines() {
+        let (service, _index_dir, outcome) = index_dump("chunk").await;
+        assert_eq!(outcome, FileOutcome::Indexed);
+        assert_eq!(service.get_document_count().unwrap(), 2);
+
+        let results = search(&service, "lastmarker").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_path, "dump.sql");
+        assert!(!results[0].is_truncated);
+        assert!(results[0].line_offset > 0);
+        assert_eq!(results[0].line_number, Some(25_000));
+        assert_eq!(results[0].size, dump().len() as u64);
+
+        let results = search(&service, "firstmarker").await;
+        assert_eq!(results[0].line_offset, 0);
+        assert_eq!(results[0].line_number, Some(10));
+    }
+}
@@ -24,6 +24,7 @@ async fn test_regex_case_sensitivity() {
             version: "main",
             extension: "txt",
             size: 12,
+            ..Default::default()
         },
         FileData {
             file_id: Uuid::new_v4(),
@@ -35,6 +36,7 @@ async fn test_regex_case_sensitivity() {
             version: "main",
             extension: "txt",
             size: 12,
+            ..Default::default()
         },
         FileData {
             file_id: Uuid::new_v4(),
@@ -46,6 +48,7 @@ async fn test_regex_case_sensitivity() {
             version: "main",
             extension: "txt",
             size: 12,
+            ..Default::default()
         },
     ];
 
@@ -132,6 +135,7 @@ async fn test_regex_multiple_flags() {
         version: "main",
         extension: "txt",
         size: 22,
+        ..Default::default()
     };
 
     service.upsert_file(file).await.unwrap();
@@ -169,6 +173,7 @@ async fn test_regex_invalid_flags_ignored() {
         version: "main",
         extension: "txt",
         size: 7,
+        ..Default::default()
     };
 
     service.upsert_file(file).await.unwrap();
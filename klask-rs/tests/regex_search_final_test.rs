@@ -29,6 +29,7 @@ mod regex_search_final_tests {
                 version: "1.0",
                 extension: "rs",
                 size: 256,
+                ..Default::default()
             },
             FileData {
                 file_id: Uuid::new_v4(),
@@ -40,6 +41,7 @@ mod regex_search_final_tests {
                 version: "1.0",
                 extension: "rs",
                 size: 256,
+                ..Default::default()
             },
             FileData {
                 file_id: Uuid::new_v4(),
@@ -51,6 +53,7 @@ mod regex_search_final_tests {
                 version: "1.0",
                 extension: "rs",
                 size: 256,
+                ..Default::default()
             },
             FileData {
                 file_id: Uuid::new_v4(),
@@ -62,6 +65,7 @@ mod regex_search_final_tests {
                 version: "1.0",
                 extension: "rs",
                 size: 512,
+                ..Default::default()
             },
             FileData {
                 file_id: Uuid::new_v4(),
@@ -73,6 +77,7 @@ mod regex_search_final_tests {
                 version: "1.0",
                 extension: "rs",
                 size: 512,
+                ..Default::default()
             },
             FileData {
                 file_id: Uuid::new_v4(),
@@ -84,6 +89,7 @@ mod regex_search_final_tests {
                 version: "1.0",
                 extension: "rs",
                 size: 256,
+                ..Default::default()
             },
         ];
 
@@ -264,6 +270,11 @@ mod regex_search_final_tests {
             regex_flags: None,
             min_size: Some(200),
             max_size: Some(600),
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 100,
             ..Default::default()
         };
@@ -498,7 +509,7 @@ mod regex_search_final_tests {
         for result in &results.results {
             // Snippets should be generated (either highlighted or plain)
             assert!(
-                !result.content_snippet.is_empty() || result.file_name.len() > 0,
+                !result.content_snippet.is_empty() || !result.file_name.is_empty(),
                 "Should have content snippet or file info"
             );
         }
@@ -519,6 +530,7 @@ mod regex_search_final_tests {
             version: "1.0",
             extension: "rs",
             size: large_content.len() as u64,
+            ..Default::default()
         };
 
         service.upsert_file(file).await.expect("Failed to index");
@@ -0,0 +1,264 @@
+mod common;
+
+use httpmock::prelude::*;
+use klask_rs::models::{FileInclusionRules, Repository};
+use klask_rs::services::crawler::CrawlProgress;
+use klask_rs::services::crawler::branch_processor::BranchProcessor;
+use klask_rs::services::crawler::git_operations::GitOperations;
+use klask_rs::services::crawler::git_tree_walker::GitTreeWalker;
+use klask_rs::services::crawler::review_refs::{ReviewRef, fetched_review_versions, is_review_version};
+use klask_rs::services::encryption::EncryptionService;
+use klask_rs::services::github::GitHubService;
+use klask_rs::services::gitlab::GitLabService;
+use klask_rs::services::progress::ProgressTracker;
+use klask_rs::services::search::{SearchQuery, SearchService};
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, RwLock};
+use tempfile::TempDir;
+use tokio_util::sync::CancellationToken;
+
+#[cfg(test)]
+mod review_refs_tests {
+    use super::*;
+
+    fn git(directory: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(directory)
+            .args(["-c", "user.name=Klask", "-c", "user.email=klask@example.com"])
+            .args(args)
+            .output()
+            .expect("git should be installed");
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// Create a repository with a `main` branch and pull request heads `refs/pull/<n>/head`, each adding a file
+    fn create_repository(directory: &Path, pull_requests: &[u64]) {
+        std::fs::create_dir_all(directory).unwrap();
+        git(directory, &["init", "-q", "-b", "main"]);
+        std::fs::write(directory.join("main.rs"), "fn main() {}").unwrap();
+        git(directory, &["add", "-A"]);
+        git(directory, &["commit", "-q", "-m", "Initial commit"]);
+
+        for number in pull_requests {
+            git(
+                directory,
+                &["checkout", "-q", "-b", &format!("feature-{}", number), "main"],
+            );
+            std::fs::write(
+                directory.join(format!("feature_{}.rs", number)),
+                format!("fn reviewed_feature_{}() {{}}", number),
+            )
+            .unwrap();
+            git(directory, &["add", "-A"]);
+            git(directory, &["commit", "-q", "-m", "Feature"]);
+            // Pull request heads are not branches, only their refs are advertised
+            git(
+                directory,
+                &["update-ref", &format!("refs/pull/{}/head", number), "HEAD"],
+            );
+            git(directory, &["checkout", "-q", "main"]);
+            git(directory, &["branch", "-q", "-D", &format!("feature-{}", number)]);
+        }
+    }
+
+    fn git_repository(url: &str) -> Repository {
+        common::repository(json!({
+            "id": "7c1f4a52-3b9e-4d8a-9f61-2e5b8c0d4a19",
+            "name": "acme/app",
+            "url": url,
+            "branch": "main",
+            "indexMergeRequests": true
+        }))
+    }
+
+    fn encryption_service() -> Arc<EncryptionService> {
+        Arc::new(EncryptionService::new("test-encryption-key-32bytes").unwrap())
+    }
+
+    #[test]
+    fn test_review_versions() {
+        let merge_request = ReviewRef::merge_request(123);
+        assert_eq!(merge_request.version, "MR!123");
+        assert_eq!(
+            merge_request.refspec(),
+            "+refs/merge-requests/123/head:refs/klask/reviews/MR!123"
+        );
+
+        let pull_request = ReviewRef::pull_request(45);
+        assert_eq!(pull_request.version, "PR#45");
+        assert_eq!(pull_request.local_ref(), "refs/klask/reviews/PR#45");
+
+        assert!(is_review_version("MR!123"));
+        assert!(is_review_version("PR#45"));
+        assert!(!is_review_version("PR#"));
+        assert!(!is_review_version("MR!12a"));
+        assert!(!is_review_version("main"));
+        assert!(!is_review_version("feature/MR!123"));
+    }
+
+    #[tokio::test]
+    async fn test_open_merge_requests_from_gitlab() {
+        let server = MockServer::start_async().await;
+        let first_page = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/api/v4/projects/42/merge_requests")
+                    .query_param("state", "opened")
+                    .query_param("page", "1")
+                    .header("Authorization", "Bearer gitlab-token");
+                then.status(200).json_body(json!((1..=100).map(|iid| json!({ "iid": iid })).collect::<Vec<_>>()));
+            })
+            .await;
+        let second_page = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v4/projects/42/merge_requests").query_param("page", "2");
+                then.status(200).json_body(json!([{ "iid": 101, "title": "Last one" }]));
+            })
+            .await;
+
+        let iids = GitLabService::new().get_open_merge_requests(&server.base_url(), "gitlab-token", 42).await.unwrap();
+        assert_eq!(iids, (1..=101).collect::<Vec<u64>>());
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_open_pull_requests_from_github() {
+        let server = MockServer::start_async().await;
+        let pulls = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/api/v3/repos/acme/app/pulls")
+                    .query_param("state", "open")
+                    .header("Authorization", "Bearer github-token");
+                then.status(200).json_body(json!([{ "number": 45 }, { "number": 7 }]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/api/v3/repos/acme/missing/pulls");
+                then.status(404).json_body(json!({ "message": "Not Found" }));
+            })
+            .await;
+
+        let service = GitHubService::new().with_api_url(&server.base_url());
+        assert_eq!(
+            service.get_open_pull_requests("github-token", "acme/app").await.unwrap(),
+            vec![45, 7]
+        );
+        pulls.assert_async().await;
+        assert!(service.get_open_pull_requests("github-token", "acme/missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_open_reviews_are_fetched_and_closed_ones_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        create_repository(&origin, &[1, 2, 3]);
+
+        let repository = git_repository(origin.to_str().unwrap());
+        let clone_path = temp_dir.path().join("clone");
+        let operations = GitOperations::new(encryption_service(), Arc::new(RwLock::new(None)));
+        operations.clone_or_update_repository(&repository, &clone_path).await.unwrap();
+
+        let reviews = [ReviewRef::pull_request(1), ReviewRef::pull_request(2)];
+        operations.fetch_review_refs(&repository, &clone_path, &reviews).await.unwrap();
+
+        let git_repo = gix::open(&clone_path).unwrap();
+        assert_eq!(fetched_review_versions(&git_repo).unwrap(), vec!["PR#1", "PR#2"]);
+        let tree_id = GitTreeWalker::get_branch_tree_id(&git_repo, "PR#2").unwrap();
+        let mut paths: Vec<String> =
+            GitTreeWalker::walk_tree(&git_repo, &tree_id, "", 0).unwrap().into_iter().map(|file| file.path).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["feature_2.rs", "main.rs"]);
+        // Review heads are not listed as branches
+        assert_eq!(GitTreeWalker::get_all_branches(&git_repo).unwrap(), vec!["main"]);
+
+        // PR#1 was merged or closed, PR#3 is not fetched as it is not open
+        operations.fetch_review_refs(&repository, &clone_path, &[ReviewRef::pull_request(2)]).await.unwrap();
+        let git_repo = gix::open(&clone_path).unwrap();
+        assert_eq!(fetched_review_versions(&git_repo).unwrap(), vec!["PR#2"]);
+        assert!(GitTreeWalker::get_branch_tree_id(&git_repo, "PR#1").is_err());
+
+        operations.fetch_review_refs(&repository, &clone_path, &[]).await.unwrap();
+        assert!(fetched_review_versions(&gix::open(&clone_path).unwrap()).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_open_reviews_are_indexed_as_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        create_repository(&origin, &[45]);
+
+        let mut repository = git_repository(origin.to_str().unwrap());
+        let clone_path = temp_dir.path().join("clone");
+        let operations = GitOperations::new(encryption_service(), Arc::new(RwLock::new(None)));
+        operations.clone_or_update_repository(&repository, &clone_path).await.unwrap();
+        operations.fetch_review_refs(&repository, &clone_path, &[ReviewRef::pull_request(45)]).await.unwrap();
+
+        let search_service = Arc::new(SearchService::new(temp_dir.path().join("index")).unwrap());
+        let processor = BranchProcessor::new(
+            search_service.clone(),
+            Arc::new(ProgressTracker::new()),
+            Arc::new(RwLock::new(FileInclusionRules::default())),
+            encryption_service(),
+        );
+        let index = |repository: Repository| {
+            let processor = processor.clone();
+            let clone_path = clone_path.clone();
+            async move {
+                let mut progress =
+                    CrawlProgress { files_processed: 0, files_indexed: 0, files_skipped_binary: 0, errors: Vec::new() };
+                processor
+                    .process_all_branches_with_tracking(
+                        &repository,
+                        &clone_path,
+                        &mut progress,
+                        &CancellationToken::new(),
+                        repository.id,
+                        0,
+                        "acme",
+                    )
+                    .await
+                    .unwrap();
+                assert!(progress.errors.is_empty(), "{:?}", progress.errors);
+            }
+        };
+        let search = |query: &str| {
+            let search_service = search_service.clone();
+            let query = query.to_string();
+            async move {
+                let mut versions: Vec<String> = search_service
+                    .search(SearchQuery { query, limit: 10, ..Default::default() })
+                    .await
+                    .unwrap()
+                    .results
+                    .into_iter()
+                    .map(|result| result.version)
+                    .collect();
+                versions.sort();
+                versions
+            }
+        };
+
+        index(repository.clone()).await;
+        search_service.commit().await.unwrap();
+        assert_eq!(search("reviewed_feature_45").await, vec!["PR#45"]);
+        assert_eq!(search("main").await, vec!["PR#45", "main"]);
+
+        // Fetched heads are ignored once the option is turned off
+        search_service.delete_project_documents("acme").await.unwrap();
+        repository.index_merge_requests = None;
+        index(repository).await;
+        search_service.commit().await.unwrap();
+        assert!(search("reviewed_feature_45").await.is_empty());
+    }
+}
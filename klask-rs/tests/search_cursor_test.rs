@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod search_cursor_tests {
+    use klask_rs::services::search::{FileData, SearchQuery, SearchService, SortField};
+    use std::collections::HashSet;
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    /// Index identical files on several branches, so that relevance scores tie
+    async fn index_fixture(service: &SearchService) {
+        for branch in 0..5 {
+            for file in 0..5 {
+                let path = format!("src/module_{}.rs", file);
+                let version = format!("release-{}", branch);
+                service
+                    .upsert_file(FileData {
+                        file_id: Uuid::new_v4(),
+                        file_name: &path[4..],
+                        file_path: &path,
+                        content: "legacy_api();",
+                        repository: "legacy",
+                        project: "legacy",
+                        version: &version,
+                        extension: "rs",
+                        size: (file * 10) as u64,
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap();
+            }
+        }
+        service.commit().await.unwrap();
+    }
+
+    /// Walk all pages with the cursor, returning the visited file ids
+    async fn walk(service: &SearchService, sort_by: Option<SortField>, page_size: usize) -> Vec<Uuid> {
+        let mut query =
+            SearchQuery { query: "legacy_api".to_string(), sort_by, limit: page_size, ..Default::default() };
+        let mut visited = Vec::new();
+
+        loop {
+            let page = service.search(query.clone()).await.unwrap();
+            assert_eq!(page.total, 25, "Total ignores the cursor");
+            assert!(page.results.len() <= page_size);
+            visited.extend(page.results.iter().map(|r| r.file_id));
+
+            match page.next_cursor {
+                Some(cursor) => query.search_after = Some(cursor),
+                None => break,
+            }
+        }
+        visited
+    }
+
+    #[tokio::test]
+    async fn test_cursor_visits_every_match_once() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        for sort_by in [None, Some(SortField::Path), Some(SortField::Size), Some(SortField::Repository)] {
+            let visited = walk(&service, sort_by, 4).await;
+            let unique: HashSet<Uuid> = visited.iter().copied().collect();
+            assert_eq!(visited.len(), 25, "Every match is returned for {:?}", sort_by);
+            assert_eq!(unique.len(), 25, "No match is returned twice for {:?}", sort_by);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pages_match_offset_pages() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let all = service
+            .search(SearchQuery {
+                query: "legacy_api".to_string(),
+                sort_by: Some(SortField::Size),
+                limit: 25,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let expected: Vec<Uuid> = all.results.iter().map(|r| r.file_id).collect();
+        assert!(all.next_cursor.is_some(), "A full page may be followed by more hits");
+
+        assert_eq!(walk(&service, Some(SortField::Size), 7).await, expected);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_or_mismatched_cursor_is_rejected() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let bad = SearchQuery {
+            query: "legacy_api".to_string(),
+            search_after: Some("not-a-cursor".to_string()),
+            limit: 5,
+            ..Default::default()
+        };
+        assert!(bad.validate_cursor().is_err());
+        assert!(service.search(bad).await.is_err());
+
+        let page = service
+            .search(SearchQuery {
+                query: "legacy_api".to_string(),
+                sort_by: Some(SortField::Path),
+                limit: 5,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let mismatched = SearchQuery {
+            query: "legacy_api".to_string(),
+            sort_by: Some(SortField::Size),
+            search_after: page.next_cursor,
+            limit: 5,
+            ..Default::default()
+        };
+        assert!(mismatched.validate_cursor().is_err());
+    }
+}
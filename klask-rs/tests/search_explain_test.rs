@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod search_explain_tests {
+    use klask_rs::services::search::{FileData, SearchQuery, SearchService};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, path: &str, content: &str) {
+        let file_name = path.rsplit('/').next().unwrap();
+        service
+            .upsert_file(FileData {
+                file_id: Uuid::new_v4(),
+                file_name,
+                file_path: path,
+                content,
+                repository: "shop",
+                project: "shop",
+                version: "main",
+                extension: "rs",
+                size: content.len() as u64,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn doc_address_of(service: &SearchService, query: &str) -> String {
+        let results =
+            service.search(SearchQuery { query: query.to_string(), limit: 1, ..Default::default() }).await.unwrap();
+        results.results[0].doc_address.clone()
+    }
+
+    #[tokio::test]
+    async fn test_explain_matching_document() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index(&service, "src/checkout.rs", "fn checkout() { apply_discount(); }").await;
+        service.commit().await.unwrap();
+
+        let doc_address = doc_address_of(&service, "checkout").await;
+        let explanation = service
+            .explain(
+                SearchQuery { query: "checkout".to_string(), ..Default::default() },
+                doc_address.clone(),
+            )
+            .await
+            .unwrap()
+            .expect("Document should exist");
+
+        assert!(explanation.matches);
+        assert_eq!(explanation.doc_address, doc_address);
+        assert_eq!(explanation.file_path, "src/checkout.rs");
+        assert_eq!(explanation.matched_fields, ["file_name", "file_path", "content"]);
+        assert!(explanation.explanation.is_some());
+        assert!(explanation.bm25_score.unwrap() > 0.0);
+        let expected = explanation.bm25_score.unwrap() * explanation.ranking_multiplier;
+        assert_eq!(explanation.final_score, Some(expected));
+        assert!(explanation.parsed_query.contains("checkout"));
+    }
+
+    #[tokio::test]
+    async fn test_explain_reports_filtered_out_document() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index(&service, "src/checkout.rs", "fn checkout() { apply_discount(); }").await;
+        service.commit().await.unwrap();
+
+        let doc_address = doc_address_of(&service, "discount").await;
+        let query = SearchQuery {
+            query: "apply_discount".to_string(),
+            extension_filter: Some("java".to_string()),
+            ..Default::default()
+        };
+        let explanation = service.explain(query, doc_address).await.unwrap().unwrap();
+
+        // The text query matches the content, but the extension filter excludes the document
+        assert!(!explanation.matches);
+        assert!(explanation.explanation.is_none());
+        assert!(explanation.final_score.is_none());
+        assert_eq!(explanation.matched_fields, ["content"]);
+    }
+
+    #[tokio::test]
+    async fn test_explain_regex_query() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index(&service, "src/payments/gateway.rs", "struct Gateway;").await;
+        service.commit().await.unwrap();
+
+        let doc_address = doc_address_of(&service, "gateway").await;
+        let query = SearchQuery { query: ".*payments/gate.*".to_string(), regex_search: true, ..Default::default() };
+        let explanation = service.explain(query, doc_address).await.unwrap().unwrap();
+
+        assert!(explanation.matches);
+        assert_eq!(explanation.matched_fields, ["file_path"]);
+    }
+
+    #[tokio::test]
+    async fn test_explain_unknown_or_invalid_doc_address() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index(&service, "src/checkout.rs", "fn checkout() {}").await;
+        service.commit().await.unwrap();
+
+        let query = SearchQuery { query: "checkout".to_string(), ..Default::default() };
+        assert!(service.explain(query.clone(), "0:999".to_string()).await.unwrap().is_none());
+        assert!(service.explain(query, "not-an-address".to_string()).await.is_err());
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod search_generated_tests {
+    use klask_rs::services::search::{FileData, SearchQuery, SearchService};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, path: &str, content: &str) {
+        let file_name = path.rsplit('/').next().unwrap();
+        let extension = file_name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+        service
+            .upsert_file(FileData {
+                file_id: Uuid::new_v4(),
+                file_name,
+                file_path: path,
+                content,
+                repository: "web",
+                project: "web",
+                version: "main",
+                extension,
+                size: content.len() as u64,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn index_fixture(service: &SearchService) {
+        index(
+            service,
+            "src/client.js",
+            "export function fetchWidget(id) {\n  return api.get(id);\n}\n",
+        )
+        .await;
+        index(
+            service,
+            "src/widget.pb.js",
+            "// Code generated by protoc. DO NOT EDIT.\nvar fetchWidget;",
+        )
+        .await;
+        index(service, "package-lock.json", "{\"name\": \"fetchWidget\"}").await;
+        index(
+            service,
+            "dist/bundle.js",
+            &"function fetchWidget(a){return a+1};".repeat(100),
+        )
+        .await;
+        service.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_generated_files_are_excluded_by_default() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = service
+            .search(SearchQuery {
+                query: "fetchWidget".to_string(),
+                include_facets: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.total, 1);
+        assert_eq!(results.results[0].file_path, "src/client.js");
+        assert!(!results.results[0].is_generated);
+
+        // Facets only count what is shown, but report the hidden generated matches
+        let facets = results.facets.unwrap();
+        assert_eq!(facets.extensions, vec![("js".to_string(), 1)]);
+        assert_eq!(facets.generated, 3);
+    }
+
+    #[tokio::test]
+    async fn test_include_generated_toggle() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = service
+            .search(SearchQuery {
+                query: "fetchWidget".to_string(),
+                include_facets: true,
+                include_generated: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.total, 4);
+        let generated: Vec<&str> =
+            results.results.iter().filter(|r| r.is_generated).map(|r| r.file_path.as_str()).collect();
+        assert_eq!(generated.len(), 3);
+        assert!(!generated.contains(&"src/client.js"));
+
+        let facets = results.facets.unwrap();
+        assert_eq!(facets.generated, 3);
+        let js = facets.extensions.iter().find(|(ext, _)| ext == "js").map(|(_, count)| *count);
+        assert_eq!(js, Some(3));
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod search_highlight_tests {
+    use klask_rs::services::search::{FileData, SearchQuery, SearchResult, SearchService};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, path: &str, content: &str) {
+        let file_name = path.rsplit('/').next().unwrap();
+        service
+            .upsert_file(FileData {
+                file_id: Uuid::new_v4(),
+                file_name,
+                file_path: path,
+                content,
+                repository: "shop",
+                project: "shop",
+                version: "main",
+                extension: "rs",
+                size: content.len() as u64,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn first_result(service: &SearchService, query: &str) -> SearchResult {
+        let mut results =
+            service.search(SearchQuery { query: query.to_string(), limit: 1, ..Default::default() }).await.unwrap();
+        results.results.remove(0)
+    }
+
+    #[tokio::test]
+    async fn test_snippet_is_plain_text_with_byte_ranges() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index(
+            &service,
+            "src/compare.rs",
+            "fn check() {\n    if a < b && deprecated(\"x\") {}\n}",
+        )
+        .await;
+        service.commit().await.unwrap();
+
+        let result = first_result(&service, "deprecated").await;
+
+        assert!(!result.content_snippet.contains("<b>"));
+        assert!(result.content_snippet.contains("a < b && deprecated"));
+        assert_eq!(result.highlights.len(), 1);
+        let range = &result.highlights[0];
+        assert_eq!(&result.content_snippet[range.start..range.end], "deprecated");
+        assert_eq!(result.line_number, Some(2));
+        assert!(result.fragment_start_line.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_char_ranges_account_for_multibyte_text() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index(
+            &service,
+            "src/i18n.rs",
+            "// café déjà vu\nlet greeting = \"héllo\"; // naïve matcher",
+        )
+        .await;
+        service.commit().await.unwrap();
+
+        let result = first_result(&service, "matcher").await;
+        let range = &result.highlights[0];
+        let chars: Vec<char> = result.content_snippet.chars().collect();
+
+        assert_eq!(&result.content_snippet[range.start..range.end], "matcher");
+        assert_eq!(
+            chars[range.char_start..range.char_end].iter().collect::<String>(),
+            "matcher"
+        );
+        assert!(
+            range.char_start < range.start,
+            "Char offsets are smaller than byte offsets after multibyte text"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_line_number_after_text_that_changes_length_when_lowercased() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        // 'İ' is two bytes but lowercases to three, which shifts offsets in a lowercased copy
+        index(&service, "src/tr.rs", "// İİİİ İstanbul\nİ\nlet Needle = 1;\n").await;
+        service.commit().await.unwrap();
+
+        let result = first_result(&service, "needle").await;
+
+        assert_eq!(result.line_number, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_fragment_start_line_for_late_match() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        let mut content = String::new();
+        for i in 0..100 {
+            content.push_str(&format!("let filler_{} = {};\n", i, i));
+        }
+        content.push_str("let needle = find_needle();\n");
+        index(&service, "src/haystack.rs", &content).await;
+        service.commit().await.unwrap();
+
+        let result = first_result(&service, "needle").await;
+        let start_line = result.fragment_start_line.expect("Fragment should be located in the content");
+        let lines_in_fragment = result.content_snippet.matches('\n').count() as u32;
+
+        assert_eq!(result.line_number, Some(101));
+        assert!(start_line <= 101 && start_line + lines_in_fragment >= 101);
+        assert!(
+            start_line > 1,
+            "Fragment starts near the match, not at the top of the file"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback_excerpt_has_no_highlights() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index(&service, "src/payments/gateway.rs", "struct Gateway<T> { inner: T }").await;
+        service.commit().await.unwrap();
+
+        // Regex matches the path only, so there is nothing to highlight in the content
+        let results = service
+            .search(SearchQuery {
+                query: ".*payments/gate.*".to_string(),
+                regex_search: true,
+                limit: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let result = &results.results[0];
+
+        assert_eq!(result.content_snippet, "struct Gateway<T> { inner: T }");
+        assert!(result.highlights.is_empty());
+        assert_eq!(result.fragment_start_line, Some(1));
+    }
+}
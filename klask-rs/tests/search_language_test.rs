@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod search_language_tests {
+    use klask_rs::services::search::{FileData, SearchQuery, SearchService};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, path: &str, content: &str) {
+        let file_name = path.rsplit('/').next().unwrap();
+        let extension = file_name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+        service
+            .upsert_file(FileData {
+                file_id: Uuid::new_v4(),
+                file_name,
+                file_path: path,
+                content,
+                repository: "engine",
+                project: "engine",
+                version: "main",
+                extension,
+                size: content.len() as u64,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn index_fixture(service: &SearchService) {
+        index(service, "src/buffer.h", "struct buffer { int size; }; // allocate").await;
+        index(service, "src/pool.h", "namespace mem { class Pool; } // allocate").await;
+        index(service, "src/pool.cpp", "void mem::Pool::allocate() {}").await;
+        index(service, "Dockerfile", "FROM alpine\nRUN ./allocate").await;
+        index(service, "scripts/bootstrap", "#!/usr/bin/env python3\nallocate()").await;
+        service.commit().await.unwrap();
+    }
+
+    fn count(facets: &[(String, u64)], value: &str) -> Option<u64> {
+        facets.iter().find(|(v, _)| v == value).map(|(_, c)| *c)
+    }
+
+    #[tokio::test]
+    async fn test_language_is_detected_and_faceted() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = service
+            .search(SearchQuery {
+                query: "allocate".to_string(),
+                include_facets: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let language_of = |path: &str| results.results.iter().find(|r| r.file_path == path).unwrap().language.clone();
+        assert_eq!(language_of("src/buffer.h"), "c");
+        assert_eq!(language_of("src/pool.h"), "cpp");
+        assert_eq!(language_of("Dockerfile"), "dockerfile");
+        assert_eq!(language_of("scripts/bootstrap"), "python");
+
+        let languages = results.facets.unwrap().languages;
+        assert_eq!(count(&languages, "cpp"), Some(2));
+        assert_eq!(count(&languages, "c"), Some(1));
+        assert_eq!(count(&languages, "python"), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_language_filter_accepts_aliases() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = service
+            .search(SearchQuery {
+                query: "allocate".to_string(),
+                language_filter: Some("C++,docker".to_string()),
+                include_facets: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.total, 3);
+        assert!(results.results.iter().all(|r| r.language == "cpp" || r.language == "dockerfile"));
+
+        // The language facet ignores its own filter, so other languages remain selectable
+        let facets = results.facets.unwrap();
+        assert_eq!(count(&facets.languages, "c"), Some(1));
+        assert_eq!(
+            count(&facets.extensions, "h"),
+            Some(1),
+            "Other facets apply the language filter"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inline_lang_filter() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = service
+            .search(SearchQuery { query: "allocate lang:c".to_string(), limit: 10, ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(results.total, 1);
+        assert_eq!(results.results[0].file_path, "src/buffer.h");
+
+        // A query made only of filters matches every file of that language
+        let results = service
+            .search(SearchQuery { query: "LANG:python".to_string(), limit: 10, ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(results.total, 1);
+        assert_eq!(results.results[0].file_path, "scripts/bootstrap");
+    }
+
+    #[test]
+    fn test_extract_inline_filters() {
+        let query = SearchQuery {
+            query: "lang:rust parse lang:go".to_string(),
+            language_filter: Some("cpp".to_string()),
+            ..Default::default()
+        }
+        .extract_inline_filters();
+        assert_eq!(query.query, "parse");
+        assert_eq!(query.language_filter.as_deref(), Some("cpp,rust,go"));
+
+        // Regex patterns are left untouched
+        let query = SearchQuery { query: "lang:.*".to_string(), regex_search: true, ..Default::default() }
+            .extract_inline_filters();
+        assert_eq!(query.query, "lang:.*");
+        assert!(query.language_filter.is_none());
+    }
+}
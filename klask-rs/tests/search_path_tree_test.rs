@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod search_path_tree_tests {
+    use klask_rs::services::search::{FileData, SearchQuery, SearchService, TreeQuery};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, version: &str, path: &str) {
+        let file_name = path.rsplit('/').next().unwrap();
+        let content = format!("fn handler() {{ /* {} */ }}", path);
+        service
+            .upsert_file(FileData {
+                file_id: Uuid::new_v4(),
+                file_name,
+                file_path: path,
+                content: &content,
+                repository: "klask",
+                project: "klask",
+                version,
+                extension: "rs",
+                size: content.len() as u64,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn index_fixture(service: &SearchService) {
+        index(service, "main", "README.rs").await;
+        index(service, "main", "src/main.rs").await;
+        index(service, "main", "src/api/search.rs").await;
+        index(service, "main", "src/api/files.rs").await;
+        index(service, "main", "src/api/admin/users.rs").await;
+        index(service, "main", "src/services/search.rs").await;
+        index(service, "main", "tests/api_test.rs").await;
+        index(service, "develop", "src/legacy/old.rs").await;
+        service.commit().await.unwrap();
+    }
+
+    fn directory_count(directories: &[(String, u64)], name: &str) -> Option<u64> {
+        directories.iter().find(|(dir, _)| dir == name).map(|(_, count)| *count)
+    }
+
+    #[tokio::test]
+    async fn test_directory_facets_at_root() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = service
+            .search(SearchQuery {
+                query: "handler".to_string(),
+                version_filter: Some("main".to_string()),
+                include_facets: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let directories = results.facets.unwrap().directories;
+        assert_eq!(directory_count(&directories, "src"), Some(5));
+        assert_eq!(directory_count(&directories, "tests"), Some(1));
+        assert_eq!(directories.len(), 2, "Only top-level directories are returned");
+    }
+
+    #[tokio::test]
+    async fn test_path_filter_includes_subdirectories() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = service
+            .search(SearchQuery {
+                query: "handler".to_string(),
+                path_filter: Some("/src/api/".to_string()),
+                include_facets: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.total, 3);
+        assert!(results.results.iter().all(|r| r.file_path.starts_with("src/api/")));
+
+        let facets = results.facets.unwrap();
+        assert_eq!(facets.directories, vec![("src/api/admin".to_string(), 1)]);
+        assert_eq!(directory_count(&facets.projects, "klask"), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_browse_tree_root_and_subdirectory() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let root = service
+            .browse_tree(TreeQuery {
+                repository: "klask".to_string(),
+                version: "main".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(root.path, "");
+        let names: Vec<&str> = root.directories.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["src", "tests"]);
+        assert_eq!(root.directories[0].file_count, 5);
+        assert_eq!(root.files.len(), 1);
+        assert_eq!(root.files[0].path, "README.rs");
+
+        let api = service
+            .browse_tree(TreeQuery {
+                repository: "klask".to_string(),
+                version: "main".to_string(),
+                path: "src/api".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(api.directories.len(), 1);
+        assert_eq!(api.directories[0].path, "src/api/admin");
+        let files: Vec<&str> = api.files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(files, ["files.rs", "search.rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_browse_tree_is_scoped_to_version() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let develop = service
+            .browse_tree(TreeQuery {
+                repository: "klask".to_string(),
+                version: "develop".to_string(),
+                path: "src".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(develop.directories.len(), 1);
+        assert_eq!(develop.directories[0].name, "legacy");
+        assert!(develop.files.is_empty());
+
+        let unknown = service
+            .browse_tree(TreeQuery {
+                repository: "other".to_string(),
+                version: "main".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(unknown.directories.is_empty() && unknown.files.is_empty());
+    }
+}
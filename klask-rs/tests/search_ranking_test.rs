@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod search_ranking_tests {
+    use klask_rs::models::RankingConfig;
+    use klask_rs::services::search::{FileData, SearchQuery, SearchService};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, version: &str, path: &str, content: &str) {
+        let file_name = path.rsplit('/').next().unwrap();
+        let extension = file_name.rsplit('.').next().unwrap();
+        service
+            .upsert_file(FileData {
+                file_id: Uuid::new_v4(),
+                file_name,
+                file_path: path,
+                content,
+                repository: "shop",
+                project: "shop",
+                version,
+                extension,
+                size: content.len() as u64,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn search_paths(service: &SearchService, query: &str) -> Vec<(String, String)> {
+        service
+            .search(SearchQuery { query: query.to_string(), limit: 10, ..Default::default() })
+            .await
+            .unwrap()
+            .results
+            .into_iter()
+            .map(|r| (r.file_path, r.version))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_file_name_match_outranks_test_mentions() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+
+        let mentions = "UserService ".repeat(40);
+        index(&service, "develop", "src/test/java/shop/UserServiceIT.java", &mentions).await;
+        index(
+            &service,
+            "develop",
+            "src/main/java/shop/UserService.java",
+            "public class Accounts { void register() {} }",
+        )
+        .await;
+        service.commit().await.unwrap();
+
+        let results = search_paths(&service, "UserService").await;
+        assert_eq!(results[0].0, "src/main/java/shop/UserService.java");
+    }
+
+    #[tokio::test]
+    async fn test_default_branch_and_shallow_paths_are_preferred() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+
+        index(&service, "feature/checkout", "src/cart.rs", "fn checkout_total() {}").await;
+        index(&service, "main", "src/cart.rs", "fn checkout_total() {}").await;
+        index(
+            &service,
+            "develop",
+            "a/b/c/d/cart_helpers.rs",
+            "fn checkout_helper() {}",
+        )
+        .await;
+        index(&service, "develop", "cart_helpers.rs", "fn checkout_helper() {}").await;
+        service.commit().await.unwrap();
+
+        let results = search_paths(&service, "checkout_total").await;
+        assert_eq!(results[0].1, "main");
+
+        let results = search_paths(&service, "checkout_helper").await;
+        assert_eq!(results[0].0, "cart_helpers.rs");
+    }
+
+    #[tokio::test]
+    async fn test_repository_default_branch_is_preferred() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+
+        index(&service, "main", "src/cart.rs", "fn checkout_total() {}").await;
+        index(&service, "develop", "src/cart.rs", "fn checkout_total() {}").await;
+        service.commit().await.unwrap();
+
+        service.set_default_branch("shop", Some("develop"));
+        let results = search_paths(&service, "checkout_total").await;
+        assert_eq!(results[0].1, "develop");
+
+        // Without a known default branch, the configured names apply
+        service.set_default_branch("shop", None);
+        let results = search_paths(&service, "checkout_total").await;
+        assert_eq!(results[0].1, "main");
+    }
+
+    #[tokio::test]
+    async fn test_ranking_configuration_is_applied_at_runtime() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+
+        index(
+            &service,
+            "develop",
+            "vendor/payments/gateway.rs",
+            "gateway gateway gateway",
+        )
+        .await;
+        index(&service, "develop", "src/payments/gateway.rs", "gateway").await;
+        service.commit().await.unwrap();
+
+        let results = search_paths(&service, "gateway").await;
+        assert_eq!(
+            results[0].0, "src/payments/gateway.rs",
+            "Vendored code is demoted by default"
+        );
+
+        // Stop demoting vendored code: the denser vendored copy wins again
+        service.set_ranking_config(RankingConfig { demotion_factor: 1.0, ..Default::default() });
+        assert_eq!(service.ranking_config().demotion_factor, 1.0);
+
+        let results = search_paths(&service, "gateway").await;
+        assert_eq!(results[0].0, "vendor/payments/gateway.rs");
+    }
+}
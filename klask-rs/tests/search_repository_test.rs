@@ -41,6 +41,7 @@ mod search_repository_tests {
                 version: "main",
                 extension: "rs",
                 size: 1024,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -60,12 +61,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -105,6 +112,7 @@ mod search_repository_tests {
                 version: "main",
                 extension: "rs",
                 size: 512,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -120,12 +128,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -150,6 +164,7 @@ mod search_repository_tests {
             version: "v1.0.0",
             extension: "rs",
             size: 256,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
         service.commit().await.unwrap();
@@ -163,12 +178,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -198,6 +219,7 @@ mod search_repository_tests {
                 version: "main",
                 extension: "rs",
                 size: 1024,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -213,12 +235,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -250,6 +278,7 @@ mod search_repository_tests {
             version: "main",
             extension: "rs",
             size: 768,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
         service.commit().await.unwrap();
@@ -263,12 +292,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -293,6 +328,7 @@ mod search_repository_tests {
                 version: "main",
                 extension: "rs",
                 size: 2048,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -310,6 +346,7 @@ mod search_repository_tests {
                 version: "main",
                 extension: "rs",
                 size: 1536,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -325,12 +362,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -371,6 +414,7 @@ mod search_repository_tests {
                     version: "main",
                     extension: "rs",
                     size: 4096,
+                    ..Default::default()
                 };
                 service.upsert_file(file_data).await.unwrap();
             }
@@ -389,12 +433,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -433,6 +483,7 @@ mod search_repository_tests {
                     version: "main",
                     extension: "rs",
                     size: 1024,
+                    ..Default::default()
                 };
                 service.upsert_file(file_data).await.unwrap();
             }
@@ -459,12 +510,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -492,6 +549,7 @@ mod search_repository_tests {
                 version: "main",
                 extension: "rs",
                 size: 2048,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -511,12 +569,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -532,12 +596,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let old_results = service.search(old_query).await.unwrap();
@@ -549,7 +619,7 @@ mod search_repository_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index files with different combinations
-        let files = vec![
+        let files = [
             ("repo-a", "v1.0", "rs"),
             ("repo-a", "v1.0", "js"),
             ("repo-a", "v2.0", "rs"),
@@ -569,6 +639,7 @@ mod search_repository_tests {
                 version,
                 extension: ext,
                 size: 3072,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -584,12 +655,18 @@ mod search_repository_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
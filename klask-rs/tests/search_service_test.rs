@@ -39,6 +39,7 @@ mod search_service_tests {
             version: "1.0.0",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         let result = service.upsert_file(file_data).await;
         eprintln!("Upsert result: {:?}", result);
@@ -71,6 +72,7 @@ mod search_service_tests {
             version: "1.0.0",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data1).await.unwrap();
 
@@ -87,6 +89,7 @@ mod search_service_tests {
             version: "1.0.1",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data2).await.unwrap();
 
@@ -105,12 +108,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             offset: 0,
             limit: 10,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
         let search_result = service.search(search_query).await.unwrap();
         assert!(search_result.total >= 1, "Should find at least one result");
@@ -159,6 +168,7 @@ mod search_service_tests {
                 version: "1.0.0",
                 extension: ext,
                 size: 1024,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -174,12 +184,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -249,6 +265,7 @@ mod search_service_tests {
                 version,
                 extension: ext,
                 size: 1024,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -268,12 +285,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let basic_results = service.search(basic_query).await.unwrap();
@@ -290,12 +313,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let _project_results = service.search(project_query).await.unwrap();
@@ -310,12 +339,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let _ext_results = service.search(ext_query).await.unwrap();
@@ -330,12 +365,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let _version_results = service.search(version_query).await.unwrap();
@@ -363,6 +404,7 @@ mod search_service_tests {
                 version: "1.0.0",
                 extension: "rs",
                 size: 1024,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -378,12 +420,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let first_results = service.search(first_page).await.unwrap();
@@ -400,12 +448,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 10,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let _second_results = service.search(second_page).await.unwrap();
@@ -420,12 +474,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 20,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let _last_results = service.search(last_page).await.unwrap();
@@ -447,6 +507,7 @@ mod search_service_tests {
             version: "1.0.0",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
 
@@ -461,12 +522,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 1,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let search_results = service.search(query).await.unwrap();
@@ -501,6 +568,7 @@ mod search_service_tests {
             version: "1.0.0",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
 
@@ -519,12 +587,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             offset: 0,
             limit: 10,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
         let search_result = service.search(search_query).await.unwrap();
         assert_eq!(search_result.total, 0);
@@ -547,6 +621,7 @@ mod search_service_tests {
             version: "1.0.0",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
 
@@ -585,6 +660,7 @@ mod search_service_tests {
                 version: "1.0.0",
                 extension: "rs",
                 size: 1024,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -626,6 +702,7 @@ mod search_service_tests {
             version: "1.0.0",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
 
@@ -643,12 +720,18 @@ mod search_service_tests {
                 repository_filter: None,
                 min_size: None,
                 max_size: None,
+                path_filter: None,
+                sort_by: None,
+                sort_order: None,
+                search_after: None,
+                language_filter: None,
                 limit: 10,
                 offset: 0,
                 include_facets: false,
                 fuzzy_search: false,
                 regex_search: false,
                 regex_flags: None,
+                include_generated: false,
             };
 
             let results = service.search(query).await.unwrap();
@@ -677,6 +760,7 @@ mod search_service_tests {
             version: "1.0.0",
             extension: "rs",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
         service.commit().await.unwrap();
@@ -690,12 +774,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         // Empty query should return no results but not error
@@ -711,12 +801,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let long_results = service.search(long_query).await;
@@ -750,6 +846,7 @@ mod search_service_tests {
                 version: &version,
                 extension: "rs",
                 size: 1024,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -766,12 +863,18 @@ mod search_service_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(search_query).await.unwrap();
@@ -790,4 +893,32 @@ mod search_service_tests {
         // The search service should be functional regardless of duplicate handling
         assert!(service.get_document_count().unwrap() > 0);
     }
+
+    #[tokio::test]
+    async fn test_outdated_index_is_moved_aside() {
+        let temp_dir = TempDir::new().unwrap();
+        let index_path = temp_dir.path().join("index");
+        std::fs::create_dir_all(&index_path).unwrap();
+
+        // An index built with another schema, as left by a previous release
+        let mut schema_builder = tantivy::schema::Schema::builder();
+        schema_builder.add_text_field("legacy", tantivy::schema::STRING | tantivy::schema::STORED);
+        tantivy::Index::create_in_dir(&index_path, schema_builder.build()).unwrap();
+
+        let service = SearchService::new(&index_path).expect("An outdated index should not prevent startup");
+        let outdated_index_dir = service.outdated_index_dir().expect("The outdated index should be kept").to_owned();
+
+        assert!(outdated_index_dir.starts_with(temp_dir.path()));
+        assert!(outdated_index_dir.join("meta.json").exists());
+        assert_eq!(service.get_document_count().unwrap(), 0);
+
+        // Deleted once every repository was re-indexed
+        service.remove_outdated_index().unwrap();
+        assert!(!outdated_index_dir.exists());
+
+        // The recreated index opens normally afterwards
+        drop(service);
+        let service = SearchService::new(&index_path).unwrap();
+        assert!(service.outdated_index_dir().is_none());
+    }
 }
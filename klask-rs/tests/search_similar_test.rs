@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod search_similar_tests {
+    use klask_rs::services::search::{FileData, SearchService, SimilarQuery};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    const VULNERABLE_CODE: &str = "fn parse_header(input: &str) -> String { let decoded = base64_decode(input); \
+        unsafe_deserialize(decoded) } fn base64_decode(value: &str) -> Vec<u8> { legacy_codec(value) }";
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, project: &str, version: &str, path: &str, content: &str) -> Uuid {
+        let file_id = Uuid::new_v4();
+        let file_name = path.rsplit('/').next().unwrap();
+        service
+            .upsert_file(FileData {
+                file_id,
+                file_name,
+                file_path: path,
+                content,
+                repository: project,
+                project,
+                version,
+                extension: "rs",
+                size: content.len() as u64,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        file_id
+    }
+
+    async fn index_fixture(service: &SearchService) -> Uuid {
+        let source = index(service, "repo-a", "main", "src/header.rs", VULNERABLE_CODE).await;
+        index(service, "repo-a", "develop", "src/header.rs", VULNERABLE_CODE).await;
+        index(service, "repo-b", "main", "lib/copied_header.rs", VULNERABLE_CODE).await;
+        index(
+            service,
+            "repo-c",
+            "main",
+            "src/unrelated.rs",
+            "fn render_page() { draw_widgets(); }",
+        )
+        .await;
+        service.commit().await.unwrap();
+        source
+    }
+
+    #[tokio::test]
+    async fn test_find_similar_by_file_id_excludes_source() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        let source = index_fixture(&service).await;
+
+        let similar = service
+            .find_similar(SimilarQuery { file_id: Some(source), limit: 10, ..Default::default() })
+            .await
+            .unwrap()
+            .expect("Source document should be found");
+
+        assert!(
+            similar.results.iter().all(|r| r.file_id != source),
+            "Source file must not be returned"
+        );
+        assert_eq!(similar.total, 2, "Both copies should be found");
+        assert!(similar.results.iter().any(|r| r.file_path == "lib/copied_header.rs"));
+        assert!(similar.results.iter().all(|r| r.file_path != "src/unrelated.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_find_similar_excludes_same_path_on_other_branches() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        let source = index_fixture(&service).await;
+
+        let similar = service
+            .find_similar(SimilarQuery {
+                file_id: Some(source),
+                exclude_same_path: true,
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .expect("Source document should be found");
+
+        assert_eq!(similar.total, 1);
+        assert_eq!(similar.results[0].project, "repo-b");
+        assert_eq!(similar.results[0].file_path, "lib/copied_header.rs");
+    }
+
+    #[tokio::test]
+    async fn test_find_similar_by_doc_address() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        let source = index_fixture(&service).await;
+
+        let doc_address = service.get_file_by_id(source).await.unwrap().unwrap().doc_address;
+        let similar = service
+            .find_similar(SimilarQuery { doc_address: Some(doc_address), limit: 10, ..Default::default() })
+            .await
+            .unwrap()
+            .expect("Source document should be found");
+
+        assert_eq!(similar.total, 2);
+    }
+
+    #[tokio::test]
+    async fn test_find_similar_unknown_source() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let similar = service
+            .find_similar(SimilarQuery { file_id: Some(Uuid::new_v4()), limit: 10, ..Default::default() })
+            .await
+            .unwrap();
+        assert!(similar.is_none());
+
+        let missing_source = service.find_similar(SimilarQuery { limit: 10, ..Default::default() }).await;
+        assert!(missing_source.is_err(), "A file_id or doc_address is required");
+    }
+}
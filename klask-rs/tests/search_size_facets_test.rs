@@ -25,7 +25,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index files with specific sizes distributed across buckets
-        let test_files = vec![
+        let test_files = [
             ("file1.txt", 512, "small file"),         // < 1 KB (0-1023)
             ("file2.txt", 2048, "medium file"),       // 1 KB - 10 KB
             ("file3.txt", 50000, "large file"),       // 10 KB - 100 KB
@@ -34,7 +34,7 @@ mod search_size_facets_tests {
             ("file6.txt", 50000000, "enormous file"), // > 1 MB
         ];
 
-        for (_i, (name, size, content)) in test_files.iter().enumerate() {
+        for (name, size, content) in test_files.iter() {
             let file_id = Uuid::new_v4();
             let file_data = FileData {
                 file_id,
@@ -46,6 +46,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -61,12 +62,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -80,7 +87,7 @@ mod search_size_facets_tests {
         assert_eq!(facets.size_ranges.len(), 5, "Should have exactly 5 size buckets");
 
         // Expected counts: one file in each bucket, except last bucket has 2 (merged from old buckets)
-        let expected_counts = vec![1, 1, 1, 1, 2];
+        let expected_counts = [1, 1, 1, 1, 2];
         for (i, (label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -111,7 +118,7 @@ mod search_size_facets_tests {
             ("boundary_over_10mb.txt", 10485761, "boundary searchable"), // Just over 10MB
         ];
 
-        for (_i, (name, size, content)) in boundary_files.iter().enumerate() {
+        for (name, size, content) in boundary_files.iter() {
             let file_id = Uuid::new_v4();
             let file_data = FileData {
                 file_id,
@@ -123,6 +130,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -138,12 +146,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 100,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -155,7 +169,7 @@ mod search_size_facets_tests {
         // 10 KB - 100 KB: files at 10240, 102399 = 2 files
         // 100 KB - 1 MB: files at 102400, 1048575 = 2 files
         // > 1 MB: files at 1048576, 10485759, 10485760, 10485761 = 4 files (merged old buckets)
-        let expected_counts = vec![2, 2, 2, 2, 4];
+        let expected_counts = [2, 2, 2, 2, 4];
 
         for (i, (label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
@@ -187,6 +201,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: size as u64,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -205,6 +220,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: size as u64,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -223,6 +239,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: size as u64,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -238,19 +255,25 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 100,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
         let facets = results.facets.expect("Facets should be present");
 
         // Verify expected counts
-        let expected_counts = vec![5, 3, 2, 0, 0, 0];
+        let expected_counts = [5, 3, 2, 0, 0, 0];
         for (i, (label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -288,6 +311,7 @@ mod search_size_facets_tests {
                     version: "main",
                     extension: "txt",
                     size,
+                    ..Default::default()
                 };
                 service.upsert_file(file_data).await.unwrap();
             }
@@ -304,12 +328,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -319,7 +349,7 @@ mod search_size_facets_tests {
 
         // Verify size_ranges only reflects project-a results
         // < 1 KB: 1, 1 KB - 10 KB: 1, 10 KB - 100 KB: 1, rest: 0
-        let expected_counts = vec![1, 1, 1, 0, 0, 0];
+        let expected_counts = [1, 1, 1, 0, 0, 0];
         for (i, (label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -354,6 +384,7 @@ mod search_size_facets_tests {
                     version: "main",
                     extension: ext,
                     size,
+                    ..Default::default()
                 };
                 service.upsert_file(file_data).await.unwrap();
             }
@@ -370,12 +401,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -384,7 +421,7 @@ mod search_size_facets_tests {
         let facets = results.facets.expect("Facets should be present");
 
         // Verify size_ranges only reflects RS files: 1 < 1KB, 1 in 1-10KB, rest 0
-        let expected_counts = vec![1, 1, 0, 0, 0, 0];
+        let expected_counts = [1, 1, 0, 0, 0, 0];
         for (i, (label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -400,7 +437,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index diverse files
-        let test_data = vec![
+        let test_data = [
             ("repo-a", "v1.0", "rs", 512),   // < 1 KB
             ("repo-a", "v1.0", "rs", 5000),  // 1-10 KB
             ("repo-a", "v1.0", "js", 50000), // 10-100 KB
@@ -420,6 +457,7 @@ mod search_size_facets_tests {
                 version,
                 extension: ext,
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -435,12 +473,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -449,7 +493,7 @@ mod search_size_facets_tests {
         let facets = results.facets.expect("Facets should be present");
 
         // Both files are in different size buckets: 512 < 1KB, 5000 in 1-10KB
-        let expected_counts = vec![1, 1, 0, 0, 0, 0];
+        let expected_counts = [1, 1, 0, 0, 0, 0];
         for (i, (_label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -466,7 +510,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index files across all size ranges
-        let sizes = vec![
+        let sizes = [
             512,      // < 1 KB
             2048,     // 1-10 KB
             50000,    // 10-100 KB
@@ -487,6 +531,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -503,12 +548,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: Some(10000), // Only files >= 10KB in results
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 100,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -525,7 +576,7 @@ mod search_size_facets_tests {
         // 100KB-1MB: 1 (500KB)
         // 1-10MB: 1 (5MB)
         // > 10MB: 1 (50MB)
-        let expected_counts = vec![1, 1, 1, 1, 2];
+        let expected_counts = [1, 1, 1, 1, 2];
         for (i, (_label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -553,6 +604,7 @@ mod search_size_facets_tests {
             version: "main",
             extension: "txt",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
         service.commit().await.unwrap();
@@ -566,12 +618,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -580,7 +638,7 @@ mod search_size_facets_tests {
         let facets = results.facets.expect("Facets should be present even with no results");
 
         // All size buckets should have 0 count
-        let expected_counts = vec![0, 0, 0, 0, 0, 0];
+        let expected_counts = [0, 0, 0, 0, 0, 0];
         for (i, (_label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -597,7 +655,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index files only in < 1KB and > 10MB buckets
-        let sizes = vec![
+        let sizes = [
             512,      // < 1 KB
             50000000, // > 10MB
         ];
@@ -614,6 +672,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -629,19 +688,25 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
         let facets = results.facets.expect("Facets should be present");
 
         // Expected: 1 file < 1KB, 0 in middle buckets, 1 file > 1MB (merged buckets)
-        let expected_counts = vec![1, 0, 0, 0, 1];
+        let expected_counts = [1, 0, 0, 0, 1];
         for (i, (_label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -671,6 +736,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: size as u64,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -686,12 +752,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 100,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -700,7 +772,7 @@ mod search_size_facets_tests {
         let facets = results.facets.expect("Facets should be present");
 
         // All 10 files in 1-10KB bucket, rest empty
-        let expected_counts = vec![0, 10, 0, 0, 0, 0];
+        let expected_counts = [0, 10, 0, 0, 0, 0];
         for (i, (label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -717,7 +789,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index files across all size ranges
-        let sizes = vec![
+        let sizes = [
             512,      // < 1 KB
             2048,     // 1-10 KB
             50000,    // 10-100 KB
@@ -738,6 +810,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -753,12 +826,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: Some(102400), // Only files <= 100KB in results
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 100,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -775,7 +854,7 @@ mod search_size_facets_tests {
         // 100KB-1MB: 1 (500000 - still shown even though filtered out of results)
         // 1-10MB: 1 (5000000 - still shown even though filtered out of results)
         // > 10MB: 1 (50000000 - still shown even though filtered out of results)
-        let expected_counts = vec![1, 1, 1, 1, 2];
+        let expected_counts = [1, 1, 1, 1, 2];
         for (i, (_label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -792,7 +871,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index files across all size ranges
-        let sizes = vec![
+        let sizes = [
             512,      // < 1 KB
             2048,     // 1-10 KB
             50000,    // 10-100 KB
@@ -813,6 +892,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -828,12 +908,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: Some(10240),   // >= 10KB in results only
             max_size: Some(1048576), // <= 1MB in results only
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 100,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -849,7 +935,7 @@ mod search_size_facets_tests {
         // 100KB-1MB: 1 (500000 - matches and shown)
         // 1-10MB: 1 (5000000 - still shown even though filtered out of results)
         // > 10MB: 1 (50000000 - still shown even though filtered out of results)
-        let expected_counts = vec![1, 1, 1, 1, 2];
+        let expected_counts = [1, 1, 1, 1, 2];
         for (i, (_label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -866,7 +952,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index one file in each bucket to verify order
-        let sizes = vec![512, 2048, 50000, 500000, 5000000, 50000000];
+        let sizes = [512, 2048, 50000, 500000, 5000000, 50000000];
 
         for (i, size) in sizes.iter().enumerate() {
             let file_id = Uuid::new_v4();
@@ -880,6 +966,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "txt",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -895,19 +982,25 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
         let facets = results.facets.expect("Facets should be present");
 
         // Verify the expected order of buckets
-        let expected_labels = vec!["< 1 KB", "1 KB - 10 KB", "10 KB - 100 KB", "100 KB - 1 MB", "> 1 MB"];
+        let expected_labels = ["< 1 KB", "1 KB - 10 KB", "10 KB - 100 KB", "100 KB - 1 MB", "> 1 MB"];
 
         for (i, (actual_label, _count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
@@ -925,7 +1018,7 @@ mod search_size_facets_tests {
         let (service, _temp_dir, _guard) = create_test_search_service().await;
 
         // Index very large files
-        let sizes = vec![
+        let sizes = [
             100000000,   // 100 MB (> 1 MB)
             1000000000,  // 1 GB (> 1 MB)
             10000000000, // 10 GB (> 1 MB)
@@ -943,6 +1036,7 @@ mod search_size_facets_tests {
                 version: "main",
                 extension: "bin",
                 size: *size,
+                ..Default::default()
             };
             service.upsert_file(file_data).await.unwrap();
         }
@@ -958,12 +1052,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: true,
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
@@ -972,7 +1072,7 @@ mod search_size_facets_tests {
         let facets = results.facets.expect("Facets should be present");
 
         // All files should be in > 1 MB bucket (merged buckets)
-        let expected_counts = vec![0, 0, 0, 0, 3];
+        let expected_counts = [0, 0, 0, 0, 3];
         for (i, (_label, count)) in facets.size_ranges.iter().enumerate() {
             assert_eq!(
                 *count, expected_counts[i],
@@ -1000,6 +1100,7 @@ mod search_size_facets_tests {
             version: "main",
             extension: "txt",
             size: 1024,
+            ..Default::default()
         };
         service.upsert_file(file_data).await.unwrap();
         service.commit().await.unwrap();
@@ -1013,12 +1114,18 @@ mod search_size_facets_tests {
             repository_filter: None,
             min_size: None,
             max_size: None,
+            path_filter: None,
+            sort_by: None,
+            sort_order: None,
+            search_after: None,
+            language_filter: None,
             limit: 10,
             offset: 0,
             include_facets: false, // Facets not requested
             fuzzy_search: false,
             regex_search: false,
             regex_flags: None,
+            include_generated: false,
         };
 
         let results = service.search(query).await.unwrap();
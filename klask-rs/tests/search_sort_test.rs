@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod search_sort_tests {
+    use klask_rs::services::search::{FileData, SearchQuery, SearchService, SortField, SortOrder};
+    use std::sync::LazyLock;
+    use tempfile::TempDir;
+    use tokio::sync::Mutex as AsyncMutex;
+    use uuid::Uuid;
+
+    // Global mutex to ensure tests don't interfere with each other
+    static TEST_MUTEX: LazyLock<AsyncMutex<()>> = LazyLock::new(|| AsyncMutex::new(()));
+
+    async fn create_test_search_service() -> (SearchService, TempDir, tokio::sync::MutexGuard<'static, ()>) {
+        let _guard = TEST_MUTEX.lock().await;
+        let temp_dir = TempDir::new().unwrap();
+        let test_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+        let index_path = temp_dir.path().join(format!("test_index_{}", test_id));
+        let service = SearchService::new(&index_path).expect("Failed to create search service");
+        (service, temp_dir, _guard)
+    }
+
+    async fn index(service: &SearchService, repository: &str, path: &str, size: u64, last_modified: i64) {
+        index_dated(service, repository, path, size, Some(last_modified)).await;
+    }
+
+    async fn index_dated(service: &SearchService, repository: &str, path: &str, size: u64, last_modified: Option<i64>) {
+        let file_name = path.rsplit('/').next().unwrap();
+        service
+            .upsert_file(FileData {
+                file_id: Uuid::new_v4(),
+                file_name,
+                file_path: path,
+                content: "audit trail",
+                repository,
+                project: repository,
+                version: "main",
+                extension: "rs",
+                size,
+                last_modified,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn index_fixture(service: &SearchService) {
+        index(service, "billing", "src/ledger.rs", 300, 1_700_000_300).await;
+        index(service, "accounts", "src/users.rs", 100, 1_700_000_100).await;
+        index(service, "accounts", "src/audit.rs", 200, 1_700_000_200).await;
+        index(service, "billing", "src/audit.rs", 200, 1_700_000_400).await;
+        service.commit().await.unwrap();
+    }
+
+    async fn sorted(
+        service: &SearchService,
+        sort_by: SortField,
+        sort_order: Option<SortOrder>,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<(String, String)> {
+        service
+            .search(SearchQuery {
+                query: "audit".to_string(),
+                sort_by: Some(sort_by),
+                sort_order,
+                offset,
+                limit,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .results
+            .into_iter()
+            .map(|r| (r.repository, r.file_path))
+            .collect()
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected.iter().map(|(r, p)| (r.to_string(), p.to_string())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_sort_by_repository_then_path() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let results = sorted(&service, SortField::Repository, None, 0, 10).await;
+        assert_eq!(
+            results,
+            pairs(&[
+                ("accounts", "src/audit.rs"),
+                ("accounts", "src/users.rs"),
+                ("billing", "src/audit.rs"),
+                ("billing", "src/ledger.rs"),
+            ])
+        );
+
+        let results = sorted(&service, SortField::Repository, Some(SortOrder::Desc), 0, 10).await;
+        assert_eq!(results[0], ("billing".to_string(), "src/ledger.rs".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_sort_by_size_and_date() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        let by_size = service
+            .search(SearchQuery {
+                query: "audit".to_string(),
+                sort_by: Some(SortField::Size),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let sizes: Vec<u64> = by_size.results.iter().map(|r| r.size).collect();
+        assert_eq!(sizes, [300, 200, 200, 100], "Largest files first by default");
+
+        let by_date = service
+            .search(SearchQuery {
+                query: "audit".to_string(),
+                sort_by: Some(SortField::Date),
+                sort_order: Some(SortOrder::Asc),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let dates: Vec<i64> = by_date.results.iter().filter_map(|r| r.last_modified).collect();
+        assert_eq!(dates, [1_700_000_100, 1_700_000_200, 1_700_000_300, 1_700_000_400]);
+    }
+
+    #[tokio::test]
+    async fn test_files_without_date_sort_last() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+        index_dated(&service, "git", "src/audit_log.rs", 50, None).await;
+        service.commit().await.unwrap();
+
+        for sort_order in [SortOrder::Asc, SortOrder::Desc] {
+            let results = sorted(&service, SortField::Date, Some(sort_order), 0, 10).await;
+            assert_eq!(results.len(), 5);
+            assert_eq!(results[4], ("git".to_string(), "src/audit_log.rs".to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sorted_pagination_is_consistent() {
+        let (service, _temp_dir, _guard) = create_test_search_service().await;
+        index_fixture(&service).await;
+
+        // Two files share the same size and path: the tie-breaker keeps pages disjoint and stable
+        let all = sorted(&service, SortField::Size, None, 0, 10).await;
+        let mut paged = sorted(&service, SortField::Size, None, 0, 2).await;
+        paged.extend(sorted(&service, SortField::Size, None, 2, 2).await);
+        assert_eq!(paged, all);
+
+        let all = sorted(&service, SortField::Path, None, 0, 10).await;
+        let mut paged = sorted(&service, SortField::Path, None, 0, 1).await;
+        for offset in 1..4 {
+            paged.extend(sorted(&service, SortField::Path, None, offset, 1).await);
+        }
+        assert_eq!(paged, all);
+        assert_eq!(all[0].1, "src/audit.rs");
+        assert_eq!(all[3].1, "src/users.rs");
+    }
+}
@@ -0,0 +1,327 @@
+mod common;
+
+use klask_rs::models::Repository;
+use klask_rs::services::crawler::git_operations::GitOperations;
+use klask_rs::services::crawler::git_tree_walker::GitTreeWalker;
+use klask_rs::services::crawler::submodules::{
+    MAX_SUBMODULE_DEPTH, Submodule, is_fetchable_submodule_url, parse_gitmodules, resolve_submodule_url, same_host,
+    submodule_ref_prefix, url_host,
+};
+use klask_rs::services::encryption::EncryptionService;
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, OnceLock, RwLock};
+use tempfile::TempDir;
+
+#[cfg(test)]
+mod submodules_tests {
+    use super::*;
+
+    fn git(directory: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(directory)
+            .args(["-c", "user.name=Klask", "-c", "user.email=klask@example.com"])
+            .args(args)
+            .output()
+            .expect("git should be installed");
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// Create a repository with one commit of `files`, and the submodules `(path, url, commit)` pinned in it
+    fn create_repository(directory: &Path, files: &[(&str, &str)], submodules: &[(&str, &str, &str)]) -> String {
+        std::fs::create_dir_all(directory).unwrap();
+        git(directory, &["init", "-q", "-b", "main"]);
+        for (path, content) in files {
+            let path = directory.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        git(directory, &["add", "-A"]);
+
+        if !submodules.is_empty() {
+            let gitmodules: String = submodules
+                .iter()
+                .map(|(path, url, _)| format!("[submodule \"{}\"]\n\tpath = {}\n\turl = {}\n", path, path, url))
+                .collect();
+            std::fs::write(directory.join(".gitmodules"), gitmodules).unwrap();
+            git(directory, &["add", ".gitmodules"]);
+            for (path, _, commit) in submodules {
+                git(
+                    directory,
+                    &["update-index", "--add", "--cacheinfo", &format!("160000,{},{}", commit, path)],
+                );
+            }
+        }
+
+        git(directory, &["commit", "-q", "-m", "Initial commit"]);
+        git(directory, &["rev-parse", "HEAD"])
+    }
+
+    fn git_repository(url: &str, index_submodules: Option<bool>) -> Repository {
+        common::repository(json!({
+            "id": "2b7d1c0e-51a4-4f0e-8a1b-6c3f1e9d2a47",
+            "name": "app",
+            "url": url,
+            "branch": "main",
+            "indexSubmodules": index_submodules
+        }))
+    }
+
+    /// SSH URL of a local repository, served by a stand-in `ssh` that runs the remote command on this machine
+    fn ssh_url(path: &Path) -> String {
+        static FAKE_SSH: OnceLock<TempDir> = OnceLock::new();
+        FAKE_SSH.get_or_init(|| {
+            let bin = TempDir::new().unwrap();
+            let script = bin.path().join("ssh");
+            // Skip the options and the host, then run the remote command (`git-upload-pack '<path>'`)
+            std::fs::write(
+                &script,
+                "#!/bin/sh\nwhile [ $# -gt 0 ]; do\n  case \"$1\" in\n    -o|-p|-i|-l) shift 2 ;;\n    -*) shift ;;\n    *) shift; break ;;\n  esac\ndone\nexec sh -c \"$*\"\n",
+            )
+            .unwrap();
+            std::fs::set_permissions(&script, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+            let path = format!("{}:{}", bin.path().display(), std::env::var("PATH").unwrap_or_default());
+            // SAFETY: only prepends a directory holding `ssh`, which no other test of this binary runs
+            unsafe { std::env::set_var("PATH", path) };
+            bin
+        });
+        format!("ssh://localhost{}", path.display())
+    }
+
+    fn git_operations() -> GitOperations {
+        let encryption_service = Arc::new(EncryptionService::new("test-encryption-key-32bytes").unwrap());
+        GitOperations::new(encryption_service, Arc::new(RwLock::new(None)))
+    }
+
+    fn indexed_paths(repo_path: &Path, submodule_depth: usize) -> Vec<String> {
+        let git_repo = gix::open(repo_path).unwrap();
+        let tree_id = GitTreeWalker::get_branch_tree_id(&git_repo, "main").unwrap();
+        let mut paths: Vec<String> = GitTreeWalker::walk_tree(&git_repo, &tree_id, "", submodule_depth)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_parse_gitmodules() {
+        let content = b"[submodule \"lib\"]\n\tpath = vendor/lib/\n\turl = ../lib.git\n\
+            [submodule \"docs\"]\n\tpath = docs\n\turl = https://git.example.com/team/docs.git\n\tbranch = stable\n\
+            [submodule \"broken\"]\n\turl = https://git.example.com/team/broken.git\n";
+
+        let submodules = parse_gitmodules(content).unwrap();
+        assert_eq!(
+            submodules,
+            vec![
+                Submodule { name: "lib".to_string(), path: "vendor/lib".to_string(), url: "../lib.git".to_string() },
+                Submodule {
+                    name: "docs".to_string(),
+                    path: "docs".to_string(),
+                    url: "https://git.example.com/team/docs.git".to_string()
+                },
+            ]
+        );
+
+        assert!(parse_gitmodules(b"").unwrap().is_empty());
+        assert!(parse_gitmodules(b"[submodule \"lib\"\n").is_err());
+    }
+
+    #[test]
+    fn test_resolve_relative_urls() {
+        let parent = "https://git.example.com/team/app.git";
+        assert_eq!(
+            resolve_submodule_url("../lib.git", parent),
+            "https://git.example.com/team/lib.git"
+        );
+        assert_eq!(
+            resolve_submodule_url("../../shared/lib.git", parent),
+            "https://git.example.com/shared/lib.git"
+        );
+        assert_eq!(
+            resolve_submodule_url("./lib.git", parent),
+            "https://git.example.com/team/app.git/lib.git"
+        );
+        assert_eq!(
+            resolve_submodule_url("../lib.git", "git@git.example.com:team/app.git"),
+            "git@git.example.com:team/lib.git"
+        );
+        assert_eq!(
+            resolve_submodule_url("../lib.git", "ssh://git@git.example.com:2222/team/app.git/"),
+            "ssh://git@git.example.com:2222/team/lib.git"
+        );
+        assert_eq!(resolve_submodule_url("../lib", "/srv/git/app"), "/srv/git/lib");
+        assert_eq!(
+            resolve_submodule_url("../lib", "file:///srv/git/app"),
+            "file:///srv/git/lib"
+        );
+
+        // Absolute URLs are kept as they are
+        assert_eq!(
+            resolve_submodule_url("https://other.example.com/lib.git", parent),
+            "https://other.example.com/lib.git"
+        );
+    }
+
+    #[test]
+    fn test_credentials_only_shared_with_the_same_host() {
+        assert_eq!(
+            url_host("https://oauth2@Git.Example.com:8443/team/app.git").as_deref(),
+            Some("git.example.com")
+        );
+        assert_eq!(url_host("ssh://git@[::1]:2222/team/app.git").as_deref(), Some("::1"));
+        assert_eq!(
+            url_host("git@git.example.com:team/app.git").as_deref(),
+            Some("git.example.com")
+        );
+        assert_eq!(url_host("file:///srv/git/app"), None);
+        assert_eq!(url_host("/srv/git/app"), None);
+
+        let parent = "https://git.example.com/team/app.git";
+        assert!(same_host("https://git.example.com/team/lib.git", parent));
+        assert!(same_host("git@git.example.com:team/lib.git", parent));
+        assert!(!same_host("https://github.com/team/lib.git", parent));
+        assert!(!same_host("https://git.example.com.evil.test/team/lib.git", parent));
+        assert!(!same_host("/srv/git/lib", "/srv/git/app"));
+    }
+
+    #[test]
+    fn test_submodule_ref_prefix_is_stable_per_url() {
+        let prefix = submodule_ref_prefix("https://git.example.com/team/lib.git");
+        assert!(prefix.starts_with("refs/klask/submodules/"));
+        assert_eq!(prefix.len(), "refs/klask/submodules/".len() + 16);
+        assert_eq!(prefix, submodule_ref_prefix("https://git.example.com/team/lib.git"));
+        assert_ne!(prefix, submodule_ref_prefix("https://git.example.com/team/docs.git"));
+    }
+
+    #[tokio::test]
+    async fn test_nested_submodules_are_fetched_and_indexed() {
+        let temp_dir = TempDir::new().unwrap();
+        let remotes = temp_dir.path().join("remotes");
+
+        // app -> lib (relative URL) -> util (absolute URL)
+        let util_path = remotes.join("util");
+        let util_commit = create_repository(&util_path, &[("util.c", "int util(void);")], &[]);
+        let lib_commit = create_repository(
+            &remotes.join("lib"),
+            &[("src/lib.rs", "pub fn lib() {}")],
+            &[("third_party/util", &ssh_url(&util_path), &util_commit)],
+        );
+        create_repository(
+            &remotes.join("app"),
+            &[("README.md", "# App")],
+            &[("lib", "../lib", &lib_commit), ("missing", "../missing", &util_commit)],
+        );
+
+        let app_url = ssh_url(&remotes.join("app"));
+        let clone_path = temp_dir.path().join("clone");
+        let operations = git_operations();
+        operations.clone_or_update_repository(&git_repository(&app_url, Some(true)), &clone_path).await.unwrap();
+
+        assert_eq!(
+            indexed_paths(&clone_path, MAX_SUBMODULE_DEPTH),
+            vec![
+                ".gitmodules",
+                "README.md",
+                "lib/.gitmodules",
+                "lib/src/lib.rs",
+                "lib/third_party/util/util.c",
+                // Pinned to a commit of util, which was fetched for lib
+                "missing/util.c",
+            ]
+        );
+        assert_eq!(
+            indexed_paths(&clone_path, 1),
+            vec![".gitmodules", "README.md", "lib/.gitmodules", "lib/src/lib.rs", "missing/util.c"]
+        );
+        assert_eq!(indexed_paths(&clone_path, 0), vec![".gitmodules", "README.md"]);
+    }
+
+    #[tokio::test]
+    async fn test_submodules_not_fetched_by_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let remotes = temp_dir.path().join("remotes");
+
+        let lib_commit = create_repository(&remotes.join("lib"), &[("src/lib.rs", "pub fn lib() {}")], &[]);
+        create_repository(
+            &remotes.join("app"),
+            &[("README.md", "# App")],
+            &[("lib", "../lib", &lib_commit)],
+        );
+
+        let app_url = ssh_url(&remotes.join("app"));
+        let clone_path = temp_dir.path().join("clone");
+        let operations = git_operations();
+        operations.clone_or_update_repository(&git_repository(&app_url, None), &clone_path).await.unwrap();
+
+        // The pinned commit was not fetched, the submodule is skipped even when walked
+        assert_eq!(
+            indexed_paths(&clone_path, MAX_SUBMODULE_DEPTH),
+            vec![".gitmodules", "README.md"]
+        );
+
+        // Enabling the option fetches the submodule on the next update
+        operations.clone_or_update_repository(&git_repository(&app_url, Some(true)), &clone_path).await.unwrap();
+        assert_eq!(
+            indexed_paths(&clone_path, MAX_SUBMODULE_DEPTH),
+            vec![".gitmodules", "README.md", "lib/src/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn test_only_remote_urls_are_fetchable() {
+        assert!(is_fetchable_submodule_url("https://git.example.com/team/lib.git"));
+        assert!(is_fetchable_submodule_url(
+            "ssh://git@git.example.com:2222/team/lib.git"
+        ));
+        assert!(is_fetchable_submodule_url("git@git.example.com:team/lib.git"));
+
+        assert!(!is_fetchable_submodule_url("file:///srv/git/lib"));
+        assert!(!is_fetchable_submodule_url("/var/lib/klask/repos/other"));
+        assert!(!is_fetchable_submodule_url("../other"));
+        assert!(!is_fetchable_submodule_url("http://git.example.com/team/lib.git"));
+        assert!(!is_fetchable_submodule_url("git://git.example.com/team/lib.git"));
+        assert!(!is_fetchable_submodule_url("ext::sh -c touch% /tmp/pwned"));
+        assert!(!is_fetchable_submodule_url("-oProxyCommand=touch:team/lib.git"));
+    }
+
+    #[tokio::test]
+    async fn test_local_submodules_are_not_fetched() {
+        let temp_dir = TempDir::new().unwrap();
+        let remotes = temp_dir.path().join("remotes");
+
+        // Another repository of the Klask host, referenced by file:// URL, absolute path and relative path
+        let secret_path = remotes.join("secret");
+        let secret_commit = create_repository(&secret_path, &[("secret.txt", "password")], &[]);
+        let app_path = remotes.join("app");
+        create_repository(
+            &app_path,
+            &[("README.md", "# App")],
+            &[
+                ("by_url", &format!("file://{}", secret_path.display()), &secret_commit),
+                ("by_path", secret_path.to_str().unwrap(), &secret_commit),
+                ("by_relative_path", "../secret", &secret_commit),
+            ],
+        );
+
+        let clone_path = temp_dir.path().join("clone");
+        let operations = git_operations();
+        operations
+            .clone_or_update_repository(&git_repository(app_path.to_str().unwrap(), Some(true)), &clone_path)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            indexed_paths(&clone_path, MAX_SUBMODULE_DEPTH),
+            vec![".gitmodules", "README.md"]
+        );
+    }
+}
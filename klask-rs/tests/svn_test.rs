@@ -0,0 +1,407 @@
+use httpmock::prelude::*;
+use klask_rs::models::RepositoryType;
+use klask_rs::services::crawler::svn_crawler::{SvnSource, SvnVersion};
+use klask_rs::services::svn::{SvnLocation, dump_local_repository};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio_util::sync::CancellationToken;
+
+#[cfg(test)]
+mod svn_tests {
+    use super::*;
+
+    fn props(pairs: &[(&str, &str)]) -> String {
+        let mut block = String::new();
+        for (key, value) in pairs {
+            block.push_str(&format!("K {}\n{}\nV {}\n{}\n", key.len(), key, value.len(), value));
+        }
+        block.push_str("PROPS-END\n");
+        block
+    }
+
+    fn revision(number: u64, date: &str) -> String {
+        let props = props(&[("svn:author", "alice"), ("svn:date", date), ("svn:log", "commit")]);
+        format!(
+            "Revision-number: {}\nProp-content-length: {}\nContent-length: {}\n\n{}\n",
+            number,
+            props.len(),
+            props.len(),
+            props
+        )
+    }
+
+    fn node(
+        path: &str,
+        kind: Option<&str>,
+        action: &str,
+        copy_from: Option<(&str, u64)>,
+        text: Option<&str>,
+    ) -> String {
+        let mut record = format!("Node-path: {}\n", path);
+        if let Some(kind) = kind {
+            record.push_str(&format!("Node-kind: {}\n", kind));
+        }
+        record.push_str(&format!("Node-action: {}\n", action));
+        if let Some((from_path, from_revision)) = copy_from {
+            record.push_str(&format!(
+                "Node-copyfrom-rev: {}\nNode-copyfrom-path: {}\n",
+                from_revision, from_path
+            ));
+        }
+        if action == "delete" {
+            return record + "\n\n";
+        }
+
+        let props = props(&[]);
+        match text {
+            Some(text) => {
+                record.push_str(&format!(
+                    "Prop-content-length: {}\nText-content-length: {}\nContent-length: {}\n\n{}{}\n\n",
+                    props.len(),
+                    text.len(),
+                    props.len() + text.len(),
+                    props,
+                    text
+                ));
+            }
+            None => {
+                record.push_str(&format!(
+                    "Prop-content-length: {}\nContent-length: {}\n\n{}\n\n",
+                    props.len(),
+                    props.len(),
+                    props
+                ));
+            }
+        }
+        record
+    }
+
+    /// trunk with two files, a branch copied from trunk and changed, and a tag of an older trunk
+    fn write_dump(dir: &Path) -> PathBuf {
+        let dump = [
+            "SVN-fs-dump-format-version: 2\n\nUUID: 0b5c3bbf-2c2e-4f2a-9b3e-0d1f7c3e6a11\n\n".to_string(),
+            revision(0, "2024-01-01T00:00:00.000000Z"),
+            revision(1, "2024-01-02T10:00:00.000000Z"),
+            node("trunk", Some("dir"), "add", None, None),
+            node("branches", Some("dir"), "add", None, None),
+            node("tags", Some("dir"), "add", None, None),
+            node("trunk/README.md", Some("file"), "add", None, Some("hello\n")),
+            node("trunk/src", Some("dir"), "add", None, None),
+            node(
+                "trunk/src/main.c",
+                Some("file"),
+                "add",
+                None,
+                Some("int main() { return 0; }\n"),
+            ),
+            node("trunk/logo.png", Some("file"), "add", None, Some("PNG")),
+            revision(2, "2024-01-03T10:00:00.000000Z"),
+            node("branches/feature", Some("dir"), "add", Some(("trunk", 1)), None),
+            node("trunk/README.md", Some("file"), "change", None, Some("hello v2\n")),
+            revision(3, "2024-01-04T10:00:00.000000Z"),
+            node(
+                "branches/feature/src/main.c",
+                Some("file"),
+                "change",
+                None,
+                Some("int main() { return 1; }\n"),
+            ),
+            node("tags/v1.0", Some("dir"), "add", Some(("trunk", 2)), None),
+            revision(4, "2024-01-05T10:00:00.000000Z"),
+            node("trunk/src/main.c", None, "delete", None, None),
+            node("branches/feature/README.md", None, "delete", None, None),
+        ]
+        .concat();
+
+        let path = dir.join("repository.svndump");
+        std::fs::write(&path, dump).unwrap();
+        path
+    }
+
+    async fn export(source: &SvnSource, version: &SvnVersion, destination: &Path) -> (usize, u64) {
+        let export = source
+            .export_version(
+                version,
+                destination,
+                |path, _size| !path.ends_with(".png"),
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+        (export.files, export.revision)
+    }
+
+    #[test]
+    fn test_svn_repository_type_serialization() {
+        assert_eq!(serde_json::to_value(RepositoryType::Svn).unwrap(), json!("Svn"));
+        let parsed: RepositoryType = serde_json::from_value(json!("Svn")).unwrap();
+        assert!(matches!(parsed, RepositoryType::Svn));
+    }
+
+    #[test]
+    fn test_svn_location_parsing() {
+        let dir = TempDir::new().unwrap();
+
+        assert_eq!(
+            SvnLocation::parse("https://svn.example.com/repos/legacy/").unwrap(),
+            SvnLocation::Http("https://svn.example.com/repos/legacy".to_string())
+        );
+        assert_eq!(
+            SvnLocation::parse(&format!("file://{}", dir.path().display())).unwrap(),
+            SvnLocation::Local(dir.path().to_path_buf())
+        );
+        assert_eq!(
+            SvnLocation::parse("/backups/legacy.svndump").unwrap(),
+            SvnLocation::Dump(PathBuf::from("/backups/legacy.svndump"))
+        );
+        assert!(SvnLocation::parse("svn://svn.example.com/legacy").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dump_versions_and_export() {
+        let dir = TempDir::new().unwrap();
+        let source = SvnSource::dump(&write_dump(dir.path())).unwrap();
+
+        assert_eq!(source.youngest_revision().await.unwrap(), 4);
+
+        let versions = source.versions("trunk").await.unwrap();
+        let names: Vec<&str> = versions.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["trunk", "branches/feature", "tags/v1.0"]);
+
+        // trunk: README changed in r2, main.c deleted in r4, logo.png not exported
+        let trunk = dir.path().join("trunk");
+        assert_eq!(export(&source, &versions[0], &trunk).await, (1, 4));
+        assert_eq!(std::fs::read_to_string(trunk.join("README.md")).unwrap(), "hello v2\n");
+        assert!(!trunk.join("src/main.c").exists());
+        assert!(!trunk.join("logo.png").exists());
+
+        // Files carry the date of the revision that last changed them
+        let modified = std::fs::metadata(trunk.join("README.md")).unwrap().modified().unwrap();
+        let expected: std::time::SystemTime =
+            chrono::DateTime::parse_from_rfc3339("2024-01-03T10:00:00Z").unwrap().with_timezone(&chrono::Utc).into();
+        assert_eq!(modified, expected);
+
+        // The branch was copied from trunk@1, then changed
+        let feature = dir.path().join("feature");
+        assert_eq!(export(&source, &versions[1], &feature).await, (1, 4));
+        assert_eq!(
+            std::fs::read_to_string(feature.join("src/main.c")).unwrap(),
+            "int main() { return 1; }\n"
+        );
+        assert!(!feature.join("README.md").exists());
+
+        // The tag was copied from trunk@2, before main.c was deleted
+        let tag = dir.path().join("tag");
+        assert_eq!(export(&source, &versions[2], &tag).await, (2, 3));
+        assert_eq!(std::fs::read_to_string(tag.join("README.md")).unwrap(), "hello v2\n");
+        assert_eq!(
+            std::fs::read_to_string(tag.join("src/main.c")).unwrap(),
+            "int main() { return 0; }\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dump_without_standard_layout() {
+        let dir = TempDir::new().unwrap();
+        let dump = [
+            "SVN-fs-dump-format-version: 2\n\n".to_string(),
+            revision(1, "2024-01-02T10:00:00.000000Z"),
+            node("Makefile", Some("file"), "add", None, Some("all:\n")),
+        ]
+        .concat();
+        let path = dir.path().join("flat.svndump");
+        std::fs::write(&path, dump).unwrap();
+
+        let source = SvnSource::dump(&path).unwrap();
+        let versions = source.versions("main").await.unwrap();
+        assert_eq!(
+            versions,
+            vec![SvnVersion { name: "main".to_string(), path: String::new(), revision: 1 }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dump_with_deltas_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let dump = [
+            "SVN-fs-dump-format-version: 3\n\n".to_string(),
+            revision(1, "2024-01-02T10:00:00.000000Z"),
+            "Node-path: a.txt\nNode-kind: file\nNode-action: add\nText-delta: true\nText-content-length: 0\nContent-length: 0\n\n\n".to_string(),
+        ]
+        .concat();
+        let path = dir.path().join("deltas.svndump");
+        std::fs::write(&path, dump).unwrap();
+
+        assert!(SvnSource::dump(&path).is_err());
+    }
+
+    fn multistatus(responses: &[(&str, bool, u64, u64)]) -> String {
+        let mut body = String::from(
+            r#"<?xml version="1.0" encoding="utf-8"?><D:multistatus xmlns:D="DAV:" xmlns:lp1="DAV:" xmlns:lp2="http://subversion.tigris.org/xmlns/dav/">"#,
+        );
+        for (href, is_dir, revision, size) in responses {
+            let resource_type = if *is_dir { "<D:collection/>" } else { "" };
+            body.push_str(&format!(
+                "<D:response><D:href>{}</D:href><D:propstat><D:prop>\
+                 <lp1:resourcetype>{}</lp1:resourcetype>\
+                 <lp1:version-name>{}</lp1:version-name>\
+                 <lp1:getlastmodified>Tue, 02 Jan 2024 10:00:00 GMT</lp1:getlastmodified>\
+                 <lp1:getcontentlength>{}</lp1:getcontentlength>\
+                 </D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>",
+                href, resource_type, revision, size
+            ));
+        }
+        body.push_str("</D:multistatus>");
+        body
+    }
+
+    #[tokio::test]
+    async fn test_web_dav_versions_and_export() {
+        let server = MockServer::start_async().await;
+        // Basic authentication of alice:secret
+        let authorization = "Basic YWxpY2U6c2VjcmV0";
+
+        let root = server
+            .mock_async(|when, then| {
+                when.path("/svn/legacy/")
+                    .header("Depth", "1")
+                    .header("Label", "7")
+                    .header("Authorization", authorization);
+                then.status(207).body(multistatus(&[
+                    ("/svn/legacy/", true, 7, 0),
+                    ("/svn/legacy/trunk/", true, 7, 0),
+                    ("/svn/legacy/tags/", true, 3, 0),
+                ]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.path("/svn/legacy").header("Depth", "0");
+                then.status(207).body(multistatus(&[("/svn/legacy/", true, 7, 0)]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.path("/svn/legacy/tags/").header("Depth", "1").header("Label", "7");
+                then.status(207).body(multistatus(&[
+                    ("/svn/legacy/tags/", true, 3, 0),
+                    ("/svn/legacy/tags/v1/", true, 3, 0),
+                ]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.path("/svn/legacy/trunk/").header("Depth", "1").header("Label", "7");
+                then.status(207).body(multistatus(&[
+                    ("/svn/legacy/trunk/", true, 7, 0),
+                    ("/svn/legacy/trunk/Main.java", false, 5, 13),
+                    ("/svn/legacy/trunk/lib/", true, 7, 0),
+                ]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.path("/svn/legacy/trunk/lib/").header("Depth", "1").header("Label", "7");
+                then.status(207).body(multistatus(&[
+                    ("/svn/legacy/trunk/lib/", true, 7, 0),
+                    ("/svn/legacy/trunk/lib/Util.java", false, 7, 12),
+                    ("/svn/legacy/trunk/lib/big.jar", false, 6, 50_000_000),
+                ]));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/svn/legacy/trunk/Main.java").header("Label", "7");
+                then.status(200).body("class Main {}");
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/svn/legacy/trunk/lib/Util.java").header("Label", "7");
+                then.status(200).body("class Util {}");
+            })
+            .await;
+
+        let mut source = SvnSource::web_dav(&server.url("/svn/legacy/"), Some("alice:secret".to_string()));
+        assert_eq!(source.youngest_revision().await.unwrap(), 7);
+        // Listings and downloads are pinned to the revision read at the start of the crawl
+        source.pin_revision(7);
+
+        let versions = source.versions("trunk").await.unwrap();
+        let names: Vec<&str> = versions.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["trunk", "tags/v1"]);
+        root.assert_async().await;
+
+        // Files above the size limit are not downloaded
+        let dir = TempDir::new().unwrap();
+        let export = source
+            .export_version(
+                &versions[0],
+                dir.path(),
+                |_path, size| size < 1024,
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!((export.files, export.revision), (2, 7));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("Main.java")).unwrap(),
+            "class Main {}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib/Util.java")).unwrap(),
+            "class Util {}"
+        );
+        assert!(!dir.path().join("lib/big.jar").exists());
+    }
+
+    #[tokio::test]
+    async fn test_web_dav_authentication_failure() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.path("/svn/legacy");
+                then.status(401).body("Authorization Required");
+            })
+            .await;
+
+        let source = SvnSource::web_dav(&server.url("/svn/legacy"), Some("alice:wrong".to_string()));
+        let error = source.youngest_revision().await.unwrap_err();
+        assert!(error.to_string().contains("authentication failed"));
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires svnadmin"]
+    async fn test_local_repository_through_svnadmin() {
+        let dir = TempDir::new().unwrap();
+        let repository = dir.path().join("repository");
+
+        // Load the handcrafted dump into a file-based repository, then read it back
+        let status = std::process::Command::new("svnadmin").arg("create").arg(&repository).status().unwrap();
+        assert!(status.success());
+        let status = std::process::Command::new("svnadmin")
+            .arg("load")
+            .arg("--quiet")
+            .arg(&repository)
+            .stdin(std::fs::File::open(write_dump(dir.path())).unwrap())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let url = format!("file://{}", repository.display());
+        let SvnLocation::Local(path) = SvnLocation::parse(&url).unwrap() else {
+            panic!("file:// URL of a directory is a local repository");
+        };
+        let dump_path = dir.path().join("export.svndump");
+        dump_local_repository(&path, &dump_path).await.unwrap();
+
+        let source = SvnSource::dump(&dump_path).unwrap();
+        assert_eq!(source.youngest_revision().await.unwrap(), 4);
+        let versions = source.versions("trunk").await.unwrap();
+        assert_eq!(versions.len(), 3);
+
+        let tag = dir.path().join("tag");
+        assert_eq!(export(&source, &versions[2], &tag).await.0, 2);
+    }
+}
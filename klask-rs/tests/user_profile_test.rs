@@ -129,6 +129,7 @@ async fn test_partial_profile_update() {
 }
 
 #[tokio::test]
+#[allow(clippy::const_is_empty)]
 async fn test_full_name_validation() {
     // Test full name length constraints
     let valid_name = "John Doe";